@@ -0,0 +1,484 @@
+use std::{fmt::Debug, marker};
+
+use derivative::Derivative;
+use fnv::FnvHashMap;
+use serde::{Deserialize, Serialize};
+
+use amethyst_assets::{Asset, Handle};
+use amethyst_core::ecs::prelude::{Component, DenseVecStorage, VecStorage};
+
+use crate::resources::{AnimationSampling, EndControl};
+
+/// Value of a parameter of an `Animator`, tested by the conditions of the transitions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AnimatorParameter {
+    /// A boolean
+    Bool(bool),
+    /// A floating point number
+    Float(f32),
+    /// An integer
+    Int(i32),
+    /// A boolean reset when a transition testing it fires
+    Trigger(bool),
+}
+
+impl AnimatorParameter {
+    fn as_number(&self) -> Option<f32> {
+        match *self {
+            AnimatorParameter::Float(value) => Some(value),
+            AnimatorParameter::Int(value) => Some(value as f32),
+            _ => None,
+        }
+    }
+}
+
+/// Condition on a named parameter of an `Animator`
+///
+/// Conditions on parameters that don't exist, or have another type, are never met.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AnimatorCondition {
+    /// The `Bool` parameter is true, or the `Trigger` parameter is set
+    If(String),
+    /// The `Bool` parameter is false, or the `Trigger` parameter is not set
+    IfNot(String),
+    /// The `Float` or `Int` parameter is greater than the value
+    Greater(String, f32),
+    /// The `Float` or `Int` parameter is less than the value
+    Less(String, f32),
+    /// The `Int` parameter is equal to the value
+    Equals(String, i32),
+    /// The `Int` parameter is not equal to the value
+    NotEquals(String, i32),
+}
+
+impl AnimatorCondition {
+    /// Check if the condition is met by the given parameters
+    pub fn check(&self, parameters: &FnvHashMap<String, AnimatorParameter>) -> bool {
+        use self::AnimatorCondition::*;
+        use self::AnimatorParameter::{Bool, Int, Trigger};
+
+        match *self {
+            If(ref name) => match parameters.get(name) {
+                Some(Bool(value)) | Some(Trigger(value)) => *value,
+                _ => false,
+            },
+            IfNot(ref name) => match parameters.get(name) {
+                Some(Bool(value)) | Some(Trigger(value)) => !*value,
+                _ => false,
+            },
+            Greater(ref name, threshold) => parameters
+                .get(name)
+                .and_then(AnimatorParameter::as_number)
+                .map_or(false, |value| value > threshold),
+            Less(ref name, threshold) => parameters
+                .get(name)
+                .and_then(AnimatorParameter::as_number)
+                .map_or(false, |value| value < threshold),
+            Equals(ref name, expected) => match parameters.get(name) {
+                Some(Int(value)) => *value == expected,
+                _ => false,
+            },
+            NotEquals(ref name, expected) => match parameters.get(name) {
+                Some(Int(value)) => *value != expected,
+                _ => false,
+            },
+        }
+    }
+}
+
+/// A state of an `AnimatorGraph`, playing a single animation
+///
+/// ### Type parameters:
+///
+/// - `I`: id of the animation in the `AnimationSet` of the animated entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimatorState<I> {
+    /// Name of the state, used by the transitions
+    pub name: String,
+    /// Id of the animation played in this state, in the `AnimationSet` of the entity. The same id
+    /// is used in the `AnimationControlSet`.
+    pub animation: I,
+    /// Loop the animation, otherwise it stays on its last frame once done
+    #[serde(default)]
+    pub looping: bool,
+    /// Rate of the animation, default is 1.0
+    #[serde(default = "default_speed")]
+    pub speed: f32,
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+impl<I> AnimatorState<I> {
+    /// Create a new state playing the animation `animation` once
+    pub fn new<S: Into<String>>(name: S, animation: I) -> Self {
+        AnimatorState {
+            name: name.into(),
+            animation,
+            looping: false,
+            speed: 1.0,
+        }
+    }
+
+    /// Loop the animation of the state
+    pub fn looping(mut self) -> Self {
+        self.looping = true;
+        self
+    }
+
+    /// Set the rate of the animation of the state
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub(crate) fn end_control(&self) -> EndControl {
+        if self.looping {
+            EndControl::Loop(None)
+        } else {
+            EndControl::Stay
+        }
+    }
+}
+
+/// A transition between two states of an `AnimatorGraph`
+///
+/// The transition fires once all its conditions are met, cross-fading the animations of the
+/// states for `duration` seconds with `AnimationControlSet::cross_fade`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimatorTransition {
+    /// Name of the state the transition leaves, `None` to leave any other state
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Name of the state the transition enters
+    pub to: String,
+    /// Conditions that must all be met for the transition to fire
+    #[serde(default)]
+    pub conditions: Vec<AnimatorCondition>,
+    /// Only fire once this fraction of the animation of the left state has played, 1.0 being its
+    /// end
+    #[serde(default)]
+    pub exit_time: Option<f32>,
+    /// Duration of the cross-fade, in seconds
+    #[serde(default)]
+    pub duration: f32,
+}
+
+impl AnimatorTransition {
+    /// Create a new transition from the state `from` to the state `to`
+    pub fn new<F, S>(from: F, to: S) -> Self
+    where
+        F: Into<String>,
+        S: Into<String>,
+    {
+        AnimatorTransition {
+            from: Some(from.into()),
+            to: to.into(),
+            conditions: Vec::default(),
+            exit_time: None,
+            duration: 0.,
+        }
+    }
+
+    /// Create a new transition from any other state to the state `to`
+    pub fn from_any<S: Into<String>>(to: S) -> Self {
+        AnimatorTransition {
+            from: None,
+            ..AnimatorTransition::new("", to)
+        }
+    }
+
+    /// Add a condition to the transition
+    pub fn with_condition(mut self, condition: AnimatorCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Only fire once the given fraction of the animation of the left state has played
+    pub fn with_exit_time(mut self, exit_time: f32) -> Self {
+        self.exit_time = Some(exit_time);
+        self
+    }
+
+    /// Set the duration of the cross-fade, in seconds
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+}
+
+/// Declarative state machine driving the `AnimationControlSet` of an entity, through its
+/// `Animator` component.
+///
+/// The first state is entered when the animator starts. Transitions are tested in order, the
+/// first one whose conditions are met fires.
+///
+/// ### Type parameters:
+///
+/// - `I`: id of the animations in the `AnimationSet` of the animated entities
+#[derive(Derivative, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derivative(Default(bound = ""))]
+pub struct AnimatorGraph<I> {
+    /// The states, the first one is the entry state
+    pub states: Vec<AnimatorState<I>>,
+    /// The transitions between the states
+    #[serde(default)]
+    pub transitions: Vec<AnimatorTransition>,
+    /// The parameters and their initial values
+    #[serde(default)]
+    pub parameters: Vec<(String, AnimatorParameter)>,
+}
+
+impl<I> AnimatorGraph<I> {
+    /// Create an empty graph
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a state, the first one added is the entry state
+    pub fn with_state(mut self, state: AnimatorState<I>) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    /// Add a transition
+    pub fn with_transition(mut self, transition: AnimatorTransition) -> Self {
+        self.transitions.push(transition);
+        self
+    }
+
+    /// Add a parameter with its initial value
+    pub fn with_parameter<S: Into<String>>(mut self, name: S, value: AnimatorParameter) -> Self {
+        self.parameters.push((name.into(), value));
+        self
+    }
+
+    /// Find the index of the state with the given name
+    pub fn state_index(&self, name: &str) -> Option<usize> {
+        self.states.iter().position(|state| state.name == name)
+    }
+
+    /// Find the first transition leaving the state named `state` that can fire, `progress` being
+    /// the fraction of the animation of the state played so far, if known
+    pub(crate) fn find_transition(
+        &self,
+        state: &str,
+        progress: Option<f32>,
+        parameters: &FnvHashMap<String, AnimatorParameter>,
+    ) -> Option<&AnimatorTransition> {
+        self.transitions.iter().find(|transition| {
+            transition.from.as_ref().map_or(true, |from| from == state)
+                && transition.to != state
+                && transition
+                    .exit_time
+                    .map_or(true, |exit_time| progress.map_or(false, |p| p >= exit_time))
+                && transition
+                    .conditions
+                    .iter()
+                    .all(|condition| condition.check(parameters))
+        })
+    }
+}
+
+impl<I> Asset for AnimatorGraph<I>
+where
+    I: Send + Sync + 'static,
+{
+    const NAME: &'static str = "animation::AnimatorGraph";
+    type Data = Self;
+    type HandleStorage = VecStorage<Handle<Self>>;
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct AnimatorFade<I> {
+    pub from: I,
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+/// Runs an `AnimatorGraph` on an entity, driven by the `AnimatorSystem`.
+///
+/// The animator owns the ids of the animations of its states in the `AnimationControlSet` of the
+/// entity, they should not be started or aborted by hand.
+///
+/// ### Type parameters:
+///
+/// - `I`: id of the animations in the `AnimationSet` of the entity
+/// - `T`: the component type that the animations should be applied to
+#[derive(Derivative)]
+#[derivative(Debug(bound = "I: Debug"), Clone(bound = "I: Clone"))]
+pub struct Animator<I, T> {
+    /// The graph run by the animator
+    pub graph: Handle<AnimatorGraph<I>>,
+    pub(crate) parameters: FnvHashMap<String, AnimatorParameter>,
+    pub(crate) current: Option<usize>,
+    pub(crate) state_time: f32,
+    pub(crate) fade: Option<AnimatorFade<I>>,
+    m: marker::PhantomData<T>,
+}
+
+impl<I, T> Animator<I, T> {
+    /// Create an animator running the given graph
+    pub fn new(graph: Handle<AnimatorGraph<I>>) -> Self {
+        Animator {
+            graph,
+            parameters: FnvHashMap::default(),
+            current: None,
+            state_time: 0.,
+            fade: None,
+            m: marker::PhantomData,
+        }
+    }
+
+    /// Set a parameter
+    pub fn set_parameter<S: Into<String>>(&mut self, name: S, value: AnimatorParameter) {
+        self.parameters.insert(name.into(), value);
+    }
+
+    /// Set a `Bool` parameter
+    pub fn set_bool<S: Into<String>>(&mut self, name: S, value: bool) {
+        self.set_parameter(name, AnimatorParameter::Bool(value));
+    }
+
+    /// Set a `Float` parameter
+    pub fn set_float<S: Into<String>>(&mut self, name: S, value: f32) {
+        self.set_parameter(name, AnimatorParameter::Float(value));
+    }
+
+    /// Set an `Int` parameter
+    pub fn set_int<S: Into<String>>(&mut self, name: S, value: i32) {
+        self.set_parameter(name, AnimatorParameter::Int(value));
+    }
+
+    /// Set a `Trigger` parameter, it stays set until a transition testing it fires
+    pub fn set_trigger<S: Into<String>>(&mut self, name: S) {
+        self.set_parameter(name, AnimatorParameter::Trigger(true));
+    }
+
+    /// Reset a `Trigger` parameter
+    pub fn reset_trigger<S: Into<String>>(&mut self, name: S) {
+        self.set_parameter(name, AnimatorParameter::Trigger(false));
+    }
+
+    /// Get a parameter
+    pub fn parameter(&self, name: &str) -> Option<&AnimatorParameter> {
+        self.parameters.get(name)
+    }
+
+    /// Index of the current state in the graph, `None` until the graph is loaded and the entry
+    /// state started
+    pub fn current_state(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Time spent in the current state, in seconds scaled by the speed of the state
+    pub fn state_time(&self) -> f32 {
+        self.state_time
+    }
+
+    /// Is the animator cross-fading between two states
+    pub fn in_transition(&self) -> bool {
+        self.fade.is_some()
+    }
+}
+
+impl<I, T> Component for Animator<I, T>
+where
+    I: Send + Sync + 'static,
+    T: AnimationSampling,
+{
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> FnvHashMap<String, AnimatorParameter> {
+        vec![
+            ("grounded", AnimatorParameter::Bool(true)),
+            ("jump", AnimatorParameter::Trigger(false)),
+            ("speed", AnimatorParameter::Float(2.5)),
+            ("weapon", AnimatorParameter::Int(3)),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+    }
+
+    #[test]
+    fn conditions() {
+        use self::AnimatorCondition::*;
+
+        let parameters = parameters();
+        let check = |condition: AnimatorCondition| condition.check(&parameters);
+        assert!(check(If("grounded".into())));
+        assert!(!check(IfNot("grounded".into())));
+        assert!(!check(If("jump".into())));
+        assert!(check(IfNot("jump".into())));
+        assert!(check(Greater("speed".into(), 2.)));
+        assert!(!check(Less("speed".into(), 2.)));
+        assert!(check(Greater("weapon".into(), 2.)));
+        assert!(check(Equals("weapon".into(), 3)));
+        assert!(check(NotEquals("weapon".into(), 4)));
+        // Missing parameters and mismatched types are never met
+        assert!(!check(If("missing".into())));
+        assert!(!check(IfNot("missing".into())));
+        assert!(!check(If("speed".into())));
+        assert!(!check(Greater("grounded".into(), 0.)));
+        assert!(!check(Equals("speed".into(), 2)));
+    }
+
+    #[test]
+    fn transition_selection() {
+        let graph = AnimatorGraph::<u32>::new()
+            .with_state(AnimatorState::new("idle", 0))
+            .with_state(AnimatorState::new("run", 1))
+            .with_state(AnimatorState::new("attack", 2))
+            .with_transition(
+                AnimatorTransition::new("idle", "run")
+                    .with_condition(AnimatorCondition::Greater("speed".into(), 3.)),
+            )
+            .with_transition(AnimatorTransition::new("attack", "idle").with_exit_time(1.))
+            .with_transition(
+                AnimatorTransition::from_any("attack")
+                    .with_condition(AnimatorCondition::If("grounded".into())),
+            )
+            .with_transition(AnimatorTransition::from_any("run"));
+        let mut parameters = parameters();
+        let to = |transition: Option<&AnimatorTransition>| transition.map(|t| t.to.clone());
+
+        // The first transition whose conditions are met fires
+        assert_eq!(
+            to(graph.find_transition("idle", None, &parameters)),
+            Some("attack".into())
+        );
+        parameters.insert("speed".into(), AnimatorParameter::Float(4.));
+        assert_eq!(
+            to(graph.find_transition("idle", None, &parameters)),
+            Some("run".into())
+        );
+
+        // Transitions don't re-enter the current state, and wait for their exit time
+        parameters.insert("grounded".into(), AnimatorParameter::Bool(false));
+        assert_eq!(to(graph.find_transition("run", None, &parameters)), None);
+        assert_eq!(
+            to(graph.find_transition("attack", Some(0.5), &parameters)),
+            Some("run".into())
+        );
+        let graph = AnimatorGraph {
+            transitions: graph.transitions[1..2].to_vec(),
+            ..graph
+        };
+        assert_eq!(
+            to(graph.find_transition("attack", Some(0.5), &parameters)),
+            None
+        );
+        assert_eq!(to(graph.find_transition("attack", None, &parameters)), None);
+        assert_eq!(
+            to(graph.find_transition("attack", Some(1.), &parameters)),
+            Some("idle".into())
+        );
+    }
+}
@@ -0,0 +1,698 @@
+use std::{
+    fmt::{Formatter, Result as FmtResult},
+    hash::Hash,
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use derivative::Derivative;
+use log::warn;
+use minterpolate::InterpolationFunction;
+use serde::{
+    de::{DeserializeOwned, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+
+use amethyst_assets::{
+    Format, FormatValue, Handle, Prefab, PrefabData, ProgressCounter, Reload, SingleFile, Source,
+};
+use amethyst_core::ecs::prelude::{Component, DenseVecStorage, Entity, WriteStorage};
+use amethyst_derive::PrefabData;
+use amethyst_error::Error;
+use amethyst_rendy::{
+    formats::texture::{ImageFormat, TexturePrefab},
+    sprite::{
+        prefab::{SpriteRenderPrefab, SpriteScenePrefab, SpriteSheetPrefab, SpriteSheetReference},
+        SpriteList, SpritePosition, SpriteRender, SpriteSheet, Sprites,
+    },
+    types::Texture,
+};
+
+use crate::{
+    prefab::{AnimationPrefab, AnimationSetPrefab},
+    resources::Sampler,
+    sprite::{SpriteRenderChannel, SpriteRenderPrimitive},
+};
+
+/// Rectangle in pixels, from the top left corner of the image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AsepriteRect {
+    /// Left side
+    pub x: i32,
+    /// Top side
+    pub y: i32,
+    /// Width
+    pub w: u32,
+    /// Height
+    pub h: u32,
+}
+
+/// Size in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AsepriteSize {
+    /// Width
+    pub w: u32,
+    /// Height
+    pub h: u32,
+}
+
+/// Point in pixels, from the top left corner of a slice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AsepritePoint {
+    /// Horizontal position
+    pub x: i32,
+    /// Vertical position
+    pub y: i32,
+}
+
+/// A frame of an Aseprite sprite sheet export
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AsepriteFrame {
+    /// Position of the frame in the image
+    pub frame: AsepriteRect,
+    /// Is the frame trimmed of its transparent borders
+    #[serde(default)]
+    pub trimmed: bool,
+    /// Position of the trimmed frame in the untrimmed frame
+    pub sprite_source_size: AsepriteRect,
+    /// Size of the untrimmed frame
+    pub source_size: AsepriteSize,
+    /// Duration of the frame, in milliseconds
+    pub duration: u32,
+}
+
+/// Direction a tag is played in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AsepriteDirection {
+    /// From the first frame to the last frame
+    #[serde(rename = "forward")]
+    Forward,
+    /// From the last frame to the first frame
+    #[serde(rename = "reverse")]
+    Reverse,
+    /// From the first frame to the last frame and back
+    #[serde(rename = "pingpong")]
+    PingPong,
+    /// From the last frame to the first frame and back
+    #[serde(rename = "pingpong_reverse")]
+    PingPongReverse,
+}
+
+impl Default for AsepriteDirection {
+    fn default() -> Self {
+        AsepriteDirection::Forward
+    }
+}
+
+/// A named range of frames, imported as an animation
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AsepriteTag {
+    /// Name of the tag
+    pub name: String,
+    /// Index of the first frame
+    pub from: usize,
+    /// Index of the last frame, included
+    pub to: usize,
+    /// Direction the frames are played in
+    #[serde(default)]
+    pub direction: AsepriteDirection,
+}
+
+impl AsepriteTag {
+    /// Indices of the frames in the order they are played
+    pub fn frames(&self) -> Vec<usize> {
+        let (from, to) = (self.from.min(self.to), self.from.max(self.to));
+        match self.direction {
+            AsepriteDirection::Forward => (from..=to).collect(),
+            AsepriteDirection::Reverse => (from..=to).rev().collect(),
+            AsepriteDirection::PingPong => (from..=to).chain((from + 1..to).rev()).collect(),
+            AsepriteDirection::PingPongReverse => (from..=to).rev().chain(from + 1..to).collect(),
+        }
+    }
+}
+
+/// The bounds of a slice from a given frame
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AsepriteSliceKey {
+    /// Index of the first frame using these bounds
+    pub frame: usize,
+    /// Bounds of the slice, in the untrimmed frame
+    pub bounds: AsepriteRect,
+    /// Center of a 9-patch slice, relative to the bounds
+    #[serde(default)]
+    pub center: Option<AsepriteRect>,
+    /// Pivot of the slice, relative to the bounds
+    #[serde(default)]
+    pub pivot: Option<AsepritePoint>,
+}
+
+/// A named region of the frames, like a hitbox or an attachment point
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AsepriteSlice {
+    /// Name of the slice
+    pub name: String,
+    /// Bounds of the slice, each key applying from its frame until the next key
+    pub keys: Vec<AsepriteSliceKey>,
+}
+
+impl AsepriteSlice {
+    /// Bounds of the slice on the given frame
+    pub fn key(&self, frame: usize) -> Option<&AsepriteSliceKey> {
+        self.keys.iter().filter(|key| key.frame <= frame).last()
+    }
+}
+
+/// The slices of an Aseprite export, added by the `AsepriteFormat` to the entity rendering the
+/// sprite sheet, to look up hitboxes or attachment points on the current frame.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+pub struct AsepriteSlices {
+    /// The slices
+    pub slices: Vec<AsepriteSlice>,
+}
+
+impl AsepriteSlices {
+    /// Get a slice by name
+    pub fn get(&self, name: &str) -> Option<&AsepriteSlice> {
+        self.slices.iter().find(|slice| slice.name == name)
+    }
+
+    /// Bounds of the slice with the given name on the given frame
+    pub fn key(&self, name: &str, frame: usize) -> Option<&AsepriteSliceKey> {
+        self.get(name).and_then(|slice| slice.key(frame))
+    }
+}
+
+impl Component for AsepriteSlices {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Metadata of an Aseprite sprite sheet export
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AsepriteMeta {
+    /// Path of the image, relative to the exported JSON file
+    #[serde(default)]
+    pub image: Option<String>,
+    /// Size of the image
+    pub size: AsepriteSize,
+    /// Tags of the animation
+    #[serde(default)]
+    pub frame_tags: Vec<AsepriteTag>,
+    /// Slices of the sprite
+    #[serde(default)]
+    pub slices: Vec<AsepriteSlice>,
+}
+
+/// A sprite sheet exported by Aseprite as JSON, with either the hash or array frame layout.
+///
+/// Each frame becomes a sprite of the `SpriteSheet`, in the same order, and each tag becomes an
+/// `Animation` of the `SpriteRender` keyed by the tag name.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct AsepriteExport {
+    /// The frames, in order
+    #[serde(deserialize_with = "deserialize_frames")]
+    pub frames: Vec<AsepriteFrame>,
+    /// The metadata
+    pub meta: AsepriteMeta,
+}
+
+impl AsepriteExport {
+    /// Parse an export from JSON
+    pub fn from_json(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    /// Positions of the frames in the image, trimmed frames being offset so they stay aligned
+    /// with the untrimmed frames.
+    pub fn sprite_list(&self) -> SpriteList {
+        SpriteList {
+            texture_width: self.meta.size.w,
+            texture_height: self.meta.size.h,
+            sprites: self
+                .frames
+                .iter()
+                .map(|frame| {
+                    let source = &frame.sprite_source_size;
+                    let center_x = source.x as f32 + source.w as f32 / 2.;
+                    let center_y = source.y as f32 + source.h as f32 / 2.;
+                    SpritePosition {
+                        x: frame.frame.x as u32,
+                        y: frame.frame.y as u32,
+                        width: frame.frame.w,
+                        height: frame.frame.h,
+                        offsets: Some([
+                            frame.source_size.w as f32 / 2. - center_x,
+                            center_y - frame.source_size.h as f32 / 2.,
+                        ]),
+                        flip_horizontal: false,
+                        flip_vertical: false,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Create the `SpriteSheet` of the frames, on the given texture
+    pub fn sprite_sheet(&self, texture: Handle<Texture>) -> SpriteSheet {
+        SpriteSheet {
+            texture,
+            sprites: self.sprite_list().build_sprites(),
+        }
+    }
+
+    /// Create a sampler playing the given frames for their durations
+    pub fn sampler(&self, frames: &[usize]) -> Sampler<SpriteRenderPrimitive> {
+        let mut input = Vec::with_capacity(frames.len() + 1);
+        let mut output = Vec::with_capacity(frames.len() + 1);
+        let mut time = 0.;
+        for &index in frames {
+            input.push(time);
+            output.push(SpriteRenderPrimitive::SpriteIndex(index));
+            time += self
+                .frames
+                .get(index)
+                .map_or(0., |frame| frame.duration as f32 / 1000.);
+        }
+        // Hold the last frame for its duration
+        if let Some(&last) = frames.last() {
+            input.push(time);
+            output.push(SpriteRenderPrimitive::SpriteIndex(last));
+        }
+        Sampler {
+            input,
+            output,
+            function: InterpolationFunction::Step,
+        }
+    }
+
+    /// Create the animations of the tags, keyed by the tag names deserialized as `I`, for example
+    /// the variants of an enum or `String`s. Tags whose name isn't a valid id are skipped.
+    pub fn animation_set<I>(&self) -> AnimationSetPrefab<I, SpriteRender>
+    where
+        I: DeserializeOwned,
+    {
+        let animations = self
+            .meta
+            .frame_tags
+            .iter()
+            .filter_map(|tag| {
+                if tag.from.max(tag.to) >= self.frames.len() {
+                    warn!("Ignoring Aseprite tag {} with missing frames", tag.name);
+                    return None;
+                }
+                let id = match serde_json::from_value(tag.name.clone().into()) {
+                    Ok(id) => id,
+                    Err(err) => {
+                        warn!("Ignoring Aseprite tag {}: {}", tag.name, err);
+                        return None;
+                    }
+                };
+                let mut animation = AnimationPrefab::default();
+                animation.samplers = vec![(
+                    0,
+                    SpriteRenderChannel::SpriteIndex,
+                    self.sampler(&tag.frames()),
+                )];
+                Some((id, animation))
+            })
+            .collect();
+        AnimationSetPrefab { animations }
+    }
+
+    /// Get a slice by name
+    pub fn slice(&self, name: &str) -> Option<&AsepriteSlice> {
+        self.meta.slices.iter().find(|slice| slice.name == name)
+    }
+}
+
+fn deserialize_frames<'de, D>(deserializer: D) -> Result<Vec<AsepriteFrame>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct FramesVisitor;
+
+    impl<'de> Visitor<'de> for FramesVisitor {
+        type Value = Vec<AsepriteFrame>;
+
+        fn expecting(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+            write!(formatter, "an array or a map of frames")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut frames = Vec::new();
+            while let Some(frame) = seq.next_element()? {
+                frames.push(frame);
+            }
+            Ok(frames)
+        }
+
+        // Frames are keyed by file name, in order
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut frames = Vec::new();
+            while let Some((_, frame)) = map.next_entry::<String, AsepriteFrame>()? {
+                frames.push(frame);
+            }
+            Ok(frames)
+        }
+    }
+
+    deserializer.deserialize_any(FramesVisitor)
+}
+
+/// `PrefabData` of an entity rendering a sprite sheet imported from Aseprite, with its
+/// animations. Created by the `AsepriteFormat`.
+///
+/// ### Type parameters
+///
+/// - `I`: Id type of the animations, deserialized from the tag names
+#[derive(Clone, Debug, PrefabData)]
+pub struct AsepritePrefab<I>
+where
+    I: Clone + Hash + Eq + Send + Sync + 'static,
+{
+    /// The sprite sheet and the `SpriteRender` of the entity
+    pub sprite_scene: SpriteScenePrefab,
+    /// The animations of the tags
+    pub animation_set: AnimationSetPrefab<I, SpriteRender>,
+    /// The slices, if the export has any
+    pub slices: Option<AsepriteSlices>,
+}
+
+/// Format loading an Aseprite JSON export as a `Prefab` of a single entity, with a `SpriteRender`
+/// on the first frame, an `AnimationSet` of the tags and the `AsepriteSlices`. The image is loaded
+/// from the path in the metadata, relative to the JSON file.
+///
+/// ```rust,ignore
+/// let prefab = world.exec(|loader: PrefabLoader<'_, AsepritePrefab<AnimationId>>| {
+///     loader.load("sprites/bat.json", AsepriteFormat::default(), ())
+/// });
+/// ```
+///
+/// ### Type parameters
+///
+/// - `I`: Id type of the animations, deserialized from the tag names
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), Default(bound = ""))]
+pub struct AsepriteFormat<I> {
+    /// Format of the image
+    pub image: ImageFormat,
+    m: PhantomData<I>,
+}
+
+impl<I> AsepriteFormat<I> {
+    /// Create a format loading the image with the given format, for example with nearest
+    /// filtering for pixel art
+    pub fn new(image: ImageFormat) -> Self {
+        AsepriteFormat {
+            image,
+            m: PhantomData,
+        }
+    }
+
+    fn import_prefab(&self, name: &str, bytes: &[u8]) -> Result<Prefab<AsepritePrefab<I>>, Error>
+    where
+        I: Clone + Hash + Eq + Send + Sync + DeserializeOwned + 'static,
+    {
+        let export = AsepriteExport::from_json(bytes)?;
+        let image =
+            export.meta.image.as_ref().ok_or_else(|| {
+                Error::from_string(format!("Aseprite export {} has no image", name))
+            })?;
+        let image = match name.rfind('/') {
+            Some(end) => format!("{}/{}", &name[..end], image),
+            None => image.clone(),
+        };
+        let sprite_scene = SpriteScenePrefab {
+            sheet: Some(SpriteSheetPrefab::Sheet {
+                texture: TexturePrefab::File(image, Box::new(self.image.clone())),
+                sprites: vec![Sprites::List(export.sprite_list())],
+                name: Some(name.to_string()),
+            }),
+            render: Some(SpriteRenderPrefab::new(
+                Some(SpriteSheetReference::Name(name.to_string())),
+                0,
+            )),
+            transform: None,
+        };
+        let slices = if export.meta.slices.is_empty() {
+            None
+        } else {
+            Some(AsepriteSlices {
+                slices: export.meta.slices.clone(),
+            })
+        };
+        Ok(Prefab::new_main(AsepritePrefab {
+            sprite_scene,
+            animation_set: export.animation_set(),
+            slices,
+        }))
+    }
+}
+
+impl<I> Format<Prefab<AsepritePrefab<I>>> for AsepriteFormat<I>
+where
+    I: Clone + Hash + Eq + Send + Sync + DeserializeOwned + 'static,
+{
+    fn name(&self) -> &'static str {
+        "ASEPRITE"
+    }
+
+    fn import(
+        &self,
+        name: String,
+        source: Arc<dyn Source>,
+        create_reload: Option<Box<dyn Format<Prefab<AsepritePrefab<I>>>>>,
+    ) -> Result<FormatValue<Prefab<AsepritePrefab<I>>>, Error> {
+        let (bytes, modified) = source.load_with_metadata(&name)?;
+        let data = self.import_prefab(&name, &bytes)?;
+        Ok(FormatValue {
+            data,
+            reload: create_reload.map(|format| {
+                Box::new(SingleFile::new(format, modified, name, source))
+                    as Box<dyn Reload<Prefab<AsepritePrefab<I>>>>
+            }),
+        })
+    }
+}
+
+/// Format loading only the `SpriteSheet` of an Aseprite JSON export, like `SpriteSheetFormat`.
+#[derive(Clone, Debug)]
+pub struct AsepriteSheetFormat(pub Handle<Texture>);
+
+impl Format<SpriteSheet> for AsepriteSheetFormat {
+    fn name(&self) -> &'static str {
+        "ASEPRITE_SHEET"
+    }
+
+    fn import_simple(&self, bytes: Vec<u8>) -> Result<SpriteSheet, Error> {
+        Ok(AsepriteExport::from_json(&bytes)?.sprite_sheet(self.0.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARRAY: &str = r##"{
+        "frames": [
+            {
+                "filename": "bat 0.ase",
+                "frame": { "x": 0, "y": 0, "w": 32, "h": 32 },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 32, "h": 32 },
+                "sourceSize": { "w": 32, "h": 32 },
+                "duration": 100
+            },
+            {
+                "filename": "bat 1.ase",
+                "frame": { "x": 32, "y": 0, "w": 16, "h": 8 },
+                "rotated": false,
+                "trimmed": true,
+                "spriteSourceSize": { "x": 4, "y": 8, "w": 16, "h": 8 },
+                "sourceSize": { "w": 32, "h": 32 },
+                "duration": 200
+            }
+        ],
+        "meta": {
+            "app": "http://www.aseprite.org/",
+            "image": "bat.png",
+            "format": "RGBA8888",
+            "size": { "w": 48, "h": 32 },
+            "scale": "1",
+            "frameTags": [
+                { "name": "fly", "from": 0, "to": 1, "direction": "pingpong_reverse" }
+            ],
+            "slices": [
+                {
+                    "name": "hitbox",
+                    "color": "#0000ffff",
+                    "keys": [
+                        { "frame": 0, "bounds": { "x": 8, "y": 8, "w": 16, "h": 16 } },
+                        {
+                            "frame": 1,
+                            "bounds": { "x": 4, "y": 8, "w": 24, "h": 8 },
+                            "pivot": { "x": 12, "y": 4 }
+                        }
+                    ]
+                }
+            ]
+        }
+    }"##;
+
+    const HASH: &str = r#"{
+        "frames": {
+            "bat 1.ase": {
+                "frame": { "x": 32, "y": 0, "w": 32, "h": 32 },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 32, "h": 32 },
+                "sourceSize": { "w": 32, "h": 32 },
+                "duration": 150
+            },
+            "bat 0.ase": {
+                "frame": { "x": 0, "y": 0, "w": 32, "h": 32 },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 32, "h": 32 },
+                "sourceSize": { "w": 32, "h": 32 },
+                "duration": 50
+            }
+        },
+        "meta": {
+            "image": "bat.png",
+            "size": { "w": 64, "h": 32 }
+        }
+    }"#;
+
+    fn tag(from: usize, to: usize, direction: AsepriteDirection) -> AsepriteTag {
+        AsepriteTag {
+            name: "tag".to_string(),
+            from,
+            to,
+            direction,
+        }
+    }
+
+    fn export(durations: &[u32]) -> AsepriteExport {
+        let rect = AsepriteRect {
+            x: 0,
+            y: 0,
+            w: 1,
+            h: 1,
+        };
+        AsepriteExport {
+            frames: durations
+                .iter()
+                .map(|&duration| AsepriteFrame {
+                    frame: rect,
+                    trimmed: false,
+                    sprite_source_size: rect,
+                    source_size: AsepriteSize { w: 1, h: 1 },
+                    duration,
+                })
+                .collect(),
+            meta: AsepriteMeta {
+                image: None,
+                size: AsepriteSize { w: 1, h: 1 },
+                frame_tags: Vec::new(),
+                slices: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn tag_frames() {
+        use self::AsepriteDirection::*;
+
+        assert_eq!(tag(0, 3, Forward).frames(), vec![0, 1, 2, 3]);
+        assert_eq!(tag(0, 3, Reverse).frames(), vec![3, 2, 1, 0]);
+        assert_eq!(tag(0, 3, PingPong).frames(), vec![0, 1, 2, 3, 2, 1]);
+        assert_eq!(tag(0, 3, PingPongReverse).frames(), vec![3, 2, 1, 0, 1, 2]);
+        assert_eq!(tag(2, 4, PingPongReverse).frames(), vec![4, 3, 2, 3]);
+        for direction in &[Forward, Reverse, PingPong, PingPongReverse] {
+            assert_eq!(tag(2, 2, *direction).frames(), vec![2]);
+        }
+    }
+
+    #[test]
+    fn sampler_durations() {
+        let sampler = export(&[100, 200, 300]).sampler(&[2, 0, 1]);
+        assert_eq!(sampler.input.len(), 4);
+        for (input, expected) in sampler.input.iter().zip(&[0., 0.3, 0.4, 0.6]) {
+            assert!((input - expected).abs() < 1e-6);
+        }
+        assert_eq!(
+            sampler.output,
+            [2, 0, 1, 1]
+                .iter()
+                .map(|&index| SpriteRenderPrimitive::SpriteIndex(index))
+                .collect::<Vec<_>>()
+        );
+        match sampler.function {
+            InterpolationFunction::Step => {}
+            ref function => panic!("Expected a step function, got {:?}", function),
+        }
+    }
+
+    #[test]
+    fn array_layout() {
+        let export = AsepriteExport::from_json(ARRAY.as_bytes()).unwrap();
+        assert_eq!(export.frames.len(), 2);
+        assert_eq!(export.frames[1].duration, 200);
+        assert!(export.frames[1].trimmed);
+        assert_eq!(
+            export.meta.frame_tags[0].direction,
+            AsepriteDirection::PingPongReverse
+        );
+
+        let slices = AsepriteSlices {
+            slices: export.meta.slices.clone(),
+        };
+        assert_eq!(slices.key("hitbox", 0).unwrap().bounds.w, 16);
+        assert_eq!(
+            slices.key("hitbox", 5).unwrap().pivot,
+            Some(AsepritePoint { x: 12, y: 4 })
+        );
+        assert!(slices.key("hurtbox", 0).is_none());
+    }
+
+    #[test]
+    fn hash_layout() {
+        let export = AsepriteExport::from_json(HASH.as_bytes()).unwrap();
+        // In the order of the file, not of the names
+        assert_eq!(
+            export
+                .frames
+                .iter()
+                .map(|frame| frame.duration)
+                .collect::<Vec<_>>(),
+            vec![150, 50]
+        );
+        assert!(export.meta.frame_tags.is_empty());
+        assert!(export.meta.slices.is_empty());
+    }
+
+    #[test]
+    fn sprite_offsets() {
+        let export = AsepriteExport::from_json(ARRAY.as_bytes()).unwrap();
+        let list = export.sprite_list();
+        assert_eq!((list.texture_width, list.texture_height), (48, 32));
+        assert_eq!(list.sprites[0].offsets, Some([0., 0.]));
+        // Trimmed to the 16x8 area at (4, 8), centered at (12, 12) in the 32x32 frame
+        let trimmed = &list.sprites[1];
+        assert_eq!(
+            (trimmed.x, trimmed.y, trimmed.width, trimmed.height),
+            (32, 0, 16, 8)
+        );
+        assert_eq!(trimmed.offsets, Some([4., -4.]));
+    }
+}
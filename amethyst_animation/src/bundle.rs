@@ -2,8 +2,9 @@ use crate::{
     resources::AnimationSampling,
     skinning::VertexSkinningSystemDesc,
     systems::{
-        AnimationControlSystemDesc, AnimationProcessor, SamplerInterpolationSystem,
-        SamplerProcessor,
+        AnimationControlSystemDesc, AnimationEventSystemDesc, AnimationProcessor,
+        AnimatorGraphProcessor, AnimatorSystemDesc, InverseKinematicsSystem, RootMotionSystemDesc,
+        SamplerInterpolationSystem, SamplerProcessor, TweenSystem,
     },
 };
 use amethyst_core::{
@@ -50,6 +51,101 @@ impl<'a, 'b, 'c> SystemBundle<'a, 'b> for VertexSkinningBundle<'c> {
     }
 }
 
+/// Bundle for inverse kinematics
+///
+/// This registers `InverseKinematicsSystem`.
+/// Note that the user must make sure this system runs after the sampling systems of `Transform`
+/// animations and before `TransformSystem`
+#[derive(Default, Debug)]
+pub struct InverseKinematicsBundle<'a> {
+    dep: &'a [&'a str],
+}
+
+impl<'a> InverseKinematicsBundle<'a> {
+    /// Create a new inverse kinematics bundle
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set dependencies for the `InverseKinematicsSystem`
+    pub fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
+        self.dep = dep;
+        self
+    }
+}
+
+impl<'a, 'b, 'c> SystemBundle<'a, 'b> for InverseKinematicsBundle<'c> {
+    fn build(
+        self,
+        _world: &mut World,
+        builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> Result<(), Error> {
+        builder.add(
+            InverseKinematicsSystem::new(),
+            "inverse_kinematics_system",
+            self.dep,
+        );
+        Ok(())
+    }
+}
+
+/// Bundle for root motion
+///
+/// This registers `RootMotionSystem<I>` as `root_motion_system`, running after the
+/// `SamplerInterpolationSystem` of `Transform` animations with the given name.
+/// Note that the user must make sure `TransformSystem` runs after this system
+///
+/// ### Type parameters:
+///
+/// - `I`: identifier type for running animations
+#[derive(Default, Debug)]
+pub struct RootMotionBundle<'a, I> {
+    sampling_name: &'a str,
+    dep: &'a [&'a str],
+    m: marker::PhantomData<I>,
+}
+
+impl<'a, I> RootMotionBundle<'a, I> {
+    /// Create a new root motion bundle
+    ///
+    /// ### Parameters:
+    ///
+    /// - `sampling_name`: name of the `SamplerInterpolationSystem` of `Transform` animations
+    pub fn new(sampling_name: &'a str) -> Self {
+        Self {
+            sampling_name,
+            dep: &[],
+            m: marker::PhantomData,
+        }
+    }
+
+    /// Set additional dependencies for the `RootMotionSystem`
+    pub fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
+        self.dep = dep;
+        self
+    }
+}
+
+impl<'a, 'b, 'c, I> SystemBundle<'a, 'b> for RootMotionBundle<'c, I>
+where
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    fn build(
+        self,
+        world: &mut World,
+        builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> Result<(), Error> {
+        let mut dep = self.dep.to_vec();
+        dep.push(self.sampling_name);
+        builder.add(
+            RootMotionSystemDesc::<I>::default().build(world),
+            "root_motion_system",
+            &dep,
+        );
+        Ok(())
+    }
+}
+
 /// Bundle for only the sampler interpolation.
 ///
 /// Will add `SamplerInterpolationSystem<T>` with the given name.
@@ -101,12 +197,100 @@ where
     }
 }
 
+/// Bundle for tweens
+///
+/// Will add `TweenSystem<T>` with the given name.
+///
+/// ### Type parameters:
+///
+/// - `T`: the component type that tweens should be applied to
+#[derive(Default, Debug)]
+pub struct TweenBundle<'a, T> {
+    name: &'a str,
+    dep: &'a [&'a str],
+    m: marker::PhantomData<T>,
+}
+
+impl<'a, T> TweenBundle<'a, T> {
+    /// Create a new tween bundle
+    ///
+    /// ### Parameters:
+    ///
+    /// - `name`: name of the `TweenSystem`
+    pub fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            dep: &[],
+            m: marker::PhantomData,
+        }
+    }
+
+    /// Set dependencies for the `TweenSystem`
+    pub fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
+        self.dep = dep;
+        self
+    }
+}
+
+impl<'a, 'b, 'c, T> SystemBundle<'a, 'b> for TweenBundle<'c, T>
+where
+    T: AnimationSampling + Component,
+{
+    fn build(
+        self,
+        _world: &mut World,
+        builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> Result<(), Error> {
+        builder.add(TweenSystem::<T>::new(), self.name, self.dep);
+        Ok(())
+    }
+}
+
+/// Bundle for loading `AnimatorGraph`s
+///
+/// Will add `AnimatorGraphProcessor<I>`. Add it once per animation id type, next to the
+/// `AnimationBundle`s of the component types animated by `Animator`s.
+///
+/// ### Type parameters:
+///
+/// - `I`: identifier type of the animations played by the graphs
+#[derive(Default, Debug)]
+pub struct AnimatorBundle<I> {
+    m: marker::PhantomData<I>,
+}
+
+impl<I> AnimatorBundle<I> {
+    /// Create a new animator bundle
+    pub fn new() -> Self {
+        Self {
+            m: marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b, I> SystemBundle<'a, 'b> for AnimatorBundle<I>
+where
+    I: Send + Sync + 'static,
+{
+    fn build(
+        self,
+        _world: &mut World,
+        builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> Result<(), Error> {
+        builder.add(AnimatorGraphProcessor::<I>::new(), "", &[]);
+        Ok(())
+    }
+}
+
 /// Bundle for a complete animation setup including sampler interpolation and animation control.
 ///
 /// This will also add `SamplingBundle`, because it is a dependency of this bundle.
 ///
-/// Will add `AnimationControlSystem<T>` with the given name.
-/// Will also add `AnimationProcessor<T>`.
+/// Will add `AnimationControlSystem<T>` with the given name, `AnimatorSystem<I, T>` running
+/// before it, named after it with an `_animator` suffix, and `AnimationEventSystem<I, T>` running
+/// after the sampling, named after it with an `_events` suffix.
+/// Will also add `AnimationProcessor<T>`. The `AnimatorGraph`s of `Animator`s are loaded by the
+/// `AnimatorBundle`.
 ///
 /// ### Type parameters:
 ///
@@ -137,7 +321,7 @@ impl<'a, I, T> AnimationBundle<'a, I, T> {
         }
     }
 
-    /// Set dependencies for the `AnimationControlSystem`
+    /// Set dependencies for the `AnimationControlSystem` and `AnimatorSystem`
     pub fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
         self.dep = dep;
         self
@@ -155,13 +339,27 @@ where
         builder: &mut DispatcherBuilder<'a, 'b>,
     ) -> Result<(), Error> {
         builder.add(AnimationProcessor::<T>::new(), "", &[]);
+        let animator_name = format!("{}_animator", self.animation_name);
+        builder.add(
+            AnimatorSystemDesc::<I, T>::default().build(world),
+            &animator_name,
+            self.dep,
+        );
+        let mut dep = self.dep.to_vec();
+        dep.push(&animator_name);
         builder.add(
             AnimationControlSystemDesc::<I, T>::default().build(world),
             self.animation_name,
-            self.dep,
+            &dep,
         );
         SamplingBundle::<T>::new(self.sampling_name)
             .with_dep(&[self.animation_name])
-            .build(world, builder)
+            .build(world, builder)?;
+        builder.add(
+            AnimationEventSystemDesc::<I, T>::default().build(world),
+            &format!("{}_events", self.animation_name),
+            &[self.sampling_name],
+        );
+        Ok(())
     }
 }
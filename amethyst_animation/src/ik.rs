@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+use amethyst_core::{
+    ecs::prelude::{Component, DenseVecStorage, Entity},
+    math::Vector3,
+};
+
+/// Position an `IkChain` reaches for, or bends toward
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IkTarget {
+    /// A position in world space
+    Position(Vector3<f32>),
+    /// The position of an entity with a `Transform`
+    Entity(Entity),
+}
+
+/// Algorithm solving an `IkChain`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum IkSolver {
+    /// Analytic solver for chains of exactly three joints, like a hip, knee and ankle
+    TwoBone,
+    /// Forward And Backward Reaching Inverse Kinematics, for chains of any length
+    Fabrik {
+        /// Maximum number of iterations per frame
+        iterations: usize,
+        /// Distance from the target at which the chain is solved
+        tolerance: f32,
+    },
+    /// Cyclic Coordinate Descent, for chains of any length
+    Ccd {
+        /// Maximum number of iterations per frame
+        iterations: usize,
+        /// Distance from the target at which the chain is solved
+        tolerance: f32,
+    },
+}
+
+/// A joint of an `IkChain`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IkJoint {
+    /// The joint entity, with a `Transform`
+    pub entity: Entity,
+    /// Maximum angle the solver may rotate the joint away from its animated rotation, in radians
+    pub max_rotation: Option<f32>,
+}
+
+impl IkJoint {
+    /// Create an unconstrained joint
+    pub fn new(entity: Entity) -> Self {
+        IkJoint {
+            entity,
+            max_rotation: None,
+        }
+    }
+
+    /// Limit the angle the solver may rotate the joint away from its animated rotation
+    pub fn with_max_rotation(mut self, max_rotation: f32) -> Self {
+        self.max_rotation = Some(max_rotation);
+        self
+    }
+}
+
+/// Chain of joints whose `Transform`s are rotated by the `InverseKinematicsSystem` so the last
+/// joint reaches a target, for example to plant feet on uneven terrain or reach objects by hand.
+///
+/// Each joint must be a descendant of the previous one, the last joint being the end effector.
+/// The chain is solved on top of the animated pose, so the `InverseKinematicsSystem` must run after
+/// the sampling systems and before the `TransformSystem`. Joints that aren't resampled between two
+/// frames are solved from the same animated pose again, not from the solved one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IkChain {
+    /// The joints, from the root of the chain to the end effector
+    pub joints: Vec<IkJoint>,
+    /// Algorithm solving the chain
+    pub solver: IkSolver,
+    /// Position the end effector reaches for
+    pub target: IkTarget,
+    /// Position the joints between the root and the end effector bend toward, for example in front
+    /// of the knee
+    pub pole: Option<IkTarget>,
+    /// Blend between the animated pose at 0.0 and the solved pose at 1.0
+    pub weight: f32,
+}
+
+impl IkChain {
+    /// Create a chain of the given joint entities, from the root to the end effector
+    pub fn new<J>(joints: J, solver: IkSolver, target: IkTarget) -> Self
+    where
+        J: IntoIterator<Item = Entity>,
+    {
+        IkChain {
+            joints: joints.into_iter().map(IkJoint::new).collect(),
+            solver,
+            target,
+            pole: None,
+            weight: 1.0,
+        }
+    }
+
+    /// Create a chain solved by `IkSolver::TwoBone`, like a hip, knee and ankle
+    pub fn two_bone(root: Entity, middle: Entity, end: Entity, target: IkTarget) -> Self {
+        Self::new(vec![root, middle, end], IkSolver::TwoBone, target)
+    }
+
+    /// Set the pole the joints bend toward
+    pub fn with_pole(mut self, pole: IkTarget) -> Self {
+        self.pole = Some(pole);
+        self
+    }
+
+    /// Set the blend weight of the solved pose
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Limit the angle the solver may rotate the joint at the given index
+    pub fn with_max_rotation(mut self, index: usize, max_rotation: f32) -> Self {
+        if let Some(joint) = self.joints.get_mut(index) {
+            joint.max_rotation = Some(max_rotation);
+        }
+        self
+    }
+}
+
+impl Component for IkChain {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -57,31 +57,60 @@
 pub use minterpolate::{InterpolationFunction, InterpolationPrimitive};
 
 pub use self::{
-    bundle::{AnimationBundle, SamplingBundle, VertexSkinningBundle},
+    animator::{
+        Animator, AnimatorCondition, AnimatorGraph, AnimatorParameter, AnimatorState,
+        AnimatorTransition,
+    },
+    aseprite::{
+        AsepriteDirection, AsepriteExport, AsepriteFormat, AsepriteFrame, AsepriteMeta,
+        AsepritePoint, AsepritePrefab, AsepriteRect, AsepriteSheetFormat, AsepriteSize,
+        AsepriteSlice, AsepriteSliceKey, AsepriteSlices, AsepriteTag,
+    },
+    bundle::{
+        AnimationBundle, AnimatorBundle, InverseKinematicsBundle, RootMotionBundle, SamplingBundle,
+        TweenBundle, VertexSkinningBundle,
+    },
+    ik::{IkChain, IkJoint, IkSolver, IkTarget},
     material::{MaterialChannel, MaterialPrimitive},
-    prefab::{AnimatablePrefab, AnimationHierarchyPrefab, AnimationPrefab, AnimationSetPrefab},
+    prefab::{
+        AnimatablePrefab, AnimationHierarchyPrefab, AnimationPrefab, AnimationSetPrefab,
+        AnimatorGraphPrefab, AnimatorPrefab,
+    },
     resources::{
-        Animation, AnimationCommand, AnimationControl, AnimationControlSet, AnimationHierarchy,
-        AnimationSampling, AnimationSet, ApplyData, BlendMethod, ControlState, DeferStartRelation,
-        EndControl, RestState, Sampler, SamplerControl, SamplerControlSet, StepDirection,
+        Animation, AnimationCommand, AnimationControl, AnimationControlSet, AnimationEvent,
+        AnimationHierarchy, AnimationMarker, AnimationMask, AnimationSampling, AnimationSet,
+        ApplyData, BlendFade, BlendMethod, BlendMode, ControlState, DeferStartRelation, EndControl,
+        RestState, Sampler, SamplerControl, SamplerControlSet, StepDirection,
+    },
+    retarget::{
+        RetargetJoint, RetargetMap, RetargetSkeleton, RetargetTranslation,
+        RetargetedAnimationSetPrefab,
     },
     skinning::{Joint, JointPrefab, Skin, SkinPrefab, SkinnablePrefab, VertexSkinningSystem},
     sprite::{SpriteRenderChannel, SpriteRenderPrimitive},
     systems::{
-        AnimationControlSystem, AnimationProcessor, SamplerInterpolationSystem, SamplerProcessor,
+        AnimationControlSystem, AnimationEventSystem, AnimationProcessor, AnimatorGraphProcessor,
+        AnimatorSystem, InverseKinematicsSystem, RootMotionSystem, RootMotionSystemDesc,
+        SamplerInterpolationSystem, SamplerProcessor,
     },
-    transform::TransformChannel,
+    transform::{RootMotionDelta, TransformChannel},
+    tween::{Easing, Tween, TweenEvent, TweenRepeat},
     ui_transform::UiTransformChannel,
     util::{get_animation_set, SamplerPrimitive},
 };
 
+mod animator;
+mod aseprite;
 mod bundle;
+mod ik;
 mod material;
 mod prefab;
 mod resources;
+mod retarget;
 mod skinning;
 mod sprite;
 mod systems;
 mod transform;
+mod tween;
 mod ui_transform;
 mod util;
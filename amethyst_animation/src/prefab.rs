@@ -6,14 +6,17 @@ use std::{
 };
 
 use derivative::Derivative;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-use amethyst_assets::{AssetStorage, Handle, Loader, PrefabData, ProgressCounter};
+use amethyst_assets::{AssetStorage, Handle, Loader, PrefabData, ProgressCounter, RonFormat};
 use amethyst_core::ecs::prelude::{Entity, Read, ReadExpect, WriteStorage};
 use amethyst_derive::PrefabData;
 use amethyst_error::Error;
 
-use crate::{Animation, AnimationHierarchy, AnimationSampling, AnimationSet, RestState, Sampler};
+use crate::{
+    Animation, AnimationHierarchy, AnimationMarker, AnimationSampling, AnimationSet, Animator,
+    AnimatorGraph, RestState, Sampler,
+};
 
 /// `PrefabData` for loading a single `Animation`
 ///
@@ -34,6 +37,9 @@ where
 {
     /// All samplers in the `Animation`
     pub samplers: Vec<(usize, T::Channel, Sampler<T::Primitive>)>,
+    /// All event markers in the `Animation`
+    #[serde(default)]
+    pub events: Vec<AnimationMarker>,
     #[serde(skip, default = "default_handle")]
     handle: Option<Handle<Animation<T>>>,
 }
@@ -52,6 +58,7 @@ where
     fn default() -> Self {
         AnimationPrefab {
             samplers: Vec::default(),
+            events: Vec::default(),
             handle: None,
         }
     }
@@ -113,6 +120,7 @@ where
                     )
                 })
                 .collect(),
+            events: self.events.clone(),
         };
         self.handle = Some(loader.load_from_data(animation, progress, animation_storage));
         Ok(true)
@@ -229,6 +237,92 @@ where
     }
 }
 
+/// Where the `AnimatorGraph` of an `AnimatorPrefab` comes from
+///
+/// ### Type parameters
+///
+/// - `I`: Id type of `Animation`s in `AnimationSet`s
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(bound(serialize = "I: Serialize", deserialize = "I: Deserialize<'de>"))]
+pub enum AnimatorGraphPrefab<I> {
+    /// A graph written in the prefab
+    Graph(AnimatorGraph<I>),
+    /// A graph loaded from a RON file, which several prefabs can use
+    File(String),
+    /// From an existing handle
+    #[serde(skip)]
+    Handle(Handle<AnimatorGraph<I>>),
+}
+
+impl<I> Default for AnimatorGraphPrefab<I> {
+    fn default() -> Self {
+        AnimatorGraphPrefab::Graph(AnimatorGraph::default())
+    }
+}
+
+/// `PrefabData` for loading an `AnimatorGraph` and placing an `Animator` running it on the
+/// `Entity`.
+///
+/// ### Type parameters
+///
+/// - `I`: Id type of `Animation`s in `AnimationSet`s
+/// - `T`: The animatable `Component`
+#[derive(Derivative, Clone, Debug, Deserialize, Serialize)]
+#[derivative(Default(bound = ""))]
+#[serde(bound(serialize = "I: Serialize", deserialize = "I: Deserialize<'de>"))]
+pub struct AnimatorPrefab<I, T> {
+    /// The graph run by the `Animator`
+    pub graph: AnimatorGraphPrefab<I>,
+    #[serde(skip)]
+    _m: PhantomData<T>,
+}
+
+impl<'a, I, T> PrefabData<'a> for AnimatorPrefab<I, T>
+where
+    T: AnimationSampling,
+    I: Clone + DeserializeOwned + Send + Sync + 'static,
+{
+    type SystemData = (
+        ReadExpect<'a, Loader>,
+        Read<'a, AssetStorage<AnimatorGraph<I>>>,
+        WriteStorage<'a, Animator<I, T>>,
+    );
+    type Result = ();
+
+    fn add_to_entity(
+        &self,
+        entity: Entity,
+        &mut (_, _, ref mut animators): &mut Self::SystemData,
+        _: &[Entity],
+        _: &[Entity],
+    ) -> Result<(), Error> {
+        let handle = match self.graph {
+            AnimatorGraphPrefab::Handle(ref handle) => handle.clone(),
+            _ => return Err(MissingAssetHandle.into()),
+        };
+        animators.insert(entity, Animator::new(handle))?;
+        Ok(())
+    }
+
+    fn load_sub_assets(
+        &mut self,
+        progress: &mut ProgressCounter,
+        &mut (ref loader, ref graph_storage, _): &mut Self::SystemData,
+    ) -> Result<bool, Error> {
+        let handle = match self.graph {
+            AnimatorGraphPrefab::Graph(ref graph) => {
+                loader.load_from_data(graph.clone(), progress, graph_storage)
+            }
+            AnimatorGraphPrefab::File(ref name) => {
+                loader.load(name.as_str(), RonFormat, progress, graph_storage)
+            }
+            AnimatorGraphPrefab::Handle(_) => return Ok(false),
+        };
+        self.graph = AnimatorGraphPrefab::Handle(handle);
+        Ok(true)
+    }
+}
+
 /// `PrefabData` for full animation support
 ///
 /// ### Type parameters
@@ -239,8 +333,8 @@ where
 #[serde(
     default,
     bound(
-        serialize = "T: Serialize, AnimationSetPrefab<I, T>: Serialize",
-        deserialize = "T: Deserialize<'de>, AnimationSetPrefab<I, T>: Deserialize<'de>",
+        serialize = "T: Serialize, I: Serialize, AnimationSetPrefab<I, T>: Serialize",
+        deserialize = "T: Deserialize<'de>, I: Deserialize<'de>, AnimationSetPrefab<I, T>: Deserialize<'de>",
     )
 )]
 #[derivative(Default(bound = ""))]
@@ -248,7 +342,7 @@ pub struct AnimatablePrefab<I, T>
 where
     T: AnimationSampling + Clone,
     T::Primitive: Debug,
-    I: Clone + Hash + Eq + Send + Sync + 'static,
+    I: Clone + Hash + Eq + DeserializeOwned + Send + Sync + 'static,
 {
     /// Place an `AnimationSet` on the `Entity`
     pub animation_set: Option<AnimationSetPrefab<I, T>>,
@@ -256,4 +350,6 @@ where
     pub hierarchy: Option<AnimationHierarchyPrefab<T>>,
     /// Place a `RestState` on the `Entity`
     pub rest_state: Option<RestState<T>>,
+    /// Place an `Animator` on the `Entity`
+    pub animator: Option<AnimatorPrefab<I, T>>,
 }
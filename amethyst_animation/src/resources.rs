@@ -22,6 +22,156 @@ pub enum BlendMethod {
     Linear,
 }
 
+/// How the samplers of an animation are combined with the other samplers on the same channel.
+///
+/// Only used for channels with a `BlendMethod`, on other channels the last added sampler wins.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BlendMode {
+    /// Blended with the other samplers, by blend weight
+    Override,
+    /// The difference between the sample and the sample at the given input value (the reference
+    /// pose, in seconds) is added on top of the blended samplers, scaled by the blend weight. If
+    /// there are no other samplers on the channel, it is added on top of the `RestState` of the
+    /// node instead, as captured in `SamplerControl::after` when the animation started.
+    ///
+    /// Useful for layered animations like breathing or aiming. Rotations are added component-wise,
+    /// which is only accurate for small offsets.
+    Additive(f32),
+}
+
+impl Default for BlendMode {
+    fn default() -> Self {
+        BlendMode::Override
+    }
+}
+
+/// Ramp of the blend weight of a sampler, see `AnimationControlSet::fade`
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlendFade {
+    /// Blend weight at the end of the fade
+    pub target: f32,
+    /// Remaining duration of the fade, in seconds
+    pub duration: f32,
+    /// Abort the sampler once the fade is done
+    pub abort: bool,
+}
+
+impl BlendFade {
+    /// Ramp the blend weight to `target` over `duration` seconds
+    pub fn new(target: f32, duration: f32) -> Self {
+        BlendFade {
+            target,
+            duration,
+            abort: false,
+        }
+    }
+
+    /// Ramp the blend weight to zero over `duration` seconds, then abort
+    pub fn out(duration: f32) -> Self {
+        BlendFade {
+            target: 0.,
+            duration,
+            abort: true,
+        }
+    }
+}
+
+/// Restricts an animation to some of its nodes and channels, for example to let an upper body
+/// animation only override the rotations of the upper body joints.
+///
+/// ### Type parameters:
+///
+/// - `T`: the component type that the animation should be applied to
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""), Default(bound = ""))]
+pub struct AnimationMask<T>
+where
+    T: AnimationSampling,
+{
+    /// The node indices animated, `None` for all of them
+    pub nodes: Option<Vec<usize>>,
+    /// The channels animated, `None` for all of them
+    pub channels: Option<Vec<T::Channel>>,
+}
+
+impl<T> AnimationMask<T>
+where
+    T: AnimationSampling,
+{
+    /// Create a mask letting all nodes and channels through
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only animate the given node indices
+    pub fn with_nodes<N: IntoIterator<Item = usize>>(mut self, nodes: N) -> Self {
+        self.nodes = Some(nodes.into_iter().collect());
+        self
+    }
+
+    /// Only animate the given channels
+    pub fn with_channels<C: IntoIterator<Item = T::Channel>>(mut self, channels: C) -> Self {
+        self.channels = Some(channels.into_iter().collect());
+        self
+    }
+
+    /// Check if the mask lets a sampler for the given node and channel through
+    pub fn contains(&self, node_index: usize, channel: &T::Channel) -> bool {
+        self.nodes
+            .as_ref()
+            .map_or(true, |nodes| nodes.contains(&node_index))
+            && self
+                .channels
+                .as_ref()
+                .map_or(true, |channels| channels.contains(channel))
+    }
+}
+
+/// Extracts the motion of a node of the `AnimationHierarchy` from an animation, for example the
+/// root joint of a locomotion clip, so the character doesn't slide back when the animation loops.
+///
+/// The extracted channels of the node are kept at their value at the start of the animation, and
+/// the motion they would have made is added to the `RootMotionDelta` of the entity by the
+/// `RootMotionSystem`. Only used by animations on `Transform`s.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RootMotion {
+    /// Index of the node in the `AnimationHierarchy`
+    pub node: usize,
+    /// Extract the translation of the node
+    #[serde(default = "default_extract")]
+    pub translation: bool,
+    /// Extract the rotation of the node
+    #[serde(default = "default_extract")]
+    pub rotation: bool,
+}
+
+fn default_extract() -> bool {
+    true
+}
+
+impl RootMotion {
+    /// Extract both the translation and rotation of the node with the given index
+    pub fn new(node: usize) -> Self {
+        RootMotion {
+            node,
+            translation: true,
+            rotation: true,
+        }
+    }
+
+    /// Set if the translation of the node is extracted
+    pub fn with_translation(mut self, translation: bool) -> Self {
+        self.translation = translation;
+        self
+    }
+
+    /// Set if the rotation of the node is extracted
+    pub fn with_rotation(mut self, rotation: bool) -> Self {
+        self.rotation = rotation;
+        self
+    }
+}
+
 /// Extra data to extract from `World`, for use when applying or fetching a sample
 pub trait ApplyData<'a> {
     /// The actual data, must implement `SystemData`
@@ -207,6 +357,44 @@ where
     type Storage = DenseVecStorage<Self>;
 }
 
+/// A named marker at a point in time of an `Animation`, emitted as an `AnimationEvent` when the
+/// animation plays past it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AnimationMarker {
+    /// Time of the marker, in seconds
+    pub time: f32,
+    /// Name of the marker, for example `footstep` or `hit`
+    pub name: String,
+}
+
+impl AnimationMarker {
+    /// Create a new marker
+    pub fn new<S: Into<String>>(time: f32, name: S) -> Self {
+        AnimationMarker {
+            time,
+            name: name.into(),
+        }
+    }
+}
+
+/// Event emitted by the `AnimationEventSystem` when a running animation plays past one of its
+/// `AnimationMarker`s.
+///
+/// ### Type parameters:
+///
+/// - `I`: identifier type for running animations
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationEvent<I> {
+    /// The entity the `AnimationControlSet` playing the animation is on
+    pub entity: Entity,
+    /// The id of the animation in the `AnimationControlSet`
+    pub animation: I,
+    /// Name of the marker
+    pub name: String,
+    /// Time of the marker, in seconds
+    pub time: f32,
+}
+
 /// Defines a single animation.
 ///
 /// An animation is a set of [`Sampler`][sampler]s that should always run together as a unit.
@@ -214,6 +402,8 @@ where
 /// Defines relationships between the node index in `AnimationHierarchy` and a `Sampler` handle.
 /// If the animation only targets a single node index, `AnimationHierarchy` is not required.
 ///
+/// Gameplay logic can be run at points of the animation with `AnimationMarker`s.
+///
 /// ### Type parameters:
 ///
 /// - `T`: the component type that the animation should be applied to
@@ -226,6 +416,8 @@ where
 {
     /// node index -> sampler handle
     pub nodes: Vec<(usize, T::Channel, Handle<Sampler<T::Primitive>>)>,
+    /// Markers emitted as `AnimationEvent`s when the animation plays past them
+    pub events: Vec<AnimationMarker>,
 }
 
 impl<T> Animation<T>
@@ -234,7 +426,10 @@ where
 {
     /// Create new empty animation
     pub fn new() -> Self {
-        Animation { nodes: vec![] }
+        Animation {
+            nodes: vec![],
+            events: vec![],
+        }
     }
 
     /// Create an animation with a single sampler
@@ -245,6 +440,7 @@ where
     ) -> Self {
         Animation {
             nodes: vec![(index, channel, sampler)],
+            events: vec![],
         }
     }
 
@@ -268,6 +464,30 @@ where
         self.nodes.push((node_index, channel, sampler));
         self
     }
+
+    /// Add an event marker to the animation
+    pub fn add_event<S: Into<String>>(&mut self, time: f32, name: S) {
+        self.events.push(AnimationMarker::new(time, name));
+    }
+
+    /// Add an event marker to the animation
+    pub fn with_event<S: Into<String>>(mut self, time: f32, name: S) -> Self {
+        self.add_event(time, name);
+        self
+    }
+
+    /// Duration of the animation in seconds, the end of its longest sampler. `None` if some
+    /// samplers aren't loaded yet.
+    pub fn duration(&self, samplers: &AssetStorage<Sampler<T::Primitive>>) -> Option<f32> {
+        self.nodes
+            .iter()
+            .map(|(_, _, handle)| {
+                samplers
+                    .get(handle)
+                    .and_then(|sampler| sampler.input.last().cloned())
+            })
+            .fold(Some(0.), |duration, end| Some(duration?.max(end?)))
+    }
 }
 
 impl<T> Asset for Animation<T>
@@ -341,6 +561,10 @@ where
     pub channel: T::Channel,
     /// Blend weight
     pub blend_weight: f32,
+    /// How the sampler is combined with the other samplers on its channel
+    pub blend_mode: BlendMode,
+    /// Automatic ramp of the blend weight, updated by the `SamplerInterpolationSystem`
+    pub fade: Option<BlendFade>,
     /// Sampler
     pub sampler: Handle<Sampler<T::Primitive>>,
     /// State of sampling
@@ -506,6 +730,15 @@ where
             });
     }
 
+    /// Ramp the blend weight of all samplers of the control set, from their current weight
+    pub fn fade(&mut self, control_id: u64, fade: &BlendFade) {
+        self.samplers
+            .iter_mut()
+            .filter(|t| t.control_id == control_id)
+            .filter(|t| t.state != ControlState::Done)
+            .for_each(|t| t.fade = Some(fade.clone()));
+    }
+
     /// Set blend weight for a sampler
     pub fn set_blend_weight(&mut self, control_id: u64, channel: &T::Channel, blend_weight: f32) {
         self.samplers
@@ -596,6 +829,15 @@ where
     Init,
 }
 
+/// Jump of the input value of an animation, made by the `AnimationControlSystem`
+#[derive(Clone, Debug)]
+pub(crate) enum InputJump {
+    /// `AnimationCommand::Step`
+    Step(StepDirection),
+    /// `AnimationCommand::SetInputValue`
+    Seek,
+}
+
 /// Controls the state of a single running animation on a specific component type
 ///
 /// ### Type parameters:
@@ -619,6 +861,17 @@ where
     pub command: AnimationCommand<T>,
     /// Control the rate of animation, default is 1.0
     pub rate_multiplier: f32,
+    /// Blend weight the samplers start with, default is 1.0
+    pub blend_weight: f32,
+    /// How the animation is combined with the other animations, read when it starts
+    pub blend_mode: BlendMode,
+    /// Restricts the animation to some nodes and channels, read when it starts
+    pub mask: Option<AnimationMask<T>>,
+    /// Ramp of the blend weights requested, applied to the samplers by the control system
+    pub fade: Option<BlendFade>,
+    /// Extract the motion of a node instead of applying it
+    pub root_motion: Option<RootMotion>,
+    pub(crate) jump: Option<InputJump>,
     m: marker::PhantomData<T>,
 }
 
@@ -641,6 +894,12 @@ where
             state,
             command,
             rate_multiplier,
+            blend_weight: 1.0,
+            blend_mode: BlendMode::Override,
+            mask: None,
+            fade: None,
+            root_motion: None,
+            jump: None,
             m: marker::PhantomData,
         }
     }
@@ -726,15 +985,20 @@ where
         self
     }
 
+    fn control_mut(&mut self, id: I) -> Option<&mut AnimationControl<T>> {
+        if let Some(index) = self.animations.iter().position(|a| a.0 == id) {
+            Some(&mut self.animations[index].1)
+        } else {
+            self.deferred_animations
+                .iter_mut()
+                .find(|a| a.animation_id == id)
+                .map(|a| &mut a.control)
+        }
+    }
+
     fn set_command(&mut self, id: I, command: AnimationCommand<T>) -> &mut Self {
-        if let Some(&mut (_, ref mut control)) = self.animations.iter_mut().find(|a| a.0 == id) {
+        if let Some(control) = self.control_mut(id) {
             control.command = command;
-        } else if let Some(ref mut control) = self
-            .deferred_animations
-            .iter_mut()
-            .find(|a| a.animation_id == id)
-        {
-            control.control.command = command;
         }
 
         self
@@ -799,6 +1063,78 @@ where
         self.set_command(id, AnimationCommand::Abort)
     }
 
+    /// Ramp the blend weights of the animation to `target` over `duration` seconds
+    pub fn fade(&mut self, id: I, target: f32, duration: f32) -> &mut Self {
+        if let Some(control) = self.control_mut(id) {
+            control.fade = Some(BlendFade::new(target, duration));
+        }
+        self
+    }
+
+    /// Ramp the blend weights of the animation to zero over `duration` seconds, then abort it
+    pub fn fade_out(&mut self, id: I, duration: f32) -> &mut Self {
+        if let Some(control) = self.control_mut(id) {
+            control.fade = Some(BlendFade::out(duration));
+        }
+        self
+    }
+
+    /// Add animation with the given id, unless it already exists, and cross-fade to it from the
+    /// animation `from` over `duration` seconds. `from` is aborted once faded out.
+    ///
+    /// The blend weights are ramped by the `SamplerInterpolationSystem`, the cross-fade is only
+    /// visible for components with a `BlendMethod`.
+    pub fn cross_fade(
+        &mut self,
+        from: I,
+        id: I,
+        animation: &Handle<Animation<T>>,
+        end: EndControl,
+        rate_multiplier: f32,
+        duration: f32,
+    ) -> &mut Self {
+        if self.animations.iter().any(|a| a.0 == id) {
+            return self;
+        }
+        self.fade_out(from, duration);
+        let mut control = AnimationControl::new(
+            animation.clone(),
+            end,
+            ControlState::Requested,
+            AnimationCommand::Start,
+            rate_multiplier,
+        );
+        control.blend_weight = 0.;
+        control.fade = Some(BlendFade::new(1., duration));
+        self.animations.push((id, control));
+        self
+    }
+
+    /// Set how the animation is combined with the other animations, must be set before the
+    /// animation starts
+    pub fn set_blend_mode(&mut self, id: I, blend_mode: BlendMode) -> &mut Self {
+        if let Some(control) = self.control_mut(id) {
+            control.blend_mode = blend_mode;
+        }
+        self
+    }
+
+    /// Restrict the animation to some nodes and channels, must be set before the animation starts
+    pub fn set_mask(&mut self, id: I, mask: AnimationMask<T>) -> &mut Self {
+        if let Some(control) = self.control_mut(id) {
+            control.mask = Some(mask);
+        }
+        self
+    }
+
+    /// Extract the motion of a node from the animation with the given id
+    pub fn set_root_motion(&mut self, id: I, root_motion: RootMotion) -> &mut Self {
+        if let Some(control) = self.control_mut(id) {
+            control.root_motion = Some(root_motion);
+        }
+        self
+    }
+
     /// Add animation with the given id, unless it already exists
     pub fn add_animation(
         &mut self,
@@ -0,0 +1,702 @@
+use std::hash::Hash;
+
+use fnv::FnvHashMap;
+use log::warn;
+use minterpolate::InterpolationFunction;
+use serde::{Deserialize, Serialize};
+
+use amethyst_assets::{AssetStorage, Handle, Loader, PrefabData, ProgressCounter};
+use amethyst_core::{
+    ecs::prelude::{Entity, ReadStorage},
+    math::{Quaternion, Vector3, Vector4},
+    Named, Transform,
+};
+use amethyst_error::Error;
+
+use crate::{
+    prefab::{AnimationPrefab, AnimationSetPrefab},
+    resources::{Animation, AnimationHierarchy, RestState, Sampler},
+    transform::TransformChannel,
+    util::SamplerPrimitive,
+};
+
+const EPSILON: f32 = 1.0e-5;
+
+/// A named joint of a `RetargetSkeleton`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetargetJoint {
+    /// Node index of the joint in the `AnimationHierarchy` of the skeleton
+    pub node: usize,
+    /// Name of the joint
+    pub name: String,
+    /// Local transform of the joint in the reference pose
+    pub rest: Transform,
+}
+
+/// Joint names and reference pose of a skeleton, the source or the target of a `RetargetMap`.
+///
+/// Both skeletons should be in a similar reference pose, usually a T-pose or an A-pose, as the
+/// retargeted animation moves the target joints away from their reference pose the same way the
+/// source animation moves the source joints away from theirs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetargetSkeleton {
+    /// The joints of the skeleton
+    pub joints: Vec<RetargetJoint>,
+}
+
+impl RetargetSkeleton {
+    /// Create an empty skeleton
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a joint to the skeleton
+    pub fn with_joint<S: Into<String>>(mut self, node: usize, name: S, rest: Transform) -> Self {
+        self.joints.push(RetargetJoint {
+            node,
+            name: name.into(),
+            rest,
+        });
+        self
+    }
+
+    /// Create the skeleton of the named entities of a hierarchy, using their `RestState` as
+    /// reference pose, or their current `Transform` if they have none.
+    pub fn from_hierarchy(
+        hierarchy: &AnimationHierarchy<Transform>,
+        names: &ReadStorage<'_, Named>,
+        rest_states: &ReadStorage<'_, RestState<Transform>>,
+        transforms: &ReadStorage<'_, Transform>,
+    ) -> Self {
+        let mut joints = hierarchy
+            .nodes
+            .iter()
+            .filter_map(|(node, entity)| joint(*node, *entity, names, rest_states, transforms))
+            .collect::<Vec<_>>();
+        joints.sort_by_key(|joint| joint.node);
+        RetargetSkeleton { joints }
+    }
+
+    /// Get the joint with the given name
+    pub fn joint(&self, name: &str) -> Option<&RetargetJoint> {
+        self.joints.iter().find(|joint| joint.name == name)
+    }
+}
+
+fn joint(
+    node: usize,
+    entity: Entity,
+    names: &ReadStorage<'_, Named>,
+    rest_states: &ReadStorage<'_, RestState<Transform>>,
+    transforms: &ReadStorage<'_, Transform>,
+) -> Option<RetargetJoint> {
+    let name = names.get(entity)?.name.to_string();
+    let rest = rest_states
+        .get(entity)
+        .map(|state| state.state().clone())
+        .or_else(|| transforms.get(entity).cloned())?;
+    Some(RetargetJoint { node, name, rest })
+}
+
+/// How a `RetargetMap` converts the animated translations of a joint
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RetargetTranslation {
+    /// Move the target joint away from its reference translation by the motion of the source joint,
+    /// scaled by the ratio of the reference translation lengths of both joints, or by the
+    /// `translation_scale` of the map if set
+    Scaled,
+    /// Keep the translations of the source animation
+    Unchanged,
+    /// Drop the translation samplers, the target joint keeps its own translation
+    Rest,
+}
+
+impl Default for RetargetTranslation {
+    fn default() -> Self {
+        RetargetTranslation::Scaled
+    }
+}
+
+/// Converts `Animation<Transform>`s authored for the `AnimationHierarchy` of one skeleton into
+/// animations of the `AnimationHierarchy` of another skeleton, with different proportions and joint
+/// names.
+///
+/// Source joints are mapped to target joints by name. Rotations are corrected by the difference
+/// between the reference poses of the mapped joints, translations and scales are converted relative
+/// to the reference pose, and samplers of source joints without a target joint are dropped. A target
+/// joint is only animated by one source joint: the joints listed in `joints` come first, then the
+/// first source joint in the skeleton, and a warning is logged for the others.
+///
+/// Animations can be retargeted at load time with `retarget_prefab` or the
+/// `RetargetedAnimationSetPrefab`, or on the fly from loaded assets with `retarget_animation`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetargetMap {
+    /// Pairs of source and target joint names
+    #[serde(default)]
+    pub joints: Vec<(String, String)>,
+    /// Map source joints not listed in `joints` to the target joints with the same name
+    #[serde(default = "default_match_names")]
+    pub match_names: bool,
+    /// How translations are converted
+    #[serde(default)]
+    pub translation: RetargetTranslation,
+    /// Pairs of source joint name and how its translations are converted, overriding `translation`
+    #[serde(default)]
+    pub joint_translations: Vec<(String, RetargetTranslation)>,
+    /// Scale of `RetargetTranslation::Scaled` translations, instead of the ratio of the reference
+    /// translation lengths of each joint
+    #[serde(default)]
+    pub translation_scale: Option<f32>,
+}
+
+fn default_match_names() -> bool {
+    true
+}
+
+impl Default for RetargetMap {
+    fn default() -> Self {
+        RetargetMap {
+            joints: Vec::default(),
+            match_names: true,
+            translation: RetargetTranslation::default(),
+            joint_translations: Vec::default(),
+            translation_scale: None,
+        }
+    }
+}
+
+impl RetargetMap {
+    /// Create a map matching joints by name
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map a source joint to a target joint
+    pub fn with_joint<S, T>(mut self, source: S, target: T) -> Self
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        self.joints.push((source.into(), target.into()));
+        self
+    }
+
+    /// Only map the joints listed with `with_joint`
+    pub fn without_name_matching(mut self) -> Self {
+        self.match_names = false;
+        self
+    }
+
+    /// Set how translations are converted
+    pub fn with_translation(mut self, translation: RetargetTranslation) -> Self {
+        self.translation = translation;
+        self
+    }
+
+    /// Set how translations of the given source joint are converted
+    pub fn with_joint_translation<S: Into<String>>(
+        mut self,
+        source: S,
+        translation: RetargetTranslation,
+    ) -> Self {
+        self.joint_translations.push((source.into(), translation));
+        self
+    }
+
+    /// Scale `RetargetTranslation::Scaled` translations by a fixed factor
+    pub fn with_translation_scale(mut self, scale: f32) -> Self {
+        self.translation_scale = Some(scale);
+        self
+    }
+
+    /// Get the name of the target joint of a source joint
+    pub fn target_name<'a>(&'a self, source: &'a str) -> Option<&'a str> {
+        self.joints
+            .iter()
+            .find(|(from, _)| from == source)
+            .map(|(_, to)| to.as_str())
+            .or_else(|| if self.match_names { Some(source) } else { None })
+    }
+
+    fn pairs<'s>(
+        &self,
+        source: &'s RetargetSkeleton,
+        target: &'s RetargetSkeleton,
+    ) -> FnvHashMap<usize, (&'s RetargetJoint, &'s RetargetJoint, RetargetTranslation)> {
+        let mut candidates = source
+            .joints
+            .iter()
+            .filter_map(|from| Some((from, target.joint(self.target_name(&from.name)?)?)))
+            .collect::<Vec<_>>();
+        // Explicitly mapped joints take precedence over joints matched by name
+        candidates.sort_by_key(|(from, _)| !self.joints.iter().any(|(name, _)| *name == from.name));
+
+        let mut targets = FnvHashMap::<usize, &str>::default();
+        candidates
+            .into_iter()
+            .filter_map(|(from, to)| {
+                if let Some(other) = targets.get(&to.node) {
+                    warn!(
+                        "Source joints {} and {} both map to the target joint {}, ignoring {}",
+                        other, from.name, to.name, from.name
+                    );
+                    return None;
+                }
+                targets.insert(to.node, &from.name);
+                let translation = self
+                    .joint_translations
+                    .iter()
+                    .find(|(name, _)| *name == from.name)
+                    .map_or(self.translation, |(_, translation)| *translation);
+                Some((from.node, (from, to, translation)))
+            })
+            .collect()
+    }
+
+    /// Retarget samplers of the source skeleton to the target skeleton
+    pub fn retarget_samplers<'a, S>(
+        &self,
+        source: &RetargetSkeleton,
+        target: &RetargetSkeleton,
+        samplers: S,
+    ) -> Vec<(usize, TransformChannel, Sampler<SamplerPrimitive<f32>>)>
+    where
+        S: IntoIterator<
+            Item = (
+                usize,
+                &'a TransformChannel,
+                &'a Sampler<SamplerPrimitive<f32>>,
+            ),
+        >,
+    {
+        let pairs = self.pairs(source, target);
+        samplers
+            .into_iter()
+            .filter_map(|(node, channel, sampler)| {
+                let (from, to, translation) = pairs.get(&node)?;
+                let sampler = self.retarget_sampler(from, to, *translation, channel, sampler)?;
+                Some((to.node, *channel, sampler))
+            })
+            .collect()
+    }
+
+    /// Retarget an animation prefab of the source skeleton to the target skeleton, before loading it
+    pub fn retarget_prefab(
+        &self,
+        source: &RetargetSkeleton,
+        target: &RetargetSkeleton,
+        prefab: &AnimationPrefab<Transform>,
+    ) -> AnimationPrefab<Transform> {
+        let mut retargeted = AnimationPrefab::default();
+        retargeted.samplers = self.retarget_samplers(
+            source,
+            target,
+            prefab
+                .samplers
+                .iter()
+                .map(|(node, channel, sampler)| (*node, channel, sampler)),
+        );
+        retargeted.events = prefab.events.clone();
+        retargeted
+    }
+
+    /// Retarget a loaded animation of the source skeleton to the target skeleton, loading the
+    /// retargeted animation as a new asset.
+    ///
+    /// Returns `None` if any sampler of the animation is not loaded yet.
+    #[allow(clippy::too_many_arguments)]
+    pub fn retarget_animation(
+        &self,
+        source: &RetargetSkeleton,
+        target: &RetargetSkeleton,
+        animation: &Animation<Transform>,
+        loader: &Loader,
+        progress: &mut ProgressCounter,
+        sampler_storage: &AssetStorage<Sampler<SamplerPrimitive<f32>>>,
+        animation_storage: &AssetStorage<Animation<Transform>>,
+    ) -> Option<Handle<Animation<Transform>>> {
+        let samplers = animation
+            .nodes
+            .iter()
+            .map(|(node, channel, handle)| Some((*node, channel, sampler_storage.get(handle)?)))
+            .collect::<Option<Vec<_>>>()?;
+        let retargeted = Animation {
+            nodes: self
+                .retarget_samplers(source, target, samplers)
+                .into_iter()
+                .map(|(node, channel, sampler)| {
+                    (
+                        node,
+                        channel,
+                        loader.load_from_data(sampler, &mut *progress, sampler_storage),
+                    )
+                })
+                .collect(),
+            events: animation.events.clone(),
+        };
+        Some(loader.load_from_data(retargeted, progress, animation_storage))
+    }
+
+    fn retarget_sampler(
+        &self,
+        from: &RetargetJoint,
+        to: &RetargetJoint,
+        translation: RetargetTranslation,
+        channel: &TransformChannel,
+        sampler: &Sampler<SamplerPrimitive<f32>>,
+    ) -> Option<Sampler<SamplerPrimitive<f32>>> {
+        match *channel {
+            TransformChannel::Rotation => {
+                let correction = (to.rest.rotation() * from.rest.rotation().inverse()).into_inner();
+                Some(map_output(sampler, |value, _| match value {
+                    SamplerPrimitive::Vec4(v) => {
+                        let rotated = correction * Quaternion::from(Vector4::from(v));
+                        SamplerPrimitive::Vec4(rotated.coords.into())
+                    }
+                    other => other,
+                }))
+            }
+            TransformChannel::Translation => match translation {
+                RetargetTranslation::Rest => None,
+                RetargetTranslation::Unchanged => Some(sampler.clone()),
+                RetargetTranslation::Scaled => {
+                    let (from_rest, to_rest) = (from.rest.translation(), to.rest.translation());
+                    let scale = self.translation_scale.unwrap_or_else(|| {
+                        if from_rest.norm() > EPSILON {
+                            to_rest.norm() / from_rest.norm()
+                        } else {
+                            1.
+                        }
+                    });
+                    Some(map_output(sampler, |value, tangent| match value {
+                        SamplerPrimitive::Vec3(v) if tangent => {
+                            SamplerPrimitive::Vec3((Vector3::from(v) * scale).into())
+                        }
+                        SamplerPrimitive::Vec3(v) => SamplerPrimitive::Vec3(
+                            (to_rest + (Vector3::from(v) - from_rest) * scale).into(),
+                        ),
+                        other => other,
+                    }))
+                }
+            },
+            TransformChannel::Scale => {
+                let ratio = to
+                    .rest
+                    .scale()
+                    .zip_map(from.rest.scale(), |target, source| {
+                        if source.abs() > EPSILON {
+                            target / source
+                        } else {
+                            1.
+                        }
+                    });
+                Some(map_output(sampler, |value, _| match value {
+                    SamplerPrimitive::Vec3(v) => {
+                        SamplerPrimitive::Vec3(Vector3::from(v).component_mul(&ratio).into())
+                    }
+                    other => other,
+                }))
+            }
+        }
+    }
+}
+
+/// Map the output of a sampler, the second parameter of `f` telling if the output is a tangent
+/// of a spline rather than a value
+fn map_output<F>(sampler: &Sampler<SamplerPrimitive<f32>>, f: F) -> Sampler<SamplerPrimitive<f32>>
+where
+    F: Fn(SamplerPrimitive<f32>, bool) -> SamplerPrimitive<f32>,
+{
+    let last = sampler.output.len().saturating_sub(1);
+    let output = sampler
+        .output
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let tangent = match sampler.function {
+                InterpolationFunction::CubicSpline => index % 3 != 1,
+                InterpolationFunction::CatmullRomSpline => index == 0 || index == last,
+                _ => false,
+            };
+            f(*value, tangent)
+        })
+        .collect();
+    Sampler {
+        input: sampler.input.clone(),
+        output,
+        function: sampler.function.clone(),
+    }
+}
+
+/// `PrefabData` for loading `Animation`s authored for another skeleton as part of an
+/// `AnimationSet`, retargeting them with a `RetargetMap` before loading.
+///
+/// ### Type parameters
+///
+/// - `I`: Id type
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(bound(serialize = "I: Serialize", deserialize = "I: Deserialize<'de>"))]
+pub struct RetargetedAnimationSetPrefab<I> {
+    /// Skeleton the animations were authored for
+    pub source: RetargetSkeleton,
+    /// Skeleton the animations are played on
+    pub target: RetargetSkeleton,
+    /// How the animations are retargeted
+    #[serde(default)]
+    pub map: RetargetMap,
+    /// The animations, for the source skeleton
+    pub animation_set: AnimationSetPrefab<I, Transform>,
+    #[serde(skip)]
+    retargeted: bool,
+}
+
+impl<'a, I> PrefabData<'a> for RetargetedAnimationSetPrefab<I>
+where
+    I: Clone + Hash + Eq + Send + Sync + 'static,
+{
+    type SystemData = <AnimationSetPrefab<I, Transform> as PrefabData<'a>>::SystemData;
+    type Result = ();
+
+    fn add_to_entity(
+        &self,
+        entity: Entity,
+        system_data: &mut Self::SystemData,
+        entities: &[Entity],
+        children: &[Entity],
+    ) -> Result<(), Error> {
+        self.animation_set
+            .add_to_entity(entity, system_data, entities, children)
+    }
+
+    fn load_sub_assets(
+        &mut self,
+        progress: &mut ProgressCounter,
+        system_data: &mut Self::SystemData,
+    ) -> Result<bool, Error> {
+        if !self.retargeted {
+            for (_, animation) in &mut self.animation_set.animations {
+                *animation = self
+                    .map
+                    .retarget_prefab(&self.source, &self.target, animation);
+            }
+            self.retargeted = true;
+        }
+        self.animation_set.load_sub_assets(progress, system_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use amethyst_core::math::UnitQuaternion;
+
+    use super::*;
+
+    fn transform(translation: [f32; 3], angle: f32) -> Transform {
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(translation[0], translation[1], translation[2]);
+        transform.set_rotation_y_axis(angle);
+        transform
+    }
+
+    fn skeletons(target_angle: f32) -> (RetargetSkeleton, RetargetSkeleton) {
+        (
+            RetargetSkeleton::new().with_joint(0, "hips", transform([0., 1., 0.], 0.)),
+            RetargetSkeleton::new().with_joint(3, "hips", transform([0., 2., 0.], target_angle)),
+        )
+    }
+
+    fn sampler(
+        function: InterpolationFunction<SamplerPrimitive<f32>>,
+        output: Vec<SamplerPrimitive<f32>>,
+    ) -> Sampler<SamplerPrimitive<f32>> {
+        Sampler {
+            input: (0..output.len()).map(|i| i as f32).collect(),
+            output,
+            function,
+        }
+    }
+
+    fn retarget(
+        map: &RetargetMap,
+        (source, target): &(RetargetSkeleton, RetargetSkeleton),
+        channel: TransformChannel,
+        sampler: &Sampler<SamplerPrimitive<f32>>,
+    ) -> Vec<(usize, TransformChannel, Sampler<SamplerPrimitive<f32>>)> {
+        map.retarget_samplers(source, target, vec![(0, &channel, sampler)])
+    }
+
+    fn vec3(value: &SamplerPrimitive<f32>) -> Vector3<f32> {
+        match *value {
+            SamplerPrimitive::Vec3(v) => Vector3::from(v),
+            _ => panic!("Expected a Vec3, got {:?}", value),
+        }
+    }
+
+    fn translations(sampler: &Sampler<SamplerPrimitive<f32>>) -> Vec<Vector3<f32>> {
+        sampler.output.iter().map(vec3).collect()
+    }
+
+    #[test]
+    fn rotation_correction() {
+        let skeletons = skeletons(FRAC_PI_2);
+        let turn = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 0.5);
+        let sampler = sampler(
+            InterpolationFunction::Linear,
+            vec![
+                SamplerPrimitive::Vec4([0., 0., 0., 1.]),
+                SamplerPrimitive::Vec4(turn.into_inner().coords.into()),
+            ],
+        );
+        let retargeted = retarget(
+            &RetargetMap::new(),
+            &skeletons,
+            TransformChannel::Rotation,
+            &sampler,
+        );
+        assert_eq!(retargeted.len(), 1);
+        assert_eq!(
+            (retargeted[0].0, retargeted[0].1),
+            (3, TransformChannel::Rotation)
+        );
+
+        let target_rest = *skeletons.1.joints[0].rest.rotation();
+        let rotations = retargeted[0]
+            .2
+            .output
+            .iter()
+            .map(|value| match *value {
+                SamplerPrimitive::Vec4(v) => {
+                    UnitQuaternion::new_normalize(Quaternion::from(Vector4::from(v)))
+                }
+                _ => panic!("Expected a Vec4, got {:?}", value),
+            })
+            .collect::<Vec<_>>();
+        // The source reference pose plays as the target reference pose
+        assert!(rotations[0].angle_to(&target_rest) < 1e-5);
+        assert!(rotations[1].angle_to(&(target_rest * turn)) < 1e-5);
+    }
+
+    #[test]
+    fn scaled_translation() {
+        let skeletons = skeletons(0.);
+        let sampler = sampler(
+            InterpolationFunction::Linear,
+            vec![
+                SamplerPrimitive::Vec3([0., 1., 0.]),
+                SamplerPrimitive::Vec3([1., 1.5, 0.]),
+            ],
+        );
+        let retargeted = retarget(
+            &RetargetMap::new(),
+            &skeletons,
+            TransformChannel::Translation,
+            &sampler,
+        );
+        // Twice as tall, the motion away from the reference pose is doubled
+        assert_eq!(
+            translations(&retargeted[0].2),
+            vec![Vector3::new(0., 2., 0.), Vector3::new(2., 3., 0.)]
+        );
+
+        let map = RetargetMap::new().with_translation_scale(3.);
+        let retargeted = retarget(&map, &skeletons, TransformChannel::Translation, &sampler);
+        assert_eq!(
+            translations(&retargeted[0].2),
+            vec![Vector3::new(0., 2., 0.), Vector3::new(3., 3.5, 0.)]
+        );
+
+        let map = RetargetMap::new().with_joint_translation("hips", RetargetTranslation::Unchanged);
+        let retargeted = retarget(&map, &skeletons, TransformChannel::Translation, &sampler);
+        assert_eq!(translations(&retargeted[0].2), translations(&sampler));
+
+        let map = RetargetMap::new().with_translation(RetargetTranslation::Rest);
+        assert!(retarget(&map, &skeletons, TransformChannel::Translation, &sampler).is_empty());
+    }
+
+    #[test]
+    fn spline_tangents() {
+        let skeletons = skeletons(0.);
+        // In tangent, value and out tangent of each key
+        let sampler = sampler(
+            InterpolationFunction::CubicSpline,
+            vec![
+                SamplerPrimitive::Vec3([1., 0., 0.]),
+                SamplerPrimitive::Vec3([0., 1., 0.]),
+                SamplerPrimitive::Vec3([1., 0., 0.]),
+                SamplerPrimitive::Vec3([0., 1., 1.]),
+                SamplerPrimitive::Vec3([0., 2., 0.]),
+                SamplerPrimitive::Vec3([0., 1., 1.]),
+            ],
+        );
+        let retargeted = retarget(
+            &RetargetMap::new(),
+            &skeletons,
+            TransformChannel::Translation,
+            &sampler,
+        );
+        // Tangents are only scaled, values are also moved to the target reference pose
+        assert_eq!(
+            translations(&retargeted[0].2),
+            vec![
+                Vector3::new(2., 0., 0.),
+                Vector3::new(0., 2., 0.),
+                Vector3::new(2., 0., 0.),
+                Vector3::new(0., 2., 2.),
+                Vector3::new(0., 4., 0.),
+                Vector3::new(0., 2., 2.),
+            ]
+        );
+
+        let sampler = Sampler {
+            function: InterpolationFunction::CatmullRomSpline,
+            ..sampler
+        };
+        let retargeted = retarget(
+            &RetargetMap::new(),
+            &skeletons,
+            TransformChannel::Translation,
+            &sampler,
+        );
+        let output = translations(&retargeted[0].2);
+        assert_eq!(output[0], Vector3::new(2., 0., 0.));
+        assert_eq!(output[1], Vector3::new(0., 2., 0.));
+        assert_eq!(output[5], Vector3::new(0., 2., 2.));
+    }
+
+    #[test]
+    fn duplicate_targets() {
+        let source = RetargetSkeleton::new()
+            .with_joint(0, "pelvis", Transform::default())
+            .with_joint(1, "Hips", Transform::default());
+        let target = RetargetSkeleton::new().with_joint(0, "pelvis", Transform::default());
+        let sampler = sampler(
+            InterpolationFunction::Linear,
+            vec![SamplerPrimitive::Vec3([0., 0., 0.])],
+        );
+        let samplers = vec![
+            (0, &TransformChannel::Scale, &sampler),
+            (1, &TransformChannel::Scale, &sampler),
+        ];
+
+        // Both joints match a target joint, the explicitly mapped one is kept
+        let map = RetargetMap::new().with_joint("Hips", "pelvis");
+        let pairs = map.pairs(&source, &target);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[&1].0.name, "Hips");
+        assert_eq!(
+            map.retarget_samplers(&source, &target, samplers.clone())
+                .len(),
+            1
+        );
+
+        // Without explicit mapping, the first source joint is kept
+        let map = RetargetMap::new()
+            .with_joint("pelvis", "pelvis")
+            .with_joint("Hips", "pelvis");
+        let pairs = map.pairs(&source, &target);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[&0].0.name, "pelvis");
+    }
+}
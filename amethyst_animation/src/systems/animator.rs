@@ -0,0 +1,257 @@
+use std::{hash::Hash, marker::PhantomData};
+
+use derivative::Derivative;
+use log::error;
+
+use amethyst_assets::AssetStorage;
+use amethyst_core::{
+    ecs::prelude::{Entities, Join, Read, ReadStorage, System, SystemData, World, WriteStorage},
+    SystemDesc, Time,
+};
+
+use crate::{
+    animator::{
+        Animator, AnimatorCondition, AnimatorFade, AnimatorGraph, AnimatorParameter, AnimatorState,
+    },
+    resources::{
+        Animation, AnimationCommand, AnimationControlSet, AnimationSampling, AnimationSet, Sampler,
+    },
+    util::get_animation_set,
+};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+/// Builds an `AnimatorSystem`.
+#[derive(Derivative, Debug)]
+#[derivative(Default(bound = ""))]
+pub struct AnimatorSystemDesc<I, T> {
+    marker: PhantomData<(I, T)>,
+}
+
+impl<'a, 'b, I, T> SystemDesc<'a, 'b, AnimatorSystem<I, T>> for AnimatorSystemDesc<I, T>
+where
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+    T: AnimationSampling,
+{
+    fn build(self, world: &mut World) -> AnimatorSystem<I, T> {
+        <AnimatorSystem<I, T> as System<'_>>::SystemData::setup(world);
+
+        AnimatorSystem::new()
+    }
+}
+
+/// System running the `AnimatorGraph`s of the `Animator`s, should run before
+/// `AnimationControlSystem`.
+///
+/// Starts the animation of the current state of each animator in the `AnimationControlSet` of
+/// its entity, fires the transitions whose conditions are met and cross-fades the animations of
+/// the states during transitions.
+///
+/// ### Type parameters:
+///
+/// - `I`: identifier type for running animations
+/// - `T`: the component type that the animation should be applied to
+#[derive(Derivative, Debug)]
+#[derivative(Default(bound = ""))]
+pub struct AnimatorSystem<I, T> {
+    m: PhantomData<(I, T)>,
+}
+
+impl<I, T> AnimatorSystem<I, T> {
+    /// Creates a new `AnimatorSystem`
+    pub fn new() -> Self {
+        AnimatorSystem { m: PhantomData }
+    }
+}
+
+impl<'a, I, T> System<'a> for AnimatorSystem<I, T>
+where
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+    T: AnimationSampling,
+{
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        Read<'a, AssetStorage<AnimatorGraph<I>>>,
+        Read<'a, AssetStorage<Animation<T>>>,
+        Read<'a, AssetStorage<Sampler<T::Primitive>>>,
+        ReadStorage<'a, AnimationSet<I, T>>,
+        WriteStorage<'a, Animator<I, T>>,
+        WriteStorage<'a, AnimationControlSet<I, T>>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("animator_system");
+
+        let (
+            entities,
+            time,
+            graph_storage,
+            animation_storage,
+            sampler_storage,
+            sets,
+            mut animators,
+            mut controls,
+        ) = data;
+        for (entity, animator, set) in (&*entities, &mut animators, &sets).join() {
+            let graph = match graph_storage.get(&animator.graph) {
+                Some(graph) => graph,
+                None => continue,
+            };
+            if let Some(control_set) = get_animation_set(&mut controls, entity) {
+                update_animator(
+                    animator,
+                    graph,
+                    set,
+                    control_set,
+                    &animation_storage,
+                    &sampler_storage,
+                    time.delta_seconds(),
+                );
+            }
+        }
+    }
+}
+
+/// Advance a single animator by `delta` seconds.
+fn update_animator<I, T>(
+    animator: &mut Animator<I, T>,
+    graph: &AnimatorGraph<I>,
+    set: &AnimationSet<I, T>,
+    control_set: &mut AnimationControlSet<I, T>,
+    animation_storage: &AssetStorage<Animation<T>>,
+    sampler_storage: &AssetStorage<Sampler<T::Primitive>>,
+    delta: f32,
+) where
+    I: Copy + Eq + Hash,
+    T: AnimationSampling,
+{
+    // Start with the entry state
+    let state = match animator.current.and_then(|index| graph.states.get(index)) {
+        Some(state) => state,
+        None => {
+            for (name, value) in &graph.parameters {
+                animator
+                    .parameters
+                    .entry(name.clone())
+                    .or_insert_with(|| value.clone());
+            }
+            animator.current = None;
+            if let Some(state) = graph.states.first() {
+                if start_state(state, set, control_set, None) {
+                    animator.current = Some(0);
+                    animator.state_time = 0.;
+                }
+            }
+            return;
+        }
+    };
+    animator.state_time += delta * state.speed;
+
+    // Wait for the cross-fade from the left state, ramped by the sampling system
+    if let Some(mut fade) = animator.fade.take() {
+        fade.elapsed += delta;
+        if fade.elapsed < fade.duration {
+            animator.fade = Some(fade);
+        } else {
+            control_set.abort(fade.from);
+        }
+        return;
+    }
+
+    let duration = set
+        .get(&state.animation)
+        .and_then(|handle| animation_storage.get(handle))
+        .and_then(|animation| animation.duration(sampler_storage))
+        .filter(|duration| *duration > 0.);
+    let progress = duration.map(|duration| animator.state_time / duration);
+    let transition = graph.find_transition(&state.name, progress, &animator.parameters);
+    let transition = match transition {
+        Some(transition) => transition,
+        None => return,
+    };
+    let target_index = match graph.state_index(&transition.to) {
+        Some(index) => index,
+        None => {
+            error!("Animator transition to the missing state {}", transition.to);
+            return;
+        }
+    };
+    let target = &graph.states[target_index];
+
+    if target.animation != state.animation {
+        // Wait for a previous run of the animation to be aborted
+        if control_set.has_animation(target.animation) {
+            return;
+        }
+        let from = Some((state.animation, transition.duration)).filter(|(_, d)| *d > 0.);
+        if !start_state(target, set, control_set, from) {
+            return;
+        }
+        if from.is_some() {
+            animator.fade = Some(AnimatorFade {
+                from: state.animation,
+                elapsed: 0.,
+                duration: transition.duration,
+            });
+        } else {
+            control_set.abort(state.animation);
+        }
+    }
+
+    for condition in &transition.conditions {
+        if let AnimatorCondition::If(ref name) = *condition {
+            if let Some(AnimatorParameter::Trigger(value)) = animator.parameters.get_mut(name) {
+                *value = false;
+            }
+        }
+    }
+    animator.current = Some(target_index);
+    animator.state_time = 0.;
+}
+
+/// Start the animation of a state, cross-fading from the animation `from` if given. Returns false
+/// if the entity has no such animation.
+fn start_state<I, T>(
+    state: &AnimatorState<I>,
+    set: &AnimationSet<I, T>,
+    control_set: &mut AnimationControlSet<I, T>,
+    from: Option<(I, f32)>,
+) -> bool
+where
+    I: Copy + Eq + Hash,
+    T: AnimationSampling,
+{
+    match set.get(&state.animation) {
+        Some(handle) => {
+            match from {
+                Some((from, duration)) => control_set.cross_fade(
+                    from,
+                    state.animation,
+                    handle,
+                    state.end_control(),
+                    state.speed,
+                    duration,
+                ),
+                None => control_set.add_animation(
+                    state.animation,
+                    handle,
+                    state.end_control(),
+                    state.speed,
+                    AnimationCommand::Start,
+                ),
+            };
+            true
+        }
+        None => {
+            error!(
+                "Animator state {} refers to a missing animation",
+                state.name
+            );
+            false
+        }
+    }
+}
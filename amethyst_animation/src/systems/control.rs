@@ -21,8 +21,8 @@ use amethyst_core::{
 
 use crate::resources::{
     Animation, AnimationCommand, AnimationControl, AnimationControlSet, AnimationHierarchy,
-    AnimationSampling, AnimationSet, ApplyData, ControlState, DeferStartRelation, RestState,
-    Sampler, SamplerControl, SamplerControlSet, StepDirection,
+    AnimationSampling, AnimationSet, ApplyData, BlendFade, ControlState, DeferStartRelation,
+    InputJump, RestState, Sampler, SamplerControl, SamplerControlSet, StepDirection,
 };
 
 #[cfg(feature = "profiler")]
@@ -340,6 +340,14 @@ where
             }
         }
     };
+    // Jumps are only reported for the frame they are made in
+    control.jump = None;
+    // Apply requested blend weight ramps, once the samplers exist
+    if control.state.is_running() || control.state.is_paused() {
+        if let Some(fade) = control.fade.take() {
+            fade_animation(control.id, hierarchy, samplers, &fade);
+        }
+    }
     match (&control.state, &control.command) {
         // Check for aborted or done animation
         (_, &AnimationCommand::Abort) | (&ControlState::Abort, _) | (&ControlState::Done, _) => {
@@ -406,11 +414,13 @@ where
 
         (&ControlState::Running(..), &AnimationCommand::Step(ref dir)) => {
             step_animation(control.id, hierarchy, samplers, sampler_storage, dir);
+            control.jump = Some(InputJump::Step(dir.clone()));
             None
         }
 
         (&ControlState::Running(..), &AnimationCommand::SetInputValue(value)) => {
             set_animation_input(control.id, hierarchy, samplers, value);
+            control.jump = Some(InputJump::Seek);
             None
         }
 
@@ -476,14 +486,23 @@ fn start_animation<T>(
 where
     T: AnimationSampling + Component + Clone,
 {
+    // skip the samplers masked out
+    let nodes = move || {
+        animation
+            .nodes
+            .iter()
+            .filter(move |&&(node_index, ref channel, _)| {
+                control
+                    .mask
+                    .as_ref()
+                    .map_or(true, |mask| mask.contains(node_index, channel))
+            })
+    };
+
     // check that hierarchy is valid, and all samplers exist
-    let valid = animation
-        .nodes
-        .iter()
-        .all(|&(ref node_index, _, ref sampler_handle)| {
-            hierarchy.nodes.contains_key(node_index)
-                && sampler_storage.get(sampler_handle).is_some()
-        });
+    let valid = nodes().all(|&(ref node_index, _, ref sampler_handle)| {
+        hierarchy.nodes.contains_key(node_index) && sampler_storage.get(sampler_handle).is_some()
+    });
 
     if !valid {
         return false;
@@ -498,7 +517,7 @@ where
     };
 
     // setup sampler tree
-    for &(ref node_index, ref channel, ref sampler_handle) in &animation.nodes {
+    for &(ref node_index, ref channel, ref sampler_handle) in nodes() {
         let node_entity = hierarchy.nodes.get(node_index).expect(
             "Unreachable: Existence of all nodes are checked in validation of hierarchy above",
         );
@@ -515,7 +534,9 @@ where
                 end: control.end.clone(),
                 after: component.current_sample(channel, apply_data),
                 rate_multiplier: control.rate_multiplier,
-                blend_weight: 1.0,
+                blend_weight: control.blend_weight,
+                blend_mode: control.blend_mode,
+                fade: None,
             };
             if let Some(ref mut set) = samplers.get_mut(*node_entity) {
                 set.add_control(sampler_control);
@@ -613,6 +634,21 @@ fn set_blend_weights<T>(
     }
 }
 
+fn fade_animation<T>(
+    control_id: u64,
+    hierarchy: &AnimationHierarchy<T>,
+    samplers: &mut WriteStorage<'_, SamplerControlSet<T>>,
+    fade: &BlendFade,
+) where
+    T: AnimationSampling,
+{
+    for node_entity in hierarchy.nodes.values() {
+        if let Some(ref mut s) = samplers.get_mut(*node_entity) {
+            s.fade(control_id, fade);
+        }
+    }
+}
+
 fn update_animation_rate<T>(
     control_id: u64,
     hierarchy: &AnimationHierarchy<T>,
@@ -0,0 +1,291 @@
+use std::{cmp::Ordering, hash::Hash, marker::PhantomData, mem};
+
+use derivative::Derivative;
+use fnv::FnvHashMap;
+
+use amethyst_assets::AssetStorage;
+use amethyst_core::{
+    ecs::prelude::{Entities, Entity, Join, Read, ReadStorage, System, SystemData, World, Write},
+    shrev::EventChannel,
+    SystemDesc,
+};
+
+use crate::{
+    resources::{
+        Animation, AnimationControlSet, AnimationEvent, AnimationHierarchy, AnimationMarker,
+        AnimationSampling, InputJump, Sampler, SamplerControlSet, StepDirection,
+    },
+    util::control_input,
+};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+/// Builds an `AnimationEventSystem`.
+#[derive(Derivative, Debug)]
+#[derivative(Default(bound = ""))]
+pub struct AnimationEventSystemDesc<I, T> {
+    marker: PhantomData<(I, T)>,
+}
+
+impl<'a, 'b, I, T> SystemDesc<'a, 'b, AnimationEventSystem<I, T>> for AnimationEventSystemDesc<I, T>
+where
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+    T: AnimationSampling,
+{
+    fn build(self, world: &mut World) -> AnimationEventSystem<I, T> {
+        <AnimationEventSystem<I, T> as System<'_>>::SystemData::setup(world);
+
+        AnimationEventSystem::new()
+    }
+}
+
+/// System emitting `AnimationEvent`s when running animations play past their `AnimationMarker`s,
+/// should run after `SamplerInterpolationSystem`.
+///
+/// Markers are emitted in playback order: backward for animations with a negative rate, and
+/// across the end of looping animations. `AnimationCommand::Step` emits the markers stepped over
+/// in the direction of the step, `AnimationCommand::SetInputValue` doesn't emit any.
+///
+/// ### Type parameters:
+///
+/// - `I`: identifier type for running animations
+/// - `T`: the component type that the animation should be applied to
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""), Default(bound = ""))]
+pub struct AnimationEventSystem<I, T> {
+    m: PhantomData<(I, T)>,
+    /// The input values of the animations last frame, by entity and control id
+    inputs: FnvHashMap<(Entity, u64), f32>,
+    previous_inputs: FnvHashMap<(Entity, u64), f32>,
+    crossed: Vec<(f32, String)>,
+}
+
+impl<I, T> AnimationEventSystem<I, T> {
+    /// Creates a new `AnimationEventSystem`
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<'a, I, T> System<'a> for AnimationEventSystem<I, T>
+where
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+    T: AnimationSampling,
+{
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, AssetStorage<Animation<T>>>,
+        Read<'a, AssetStorage<Sampler<T::Primitive>>>,
+        ReadStorage<'a, AnimationControlSet<I, T>>,
+        ReadStorage<'a, AnimationHierarchy<T>>,
+        ReadStorage<'a, SamplerControlSet<T>>,
+        Write<'a, EventChannel<AnimationEvent<I>>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            animation_storage,
+            sampler_storage,
+            controls,
+            hierarchies,
+            samplers,
+            mut events,
+        ): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("animation_event_system");
+
+        mem::swap(&mut self.inputs, &mut self.previous_inputs);
+        self.inputs.clear();
+        for (entity, control_set) in (&*entities, &controls).join() {
+            let hierarchy = hierarchies.get(entity);
+            for (id, control) in control_set.animations.iter() {
+                if !control.state.is_running() {
+                    continue;
+                }
+                let animation = match animation_storage.get(&control.animation) {
+                    Some(animation) if !animation.events.is_empty() => animation,
+                    _ => continue,
+                };
+                let duration = match animation.duration(&sampler_storage) {
+                    Some(duration) => duration,
+                    None => continue,
+                };
+                let reversed = control.rate_multiplier < 0.;
+
+                let input = control_input(entity, hierarchy, &samplers, control, duration);
+                let input = match input {
+                    Some(input) => input,
+                    None => continue,
+                };
+                self.inputs.insert((entity, control.id), input);
+
+                let previous = self.previous_inputs.get(&(entity, control.id)).cloned();
+                self.crossed.clear();
+                crossed_markers(
+                    &animation.events,
+                    previous,
+                    input,
+                    control.jump.as_ref(),
+                    reversed,
+                    duration,
+                    &mut self.crossed,
+                );
+                for (time, name) in self.crossed.drain(..) {
+                    events.single_write(AnimationEvent {
+                        entity,
+                        animation: *id,
+                        name,
+                        time,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Collects the markers played between the input value of last frame and `input`, in playback
+/// order.
+fn crossed_markers(
+    markers: &[AnimationMarker],
+    previous: Option<f32>,
+    input: f32,
+    jump: Option<&InputJump>,
+    reversed: bool,
+    duration: f32,
+    output: &mut Vec<(f32, String)>,
+) {
+    match (previous, jump) {
+        (_, Some(InputJump::Seek)) => {}
+        // Just started, markers at the start of the animation are emitted
+        (None, _) if !reversed => crossed(markers, -1., input, output),
+        (None, _) => crossed_backward(markers, duration + 1., input, output),
+        (Some(previous), Some(InputJump::Step(StepDirection::Forward))) => {
+            crossed(markers, previous, input, output);
+        }
+        (Some(previous), Some(InputJump::Step(StepDirection::Backward))) => {
+            crossed_backward(markers, previous, input, output);
+        }
+        // Looped past the end
+        (Some(previous), None) if !reversed && input < previous => {
+            crossed(markers, previous, duration, output);
+            crossed(markers, -1., input, output);
+        }
+        (Some(previous), None) if !reversed => crossed(markers, previous, input, output),
+        // Looped past the start
+        (Some(previous), None) if input > previous => {
+            crossed_backward(markers, previous, -1., output);
+            crossed_backward(markers, duration + 1., input, output);
+        }
+        (Some(previous), None) => crossed_backward(markers, previous, input, output),
+    }
+}
+
+/// Collects the markers in `(from, to]`, in increasing time.
+fn crossed(markers: &[AnimationMarker], from: f32, to: f32, output: &mut Vec<(f32, String)>) {
+    let start = output.len();
+    output.extend(
+        markers
+            .iter()
+            .filter(|marker| marker.time > from && marker.time <= to)
+            .map(|marker| (marker.time, marker.name.clone())),
+    );
+    output[start..].sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+}
+
+/// Collects the markers in `[to, from)`, in decreasing time.
+fn crossed_backward(
+    markers: &[AnimationMarker],
+    from: f32,
+    to: f32,
+    output: &mut Vec<(f32, String)>,
+) {
+    let start = output.len();
+    output.extend(
+        markers
+            .iter()
+            .filter(|marker| marker.time < from && marker.time >= to)
+            .map(|marker| (marker.time, marker.name.clone())),
+    );
+    output[start..].sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markers() -> Vec<AnimationMarker> {
+        vec![
+            AnimationMarker {
+                time: 0.5,
+                name: "b".to_string(),
+            },
+            AnimationMarker {
+                time: 0.,
+                name: "a".to_string(),
+            },
+            AnimationMarker {
+                time: 1.,
+                name: "c".to_string(),
+            },
+        ]
+    }
+
+    fn names(
+        previous: Option<f32>,
+        input: f32,
+        jump: Option<&InputJump>,
+        reversed: bool,
+    ) -> Vec<String> {
+        let mut output = Vec::new();
+        crossed_markers(&markers(), previous, input, jump, reversed, 1., &mut output);
+        output.into_iter().map(|(_, name)| name).collect()
+    }
+
+    #[test]
+    fn crossed_range() {
+        let mut output = Vec::new();
+        crossed(&markers(), 0., 1., &mut output);
+        assert_eq!(output, vec![(0.5, "b".to_string()), (1., "c".to_string())]);
+        output.clear();
+        crossed_backward(&markers(), 1., 0., &mut output);
+        assert_eq!(output, vec![(0.5, "b".to_string()), (0., "a".to_string())]);
+    }
+
+    #[test]
+    fn start() {
+        assert_eq!(names(None, 0.25, None, false), vec!["a"]);
+        assert_eq!(names(None, 0.75, None, true), vec!["c"]);
+    }
+
+    #[test]
+    fn playback() {
+        assert_eq!(names(Some(0.), 1., None, false), vec!["b", "c"]);
+        assert_eq!(names(Some(1.), 0., None, true), vec!["b", "a"]);
+        assert!(names(Some(0.6), 0.9, None, false).is_empty());
+    }
+
+    #[test]
+    fn looped() {
+        assert_eq!(names(Some(0.75), 0.25, None, false), vec!["c", "a"]);
+        assert_eq!(names(Some(0.25), 0.75, None, true), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn step() {
+        let forward = InputJump::Step(StepDirection::Forward);
+        let backward = InputJump::Step(StepDirection::Backward);
+        assert_eq!(names(Some(0.25), 0.5, Some(&forward), true), vec!["b"]);
+        assert_eq!(names(Some(0.75), 0.5, Some(&backward), false), vec!["b"]);
+        assert!(names(Some(0.5), 0.5, Some(&backward), false).is_empty());
+    }
+
+    #[test]
+    fn seek() {
+        assert!(names(Some(0.), 1., Some(&InputJump::Seek), false).is_empty());
+    }
+}
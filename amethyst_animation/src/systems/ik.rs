@@ -0,0 +1,463 @@
+use std::mem;
+
+use fnv::FnvHashMap;
+
+use amethyst_core::{
+    ecs::prelude::{Entity, Join, ReadStorage, System, WriteStorage},
+    math::{Matrix4, UnitQuaternion, Vector3},
+    Parent, Transform,
+};
+
+use log::error;
+
+use crate::ik::{IkChain, IkSolver, IkTarget};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+const EPSILON: f32 = 1.0e-5;
+
+/// System solving the `IkChain`s, rotating the `Transform`s of their joints.
+///
+/// Needs to run after the sampling systems and before the `TransformSystem`, world space positions
+/// are computed from the local transforms of the current frame. Rotations are solved ignoring
+/// non-uniform scaling.
+///
+/// The rotation limits and blend weight of the chains are relative to the animated rotations of the
+/// joints. When a joint still has the rotation solved last frame, because no animation sampled it
+/// since, its animated rotation from last frame is restored before solving.
+#[derive(Default, Debug)]
+pub struct InverseKinematicsSystem {
+    /// The rotations solved this frame, with the animated rotations they replaced, by joint
+    poses: FnvHashMap<Entity, (UnitQuaternion<f32>, UnitQuaternion<f32>)>,
+    previous_poses: FnvHashMap<Entity, (UnitQuaternion<f32>, UnitQuaternion<f32>)>,
+}
+
+impl InverseKinematicsSystem {
+    /// Creates a new `InverseKinematicsSystem`
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<'a> System<'a> for InverseKinematicsSystem {
+    type SystemData = (
+        ReadStorage<'a, IkChain>,
+        ReadStorage<'a, Parent>,
+        WriteStorage<'a, Transform>,
+    );
+
+    fn run(&mut self, (chains, parents, mut transforms): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("inverse_kinematics_system");
+
+        mem::swap(&mut self.poses, &mut self.previous_poses);
+        self.poses.clear();
+        for chain in chains.join() {
+            for joint in &chain.joints {
+                if let (Some(transform), Some((solved, animated))) = (
+                    transforms.get_mut(joint.entity),
+                    self.previous_poses.get(&joint.entity),
+                ) {
+                    if transform.rotation() == solved {
+                        *transform.rotation_mut() = *animated;
+                    }
+                }
+            }
+            if chain.weight <= 0. || chain.joints.len() < 2 {
+                continue;
+            }
+            if chain.solver == IkSolver::TwoBone && chain.joints.len() != 3 {
+                error!(
+                    "Two bone IK chain with {} joints instead of 3, skipping",
+                    chain.joints.len()
+                );
+                continue;
+            }
+            solve_chain(chain, &parents, &mut transforms, &mut self.poses);
+        }
+    }
+}
+
+fn solve_chain(
+    chain: &IkChain,
+    parents: &ReadStorage<'_, Parent>,
+    transforms: &mut WriteStorage<'_, Transform>,
+    poses: &mut FnvHashMap<Entity, (UnitQuaternion<f32>, UnitQuaternion<f32>)>,
+) {
+    let positions = match chain
+        .joints
+        .iter()
+        .map(|joint| world_position(joint.entity, parents, transforms))
+        .collect::<Option<Vec<_>>>()
+    {
+        Some(positions) => positions,
+        None => return,
+    };
+    let resolve = |target: &IkTarget| match *target {
+        IkTarget::Position(position) => Some(position),
+        IkTarget::Entity(entity) => world_position(entity, parents, transforms),
+    };
+    let target = match resolve(&chain.target) {
+        Some(target) => target,
+        None => return,
+    };
+    let pole = chain.pole.as_ref().and_then(resolve);
+    let lengths = positions
+        .windows(2)
+        .map(|bone| (bone[1] - bone[0]).norm())
+        .collect::<Vec<_>>();
+
+    let mut solved = positions.clone();
+    match chain.solver {
+        IkSolver::TwoBone => solve_two_bone(&mut solved, &lengths, target, pole),
+        IkSolver::Fabrik {
+            iterations,
+            tolerance,
+        } => {
+            solve_fabrik(&mut solved, &lengths, target, iterations, tolerance);
+            if let Some(pole) = pole {
+                bend_toward(&mut solved, pole);
+            }
+        }
+        IkSolver::Ccd {
+            iterations,
+            tolerance,
+        } => {
+            solve_ccd(&mut solved, target, iterations, tolerance);
+            if let Some(pole) = pole {
+                bend_toward(&mut solved, pole);
+            }
+        }
+    }
+
+    // Rotate the joints from the root, so each bone points toward its solved position
+    for (index, joint) in chain.joints.iter().enumerate().take(solved.len() - 1) {
+        let child = chain.joints[index + 1].entity;
+        let current = match (
+            world_position(joint.entity, parents, transforms),
+            world_position(child, parents, transforms),
+        ) {
+            (Some(start), Some(end)) => end - start,
+            _ => return,
+        };
+        let desired = solved[index + 1] - solved[index];
+        let delta = match UnitQuaternion::rotation_between(&current, &desired) {
+            Some(delta) => delta,
+            None => continue,
+        };
+        let parent_rotation = parents
+            .get(joint.entity)
+            .map_or_else(UnitQuaternion::identity, |parent| {
+                world_rotation(parent.entity, parents, transforms)
+            });
+        let transform = match transforms.get_mut(joint.entity) {
+            Some(transform) => transform,
+            None => return,
+        };
+        let animated = *transform.rotation();
+        let mut rotation = parent_rotation.inverse() * delta * parent_rotation * animated;
+        if let Some(max_rotation) = joint.max_rotation {
+            let angle = animated.angle_to(&rotation);
+            if angle > max_rotation {
+                rotation = interpolate(&animated, &rotation, max_rotation / angle);
+            }
+        }
+        if chain.weight < 1. {
+            rotation = interpolate(&animated, &rotation, chain.weight);
+        }
+        *transform.rotation_mut() = rotation;
+        poses.insert(joint.entity, (rotation, animated));
+    }
+}
+
+/// Place the middle joint with the law of cosines, in the plane of the pole or of the current bend
+fn solve_two_bone(
+    positions: &mut [Vector3<f32>],
+    lengths: &[f32],
+    target: Vector3<f32>,
+    pole: Option<Vector3<f32>>,
+) {
+    let (upper, lower) = (lengths[0], lengths[1]);
+    let root = positions[0];
+    let to_target = target - root;
+    let direction = match to_target.try_normalize(EPSILON) {
+        Some(direction) => direction,
+        None => return,
+    };
+    let distance = to_target
+        .norm()
+        .max((upper - lower).abs() + EPSILON)
+        .min(upper + lower - EPSILON);
+
+    let hint = pole.unwrap_or(positions[1]) - root;
+    let bend = (hint - direction * direction.dot(&hint))
+        .try_normalize(EPSILON)
+        .or_else(|| direction.cross(&Vector3::y()).try_normalize(EPSILON))
+        .unwrap_or_else(Vector3::x);
+    let cos = ((upper * upper + distance * distance - lower * lower) / (2. * upper * distance))
+        .max(-1.)
+        .min(1.);
+    let sin = (1. - cos * cos).sqrt();
+    positions[1] = root + direction * upper * cos + bend * upper * sin;
+    positions[2] = root + direction * distance;
+}
+
+fn solve_fabrik(
+    positions: &mut [Vector3<f32>],
+    lengths: &[f32],
+    target: Vector3<f32>,
+    iterations: usize,
+    tolerance: f32,
+) {
+    let last = positions.len() - 1;
+    let root = positions[0];
+    // Out of reach, stretch the chain toward the target
+    if (target - root).norm() >= lengths.iter().sum::<f32>() {
+        for i in 0..last {
+            let direction = normalize_or(target - positions[i], positions[i + 1] - positions[i]);
+            positions[i + 1] = positions[i] + direction * lengths[i];
+        }
+        return;
+    }
+    for _ in 0..iterations {
+        if (positions[last] - target).norm() <= tolerance {
+            break;
+        }
+        positions[last] = target;
+        for i in (0..last).rev() {
+            let direction = normalize_or(positions[i] - positions[i + 1], Vector3::y());
+            positions[i] = positions[i + 1] + direction * lengths[i];
+        }
+        positions[0] = root;
+        for i in 0..last {
+            let direction = normalize_or(positions[i + 1] - positions[i], Vector3::y());
+            positions[i + 1] = positions[i] + direction * lengths[i];
+        }
+    }
+}
+
+fn solve_ccd(
+    positions: &mut [Vector3<f32>],
+    target: Vector3<f32>,
+    iterations: usize,
+    tolerance: f32,
+) {
+    let last = positions.len() - 1;
+    for _ in 0..iterations {
+        if (positions[last] - target).norm() <= tolerance {
+            break;
+        }
+        for i in (0..last).rev() {
+            let pivot = positions[i];
+            let rotation = match UnitQuaternion::rotation_between(
+                &(positions[last] - pivot),
+                &(target - pivot),
+            ) {
+                Some(rotation) => rotation,
+                None => continue,
+            };
+            for position in &mut positions[i + 1..] {
+                *position = pivot + rotation * (*position - pivot);
+            }
+        }
+    }
+}
+
+/// Rotate each inner joint around the line through its neighbours, toward the pole
+fn bend_toward(positions: &mut [Vector3<f32>], pole: Vector3<f32>) {
+    for i in 1..positions.len() - 1 {
+        let start = positions[i - 1];
+        let axis = match (positions[i + 1] - start).try_normalize(EPSILON) {
+            Some(axis) => axis,
+            None => continue,
+        };
+        let joint = positions[i] - start;
+        let to_pole = pole - start;
+        let rotation = UnitQuaternion::rotation_between(
+            &(joint - axis * axis.dot(&joint)),
+            &(to_pole - axis * axis.dot(&to_pole)),
+        );
+        if let Some(rotation) = rotation {
+            positions[i] = start + rotation * joint;
+        }
+    }
+}
+
+fn normalize_or(vector: Vector3<f32>, fallback: Vector3<f32>) -> Vector3<f32> {
+    vector
+        .try_normalize(EPSILON)
+        .or_else(|| fallback.try_normalize(EPSILON))
+        .unwrap_or_else(Vector3::y)
+}
+
+fn interpolate(
+    from: &UnitQuaternion<f32>,
+    to: &UnitQuaternion<f32>,
+    t: f32,
+) -> UnitQuaternion<f32> {
+    from.try_slerp(to, t, EPSILON).unwrap_or(*to)
+}
+
+fn world_matrix(
+    entity: Entity,
+    parents: &ReadStorage<'_, Parent>,
+    transforms: &WriteStorage<'_, Transform>,
+) -> Option<Matrix4<f32>> {
+    let mut matrix = transforms.get(entity)?.matrix();
+    let mut current = entity;
+    while let Some(parent) = parents.get(current) {
+        current = parent.entity;
+        match transforms.get(current) {
+            Some(transform) => matrix = transform.matrix() * matrix,
+            None => break,
+        }
+    }
+    Some(matrix)
+}
+
+fn world_position(
+    entity: Entity,
+    parents: &ReadStorage<'_, Parent>,
+    transforms: &WriteStorage<'_, Transform>,
+) -> Option<Vector3<f32>> {
+    world_matrix(entity, parents, transforms)
+        .map(|matrix| Vector3::new(matrix[(0, 3)], matrix[(1, 3)], matrix[(2, 3)]))
+}
+
+fn world_rotation(
+    entity: Entity,
+    parents: &ReadStorage<'_, Parent>,
+    transforms: &WriteStorage<'_, Transform>,
+) -> UnitQuaternion<f32> {
+    let mut rotation = UnitQuaternion::identity();
+    let mut current = Some(entity);
+    while let Some(entity) = current {
+        match transforms.get(entity) {
+            Some(transform) => rotation = transform.rotation() * rotation,
+            None => break,
+        }
+        current = parents.get(entity).map(|parent| parent.entity);
+    }
+    rotation
+}
+
+#[cfg(test)]
+mod tests {
+    use amethyst_core::ecs::prelude::{Builder, RunNow, SystemData, World, WorldExt};
+
+    use super::*;
+
+    /// A vertical chain of two bones of length 1 from the origin, and a chain reaching to the
+    /// side
+    fn setup(chain: impl FnOnce(Entity, Entity, Entity) -> IkChain) -> (World, [Entity; 3]) {
+        let mut world = World::new();
+        <InverseKinematicsSystem as System<'_>>::SystemData::setup(&mut world);
+        let root = world.create_entity().with(Transform::default()).build();
+        let mut bone = Transform::default();
+        bone.set_translation_xyz(0., 1., 0.);
+        let middle = world
+            .create_entity()
+            .with(bone.clone())
+            .with(Parent { entity: root })
+            .build();
+        let end = world
+            .create_entity()
+            .with(bone)
+            .with(Parent { entity: middle })
+            .build();
+        world.create_entity().with(chain(root, middle, end)).build();
+        (world, [root, middle, end])
+    }
+
+    fn target() -> IkTarget {
+        IkTarget::Position(Vector3::new(1., 1., 0.))
+    }
+
+    fn position(world: &World, entity: Entity) -> Vector3<f32> {
+        world_position(entity, &world.read_storage(), &world.write_storage()).unwrap()
+    }
+
+    fn rotation(world: &World, entity: Entity) -> UnitQuaternion<f32> {
+        *world
+            .read_storage::<Transform>()
+            .get(entity)
+            .unwrap()
+            .rotation()
+    }
+
+    #[test]
+    fn two_bone_reaches_target() {
+        let (world, [_, _, end]) = setup(|root, middle, end| {
+            IkChain::two_bone(root, middle, end, target())
+                .with_pole(IkTarget::Position(Vector3::new(0., 2., 1.)))
+        });
+        let mut system = InverseKinematicsSystem::new();
+        for _ in 0..3 {
+            system.run_now(&world);
+            assert!((position(&world, end) - Vector3::new(1., 1., 0.)).norm() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn clamped_joint() {
+        let (world, [root, _, _]) = setup(|root, middle, end| {
+            IkChain::two_bone(root, middle, end, target()).with_max_rotation(0, 0.1)
+        });
+        let mut system = InverseKinematicsSystem::new();
+        system.run_now(&world);
+        let clamped = rotation(&world, root);
+        assert!((clamped.angle() - 0.1).abs() < 1e-4);
+        // Clamped from the animated pose again, not from the solved one
+        for _ in 0..3 {
+            system.run_now(&world);
+        }
+        assert!(rotation(&world, root).angle_to(&clamped) < 1e-4);
+    }
+
+    #[test]
+    fn weight() {
+        let (world, [root, middle, end]) = setup(|root, middle, end| {
+            IkChain::two_bone(root, middle, end, target()).with_weight(0.)
+        });
+        let mut system = InverseKinematicsSystem::new();
+        system.run_now(&world);
+        for entity in &[root, middle, end] {
+            assert!(rotation(&world, *entity).angle() < 1e-6);
+        }
+        assert!((position(&world, end) - Vector3::new(0., 2., 0.)).norm() < 1e-6);
+
+        for chain in (&mut world.write_storage::<IkChain>()).join() {
+            chain.weight = 0.5;
+        }
+        system.run_now(&world);
+        let half = rotation(&world, root);
+        assert!(half.angle() > 1e-3);
+        system.run_now(&world);
+        assert!(rotation(&world, root).angle_to(&half) < 1e-4);
+
+        // Back to the animated pose
+        for chain in (&mut world.write_storage::<IkChain>()).join() {
+            chain.weight = 0.;
+        }
+        system.run_now(&world);
+        assert!((position(&world, end) - Vector3::new(0., 2., 0.)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn resampled_joint() {
+        let (world, [root, _, end]) =
+            setup(|root, middle, end| IkChain::two_bone(root, middle, end, target()));
+        let mut system = InverseKinematicsSystem::new();
+        system.run_now(&world);
+        // A new animated rotation replaces the solved one
+        let animated = UnitQuaternion::from_axis_angle(&Vector3::z_axis(), 0.2);
+        world
+            .write_storage::<Transform>()
+            .get_mut(root)
+            .unwrap()
+            .set_rotation(animated);
+        system.run_now(&world);
+        assert!((position(&world, end) - Vector3::new(1., 1., 0.)).norm() < 1e-3);
+    }
+}
@@ -1,17 +1,33 @@
 use amethyst_assets::Processor;
 
-use crate::resources::{Animation, Sampler};
+use crate::{
+    animator::AnimatorGraph,
+    resources::{Animation, Sampler},
+};
 
 pub use self::{
+    animator::{AnimatorSystem, AnimatorSystemDesc},
     control::{AnimationControlSystem, AnimationControlSystemDesc},
+    events::{AnimationEventSystem, AnimationEventSystemDesc},
+    ik::InverseKinematicsSystem,
+    root_motion::{RootMotionSystem, RootMotionSystemDesc},
     sampling::SamplerInterpolationSystem,
+    tween::TweenSystem,
 };
 
+mod animator;
 mod control;
+mod events;
+mod ik;
+mod root_motion;
 mod sampling;
+mod tween;
 
 /// Asset storage processor for `Sampler`
 pub type SamplerProcessor<S> = Processor<Sampler<S>>;
 
 /// Asset storage processor for `Animation`
 pub type AnimationProcessor<T> = Processor<Animation<T>>;
+
+/// Asset storage processor for `AnimatorGraph`
+pub type AnimatorGraphProcessor<I> = Processor<AnimatorGraph<I>>;
@@ -0,0 +1,468 @@
+use std::{hash::Hash, marker::PhantomData, mem};
+
+use derivative::Derivative;
+use fnv::FnvHashMap;
+use log::error;
+
+use amethyst_assets::AssetStorage;
+use amethyst_core::{
+    ecs::prelude::{
+        Entities, Entity, Join, Read, ReadStorage, System, SystemData, World, WriteStorage,
+    },
+    math::{Quaternion, UnitQuaternion, Vector3, Vector4},
+    SystemDesc, Transform,
+};
+
+use crate::{
+    resources::{
+        Animation, AnimationControl, AnimationControlSet, AnimationHierarchy, InputJump,
+        RootMotion, Sampler, SamplerControlSet,
+    },
+    transform::{RootMotionDelta, TransformChannel},
+    util::{control_input, SamplerPrimitive},
+};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+/// Builds a `RootMotionSystem`.
+#[derive(Derivative, Debug)]
+#[derivative(Default(bound = ""))]
+pub struct RootMotionSystemDesc<I> {
+    marker: PhantomData<I>,
+}
+
+impl<'a, 'b, I> SystemDesc<'a, 'b, RootMotionSystem<I>> for RootMotionSystemDesc<I>
+where
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    fn build(self, world: &mut World) -> RootMotionSystem<I> {
+        <RootMotionSystem<I> as System<'_>>::SystemData::setup(world);
+
+        RootMotionSystem::new()
+    }
+}
+
+/// System extracting the motion of the nodes selected by the `RootMotion` of running `Transform`
+/// animations, should run after the `SamplerInterpolationSystem` for `Transform` and before the
+/// `TransformSystem`. The `RootMotionBundle` adds it in this order.
+///
+/// Keeps the extracted channels of the node at their value at the start of the animation, and
+/// writes the motion they would have made during the frame, weighted by the blend weight of the
+/// animation, to the `RootMotionDelta` of the animated entity. Loops are followed across the end
+/// of the animation, `AnimationCommand::SetInputValue` doesn't make any motion.
+///
+/// The motion is expressed in the space of the entity, assuming the node is a child of it: the
+/// translation is turned back by the rotation the node made since the start of the animation when
+/// that rotation is extracted too, so it stays relative to the facing of the entity once the
+/// extracted rotations have been applied to it. `RootMotionDelta::apply` moves the entity.
+///
+/// ### Type parameters:
+///
+/// - `I`: identifier type for running animations
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""), Default(bound = ""))]
+pub struct RootMotionSystem<I> {
+    m: PhantomData<I>,
+    /// The input values of the animations last frame, by entity and control id
+    inputs: FnvHashMap<(Entity, u64), f32>,
+    previous_inputs: FnvHashMap<(Entity, u64), f32>,
+}
+
+impl<I> RootMotionSystem<I> {
+    /// Creates a new `RootMotionSystem`
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<'a, I> System<'a> for RootMotionSystem<I>
+where
+    I: Copy + Eq + Hash + Send + Sync + 'static,
+{
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, AssetStorage<Animation<Transform>>>,
+        Read<'a, AssetStorage<Sampler<SamplerPrimitive<f32>>>>,
+        ReadStorage<'a, AnimationControlSet<I, Transform>>,
+        ReadStorage<'a, AnimationHierarchy<Transform>>,
+        ReadStorage<'a, SamplerControlSet<Transform>>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, RootMotionDelta>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            animation_storage,
+            sampler_storage,
+            controls,
+            hierarchies,
+            samplers,
+            mut transforms,
+            mut deltas,
+        ): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("root_motion_system");
+
+        mem::swap(&mut self.inputs, &mut self.previous_inputs);
+        self.inputs.clear();
+        for (entity, control_set) in (&*entities, &controls).join() {
+            let hierarchy = hierarchies.get(entity);
+            let mut delta = RootMotionDelta::default();
+            let mut extracted = false;
+            for (_, control) in &control_set.animations {
+                let root_motion = match control.root_motion {
+                    Some(ref root_motion) if control.state.is_running() => root_motion,
+                    _ => continue,
+                };
+                let animation = match animation_storage.get(&control.animation) {
+                    Some(animation) => animation,
+                    None => continue,
+                };
+                let node_entity = match hierarchy {
+                    Some(hierarchy) => hierarchy.nodes.get(&root_motion.node).cloned(),
+                    None => Some(entity),
+                };
+                let (node_entity, duration) =
+                    match (node_entity, animation.duration(&sampler_storage)) {
+                        (Some(node_entity), Some(duration)) => (node_entity, duration),
+                        _ => continue,
+                    };
+                let input = match control_input(entity, hierarchy, &samplers, control, duration) {
+                    Some(input) => input,
+                    None => continue,
+                };
+                self.inputs.insert((entity, control.id), input);
+                extracted = true;
+
+                let weight = samplers
+                    .get(node_entity)
+                    .and_then(|set| {
+                        set.samplers
+                            .iter()
+                            .find(|sampler| sampler.control_id == control.id)
+                    })
+                    .map_or(0., |sampler| sampler.blend_weight);
+                let segments = match self.previous_inputs.get(&(entity, control.id)) {
+                    Some(previous) => segments(*previous, input, duration, control),
+                    None => Vec::new(),
+                };
+                let transform = match transforms.get_mut(node_entity) {
+                    Some(transform) => transform,
+                    None => continue,
+                };
+                let sample = |channel| {
+                    animation
+                        .nodes
+                        .iter()
+                        .find(|(node, c, _)| *node == root_motion.node && *c == channel)
+                        .and_then(|(_, _, handle)| sampler_storage.get(handle))
+                        .map(|sampler| {
+                            move |input: f32| {
+                                sampler.function.interpolate(
+                                    input,
+                                    &sampler.input,
+                                    &sampler.output,
+                                    false,
+                                )
+                            }
+                        })
+                };
+
+                let rotation = sample(TransformChannel::Rotation)
+                    .filter(|_| root_motion.rotation)
+                    .map(|sample| move |input| to_rotation(&sample(input)));
+                if root_motion.translation {
+                    if let Some(sample) = sample(TransformChannel::Translation) {
+                        let sample = |input| to_translation(&sample(input));
+                        for (from, to) in &segments {
+                            let facing = rotation
+                                .as_ref()
+                                .map_or_else(UnitQuaternion::identity, |rotation| {
+                                    rotation(0.) * rotation(*from).inverse()
+                                });
+                            delta.translation += facing * (sample(*to) - sample(*from)) * weight;
+                        }
+                        // Remove the contribution of the animation to the blended translation
+                        let offset = (sample(input) - sample(0.)) * weight;
+                        *transform.translation_mut() -= offset;
+                    }
+                }
+                if let Some(sample) = rotation {
+                    // The rotation of the node relative to its start, turned into the space of
+                    // the entity
+                    let start = sample(0.);
+                    for (from, to) in &segments {
+                        let rotation =
+                            start * sample(*from).inverse() * sample(*to) * start.inverse();
+                        delta.rotation *= weighted(rotation, weight);
+                    }
+                    let offset = sample(input).inverse() * start;
+                    *transform.rotation_mut() *= weighted(offset, weight);
+                }
+            }
+
+            if extracted {
+                if let Err(err) = deltas.insert(entity, delta) {
+                    error!("Failed to write the root motion of an entity: {}", err);
+                }
+            } else if let Some(delta) = deltas.get_mut(entity) {
+                *delta = RootMotionDelta::default();
+            }
+        }
+    }
+}
+
+/// Ranges of input values played since the last frame, split where the animation looped.
+fn segments(
+    previous: f32,
+    input: f32,
+    duration: f32,
+    control: &AnimationControl<Transform>,
+) -> Vec<(f32, f32)> {
+    let reversed = control.rate_multiplier < 0.;
+    match control.jump {
+        Some(InputJump::Seek) => Vec::new(),
+        Some(InputJump::Step(_)) => vec![(previous, input)],
+        None if !reversed && input < previous => vec![(previous, duration), (0., input)],
+        None if reversed && input > previous => vec![(previous, 0.), (duration, input)],
+        None => vec![(previous, input)],
+    }
+}
+
+fn to_translation(primitive: &SamplerPrimitive<f32>) -> Vector3<f32> {
+    match *primitive {
+        SamplerPrimitive::Vec3(ref t) => Vector3::new(t[0], t[1], t[2]),
+        _ => Vector3::zeros(),
+    }
+}
+
+fn to_rotation(primitive: &SamplerPrimitive<f32>) -> UnitQuaternion<f32> {
+    match *primitive {
+        SamplerPrimitive::Vec4(ref r) => {
+            UnitQuaternion::new_normalize(Quaternion::from(Vector4::from(*r)))
+        }
+        _ => UnitQuaternion::identity(),
+    }
+}
+
+/// Interpolate from the identity to the rotation by the weight
+fn weighted(rotation: UnitQuaternion<f32>, weight: f32) -> UnitQuaternion<f32> {
+    UnitQuaternion::identity()
+        .try_slerp(&rotation, weight, 1.0e-6)
+        .unwrap_or(rotation)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    use amethyst_core::{
+        ecs::prelude::{Builder, RunNow, WorldExt},
+        secs_to_duration,
+    };
+    use minterpolate::InterpolationFunction;
+
+    use super::*;
+    use crate::resources::{AnimationCommand, BlendMode, ControlState, EndControl, SamplerControl};
+
+    fn sampler(output: Vec<SamplerPrimitive<f32>>) -> Sampler<SamplerPrimitive<f32>> {
+        Sampler {
+            input: vec![0., 1.],
+            output,
+            function: InterpolationFunction::Linear,
+        }
+    }
+
+    /// Walks 2 along x in one second, turning a quarter around y when `turn` is set.
+    fn setup(turn: bool) -> (World, RootMotionSystem<u32>, Entity) {
+        let mut world = World::new();
+        let system = RootMotionSystemDesc::<u32>::default().build(&mut world);
+
+        let mut animation = Animation::new();
+        {
+            let mut samplers =
+                world.write_resource::<AssetStorage<Sampler<SamplerPrimitive<f32>>>>();
+            animation.add(
+                0,
+                TransformChannel::Translation,
+                samplers.insert(sampler(vec![
+                    SamplerPrimitive::Vec3([0., 0., 0.]),
+                    SamplerPrimitive::Vec3([2., 0., 0.]),
+                ])),
+            );
+            animation.add(
+                0,
+                TransformChannel::Rotation,
+                samplers.insert(sampler(vec![
+                    SamplerPrimitive::Vec4([0., 0., 0., 1.]),
+                    SamplerPrimitive::Vec4([0., FRAC_PI_4.sin(), 0., FRAC_PI_4.cos()]),
+                ])),
+            );
+        }
+        let animation = world
+            .write_resource::<AssetStorage<Animation<Transform>>>()
+            .insert(animation);
+
+        let mut control = AnimationControl::new(
+            animation,
+            EndControl::Loop(None),
+            ControlState::Running(secs_to_duration(0.)),
+            AnimationCommand::Start,
+            1.,
+        );
+        control.id = 1;
+        control.root_motion = Some(RootMotion::new(0).with_rotation(turn));
+        let mut control_set = AnimationControlSet::default();
+        control_set.animations.push((0, control));
+
+        let entity = world
+            .create_entity()
+            .with(control_set)
+            .with(SamplerControlSet::<Transform>::default())
+            .with(Transform::default())
+            .build();
+        (world, system, entity)
+    }
+
+    /// Run the system with the samplers of the animation at `input`
+    fn run(world: &mut World, system: &mut RootMotionSystem<u32>, entity: Entity, input: f32) {
+        {
+            let handle = world
+                .read_storage::<AnimationControlSet<u32, Transform>>()
+                .get(entity)
+                .unwrap()
+                .animations[0]
+                .1
+                .animation
+                .clone();
+            let mut sets = world.write_storage::<SamplerControlSet<Transform>>();
+            let set = sets.get_mut(entity).unwrap();
+            set.samplers.clear();
+            set.add_control(SamplerControl {
+                control_id: 1,
+                channel: TransformChannel::Translation,
+                blend_weight: 1.,
+                blend_mode: BlendMode::Override,
+                fade: None,
+                sampler: world
+                    .read_resource::<AssetStorage<Animation<Transform>>>()
+                    .get(&handle)
+                    .unwrap()
+                    .nodes[0]
+                    .2
+                    .clone(),
+                state: ControlState::Running(secs_to_duration(input)),
+                end: EndControl::Loop(None),
+                after: SamplerPrimitive::Vec3([0., 0., 0.]),
+                rate_multiplier: 1.,
+            });
+            // As sampled by the `SamplerInterpolationSystem`
+            let mut transforms = world.write_storage::<Transform>();
+            transforms
+                .get_mut(entity)
+                .unwrap()
+                .set_translation_xyz(2. * input, 0., 0.);
+        }
+        system.run_now(world);
+        world.maintain();
+    }
+
+    fn delta(world: &World, entity: Entity) -> RootMotionDelta {
+        world
+            .read_storage::<RootMotionDelta>()
+            .get(entity)
+            .cloned()
+            .unwrap()
+    }
+
+    fn assert_near(a: &Vector3<f32>, b: &Vector3<f32>) {
+        assert!((a - b).norm() < 1e-4, "Expected {:?}, got {:?}", b, a);
+    }
+
+    #[test]
+    fn segments_follow_loops() {
+        let handle = AssetStorage::<Animation<Transform>>::new().insert(Animation::new());
+        let mut control = AnimationControl::new(
+            handle,
+            EndControl::Loop(None),
+            ControlState::Requested,
+            AnimationCommand::Start,
+            1.,
+        );
+        assert_eq!(segments(0.25, 0.75, 1., &control), vec![(0.25, 0.75)]);
+        assert_eq!(
+            segments(0.75, 0.25, 1., &control),
+            vec![(0.75, 1.), (0., 0.25)]
+        );
+        control.rate_multiplier = -1.;
+        assert_eq!(
+            segments(0.25, 0.75, 1., &control),
+            vec![(0.25, 0.), (1., 0.75)]
+        );
+        control.jump = Some(InputJump::Seek);
+        assert!(segments(0.25, 0.75, 1., &control).is_empty());
+    }
+
+    #[test]
+    fn translation() {
+        let (mut world, mut system, entity) = setup(false);
+        run(&mut world, &mut system, entity, 0.25);
+        assert_near(&delta(&world, entity).translation, &Vector3::zeros());
+        // The node is kept at its start
+        assert_near(
+            world
+                .read_storage::<Transform>()
+                .get(entity)
+                .unwrap()
+                .translation(),
+            &Vector3::zeros(),
+        );
+
+        run(&mut world, &mut system, entity, 0.75);
+        assert_near(
+            &delta(&world, entity).translation,
+            &Vector3::new(1., 0., 0.),
+        );
+
+        // Looped past the end
+        run(&mut world, &mut system, entity, 0.25);
+        assert_near(
+            &delta(&world, entity).translation,
+            &Vector3::new(1., 0., 0.),
+        );
+    }
+
+    #[test]
+    fn translation_follows_facing() {
+        let (mut world, mut system, entity) = setup(true);
+        run(&mut world, &mut system, entity, 0.5);
+        run(&mut world, &mut system, entity, 1.);
+        let delta = delta(&world, entity);
+
+        // Turned an eighth around y since the start, the motion along x is turned back
+        let eighth = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), FRAC_PI_4);
+        assert_near(
+            &delta.translation,
+            &(eighth.inverse() * Vector3::new(1., 0., 0.)),
+        );
+        assert!(delta.rotation.angle_to(&eighth) < 1e-4);
+
+        let mut transform = Transform::default();
+        transform.set_rotation_y_axis(FRAC_PI_4);
+        delta.apply(&mut transform);
+        assert_near(transform.translation(), &Vector3::new(1., 0., 0.));
+    }
+
+    #[test]
+    fn weighted_rotation() {
+        let quarter = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), FRAC_PI_2);
+        let eighth = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), FRAC_PI_4);
+        assert!(weighted(quarter, 0.5).angle_to(&eighth) < 1e-4);
+        assert!(weighted(quarter, 0.).angle() < 1e-4);
+    }
+}
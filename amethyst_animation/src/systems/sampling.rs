@@ -11,8 +11,8 @@ use amethyst_core::{
 };
 
 use crate::resources::{
-    AnimationSampling, ApplyData, BlendMethod, ControlState, EndControl, Sampler, SamplerControl,
-    SamplerControlSet,
+    AnimationSampling, ApplyData, BlendMethod, BlendMode, ControlState, EndControl, Sampler,
+    SamplerControl, SamplerControlSet,
 };
 
 #[cfg(feature = "profiler")]
@@ -24,7 +24,7 @@ use thread_profiler::profile_scope;
 /// on `AnimationControlSystem`.
 ///
 /// Will process all active `SamplerControlSet`, and update the target component for the entity they
-/// belong to.
+/// belong to. Ramps the blend weights of the samplers with a `BlendFade`.
 ///
 /// ### Type parameters:
 ///
@@ -36,6 +36,7 @@ where
 {
     m: marker::PhantomData<T>,
     inner: Vec<(f32, T::Channel, T::Primitive)>,
+    additive: Vec<(T::Channel, T::Primitive, T::Primitive)>,
     channels: Vec<T::Channel>,
 }
 
@@ -48,6 +49,7 @@ where
         Self {
             m: marker::PhantomData,
             inner: Vec::default(),
+            additive: Vec::default(),
             channels: Vec::default(),
         }
     }
@@ -71,15 +73,22 @@ where
 
         for (control_set, comp) in (&mut control_sets, &mut comps).join() {
             self.inner.clear();
+            self.additive.clear();
             for control in control_set.samplers.iter_mut() {
                 if let Some(ref sampler) = samplers.get(&control.sampler) {
-                    process_sampler(control, sampler, &time, &mut self.inner);
+                    process_sampler(control, sampler, &time, &mut self.inner, &mut self.additive);
                 }
             }
-            if !self.inner.is_empty() {
+            if !self.inner.is_empty() || !self.additive.is_empty() {
                 self.channels.clear();
-                self.channels
-                    .extend(self.inner.iter().map(|o| &o.1).unique().cloned());
+                self.channels.extend(
+                    self.inner
+                        .iter()
+                        .map(|o| &o.1)
+                        .chain(self.additive.iter().map(|o| &o.0))
+                        .unique()
+                        .cloned(),
+                );
                 for channel in &self.channels {
                     match comp.blend_method(channel) {
                         None => {
@@ -95,7 +104,9 @@ where
                         }
 
                         Some(BlendMethod::Linear) => {
-                            if let Some(p) = linear_blend::<T>(channel, &self.inner) {
+                            if let Some(p) =
+                                additive_blend::<T>(channel, &self.inner, &self.additive)
+                            {
                                 comp.apply_sample(channel, &p, &apply_data);
                             }
                         }
@@ -114,16 +125,24 @@ where
 /// - `sampler`: the sampler reference from the control object
 /// - `component`: the component to update
 /// - `now`: synchronized `Instant` for the current frame
+/// - `output`: the samples to blend, with their blend weight
+/// - `additive`: the weighted differences to add on top of the blended samples, with the rest
+///               state of the channel
 fn process_sampler<T>(
     control: &mut SamplerControl<T>,
     sampler: &Sampler<T::Primitive>,
     time: &Time,
     output: &mut Vec<(f32, T::Channel, T::Primitive)>,
+    additive: &mut Vec<(T::Channel, T::Primitive, T::Primitive)>,
 ) where
     T: AnimationSampling,
 {
     use crate::resources::ControlState::*;
 
+    if control.state.is_running() {
+        update_fade(control, time.delta_seconds());
+    }
+
     let (new_state, new_end) = update_duration_and_check(&control, sampler, time);
 
     // If a new end condition has been computed, update in control state
@@ -131,41 +150,43 @@ fn process_sampler<T>(
         control.end = end;
     }
 
+    let interpolate = |input: f32| {
+        sampler
+            .function
+            .interpolate(input, &sampler.input, &sampler.output, false)
+    };
+    let mut push = |sample: T::Primitive| match control.blend_mode {
+        BlendMode::Override => {
+            output.push((control.blend_weight, control.channel.clone(), sample));
+        }
+        BlendMode::Additive(reference) => {
+            let difference = sample.sub(&interpolate(reference));
+            additive.push((
+                control.channel.clone(),
+                difference.mul(control.blend_weight),
+                control.after.clone(),
+            ));
+        }
+    };
+
     // Do sampling
     match new_state {
         Running(duration) | Paused(duration) => {
-            output.push((
-                control.blend_weight,
-                control.channel.clone(),
-                sampler.function.interpolate(
-                    duration_to_secs(duration),
-                    &sampler.input,
-                    &sampler.output,
-                    false,
-                ),
-            ));
+            push(interpolate(duration_to_secs(duration)));
         }
         Done => {
-            if let EndControl::Normal = control.end {
-                output.push((
-                    control.blend_weight,
-                    control.channel.clone(),
-                    control.after.clone(),
-                ));
+            // The rest state is no difference to add
+            if let (EndControl::Normal, BlendMode::Override) = (&control.end, control.blend_mode) {
+                push(control.after.clone());
             }
             if let EndControl::Stay = control.end {
-                let last_frame = sampler.input.last().cloned().unwrap_or(0.);
-
-                output.push((
-                    control.blend_weight,
-                    control.channel.clone(),
-                    sampler.function.interpolate(
-                        last_frame,
-                        &sampler.input,
-                        &sampler.output,
-                        false,
-                    ),
-                ));
+                let last_frame = if control.rate_multiplier < 0. {
+                    sampler.input.first()
+                } else {
+                    sampler.input.last()
+                };
+
+                push(interpolate(last_frame.cloned().unwrap_or(0.)));
             }
         }
         _ => {}
@@ -175,6 +196,25 @@ fn process_sampler<T>(
     control.state = new_state;
 }
 
+/// Ramp the blend weight of a sampler, abort it at the end of the ramp if requested.
+fn update_fade<T>(control: &mut SamplerControl<T>, delta: f32)
+where
+    T: AnimationSampling,
+{
+    if let Some(mut fade) = control.fade.take() {
+        if fade.duration <= delta {
+            control.blend_weight = fade.target;
+            if fade.abort {
+                control.state = ControlState::Abort;
+            }
+        } else {
+            control.blend_weight += (fade.target - control.blend_weight) * delta / fade.duration;
+            fade.duration -= delta;
+            control.fade = Some(fade);
+        }
+    }
+}
+
 /// Update durations, check if the sampler is finished, start new samplers, and check for aborted
 /// samplers.
 ///
@@ -196,45 +236,57 @@ where
     T: AnimationSampling,
 {
     use crate::resources::ControlState::*;
+    let last_frame = sampler
+        .input
+        .last()
+        .cloned()
+        .map(secs_to_duration)
+        .unwrap_or(Duration::from_secs(0));
+    // `Running` holds the input value of the sampler. With a negative rate the sampler plays
+    // backward, from the last frame to the first, and the played duration is measured from the
+    // last frame. The conversion is the same both ways.
+    let reversed = control.rate_multiplier < 0.;
+    let to_input = |played: Duration| {
+        if reversed {
+            last_frame.checked_sub(played).unwrap_or_default()
+        } else {
+            played
+        }
+    };
     // Update state with new duration
     // Check duration for end of sampling
     match control.state {
         // requested sampling => start interpolating
-        Requested => (Running(Duration::from_secs(0)), None),
+        Requested => (Running(to_input(Duration::from_secs(0))), None),
 
-        // deferred start that should start now
-        Deferred(dur) => (Running(dur), None),
+        // deferred start that should start now, `dur` has already been played
+        Deferred(dur) => (Running(to_input(dur)), None),
 
         // abort sampling => end interpolating
         Abort => (Done, None),
 
         // sampling is running, update duration and check end condition
         Running(duration) => {
-            let current_dur =
-                duration + secs_to_duration(time.delta_seconds() * control.rate_multiplier);
-            let last_frame = sampler
-                .input
-                .last()
-                .cloned()
-                .map(secs_to_duration)
-                .unwrap_or(Duration::from_secs(0));
+            let current_dur = to_input(duration)
+                + secs_to_duration(time.delta_seconds() * control.rate_multiplier.abs());
             // duration is past last frame of sampling
             if current_dur > last_frame {
                 // Check end conditions
                 match control.end {
                     // Do loop control
                     EndControl::Loop(Some(i)) if i <= 1 => (Done, Some(EndControl::Normal)),
-                    EndControl::Loop(None) => {
-                        (Running(next_duration(last_frame, current_dur).0), None)
-                    }
+                    EndControl::Loop(None) => (
+                        Running(to_input(next_duration(last_frame, current_dur).0)),
+                        None,
+                    ),
                     EndControl::Loop(Some(i)) => {
                         let (next_dur, loops_removed) = next_duration(last_frame, current_dur);
-                        let remaining_loops = i - loops_removed;
+                        let remaining_loops = i.saturating_sub(loops_removed);
                         if remaining_loops <= 1 {
                             (Done, Some(EndControl::Normal))
                         } else {
                             (
-                                Running(next_dur),
+                                Running(to_input(next_dur)),
                                 Some(EndControl::Loop(Some(remaining_loops))),
                             )
                         }
@@ -244,7 +296,7 @@ where
                 }
             } else {
                 // last frame not reached, keep sampling
-                (Running(current_dur), None)
+                (Running(to_input(current_dur)), None)
             }
         }
 
@@ -261,6 +313,32 @@ fn next_duration(last_frame: Duration, duration: Duration) -> (Duration, u32) {
     (nanos_to_duration(remain_duration), loops as u32)
 }
 
+/// Blend the samples of a channel, and add the differences of the additive samplers on top.
+///
+/// Additive samplers are applied on top of the rest state when no other sampler is active, never
+/// on top of the current sample which already contains them.
+fn additive_blend<T>(
+    channel: &T::Channel,
+    output: &[(f32, T::Channel, T::Primitive)],
+    additive: &[(T::Channel, T::Primitive, T::Primitive)],
+) -> Option<T::Primitive>
+where
+    T: AnimationSampling,
+{
+    let blended = linear_blend::<T>(channel, output).or_else(|| {
+        additive
+            .iter()
+            .find(|o| o.0 == *channel)
+            .map(|o| o.2.clone())
+    })?;
+    Some(
+        additive
+            .iter()
+            .filter(|o| o.0 == *channel)
+            .fold(blended, |acc, o| acc.add(&o.1)),
+    )
+}
+
 fn linear_blend<T>(
     channel: &T::Channel,
     output: &[(f32, T::Channel, T::Primitive)],
@@ -291,3 +369,246 @@ where
 {
     primitive.mul(*weight / total)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transform::TransformChannel, util::SamplerPrimitive};
+    use amethyst_core::Transform;
+    use minterpolate::InterpolationFunction;
+
+    type Primitive = SamplerPrimitive<f32>;
+
+    /// A translation along x, from 0 to 2 over one second.
+    fn sampler() -> Sampler<Primitive> {
+        Sampler {
+            input: vec![0., 1.],
+            output: vec![
+                SamplerPrimitive::Vec3([0., 0., 0.]),
+                SamplerPrimitive::Vec3([2., 0., 0.]),
+            ],
+            function: InterpolationFunction::Linear,
+        }
+    }
+
+    fn control(storage: &mut AssetStorage<Sampler<Primitive>>) -> SamplerControl<Transform> {
+        SamplerControl {
+            control_id: 0,
+            channel: TransformChannel::Translation,
+            blend_weight: 1.,
+            blend_mode: BlendMode::Override,
+            fade: None,
+            sampler: storage.insert(sampler()),
+            state: ControlState::Requested,
+            end: EndControl::Normal,
+            after: SamplerPrimitive::Vec3([0., 0., 0.]),
+            rate_multiplier: 1.,
+        }
+    }
+
+    fn time(delta: f32) -> Time {
+        let mut time = Time::default();
+        time.set_delta_seconds(delta);
+        time
+    }
+
+    fn assert_vec3(primitive: &Primitive, expected: [f32; 3]) {
+        match *primitive {
+            SamplerPrimitive::Vec3(v) => assert!(
+                v.iter()
+                    .zip(expected.iter())
+                    .all(|(a, b)| (a - b).abs() < 1e-5),
+                "Expected {:?}, got {:?}",
+                expected,
+                v
+            ),
+            _ => panic!("Expected a Vec3, got {:?}", primitive),
+        }
+    }
+
+    fn advance(
+        control: &mut SamplerControl<Transform>,
+        delta: f32,
+    ) -> Vec<(f32, TransformChannel, Primitive)> {
+        let (mut output, mut additive) = (Vec::new(), Vec::new());
+        process_sampler(
+            control,
+            &sampler(),
+            &time(delta),
+            &mut output,
+            &mut additive,
+        );
+        output
+    }
+
+    fn running(control: &SamplerControl<Transform>) -> f32 {
+        match control.state {
+            ControlState::Running(duration) => duration_to_secs(duration),
+            ref state => panic!("Expected a running sampler, got {:?}", state),
+        }
+    }
+
+    fn loops_left(control: &SamplerControl<Transform>) -> Option<u32> {
+        match control.end {
+            EndControl::Loop(Some(i)) => Some(i),
+            EndControl::Normal => None,
+            ref end => panic!("Expected a counted loop, got {:?}", end),
+        }
+    }
+
+    #[test]
+    fn forward() {
+        let mut storage = AssetStorage::new();
+        let mut control = control(&mut storage);
+        assert_vec3(&advance(&mut control, 0.5)[0].2, [0., 0., 0.]);
+        assert_vec3(&advance(&mut control, 0.5)[0].2, [1., 0., 0.]);
+        assert!((running(&control) - 0.5).abs() < 1e-5);
+        advance(&mut control, 0.75);
+        assert_eq!(control.state, ControlState::Done);
+    }
+
+    #[test]
+    fn reversed() {
+        let mut storage = AssetStorage::new();
+        let mut control = control(&mut storage);
+        control.rate_multiplier = -2.;
+        assert_vec3(&advance(&mut control, 0.25)[0].2, [2., 0., 0.]);
+        assert!((running(&control) - 1.).abs() < 1e-5);
+        assert_vec3(&advance(&mut control, 0.25)[0].2, [1., 0., 0.]);
+        assert!((running(&control) - 0.5).abs() < 1e-5);
+        advance(&mut control, 0.5);
+        assert_eq!(control.state, ControlState::Done);
+    }
+
+    #[test]
+    fn reversed_deferred_start() {
+        let mut storage = AssetStorage::new();
+        let mut control = control(&mut storage);
+        control.rate_multiplier = -1.;
+        control.state = ControlState::Deferred(secs_to_duration(0.25));
+        assert_vec3(&advance(&mut control, 0.5)[0].2, [1.5, 0., 0.]);
+    }
+
+    #[test]
+    fn reversed_stays_on_first_frame() {
+        let mut storage = AssetStorage::new();
+        let mut control = control(&mut storage);
+        control.rate_multiplier = -1.;
+        control.end = EndControl::Stay;
+        advance(&mut control, 0.);
+        assert_vec3(&advance(&mut control, 2.)[0].2, [0., 0., 0.]);
+        assert_eq!(control.state, ControlState::Done);
+    }
+
+    #[test]
+    fn loop_count() {
+        let mut storage = AssetStorage::new();
+        let mut control = control(&mut storage);
+        control.end = EndControl::Loop(Some(3));
+        advance(&mut control, 0.);
+        advance(&mut control, 1.25);
+        assert_eq!(loops_left(&control), Some(2));
+        assert!((running(&control) - 0.25).abs() < 1e-5);
+        advance(&mut control, 1.);
+        assert_eq!(control.state, ControlState::Done);
+        assert_eq!(loops_left(&control), None);
+    }
+
+    #[test]
+    fn reversed_loop_count() {
+        let mut storage = AssetStorage::new();
+        let mut control = control(&mut storage);
+        control.end = EndControl::Loop(Some(3));
+        control.rate_multiplier = -1.;
+        advance(&mut control, 0.);
+        assert_eq!(loops_left(&control), Some(3));
+        assert!((running(&control) - 1.).abs() < 1e-5);
+        advance(&mut control, 1.25);
+        assert_eq!(loops_left(&control), Some(2));
+        assert!((running(&control) - 0.75).abs() < 1e-5);
+        advance(&mut control, 1.);
+        assert_eq!(control.state, ControlState::Done);
+    }
+
+    #[test]
+    fn reversed_endless_loop() {
+        let mut storage = AssetStorage::new();
+        let mut control = control(&mut storage);
+        control.end = EndControl::Loop(None);
+        control.rate_multiplier = -1.;
+        advance(&mut control, 0.);
+        advance(&mut control, 2.5);
+        assert!((running(&control) - 0.5).abs() < 1e-5);
+        match control.end {
+            EndControl::Loop(None) => {}
+            ref end => panic!("Expected an endless loop, got {:?}", end),
+        }
+    }
+
+    #[test]
+    fn additive_difference() {
+        let mut storage = AssetStorage::new();
+        let mut control = control(&mut storage);
+        control.blend_mode = BlendMode::Additive(0.25);
+        control.blend_weight = 0.5;
+        control.state = ControlState::Running(secs_to_duration(0.25));
+        control.after = SamplerPrimitive::Vec3([1., 1., 1.]);
+        let (mut output, mut additive) = (Vec::new(), Vec::new());
+        process_sampler(
+            &mut control,
+            &sampler(),
+            &time(0.5),
+            &mut output,
+            &mut additive,
+        );
+
+        // The sample at 0.75 is 1.5, 1.0 from the reference at 0.25, halved by the weight
+        assert!(output.is_empty());
+        assert_eq!(additive.len(), 1);
+        assert_vec3(&additive[0].1, [0.5, 0., 0.]);
+        assert_vec3(&additive[0].2, [1., 1., 1.]);
+    }
+
+    #[test]
+    fn additive_on_top_of_rest_state() {
+        let channel = TransformChannel::Translation;
+        let additive = vec![
+            (
+                channel,
+                SamplerPrimitive::Vec3([0.5, 0., 0.]),
+                SamplerPrimitive::Vec3([1., 1., 1.]),
+            ),
+            (
+                channel,
+                SamplerPrimitive::Vec3([0., 0.25, 0.]),
+                SamplerPrimitive::Vec3([1., 1., 1.]),
+            ),
+        ];
+        let blended = additive_blend::<Transform>(&channel, &[], &additive).unwrap();
+        assert_vec3(&blended, [1.5, 1.25, 1.]);
+    }
+
+    #[test]
+    fn additive_on_top_of_blended_samples() {
+        let channel = TransformChannel::Translation;
+        let output = vec![
+            (1., channel, SamplerPrimitive::Vec3([2., 0., 0.])),
+            (3., channel, SamplerPrimitive::Vec3([6., 0., 0.])),
+            (
+                1.,
+                TransformChannel::Scale,
+                SamplerPrimitive::Vec3([9., 9., 9.]),
+            ),
+        ];
+        let additive = vec![(
+            channel,
+            SamplerPrimitive::Vec3([0.5, 0., 0.]),
+            SamplerPrimitive::Vec3([1., 1., 1.]),
+        )];
+        let blended = additive_blend::<Transform>(&channel, &output, &additive).unwrap();
+        assert_vec3(&blended, [5.5, 0., 0.]);
+        assert!(
+            additive_blend::<Transform>(&TransformChannel::Rotation, &output, &additive).is_none()
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::marker::PhantomData;
+
+use minterpolate::InterpolationPrimitive;
+
+use amethyst_core::{
+    ecs::prelude::{Component, Entities, Entity, Join, Read, System, Write, WriteStorage},
+    shrev::EventChannel,
+    Time,
+};
+
+use crate::{
+    resources::{AnimationSampling, ApplyData},
+    tween::{Tween, TweenEvent},
+};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+/// System playing the `Tween`s on a component type, removing them once done and emitting a
+/// `TweenEvent` for each of them.
+///
+/// ### Type parameters:
+///
+/// - `T`: the component type that the tweens should be applied to
+#[derive(Default, Debug)]
+pub struct TweenSystem<T> {
+    m: PhantomData<T>,
+    done: Vec<Entity>,
+}
+
+impl<T> TweenSystem<T> {
+    /// Creates a new `TweenSystem`
+    pub fn new() -> Self {
+        TweenSystem {
+            m: PhantomData,
+            done: Vec::default(),
+        }
+    }
+}
+
+impl<'a, T> System<'a> for TweenSystem<T>
+where
+    T: AnimationSampling + Component,
+{
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        WriteStorage<'a, Tween<T>>,
+        WriteStorage<'a, T>,
+        Write<'a, EventChannel<TweenEvent<T>>>,
+        <T as ApplyData<'a>>::ApplyData,
+    );
+
+    fn run(
+        &mut self,
+        (entities, time, mut tweens, mut comps, mut events, apply_data): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("tween_system");
+
+        self.done.clear();
+        for (entity, tween, comp) in (&*entities, &mut tweens, &mut comps).join() {
+            tween.elapsed += time.delta_seconds();
+            let (progress, done) = match tween.progress() {
+                Some(progress) => progress,
+                None => continue,
+            };
+            if tween.from.is_none() {
+                tween.from = Some(comp.current_sample(&tween.channel, &apply_data));
+            }
+
+            let from = tween
+                .from
+                .as_ref()
+                .expect("Unreachable: start value set above");
+            let value = match comp.blend_method(&tween.channel) {
+                Some(_) => {
+                    let eased = tween.easing.apply(progress);
+                    from.add(&tween.to.sub(from).mul(eased))
+                }
+                None if progress >= 1. => tween.to.clone(),
+                None => from.clone(),
+            };
+            comp.apply_sample(&tween.channel, &value, &apply_data);
+
+            if done {
+                self.done.push(entity);
+                events.single_write(TweenEvent {
+                    entity,
+                    channel: tween.channel.clone(),
+                });
+            }
+        }
+        for entity in &self.done {
+            tweens.remove(*entity);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use amethyst_core::{
-    math::{zero, Quaternion, Unit, Vector3, Vector4},
+    ecs::prelude::{Component, DenseVecStorage},
+    math::{zero, Quaternion, Unit, UnitQuaternion, Vector3, Vector4},
     Transform,
 };
 
@@ -69,3 +70,38 @@ impl AnimationSampling for Transform {
         Some(BlendMethod::Linear)
     }
 }
+
+/// Motion extracted during the last frame from the animations of an entity using `RootMotion`,
+/// written by the `RootMotionSystem` for movement code or physics to apply to the entity.
+///
+/// Both are in the local space of the entity, so they follow its facing: the translation has to
+/// be rotated by the rotation of the entity before moving it, as done by `apply`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RootMotionDelta {
+    /// Translation of the extracted node, relative to the facing of the entity
+    pub translation: Vector3<f32>,
+    /// Rotation of the extracted node, in the space of the entity
+    pub rotation: UnitQuaternion<f32>,
+}
+
+impl RootMotionDelta {
+    /// Move and turn the transform of the entity by the extracted motion
+    pub fn apply(&self, transform: &mut Transform) {
+        let translation = transform.rotation() * self.translation;
+        *transform.translation_mut() += translation;
+        *transform.rotation_mut() *= self.rotation;
+    }
+}
+
+impl Default for RootMotionDelta {
+    fn default() -> Self {
+        RootMotionDelta {
+            translation: Vector3::zeros(),
+            rotation: UnitQuaternion::identity(),
+        }
+    }
+}
+
+impl Component for RootMotionDelta {
+    type Storage = DenseVecStorage<Self>;
+}
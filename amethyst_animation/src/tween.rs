@@ -0,0 +1,410 @@
+use std::f32::consts::PI;
+
+use derivative::Derivative;
+use serde::{Deserialize, Serialize};
+
+use amethyst_core::ecs::prelude::{Component, DenseVecStorage, Entity};
+
+use crate::resources::AnimationSampling;
+
+/// Easing curve of a `Tween`, mapping the progress of the tween to the progress of the value
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Quadratic, accelerating from zero velocity
+    QuadIn,
+    /// Quadratic, decelerating to zero velocity
+    QuadOut,
+    /// Quadratic, accelerating then decelerating
+    QuadInOut,
+    /// Cubic, accelerating from zero velocity
+    CubicIn,
+    /// Cubic, decelerating to zero velocity
+    CubicOut,
+    /// Cubic, accelerating then decelerating
+    CubicInOut,
+    /// Sinusoidal, accelerating from zero velocity
+    SineIn,
+    /// Sinusoidal, decelerating to zero velocity
+    SineOut,
+    /// Sinusoidal, accelerating then decelerating
+    SineInOut,
+    /// Exponential, accelerating from zero velocity
+    ExpoIn,
+    /// Exponential, decelerating to zero velocity
+    ExpoOut,
+    /// Exponential, accelerating then decelerating
+    ExpoInOut,
+    /// Backing up before moving to the end value
+    BackIn,
+    /// Overshooting the end value before settling on it
+    BackOut,
+    /// Oscillating around the end value before settling on it
+    ElasticOut,
+    /// Bouncing on the end value before settling on it
+    BounceOut,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::Linear
+    }
+}
+
+impl Easing {
+    /// Map the progress `t`, between 0.0 and 1.0, through the curve
+    pub fn apply(self, t: f32) -> f32 {
+        use self::Easing::*;
+
+        const BACK: f32 = 1.701_58;
+
+        let t = t.max(0.).min(1.);
+        match self {
+            Linear => t,
+            QuadIn => t * t,
+            QuadOut => t * (2. - t),
+            QuadInOut if t < 0.5 => 2. * t * t,
+            QuadInOut => -1. + (4. - 2. * t) * t,
+            CubicIn => t * t * t,
+            CubicOut => (t - 1.).powi(3) + 1.,
+            CubicInOut if t < 0.5 => 4. * t * t * t,
+            CubicInOut => (t - 1.) * (2. * t - 2.).powi(2) + 1.,
+            SineIn => 1. - (t * PI / 2.).cos(),
+            SineOut => (t * PI / 2.).sin(),
+            SineInOut => (1. - (t * PI).cos()) / 2.,
+            ExpoIn if t == 0. => 0.,
+            ExpoIn => 2f32.powf(10. * (t - 1.)),
+            ExpoOut if t == 1. => 1.,
+            ExpoOut => 1. - 2f32.powf(-10. * t),
+            ExpoInOut if t == 0. || t == 1. => t,
+            ExpoInOut if t < 0.5 => 2f32.powf(20. * t - 10.) / 2.,
+            ExpoInOut => (2. - 2f32.powf(-20. * t + 10.)) / 2.,
+            BackIn => t * t * ((BACK + 1.) * t - BACK),
+            BackOut => {
+                let t = t - 1.;
+                t * t * ((BACK + 1.) * t + BACK) + 1.
+            }
+            ElasticOut if t == 0. || t == 1. => t,
+            ElasticOut => 2f32.powf(-10. * t) * ((t * 10. - 0.75) * (2. * PI / 3.)).sin() + 1.,
+            BounceOut => bounce_out(t),
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1. / D {
+        N * t * t
+    } else if t < 2. / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984_375
+    }
+}
+
+/// How many times a `Tween` plays
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TweenRepeat {
+    /// Play once, then the given number of extra times
+    Times(u32),
+    /// Play until the `Tween` is removed
+    Forever,
+}
+
+impl Default for TweenRepeat {
+    fn default() -> Self {
+        TweenRepeat::Times(0)
+    }
+}
+
+/// Moves a single channel of a component from one value to another over time, without setting up
+/// `Sampler`s and an `Animation`. Played by the `TweenSystem`, which removes the tween once done and
+/// emits a `TweenEvent`.
+///
+/// `Tween<T>` being a component, an entity has at most one tween per component type: adding a
+/// tween replaces the one playing, even on another channel. Tweening several channels of the same
+/// component at once needs an `Animation` instead. Channels that can't be blended, like
+/// `SpriteRenderChannel` or `MaterialChannel`, switch to the end value when the tween ends.
+///
+/// ### Type parameters:
+///
+/// - `T`: the component type that the tween should be applied to
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct Tween<T>
+where
+    T: AnimationSampling,
+{
+    /// The tweened channel
+    pub channel: T::Channel,
+    /// Start value, `None` to start from the value of the channel when the tween starts
+    pub from: Option<T::Primitive>,
+    /// End value
+    pub to: T::Primitive,
+    /// Duration of a single play, in seconds
+    pub duration: f32,
+    /// Easing curve
+    pub easing: Easing,
+    /// Time to wait before starting, in seconds
+    pub delay: f32,
+    /// Play back to the start value every other time
+    pub yoyo: bool,
+    /// How many times the tween plays
+    pub repeat: TweenRepeat,
+    pub(crate) elapsed: f32,
+}
+
+impl<T> Tween<T>
+where
+    T: AnimationSampling,
+{
+    /// Create a tween of the channel, from its current value to `to` in `duration` seconds
+    pub fn new(channel: T::Channel, to: T::Primitive, duration: f32) -> Self {
+        Tween {
+            channel,
+            from: None,
+            to,
+            duration,
+            easing: Easing::Linear,
+            delay: 0.,
+            yoyo: false,
+            repeat: TweenRepeat::default(),
+            elapsed: 0.,
+        }
+    }
+
+    /// Start from the given value instead of the current value of the channel
+    pub fn with_from(mut self, from: T::Primitive) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// Set the easing curve
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Wait the given number of seconds before starting
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Play back to the start value every other time
+    pub fn yoyo(mut self) -> Self {
+        self.yoyo = true;
+        self
+    }
+
+    /// Set how many times the tween plays
+    pub fn with_repeat(mut self, repeat: TweenRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Time since the tween was added, including the delay, in seconds
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Progress from the start value to the end value, before easing, and whether the tween is
+    /// done. `None` during the delay.
+    pub(crate) fn progress(&self) -> Option<(f32, bool)> {
+        let elapsed = self.elapsed - self.delay;
+        if elapsed < 0. {
+            return None;
+        }
+        let plays = if self.duration > 0. {
+            elapsed / self.duration
+        } else {
+            std::f32::INFINITY
+        };
+        let done = match self.repeat {
+            TweenRepeat::Times(times) => plays >= (times + 1) as f32,
+            TweenRepeat::Forever => false,
+        };
+        let (play, progress) = match self.repeat {
+            TweenRepeat::Times(times) if done => (times, 1.),
+            // Zero duration tweens repeated forever stay at the end value
+            TweenRepeat::Forever if plays.is_infinite() => (0, 1.),
+            _ => (plays.floor() as u32, plays.fract()),
+        };
+        if self.yoyo && play % 2 == 1 {
+            Some((1. - progress, done))
+        } else {
+            Some((progress, done))
+        }
+    }
+}
+
+impl<T> Component for Tween<T>
+where
+    T: AnimationSampling,
+{
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Event emitted by the `TweenSystem` when a `Tween` is done playing
+///
+/// ### Type parameters:
+///
+/// - `T`: the component type that the tween was applied to
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct TweenEvent<T>
+where
+    T: AnimationSampling,
+{
+    /// The entity the tween was played on
+    pub entity: Entity,
+    /// The tweened channel
+    pub channel: T::Channel,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transform::TransformChannel, util::SamplerPrimitive};
+    use amethyst_core::Transform;
+
+    const CURVES: [Easing; 17] = [
+        Easing::Linear,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::SineIn,
+        Easing::SineOut,
+        Easing::SineInOut,
+        Easing::ExpoIn,
+        Easing::ExpoOut,
+        Easing::ExpoInOut,
+        Easing::BackIn,
+        Easing::BackOut,
+        Easing::ElasticOut,
+        Easing::BounceOut,
+    ];
+
+    fn assert_near(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "Expected {}, got {}", b, a);
+    }
+
+    fn tween_at(elapsed: f32) -> Tween<Transform> {
+        let mut tween = Tween::new(
+            TransformChannel::Translation,
+            SamplerPrimitive::Vec3([1., 0., 0.]),
+            1.,
+        );
+        tween.elapsed = elapsed;
+        tween
+    }
+
+    #[test]
+    fn curves_end_points() {
+        for easing in &CURVES {
+            assert_near(easing.apply(0.), 0.);
+            assert_near(easing.apply(1.), 1.);
+            // Progress is clamped
+            assert_near(easing.apply(-1.), 0.);
+            assert_near(easing.apply(2.), 1.);
+        }
+    }
+
+    #[test]
+    fn curves_midpoints() {
+        assert_near(Easing::Linear.apply(0.5), 0.5);
+        assert_near(Easing::QuadIn.apply(0.5), 0.25);
+        assert_near(Easing::QuadOut.apply(0.5), 0.75);
+        assert_near(Easing::CubicIn.apply(0.5), 0.125);
+        assert_near(Easing::CubicOut.apply(0.5), 0.875);
+        assert_near(Easing::SineOut.apply(0.5), 0.707);
+        assert_near(Easing::ExpoIn.apply(0.5), 0.031);
+        assert_near(Easing::BounceOut.apply(0.5), 0.766);
+        for easing in &[
+            Easing::QuadInOut,
+            Easing::CubicInOut,
+            Easing::SineInOut,
+            Easing::ExpoInOut,
+        ] {
+            assert_near(easing.apply(0.5), 0.5);
+            assert_near(easing.apply(0.25) + easing.apply(0.75), 1.);
+        }
+    }
+
+    #[test]
+    fn curves_overshoot() {
+        assert!(Easing::BackIn.apply(0.2) < 0.);
+        assert!(Easing::BackOut.apply(0.8) > 1.);
+        assert!(Easing::ElasticOut.apply(0.2) > 1.);
+        assert!((0..10).all(|i| {
+            let t = i as f32 / 10.;
+            (0. ..=1.).contains(&Easing::BounceOut.apply(t))
+        }));
+    }
+
+    #[test]
+    fn delay() {
+        let mut tween = tween_at(0.5).with_delay(1.);
+        assert_eq!(tween.progress(), None);
+        tween.elapsed = 1.25;
+        assert_eq!(tween.progress(), Some((0.25, false)));
+        tween.elapsed = 2.;
+        assert_eq!(tween.progress(), Some((1., true)));
+    }
+
+    #[test]
+    fn repeat() {
+        let tween = tween_at(1.25).with_repeat(TweenRepeat::Times(2));
+        assert_eq!(tween.progress(), Some((0.25, false)));
+        let tween = tween.with_repeat(TweenRepeat::Times(1));
+        assert_eq!(tween.progress(), Some((0.25, false)));
+        let tween = Tween {
+            elapsed: 2.5,
+            ..tween
+        };
+        assert_eq!(tween.progress(), Some((1., true)));
+        let tween = tween.with_repeat(TweenRepeat::Forever);
+        assert_eq!(tween.progress(), Some((0.5, false)));
+    }
+
+    #[test]
+    fn yoyo() {
+        let tween = tween_at(1.25).yoyo().with_repeat(TweenRepeat::Times(2));
+        assert_eq!(tween.progress(), Some((0.75, false)));
+        let tween = Tween {
+            elapsed: 2.5,
+            ..tween
+        };
+        assert_eq!(tween.progress(), Some((0.5, false)));
+        // Ends on the end value after an odd number of plays, on the start value otherwise
+        let tween = Tween {
+            elapsed: 3.5,
+            ..tween
+        };
+        assert_eq!(tween.progress(), Some((1., true)));
+        let tween = tween.with_repeat(TweenRepeat::Times(1));
+        assert_eq!(tween.progress(), Some((0., true)));
+    }
+
+    #[test]
+    fn zero_duration() {
+        let tween = Tween {
+            duration: 0.,
+            ..tween_at(0.)
+        };
+        assert_eq!(tween.progress(), Some((1., true)));
+        let tween = tween.with_repeat(TweenRepeat::Forever);
+        assert_eq!(tween.progress(), Some((1., false)));
+    }
+}
@@ -3,11 +3,15 @@ use serde::{Deserialize, Serialize};
 
 use amethyst_core::{
     alga::general::{SubsetOf, SupersetOf},
-    ecs::prelude::{Entity, WriteStorage},
+    ecs::prelude::{Entity, ReadStorage, WriteStorage},
     math::{convert, RealField, Vector2, Vector3, Vector4},
+    timing::duration_to_secs,
 };
 
-use crate::resources::{AnimationControlSet, AnimationSampling};
+use crate::resources::{
+    AnimationControl, AnimationControlSet, AnimationHierarchy, AnimationSampling, ControlState,
+    SamplerControlSet,
+};
 
 use self::SamplerPrimitive::*;
 
@@ -33,6 +37,40 @@ where
         .map(|entry| entry.or_insert_with(AnimationControlSet::default))
 }
 
+/// Get the input value of a started animation, in seconds, from any of its samplers on the nodes
+/// of the hierarchy, or on the entity itself without hierarchy. Samplers done playing an animation
+/// that doesn't loop are at its end, or at its start when played backward.
+pub(crate) fn control_input<T>(
+    entity: Entity,
+    hierarchy: Option<&AnimationHierarchy<T>>,
+    samplers: &ReadStorage<'_, SamplerControlSet<T>>,
+    control: &AnimationControl<T>,
+    duration: f32,
+) -> Option<f32>
+where
+    T: AnimationSampling,
+{
+    let sampler = match hierarchy {
+        Some(hierarchy) => hierarchy
+            .nodes
+            .values()
+            .filter_map(|node| samplers.get(*node))
+            .flat_map(|set| set.samplers.iter())
+            .find(|sampler| sampler.control_id == control.id),
+        None => samplers.get(entity).and_then(|set| {
+            set.samplers
+                .iter()
+                .find(|sampler| sampler.control_id == control.id)
+        }),
+    }?;
+    match sampler.state {
+        ControlState::Running(input) | ControlState::Paused(input) => Some(duration_to_secs(input)),
+        ControlState::Done if control.rate_multiplier < 0. => Some(0.),
+        ControlState::Done => Some(duration),
+        _ => None,
+    }
+}
+
 /// Sampler primitive
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum SamplerPrimitive<S>
@@ -8,11 +8,16 @@ use amethyst_core::{
 };
 use amethyst_error::Error;
 
-use crate::{output::Output, source::*, systems::AudioSystemDesc};
+use crate::{
+    output::Output,
+    source::*,
+    systems::{AudioMixerSystem, AudioSystemDesc},
+};
 
 /// Audio bundle
 ///
-/// This will only add the audio system and the asset processor for `Source`.
+/// This will only add the audio system, the audio mixer system and the asset processor for
+/// `Source`.
 ///
 /// `DjSystem` must be added separately if you want to use our background music system.
 ///
@@ -26,10 +31,11 @@ impl<'a, 'b> SystemBundle<'a, 'b> for AudioBundle {
         world: &mut World,
         builder: &mut DispatcherBuilder<'a, 'b>,
     ) -> Result<(), Error> {
+        builder.add(AudioMixerSystem::new(), "audio_mixer_system", &[]);
         builder.add(
             AudioSystemDesc::new(self.0).build(world),
             "audio_system",
-            &[],
+            &["audio_mixer_system"],
         );
         builder.add(Processor::<Source>::new(), "source_processor", &[]);
         Ok(())
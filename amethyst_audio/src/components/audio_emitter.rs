@@ -8,7 +8,7 @@ use smallvec::SmallVec;
 
 use amethyst_core::ecs::{prelude::Component, storage::BTreeStorage};
 
-use crate::{source::Source, DecoderError};
+use crate::{mixer::AudioBus, source::Source, DecoderError};
 
 /// An audio source, add this component to anything that emits sound.
 /// TODO: This should get a proper Debug impl parsing the sinks and sound queue
@@ -18,6 +18,7 @@ pub struct AudioEmitter {
     pub(crate) sinks: SmallVec<[(SpatialSink, Arc<AtomicBool>); 4]>,
     pub(crate) sound_queue: SmallVec<[Decoder<Cursor<Source>>; 4]>,
     pub(crate) picker: Option<Box<dyn FnMut(&mut AudioEmitter) -> bool + Send + Sync>>,
+    pub(crate) bus: AudioBus,
 }
 
 impl AudioEmitter {
@@ -28,6 +29,24 @@ impl AudioEmitter {
         Default::default()
     }
 
+    /// Creates a new AudioEmitter playing on the given bus, instead of the `Sfx` bus.
+    pub fn with_bus(bus: AudioBus) -> AudioEmitter {
+        AudioEmitter {
+            bus,
+            ..Default::default()
+        }
+    }
+
+    /// Retrieves the bus this emitter plays on.
+    pub fn bus(&self) -> AudioBus {
+        self.bus
+    }
+
+    /// Sets the bus this emitter plays on.
+    pub fn set_bus(&mut self, bus: AudioBus) {
+        self.bus = bus;
+    }
+
     /// Plays an audio source from this emitter.
     pub fn play(&mut self, source: &Source) -> Result<(), DecoderError> {
         self.sound_queue
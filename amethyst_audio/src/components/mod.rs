@@ -11,7 +11,7 @@ use amethyst_error::Error;
 
 use serde::{Deserialize, Serialize};
 
-use crate::output::Output;
+use crate::{mixer::AudioBus, output::Output};
 
 mod audio_emitter;
 mod audio_listener;
@@ -22,6 +22,9 @@ mod audio_listener;
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct AudioPrefab {
     emitter: bool,
+    /// Bus the emitter plays on
+    #[serde(default)]
+    bus: AudioBus,
     /// Left, Right
     listener: Option<(Point3<f32>, Point3<f32>)>,
 }
@@ -42,7 +45,9 @@ impl<'a> PrefabData<'a> for AudioPrefab {
         _: &[Entity],
     ) -> Result<(), Error> {
         if self.emitter {
-            system_data.0.insert(entity, AudioEmitter::default())?;
+            system_data
+                .0
+                .insert(entity, AudioEmitter::with_bus(self.bus))?;
         }
         if let Some((left_ear, right_ear)) = self.listener {
             system_data.1.insert(
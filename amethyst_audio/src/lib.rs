@@ -12,6 +12,7 @@ pub use self::{
     bundle::AudioBundle,
     components::*,
     formats::{FlacFormat, Mp3Format, OggFormat, WavFormat},
+    mixer::{AudioBus, AudioMixer, Ducking},
     sink::AudioSink,
     source::{Source, SourceHandle},
     systems::*,
@@ -28,6 +29,7 @@ mod bundle;
 mod components;
 mod end_signal;
 mod formats;
+mod mixer;
 mod sink;
 mod source;
 mod systems;
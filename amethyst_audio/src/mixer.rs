@@ -0,0 +1,326 @@
+//! Mixing of the played sounds into buses with their own volume.
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::Cursor,
+};
+
+use log::error;
+use rodio::{Decoder, Sink};
+use serde::{Deserialize, Serialize};
+
+use crate::{output::Output, source::Source, DecoderError};
+
+/// A bus sounds are routed into, with its own volume in the `AudioMixer`.
+///
+/// Every bus other than `Master` is routed into `Master`.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AudioBus {
+    /// The bus every other bus is routed into
+    Master,
+    /// Background music, played by the `AudioSink` by default
+    Music,
+    /// Sound effects, played by `AudioEmitter`s by default
+    Sfx,
+    /// Dialogue and voice overs
+    Voice,
+    /// User interface sounds, played by the `UiSoundSystem`
+    Ui,
+}
+
+impl Default for AudioBus {
+    fn default() -> Self {
+        AudioBus::Sfx
+    }
+}
+
+/// Lowers the volume of a bus while sounds are playing on another bus, for example to dip the
+/// music while dialogue plays.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Ducking {
+    /// The bus whose sounds trigger the ducking
+    pub trigger: AudioBus,
+    /// The bus whose volume is lowered
+    pub target: AudioBus,
+    /// Volume multiplier of the target bus while ducked, between 0.0 and 1.0
+    pub volume: f32,
+    /// Time to lower the volume once the trigger bus starts playing, in seconds
+    pub attack: f32,
+    /// Time to restore the volume once the trigger bus stops playing, in seconds
+    pub release: f32,
+}
+
+impl Ducking {
+    /// Lower the volume of `target` to `volume` while `trigger` is playing
+    pub fn new(trigger: AudioBus, target: AudioBus, volume: f32) -> Self {
+        Ducking {
+            trigger,
+            target,
+            volume,
+            attack: 0.1,
+            release: 0.5,
+        }
+    }
+
+    /// Set the time to lower the volume, in seconds
+    pub fn with_attack(mut self, attack: f32) -> Self {
+        self.attack = attack;
+        self
+    }
+
+    /// Set the time to restore the volume, in seconds
+    pub fn with_release(mut self, release: f32) -> Self {
+        self.release = release;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct BusState {
+    volume: f32,
+    muted: bool,
+    ducking: f32,
+}
+
+impl Default for BusState {
+    fn default() -> Self {
+        BusState {
+            volume: 1.0,
+            muted: false,
+            ducking: 1.0,
+        }
+    }
+}
+
+/// Volumes of the `AudioBus`es, stored as a resource in the `World`.
+///
+/// The `AudioMixerSystem` ducks buses according to the `Ducking`s and applies the volumes to the
+/// `AudioSink` and the sounds played with `AudioMixer::play_once`, the `AudioSystem` applies them
+/// to the `AudioEmitter`s.
+// Rodio sinks don't implement `Debug`.
+#[allow(missing_debug_implementations)]
+#[derive(Default)]
+pub struct AudioMixer {
+    buses: HashMap<AudioBus, BusState>,
+    duckings: Vec<Ducking>,
+    pub(crate) playing: HashSet<AudioBus>,
+    pub(crate) sinks: Vec<(AudioBus, f32, Sink)>,
+}
+
+impl AudioMixer {
+    /// Create a mixer with every bus at full volume and no ducking
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the volume of a bus, between 0.0 and 1.0
+    pub fn volume(&self, bus: AudioBus) -> f32 {
+        self.buses.get(&bus).map_or(1.0, |state| state.volume)
+    }
+
+    /// Set the volume of a bus, between 0.0 and 1.0
+    pub fn set_volume(&mut self, bus: AudioBus, volume: f32) {
+        self.buses.entry(bus).or_default().volume = volume;
+    }
+
+    /// Returns true if the bus is muted
+    pub fn is_muted(&self, bus: AudioBus) -> bool {
+        self.buses.get(&bus).map_or(false, |state| state.muted)
+    }
+
+    /// Mute or unmute a bus, keeping its volume
+    pub fn set_muted(&mut self, bus: AudioBus, muted: bool) {
+        self.buses.entry(bus).or_default().muted = muted;
+    }
+
+    /// Returns true if sounds were playing on the bus during the last update of the
+    /// `AudioMixerSystem`
+    pub fn is_playing(&self, bus: AudioBus) -> bool {
+        self.playing.contains(&bus)
+    }
+
+    /// Add a `Ducking` of one bus by another
+    pub fn add_ducking(&mut self, ducking: Ducking) {
+        self.duckings.push(ducking);
+    }
+
+    /// Remove all `Ducking`s of the given target bus
+    pub fn remove_ducking(&mut self, target: AudioBus) {
+        self.duckings.retain(|ducking| ducking.target != target);
+        if let Some(state) = self.buses.get_mut(&target) {
+            state.ducking = 1.0;
+        }
+    }
+
+    /// Get the volume multiplier the sounds of a bus are played with, combining the volume, mute
+    /// and ducking of the bus and of the `Master` bus
+    pub fn gain(&self, bus: AudioBus) -> f32 {
+        let own = self.own_gain(bus);
+        if bus == AudioBus::Master {
+            own
+        } else {
+            own * self.own_gain(AudioBus::Master)
+        }
+    }
+
+    fn own_gain(&self, bus: AudioBus) -> f32 {
+        match self.buses.get(&bus) {
+            Some(state) if state.muted => 0.0,
+            Some(state) => state.volume * state.ducking,
+            None => 1.0,
+        }
+    }
+
+    /// Play a sound once on a bus. A volume of 1.0 is unchanged, while 0.0 is silent.
+    ///
+    /// This may silently fail, in order to get error information use `try_play_once`.
+    pub fn play_once(&mut self, output: &Output, source: &Source, volume: f32, bus: AudioBus) {
+        self.play_n_times(output, source, volume, bus, 1);
+    }
+
+    /// Play a sound once on a bus. A volume of 1.0 is unchanged, while 0.0 is silent.
+    ///
+    /// This will return an Error if the loaded audio file in source could not be decoded.
+    pub fn try_play_once(
+        &mut self,
+        output: &Output,
+        source: &Source,
+        volume: f32,
+        bus: AudioBus,
+    ) -> Result<(), DecoderError> {
+        self.try_play_n_times(output, source, volume, bus, 1)
+    }
+
+    /// Play a sound n times on a bus. A volume of 1.0 is unchanged, while 0.0 is silent.
+    ///
+    /// This may silently fail, in order to get error information use `try_play_n_times`.
+    pub fn play_n_times(
+        &mut self,
+        output: &Output,
+        source: &Source,
+        volume: f32,
+        bus: AudioBus,
+        n: u16,
+    ) {
+        if let Err(err) = self.try_play_n_times(output, source, volume, bus, n) {
+            error!("An error occurred while trying to play a sound: {:?}", err);
+        }
+    }
+
+    /// Play a sound n times on a bus. A volume of 1.0 is unchanged, while 0.0 is silent.
+    ///
+    /// This will return an Error if the loaded audio file in source could not be decoded.
+    pub fn try_play_n_times(
+        &mut self,
+        output: &Output,
+        source: &Source,
+        volume: f32,
+        bus: AudioBus,
+        n: u16,
+    ) -> Result<(), DecoderError> {
+        let mut sink = Sink::new(&output.device);
+        sink.set_volume(volume * self.gain(bus));
+        for _ in 0..n {
+            sink.append(Decoder::new(Cursor::new(source.clone())).map_err(|_| DecoderError)?);
+        }
+        self.sinks.push((bus, volume, sink));
+        Ok(())
+    }
+
+    /// Move the ducking of each bus toward its target, `delta` seconds after the last update
+    pub(crate) fn update_ducking(&mut self, delta: f32) {
+        // Target volume and speed of each ducked bus, the lowest volume wins
+        let mut targets = HashMap::<AudioBus, (f32, f32)>::new();
+        for ducking in &self.duckings {
+            let (volume, time) = if self.playing.contains(&ducking.trigger) {
+                (ducking.volume, ducking.attack)
+            } else {
+                (1.0, ducking.release)
+            };
+            let speed = if time > 0.0 {
+                (1.0 - ducking.volume).abs() / time
+            } else {
+                std::f32::INFINITY
+            };
+            let target = targets.entry(ducking.target).or_insert((1.0, 0.0));
+            if volume < target.0 {
+                *target = (volume, speed);
+            } else if (volume - target.0).abs() < std::f32::EPSILON {
+                target.1 = target.1.max(speed);
+            }
+        }
+        for (bus, (volume, speed)) in targets {
+            let state = self.buses.entry(bus).or_default();
+            let distance = volume - state.ducking;
+            let step = if speed.is_infinite() {
+                distance.abs()
+            } else {
+                speed * delta
+            };
+            state.ducking += distance.max(-step).min(step);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_gain(mixer: &AudioMixer, bus: AudioBus, expected: f32) {
+        let gain = mixer.gain(bus);
+        assert!(
+            (gain - expected).abs() < 1.0e-5,
+            "Expected gain {} for {:?}, but was {}",
+            expected,
+            bus,
+            gain
+        );
+    }
+
+    #[test]
+    fn test_gain() {
+        let mut mixer = AudioMixer::new();
+        assert_gain(&mixer, AudioBus::Music, 1.0);
+
+        mixer.set_volume(AudioBus::Master, 0.5);
+        mixer.set_volume(AudioBus::Music, 0.5);
+        assert_gain(&mixer, AudioBus::Master, 0.5);
+        assert_gain(&mixer, AudioBus::Music, 0.25);
+        assert_gain(&mixer, AudioBus::Sfx, 0.5);
+
+        mixer.set_muted(AudioBus::Music, true);
+        assert_gain(&mixer, AudioBus::Music, 0.0);
+        assert!((mixer.volume(AudioBus::Music) - 0.5).abs() < 1.0e-5);
+
+        mixer.set_muted(AudioBus::Master, true);
+        assert_gain(&mixer, AudioBus::Sfx, 0.0);
+    }
+
+    #[test]
+    fn test_ducking() {
+        let mut mixer = AudioMixer::new();
+        mixer.add_ducking(
+            Ducking::new(AudioBus::Voice, AudioBus::Music, 0.2)
+                .with_attack(1.0)
+                .with_release(2.0),
+        );
+
+        mixer.update_ducking(0.5);
+        assert_gain(&mixer, AudioBus::Music, 1.0);
+
+        // Halfway through the attack
+        mixer.playing.insert(AudioBus::Voice);
+        mixer.update_ducking(0.5);
+        assert_gain(&mixer, AudioBus::Music, 0.6);
+        mixer.update_ducking(1.0);
+        assert_gain(&mixer, AudioBus::Music, 0.2);
+        assert_gain(&mixer, AudioBus::Voice, 1.0);
+
+        // Halfway through the release
+        mixer.playing.clear();
+        mixer.update_ducking(1.0);
+        assert_gain(&mixer, AudioBus::Music, 0.6);
+        mixer.update_ducking(2.0);
+        assert_gain(&mixer, AudioBus::Music, 1.0);
+    }
+}
@@ -44,35 +44,47 @@ impl Output {
     /// Play a sound once.  A volume of 1.0 is unchanged, while 0.0 is silent.
     ///
     /// This will return an Error if the loaded audio file in source could not be decoded.
+    #[deprecated(note = "bypasses the `AudioMixer`, use `AudioMixer::try_play_once` instead")]
     pub fn try_play_once(&self, source: &Source, volume: f32) -> Result<(), DecoderError> {
-        self.try_play_n_times(source, volume, 1)
+        self.try_play(source, volume, 1)
     }
 
     /// Play a sound once. A volume of 1.0 is unchanged, while 0.0 is silent.
     ///
     /// This may silently fail, in order to get error information use `try_play_once`.
+    #[deprecated(note = "bypasses the `AudioMixer`, use `AudioMixer::play_once` instead")]
     pub fn play_once(&self, source: &Source, volume: f32) {
-        self.play_n_times(source, volume, 1);
+        self.play(source, volume, 1);
     }
 
     /// Play a sound n times. A volume of 1.0 is unchanged, while 0.0 is silent.
     ///
     /// This may silently fail, in order to get error information use `try_play_n_times`.
+    #[deprecated(note = "bypasses the `AudioMixer`, use `AudioMixer::play_n_times` instead")]
     pub fn play_n_times(&self, source: &Source, volume: f32, n: u16) {
-        if let Err(err) = self.try_play_n_times(source, volume, n) {
-            error!("An error occurred while trying to play a sound: {:?}", err);
-        }
+        self.play(source, volume, n);
     }
 
     /// Play a sound n times. A volume of 1.0 is unchanged, while 0.0 is silent.
     ///
     /// This will return an Error if the loaded audio file in source could not be decoded.
+    #[deprecated(note = "bypasses the `AudioMixer`, use `AudioMixer::try_play_n_times` instead")]
     pub fn try_play_n_times(
         &self,
         source: &Source,
         volume: f32,
         n: u16,
     ) -> Result<(), DecoderError> {
+        self.try_play(source, volume, n)
+    }
+
+    fn play(&self, source: &Source, volume: f32, n: u16) {
+        if let Err(err) = self.try_play(source, volume, n) {
+            error!("An error occurred while trying to play a sound: {:?}", err);
+        }
+    }
+
+    fn try_play(&self, source: &Source, volume: f32, n: u16) -> Result<(), DecoderError> {
         let sink = Sink::new(&self.device);
         for _ in 0..n {
             sink.append(
@@ -173,6 +185,7 @@ mod tests {
 
     // test_play tests the play APIs for Output
     #[cfg(target_os = "linux")]
+    #[allow(deprecated)]
     fn test_play(file_name: &str, should_pass: bool) {
         // Get the full file path
         let app_root = application_root_dir().unwrap();
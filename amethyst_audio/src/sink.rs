@@ -2,7 +2,7 @@ use std::io::Cursor;
 
 use rodio::{Decoder, Sink};
 
-use crate::{output::Output, source::Source, DecoderError};
+use crate::{mixer::AudioBus, output::Output, source::Source, DecoderError};
 
 /// This structure provides a way to programmatically pick and play music.
 // TODO: This needs a proper debug implementeation. This should probably propigate up to a TODO
@@ -10,13 +10,19 @@ use crate::{output::Output, source::Source, DecoderError};
 #[allow(missing_debug_implementations)]
 pub struct AudioSink {
     sink: Sink,
+    volume: f32,
+    gain: f32,
+    bus: AudioBus,
 }
 
 impl AudioSink {
-    /// Creates a new `AudioSink` using the given audio output.
+    /// Creates a new `AudioSink` using the given audio output, playing on the `Music` bus.
     pub fn new(output: &Output) -> AudioSink {
         AudioSink {
             sink: Sink::new(&output.device),
+            volume: 1.0,
+            gain: 1.0,
+            bus: AudioBus::Music,
         }
     }
 
@@ -32,14 +38,32 @@ impl AudioSink {
         self.sink.empty()
     }
 
-    /// Retrieves the volume of the sink, between 0.0 and 1.0;
+    /// Retrieves the volume of the sink, between 0.0 and 1.0, before the volume of its bus is
+    /// applied.
     pub fn volume(&self) -> f32 {
-        self.sink.volume()
+        self.volume
     }
 
-    /// Sets the volume of the sink.
+    /// Sets the volume of the sink, before the volume of its bus is applied.
     pub fn set_volume(&mut self, volume: f32) {
-        self.sink.set_volume(volume);
+        self.volume = volume;
+        self.sink.set_volume(self.volume * self.gain);
+    }
+
+    /// Retrieves the bus the sink plays on.
+    pub fn bus(&self) -> AudioBus {
+        self.bus
+    }
+
+    /// Sets the bus the sink plays on.
+    pub fn set_bus(&mut self, bus: AudioBus) {
+        self.bus = bus;
+    }
+
+    /// Applies the volume of the bus, as given by the `AudioMixer`.
+    pub(crate) fn set_gain(&mut self, gain: f32) {
+        self.gain = gain;
+        self.sink.set_volume(self.volume * self.gain);
     }
 
     /// Resumes playback of a paused sink. Has no effect if this sink was never paused.
@@ -25,6 +25,7 @@ use amethyst_core::{
 use crate::{
     components::{AudioEmitter, AudioListener},
     end_signal::EndSignalSource,
+    mixer::AudioMixer,
     output::Output,
 };
 
@@ -59,6 +60,7 @@ impl<'a> System<'a> for AudioSystem {
     type SystemData = (
         Option<Read<'a, Output>>,
         Option<Read<'a, SelectedListener>>,
+        Read<'a, AudioMixer>,
         Entities<'a>,
         ReadStorage<'a, Transform>,
         ReadStorage<'a, AudioListener>,
@@ -67,7 +69,7 @@ impl<'a> System<'a> for AudioSystem {
 
     fn run(
         &mut self,
-        (output, select_listener, entities, transform, listener, mut audio_emitter): Self::SystemData,
+        (output, select_listener, mixer, entities, transform, listener, mut audio_emitter): Self::SystemData,
     ) {
         #[cfg(feature = "profiler")]
         profile_scope!("audio_system");
@@ -104,9 +106,11 @@ impl<'a> System<'a> for AudioSystem {
                         let z = transform.global_matrix()[(2, 3)];
                         [convert(x), convert(y), convert(z)]
                     };
+                    let gain = mixer.gain(audio_emitter.bus);
                     // Remove all sinks whose sounds have ended.
                     audio_emitter.sinks.retain(|s| !s.1.load(Ordering::Relaxed));
                     for &mut (ref mut sink, _) in &mut audio_emitter.sinks {
+                        sink.set_volume(gain);
                         sink.set_emitter_position(emitter_position);
                         sink.set_left_ear_position(left_ear_position);
                         sink.set_right_ear_position(right_ear_position);
@@ -120,12 +124,13 @@ impl<'a> System<'a> for AudioSystem {
                     }
                     while let Some(source) = audio_emitter.sound_queue.pop() {
                         if let Some(output) = &output {
-                            let sink = SpatialSink::new(
+                            let mut sink = SpatialSink::new(
                                 &output.device,
                                 emitter_position,
                                 left_ear_position,
                                 right_ear_position,
                             );
+                            sink.set_volume(gain);
                             let atomic_bool = Arc::new(AtomicBool::new(false));
                             let clone = atomic_bool.clone();
                             sink.append(EndSignalSource::new(source, move || {
@@ -0,0 +1,77 @@
+use std::{collections::HashSet, sync::atomic::Ordering};
+
+use derive_new::new;
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use amethyst_core::{
+    ecs::prelude::{Join, Read, ReadStorage, System, Write},
+    Time,
+};
+
+use crate::{
+    components::AudioEmitter,
+    mixer::{AudioBus, AudioMixer},
+    sink::AudioSink,
+};
+
+/// Tracks the buses sounds are playing on, updates the ducking of the `AudioMixer` and applies the
+/// bus volumes to the `AudioSink` and the sounds played with `AudioMixer::play_once`.
+///
+/// The bus volumes of `AudioEmitter`s are applied by the `AudioSystem`.
+#[derive(Debug, Default, new)]
+pub struct AudioMixerSystem;
+
+impl<'a> System<'a> for AudioMixerSystem {
+    type SystemData = (
+        Read<'a, Time>,
+        Write<'a, AudioMixer>,
+        Option<Write<'a, AudioSink>>,
+        ReadStorage<'a, AudioEmitter>,
+    );
+
+    fn run(&mut self, (time, mut mixer, mut audio_sink, audio_emitter): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("audio_mixer_system");
+
+        // Remove all sinks whose sounds have ended.
+        mixer.sinks.retain(|(_, _, sink)| !sink.empty());
+
+        let mut playing = mixer
+            .sinks
+            .iter()
+            .map(|(bus, _, _)| *bus)
+            .collect::<HashSet<_>>();
+        if let Some(sink) = &audio_sink {
+            if !sink.empty() && !sink.is_paused() {
+                playing.insert(sink.bus());
+            }
+        }
+        for emitter in (&audio_emitter).join() {
+            if !emitter.sound_queue.is_empty()
+                || emitter.sinks.iter().any(|s| !s.1.load(Ordering::Relaxed))
+            {
+                playing.insert(emitter.bus);
+            }
+        }
+        if !playing.is_empty() {
+            playing.insert(AudioBus::Master);
+        }
+        mixer.playing = playing;
+        mixer.update_ducking(time.delta_seconds());
+
+        if let Some(sink) = &mut audio_sink {
+            let gain = mixer.gain(sink.bus());
+            sink.set_gain(gain);
+        }
+        let gains = mixer
+            .sinks
+            .iter()
+            .map(|(bus, _, _)| mixer.gain(*bus))
+            .collect::<Vec<_>>();
+        for ((_, volume, sink), gain) in mixer.sinks.iter_mut().zip(gains) {
+            sink.set_volume(*volume * gain);
+        }
+    }
+}
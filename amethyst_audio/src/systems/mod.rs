@@ -3,7 +3,9 @@
 pub use self::{
     audio::{AudioSystem, AudioSystemDesc},
     dj::{DjSystem, DjSystemDesc},
+    mixer::AudioMixerSystem,
 };
 
 mod audio;
 mod dj;
+mod mixer;
@@ -1,10 +1,12 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, mem};
 
 use amethyst_error::Error;
+use log::{info, warn};
+use serde::Deserialize;
 
 use amethyst_animation::{
-    AnimationPrefab, AnimationSetPrefab, InterpolationFunction, InterpolationPrimitive, Sampler,
-    SamplerPrimitive, TransformChannel,
+    AnimationMarker, AnimationPrefab, AnimationSetPrefab, InterpolationFunction,
+    InterpolationPrimitive, Sampler, SamplerPrimitive, TransformChannel,
 };
 use amethyst_core::{
     math::{convert, Vector3, Vector4},
@@ -12,25 +14,156 @@ use amethyst_core::{
 };
 
 use super::Buffers;
-use crate::error;
+use crate::{error, GltfAnimationCompression, GltfAnimationStatistics};
 
 pub fn load_animations(
     gltf: &gltf::Gltf,
     buffers: &Buffers,
     node_map: &HashMap<usize, usize>,
-) -> Result<AnimationSetPrefab<usize, Transform>, Error> {
+    compression: Option<&GltfAnimationCompression>,
+    name: &str,
+) -> Result<
+    (
+        AnimationSetPrefab<usize, Transform>,
+        Option<GltfAnimationStatistics>,
+    ),
+    Error,
+> {
     let mut prefab = AnimationSetPrefab::default();
+    let mut statistics = GltfAnimationStatistics::default();
     for animation in gltf.animations() {
-        let anim = load_animation(&animation, buffers)?;
+        let mut anim = load_animation(&animation, buffers)?;
         if anim
             .samplers
             .iter()
             .any(|sampler| node_map.contains_key(&sampler.0))
         {
+            if let Some(compression) = compression {
+                for (_, channel, sampler) in &mut anim.samplers {
+                    count(&mut statistics, sampler, false);
+                    compress_sampler(*channel, sampler, compression);
+                    count(&mut statistics, sampler, true);
+                }
+            }
             prefab.animations.push((animation.index(), anim));
         }
     }
-    Ok(prefab)
+    if compression.is_none() {
+        return Ok((prefab, None));
+    }
+    info!(
+        "Compressed the animations of GLTF scene '{}' from {} to {} keyframes, {} to {} bytes",
+        name,
+        statistics.keyframes,
+        statistics.reduced_keyframes,
+        statistics.bytes,
+        statistics.reduced_bytes,
+    );
+    Ok((prefab, Some(statistics)))
+}
+
+/// Add the size of a sampler to the statistics, before or after compression
+fn count(
+    statistics: &mut GltfAnimationStatistics,
+    sampler: &Sampler<SamplerPrimitive<f32>>,
+    reduced: bool,
+) {
+    let keyframes = sampler.input.len();
+    let bytes = keyframes * mem::size_of::<f32>()
+        + sampler.output.len() * mem::size_of::<SamplerPrimitive<f32>>();
+    if reduced {
+        statistics.reduced_keyframes += keyframes;
+        statistics.reduced_bytes += bytes;
+    } else {
+        statistics.keyframes += keyframes;
+        statistics.bytes += bytes;
+    }
+}
+
+fn compress_sampler(
+    channel: TransformChannel,
+    sampler: &mut Sampler<SamplerPrimitive<f32>>,
+    compression: &GltfAnimationCompression,
+) {
+    match sampler.function {
+        InterpolationFunction::Linear
+        | InterpolationFunction::SphericalLinear
+        | InterpolationFunction::Step => {}
+        _ => return,
+    }
+    let tolerance = match channel {
+        TransformChannel::Translation => compression.translation_tolerance,
+        TransformChannel::Rotation => compression.rotation_tolerance,
+        TransformChannel::Scale => compression.scale_tolerance,
+    };
+    reduce_keyframes(channel, sampler, tolerance);
+}
+
+/// Remove the keyframes that the interpolation between their kept neighbours reproduces within
+/// the tolerance, keeping the first and last keyframes
+fn reduce_keyframes(
+    channel: TransformChannel,
+    sampler: &mut Sampler<SamplerPrimitive<f32>>,
+    tolerance: f32,
+) {
+    let len = sampler.input.len();
+    if len < 3 || sampler.output.len() != len {
+        return;
+    }
+    let mut kept = vec![0];
+    let mut start = 0;
+    for end in 2..len {
+        if !reproduces(channel, sampler, start, end, tolerance) {
+            start = end - 1;
+            kept.push(start);
+        }
+    }
+    kept.push(len - 1);
+    if kept.len() == len {
+        return;
+    }
+    sampler.input = kept.iter().map(|i| sampler.input[*i]).collect();
+    sampler.output = kept.iter().map(|i| sampler.output[*i]).collect();
+}
+
+/// Check that interpolating between the keyframes `start` and `end` reproduces every keyframe
+/// between them within the tolerance
+fn reproduces(
+    channel: TransformChannel,
+    sampler: &Sampler<SamplerPrimitive<f32>>,
+    start: usize,
+    end: usize,
+    tolerance: f32,
+) -> bool {
+    let input = [sampler.input[start], sampler.input[end]];
+    let output = [sampler.output[start], sampler.output[end]];
+    (start + 1..end).all(|i| {
+        let value = sampler
+            .function
+            .interpolate(sampler.input[i], &input, &output, false);
+        deviation(channel, &value, &sampler.output[i]) <= tolerance
+    })
+}
+
+fn deviation(
+    channel: TransformChannel,
+    a: &SamplerPrimitive<f32>,
+    b: &SamplerPrimitive<f32>,
+) -> f32 {
+    match (*a, *b) {
+        (SamplerPrimitive::Vec4(a), SamplerPrimitive::Vec4(b))
+            if channel == TransformChannel::Rotation =>
+        {
+            let dot = Vector4::from(a)
+                .normalize()
+                .dot(&Vector4::from(b).normalize());
+            2. * dot.abs().min(1.).acos()
+        }
+        (SamplerPrimitive::Vec3(a), SamplerPrimitive::Vec3(b)) => {
+            (Vector3::from(a) - Vector3::from(b)).norm()
+        }
+        _ => std::f32::INFINITY,
+    }
 }
 
 fn load_animation(
@@ -42,9 +175,36 @@ fn load_animation(
         .channels()
         .map(|ref channel| load_channel(channel, buffers))
         .collect::<Result<Vec<_>, Error>>()?;
+    a.events = load_events(animation);
     Ok(a)
 }
 
+/// Event markers stored in the extras of the animation, as
+/// `{ "events": [{ "time": 0.5, "name": "footstep" }] }`
+#[derive(Deserialize)]
+struct AnimationExtras {
+    #[serde(default)]
+    events: Vec<AnimationMarker>,
+}
+
+fn load_events(animation: &gltf::Animation<'_>) -> Vec<AnimationMarker> {
+    let extras = match animation.extras() {
+        Some(extras) => extras,
+        None => return Vec::new(),
+    };
+    match serde_json::from_str::<AnimationExtras>(extras.get()) {
+        Ok(extras) => extras.events,
+        Err(err) => {
+            warn!(
+                "Ignoring the extras of animation {}: {}",
+                animation.index(),
+                err
+            );
+            Vec::new()
+        }
+    }
+}
+
 fn load_channel(
     channel: &gltf::animation::Channel<'_>,
     buffers: &Buffers,
@@ -123,3 +283,96 @@ where
         CatmullRomSpline => InterpolationFunction::CatmullRomSpline,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use amethyst_core::math::UnitQuaternion;
+
+    use super::*;
+
+    fn translations(values: &[[f32; 3]]) -> Sampler<SamplerPrimitive<f32>> {
+        Sampler {
+            input: (0..values.len()).map(|i| i as f32).collect(),
+            output: values.iter().map(|v| SamplerPrimitive::Vec3(*v)).collect(),
+            function: InterpolationFunction::Linear,
+        }
+    }
+
+    fn rotation(angle: f32) -> [f32; 4] {
+        UnitQuaternion::from_axis_angle(&Vector3::y_axis(), angle)
+            .into_inner()
+            .coords
+            .into()
+    }
+
+    #[test]
+    fn collinear_keyframes_removed() {
+        let mut sampler = translations(&[[0., 0., 0.], [1., 0., 0.], [2., 0., 0.], [3., 0., 0.]]);
+        reduce_keyframes(TransformChannel::Translation, &mut sampler, 0.001);
+        assert_eq!(sampler.input, vec![0., 3.]);
+        assert_eq!(sampler.output.len(), 2);
+    }
+
+    #[test]
+    fn keyframes_beyond_tolerance_kept() {
+        let values = [
+            [0., 0., 0.],
+            [1., 0.01, 0.],
+            [2., 0., 0.],
+            [3., 0.0005, 0.],
+            [4., 0., 0.],
+        ];
+        let mut sampler = translations(&values);
+        reduce_keyframes(TransformChannel::Translation, &mut sampler, 0.001);
+        assert_eq!(sampler.input, vec![0., 1., 2., 4.]);
+
+        // Scales use the same tolerance on the difference of the values
+        let mut sampler = translations(&values);
+        reduce_keyframes(TransformChannel::Scale, &mut sampler, 0.1);
+        assert_eq!(sampler.input, vec![0., 4.]);
+    }
+
+    #[test]
+    fn rotation_sign_flip() {
+        let mut halfway = rotation(0.5);
+        for component in &mut halfway {
+            *component = -*component;
+        }
+        let mut sampler = Sampler {
+            input: vec![0., 1., 2.],
+            output: vec![
+                SamplerPrimitive::Vec4(rotation(0.)),
+                // The same rotation as the interpolation, with the opposite sign
+                SamplerPrimitive::Vec4(halfway),
+                SamplerPrimitive::Vec4(rotation(1.)),
+            ],
+            function: InterpolationFunction::SphericalLinear,
+        };
+        reduce_keyframes(TransformChannel::Rotation, &mut sampler, 0.01);
+        assert_eq!(sampler.input, vec![0., 2.]);
+
+        let mut sampler = Sampler {
+            output: vec![
+                SamplerPrimitive::Vec4(rotation(0.)),
+                SamplerPrimitive::Vec4(rotation(0.6)),
+                SamplerPrimitive::Vec4(rotation(1.)),
+            ],
+            ..sampler
+        };
+        sampler.input = vec![0., 1., 2.];
+        reduce_keyframes(TransformChannel::Rotation, &mut sampler, 0.01);
+        assert_eq!(sampler.input, vec![0., 1., 2.]);
+    }
+
+    #[test]
+    fn statistics() {
+        let compression = GltfAnimationCompression::default();
+        let mut statistics = GltfAnimationStatistics::default();
+        let mut sampler = translations(&[[0., 0., 0.], [1., 0., 0.], [2., 0., 0.]]);
+        count(&mut statistics, &sampler, false);
+        compress_sampler(TransformChannel::Translation, &mut sampler, &compression);
+        count(&mut statistics, &sampler, true);
+        assert_eq!((statistics.keyframes, statistics.reduced_keyframes), (3, 2));
+        assert_eq!(statistics.bytes * 2, statistics.reduced_bytes * 3);
+    }
+}
@@ -174,11 +174,18 @@ fn load_scene(
             .get_or_insert_with(Default::default)
             .hierarchy = Some(hierarchy_prefab);
 
-        prefab
-            .data_or_default(0)
-            .animatable
+        let (animation_set, statistics) = load_animations(
+            gltf,
+            buffers,
+            &node_map,
+            options.animation_compression.as_ref(),
+            name,
+        )?;
+        let data = prefab.data_or_default(0);
+        data.animatable
             .get_or_insert_with(Default::default)
-            .animation_set = Some(load_animations(gltf, buffers, &node_map)?);
+            .animation_set = Some(animation_set);
+        data.animation_statistics = statistics;
     }
 
     Ok(())
@@ -63,6 +63,9 @@ pub struct GltfPrefab {
     pub extent: Option<GltfNodeExtent>,
     /// Node name
     pub name: Option<Named>,
+    /// Size of the animations before and after compression, only on the main `Entity` when
+    /// `GltfSceneOptions::animation_compression` is set
+    pub animation_statistics: Option<GltfAnimationStatistics>,
     pub(crate) materials: Option<GltfMaterialSet>,
     pub(crate) material_id: Option<usize>,
 }
@@ -208,6 +211,42 @@ pub struct GltfSceneOptions {
     /// Load the given scene index, if not supplied will either load the default scene (if set),
     /// or the first scene (only if there is only one scene, otherwise an `Error` will be returned).
     pub scene_index: Option<usize>,
+    /// Reduce the keyframes of the loaded animations, if not supplied every keyframe is kept.
+    pub animation_compression: Option<GltfAnimationCompression>,
+}
+
+/// Keyframe reduction of the animations loaded from a GLTF file.
+///
+/// Only samplers with linear, spherical linear or step interpolation are compressed, spline
+/// samplers are kept as they are. The number of keyframes and bytes before and after compression
+/// are logged for each file, and kept in `GltfPrefab::animation_statistics`.
+#[derive(Debug, Clone, Derivative, Serialize, Deserialize)]
+#[derivative(Default)]
+#[serde(default)]
+pub struct GltfAnimationCompression {
+    /// Maximum distance between removed translation keyframes and the reduced animation
+    #[derivative(Default(value = "0.001"))]
+    pub translation_tolerance: f32,
+    /// Maximum angle between removed rotation keyframes and the reduced animation, in radians
+    #[derivative(Default(value = "0.001"))]
+    pub rotation_tolerance: f32,
+    /// Maximum difference between removed scale keyframes and the reduced animation
+    #[derivative(Default(value = "0.001"))]
+    pub scale_tolerance: f32,
+}
+
+/// Number of keyframes and bytes of the samplers of the animations loaded from a GLTF file, before
+/// and after the `GltfAnimationCompression`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GltfAnimationStatistics {
+    /// Keyframes before compression
+    pub keyframes: usize,
+    /// Keyframes after compression
+    pub reduced_keyframes: usize,
+    /// Bytes of the inputs and outputs of the samplers before compression
+    pub bytes: usize,
+    /// Bytes of the inputs and outputs of the samplers after compression
+    pub reduced_bytes: usize,
 }
 
 impl<'a> PrefabData<'a> for GltfPrefab {
@@ -12,7 +12,7 @@ use fnv::FnvHashMap as HashMap;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
-use super::{Axis, Button};
+use super::{Axis, Button, InputSequence};
 
 /// Define a set of types used for bindings configuration.
 /// Usually defaulted to `StringBindings`, which uses `String`s.
@@ -103,6 +103,9 @@ impl BindingTypes for StringBindings {
 ///     actions: {
 ///         "fire": [ [Mouse(Left)], [Key(X)] ], // Multiple bindings for one action
 ///         "reload": [ [Key(LControl), Key(R)] ] // Combinations of multiple bindings possible
+///     },
+///     sequences: { // Optional, see `InputSequence`
+///         "dash": (steps: [(buttons: [Key(Right)]), (buttons: [Key(Right)], window: 0.2)])
 ///     }
 /// )
 /// ```
@@ -119,6 +122,9 @@ pub struct Bindings<T: BindingTypes> {
     /// So for example if you want to quit by either "Esc" or "Ctrl+q" you would have
     /// `[[Esc], [Ctrl, Q]]`.
     pub(super) actions: HashMap<T::Action, SmallVec<[SmallVec<[Button; 2]>; 4]>>,
+    /// Ordered button sequences, completing one emits `InputEvent::SequenceCompleted`.
+    #[serde(default)]
+    pub(super) sequences: HashMap<T::Action, InputSequence>,
 }
 
 /// An enum of possible errors that can occur when binding an action or axis.
@@ -140,6 +146,8 @@ pub enum BindingError<T: BindingTypes> {
     /// That specific axis on that specific controller is already in use for an
     /// axis binding.
     ControllerAxisAlreadyBound(T::Axis),
+    /// Sequence provided has no steps, or one of its steps has no buttons.
+    SequenceHasEmptyStep(T::Action),
 }
 
 impl<T: BindingTypes> PartialEq for BindingError<T> {
@@ -169,6 +177,9 @@ impl<T: BindingTypes> PartialEq for BindingError<T> {
                 BindingError::MouseWheelAxisAlreadyBound(a),
                 BindingError::MouseWheelAxisAlreadyBound(x),
             ) => a == x,
+            (BindingError::SequenceHasEmptyStep(a), BindingError::SequenceHasEmptyStep(x)) => {
+                a == x
+            }
             (_, _) => false,
         }
     }
@@ -208,6 +219,11 @@ where
             BindingError::MouseWheelAxisAlreadyBound(ref id) => {
                 write!(f, "Mouse wheel axis provided is already in use by {}", id)
             }
+            BindingError::SequenceHasEmptyStep(ref id) => write!(
+                f,
+                "Sequence provided for {} has no steps or a step without buttons",
+                id
+            ),
         }
     }
 }
@@ -377,6 +393,42 @@ impl<T: BindingTypes> Bindings<T> {
         self.actions.keys()
     }
 
+    /// Assign an input sequence to an action.
+    ///
+    /// This will insert a new sequence if no entry for this id exists.
+    /// If one does exist this will replace the sequence at that id and return it.
+    pub fn insert_sequence(
+        &mut self,
+        id: T::Action,
+        sequence: InputSequence,
+    ) -> Result<Option<InputSequence>, BindingError<T>> {
+        self.check_sequence_invariants(&id, &sequence)?;
+        Ok(self.sequences.insert(id, sequence))
+    }
+
+    /// Removes a sequence, this will return the removed sequence if successful.
+    pub fn remove_sequence<A>(&mut self, id: &A) -> Option<InputSequence>
+    where
+        T::Action: Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        self.sequences.remove(id)
+    }
+
+    /// Returns a reference to a sequence.
+    pub fn sequence<A>(&self, id: &A) -> Option<&InputSequence>
+    where
+        T::Action: Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        self.sequences.get(id)
+    }
+
+    /// Gets a list of all actions bound to a sequence
+    pub fn sequences(&self) -> impl Iterator<Item = &T::Action> {
+        self.sequences.keys()
+    }
+
     /// Check that this structure upholds its guarantees. Should only be necessary when serializing or deserializing the bindings.
     pub fn check_invariants(&mut self) -> Result<(), BindingError<T>> {
         // The easiest way to do this is to use the existing code that checks for invariants when adding bindings.
@@ -403,6 +455,30 @@ impl<T: BindingTypes> Bindings<T> {
             self.remove_axis(&k);
             self.insert_axis(k, a)?;
         }
+        for (k, sequence) in self.sequences.iter() {
+            self.check_sequence_invariants(k, sequence)?;
+        }
+        Ok(())
+    }
+
+    fn check_sequence_invariants(
+        &self,
+        id: &T::Action,
+        sequence: &InputSequence,
+    ) -> Result<(), BindingError<T>> {
+        if sequence.steps.is_empty() || sequence.steps.iter().any(|s| s.buttons.is_empty()) {
+            return Err(BindingError::SequenceHasEmptyStep(id.clone()));
+        }
+        for step in sequence.steps.iter() {
+            let bind = step.buttons.as_slice();
+            for i in 0..bind.len() {
+                for j in (i + 1)..bind.len() {
+                    if bind[i] == bind[j] {
+                        return Err(BindingError::ComboContainsDuplicates(id.clone()));
+                    }
+                }
+            }
+        }
         Ok(())
     }
 
@@ -504,7 +580,7 @@ impl<T: BindingTypes> Bindings<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{button::*, controller::ControllerAxis};
+    use crate::{button::*, controller::ControllerAxis, sequence::SequenceStep};
     use winit::{MouseButton, VirtualKeyCode};
 
     #[test]
@@ -875,4 +951,40 @@ mod tests {
             Some(Axis::MouseWheel { horizontal: false })
         );
     }
+
+    #[test]
+    fn add_and_remove_sequences() {
+        let mut bindings = Bindings::<StringBindings>::new();
+        let dash = InputSequence::new(vec![
+            SequenceStep::new(vec![Button::Key(VirtualKeyCode::Right)]),
+            SequenceStep::new(vec![Button::Key(VirtualKeyCode::Right)]),
+        ]);
+        assert_eq!(
+            bindings
+                .insert_sequence(String::from("dash"), dash.clone())
+                .unwrap(),
+            None
+        );
+        assert_eq!(bindings.sequence("dash"), Some(&dash));
+        assert_eq!(
+            bindings
+                .insert_sequence(String::from("empty"), InputSequence::new(vec![]))
+                .unwrap_err(),
+            BindingError::SequenceHasEmptyStep(String::from("empty"))
+        );
+        assert_eq!(
+            bindings
+                .insert_sequence(
+                    String::from("duplicates"),
+                    InputSequence::new(vec![SequenceStep::new(vec![
+                        Button::Key(VirtualKeyCode::X),
+                        Button::Key(VirtualKeyCode::X),
+                    ])]),
+                )
+                .unwrap_err(),
+            BindingError::ComboContainsDuplicates(String::from("duplicates"))
+        );
+        assert_eq!(bindings.remove_sequence("dash"), Some(dash));
+        assert_eq!(bindings.sequences().next(), None);
+    }
 }
@@ -1,6 +1,9 @@
 //! ECS input bundle
 
-use crate::{BindingError, BindingTypes, Bindings, InputSystemDesc};
+use crate::{
+    BindingError, BindingTypes, Bindings, GestureConfig, GestureSystemDesc, InputSystemDesc,
+    PlayerInput, PlayerInputSystemDesc, SequenceSystemDesc,
+};
 use amethyst_config::{Config, ConfigError};
 use amethyst_core::{
     ecs::prelude::{DispatcherBuilder, World},
@@ -12,6 +15,8 @@ use std::{error, fmt, path::Path};
 
 #[cfg(feature = "sdl_controller")]
 use crate::sdl_events_system::ControllerMappings;
+#[cfg(all(feature = "evdev_controller", target_os = "linux"))]
+use crate::GamepadMapping;
 
 /// Bundle for adding the `InputHandler`.
 ///
@@ -32,8 +37,12 @@ use crate::sdl_events_system::ControllerMappings;
 #[derivative(Default(bound = ""))]
 pub struct InputBundle<T: BindingTypes> {
     bindings: Option<Bindings<T>>,
+    gesture_config: GestureConfig,
+    player_input: Option<PlayerInput<T>>,
     #[cfg(feature = "sdl_controller")]
     controller_mappings: Option<ControllerMappings>,
+    #[cfg(all(feature = "evdev_controller", target_os = "linux"))]
+    gamepad_mapping: GamepadMapping,
 }
 
 impl<T: BindingTypes> InputBundle<T> {
@@ -61,6 +70,18 @@ impl<T: BindingTypes> InputBundle<T> {
         Ok(self.with_bindings(bindings))
     }
 
+    /// Use the provided thresholds to recognize touch gestures
+    pub fn with_gesture_config(mut self, config: GestureConfig) -> Self {
+        self.gesture_config = config;
+        self
+    }
+
+    /// Assign input devices to player slots for local multiplayer, see `PlayerInput`
+    pub fn with_player_input(mut self, player_input: PlayerInput<T>) -> Self {
+        self.player_input = Some(player_input);
+        self
+    }
+
     /// Load SDL controller mappings from file
     #[cfg(feature = "sdl_controller")]
     pub fn with_sdl_controller_mappings(mut self, mappings: String) -> Self {
@@ -77,6 +98,13 @@ impl<T: BindingTypes> InputBundle<T> {
         self.controller_mappings = Some(ControllerMappings::FromPath(path_buf));
         self
     }
+
+    /// Use the provided mapping to translate evdev codes into controller inputs
+    #[cfg(all(feature = "evdev_controller", target_os = "linux"))]
+    pub fn with_gamepad_mapping(mut self, mapping: GamepadMapping) -> Self {
+        self.gamepad_mapping = mapping;
+        self
+    }
 }
 
 impl<'a, 'b, T: BindingTypes> SystemBundle<'a, 'b> for InputBundle<T> {
@@ -93,11 +121,46 @@ impl<'a, 'b, T: BindingTypes> SystemBundle<'a, 'b> for InputBundle<T> {
                 SdlEventsSystem::<T>::new(world, self.controller_mappings).unwrap(),
             );
         }
+        #[cfg(all(feature = "evdev_controller", target_os = "linux"))]
+        {
+            use crate::{EvdevProvider, GamepadEventsSystemDesc};
+            builder.add(
+                GamepadEventsSystemDesc::<T>::new(
+                    Box::new(EvdevProvider::default()),
+                    self.gamepad_mapping,
+                )
+                .build(world),
+                "gamepad_events_system",
+                &[],
+            );
+        }
+        #[cfg(all(feature = "evdev_controller", target_os = "linux"))]
+        let input_dependencies: &[&str] = &["gamepad_events_system"];
+        #[cfg(not(all(feature = "evdev_controller", target_os = "linux")))]
+        let input_dependencies: &[&str] = &[];
         builder.add(
             InputSystemDesc::<T>::new(self.bindings).build(world),
             "input_system",
-            &[],
+            input_dependencies,
+        );
+        builder.add(
+            SequenceSystemDesc::<T>::new().build(world),
+            "input_sequence_system",
+            &["input_system"],
         );
+        builder.add(
+            GestureSystemDesc::<T>::new(self.gesture_config).build(world),
+            "input_gesture_system",
+            &["input_system"],
+        );
+        if let Some(player_input) = self.player_input {
+            world.insert(player_input);
+            builder.add(
+                PlayerInputSystemDesc::<T>::new().build(world),
+                "player_input_system",
+                &["input_system"],
+            );
+        }
         Ok(())
     }
 }
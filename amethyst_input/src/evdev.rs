@@ -0,0 +1,211 @@
+//! Controller backend reading the Linux evdev interface, `/dev/input/event*`.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read},
+    mem,
+    os::unix::{fs::OpenOptionsExt, io::AsRawFd},
+    path::{Path, PathBuf},
+    ptr,
+};
+
+use crate::gamepad::{GamepadDevice, GamepadProvider, RawGamepadEvent};
+
+const EV_KEY: u16 = 0x01;
+const EV_ABS: u16 = 0x03;
+const KEY_MAX: usize = 0x2ff;
+const ABS_MAX: usize = 0x3f;
+/// First button code of the gamepad range, only devices reporting it are opened.
+const BTN_GAMEPAD: usize = 0x130;
+
+/// Mirrors the kernel's `struct input_event`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct InputEvent {
+    time: libc::timeval,
+    kind: u16,
+    code: u16,
+    value: i32,
+}
+
+/// Mirrors the kernel's `struct input_absinfo`.
+#[repr(C)]
+#[derive(Default)]
+struct InputAbsInfo {
+    value: i32,
+    minimum: i32,
+    maximum: i32,
+    fuzz: i32,
+    flat: i32,
+    resolution: i32,
+}
+
+/// Builds the request of a read-only evdev ioctl, like the kernel's `_IOR('E', nr, size)`.
+fn ioctl_read(nr: usize, size: usize) -> libc::c_ulong {
+    const IOC_READ: libc::c_ulong = 2;
+    (IOC_READ << 30)
+        | ((size as libc::c_ulong) << 16)
+        | ((libc::c_ulong::from(b'E')) << 8)
+        | nr as libc::c_ulong
+}
+
+fn ioctl<T>(file: &File, request: libc::c_ulong, data: &mut T) -> io::Result<()> {
+    // Safe because the size encoded in every request matches the type of `data`.
+    let result = unsafe { libc::ioctl(file.as_raw_fd(), request as _, data as *mut T) };
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+fn bit_is_set(bits: &[u8], bit: usize) -> bool {
+    bits.get(bit / 8)
+        .map(|byte| byte & (1 << (bit % 8)) != 0)
+        .unwrap_or(false)
+}
+
+/// Opens the controllers found in `/dev/input`.
+///
+/// The user needs read access to the event files, usually granted by the `input` group.
+#[derive(Debug)]
+pub struct EvdevProvider {
+    directory: PathBuf,
+    /// Event files which aren't controllers or can't be opened.
+    ignored: Vec<String>,
+}
+
+impl Default for EvdevProvider {
+    fn default() -> Self {
+        EvdevProvider::new("/dev/input")
+    }
+}
+
+impl EvdevProvider {
+    /// Creates a provider looking for event files in the given directory.
+    pub fn new<P: AsRef<Path>>(directory: P) -> Self {
+        EvdevProvider {
+            directory: directory.as_ref().to_path_buf(),
+            ignored: Vec::new(),
+        }
+    }
+}
+
+impl GamepadProvider for EvdevProvider {
+    fn discover(
+        &mut self,
+        is_open: &dyn Fn(&str) -> bool,
+    ) -> Vec<(String, Box<dyn GamepadDevice>)> {
+        let entries = match fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(_) => return Vec::new(),
+        };
+        let paths = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("event"))
+            .map(|entry| entry.path().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        // Forget devices that were unplugged, their path might be reused by a controller.
+        self.ignored.retain(|path| paths.contains(path));
+
+        let mut found = Vec::new();
+        for path in paths {
+            if is_open(&path) || self.ignored.contains(&path) {
+                continue;
+            }
+            match EvdevDevice::open(&path) {
+                Ok(Some(device)) => found.push((path, Box::new(device) as Box<dyn GamepadDevice>)),
+                Ok(None) | Err(_) => self.ignored.push(path),
+            }
+        }
+        found
+    }
+}
+
+/// A controller opened through its evdev event file.
+#[derive(Debug)]
+pub struct EvdevDevice {
+    file: File,
+    abs_ranges: Vec<(u16, i32, i32)>,
+    buffer: Vec<u8>,
+}
+
+impl EvdevDevice {
+    /// Opens the event file at `path`, returning `None` if the device isn't a controller.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Option<Self>> {
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)?;
+
+        let mut key_bits = [0u8; KEY_MAX / 8 + 1];
+        ioctl(
+            &file,
+            ioctl_read(0x20 + EV_KEY as usize, key_bits.len()),
+            &mut key_bits,
+        )?;
+        if !bit_is_set(&key_bits, BTN_GAMEPAD) {
+            return Ok(None);
+        }
+
+        let mut abs_bits = [0u8; ABS_MAX / 8 + 1];
+        ioctl(
+            &file,
+            ioctl_read(0x20 + EV_ABS as usize, abs_bits.len()),
+            &mut abs_bits,
+        )?;
+        let mut abs_ranges = Vec::new();
+        for code in (0..=ABS_MAX).filter(|code| bit_is_set(&abs_bits, *code)) {
+            let mut info = InputAbsInfo::default();
+            ioctl(
+                &file,
+                ioctl_read(0x40 + code, mem::size_of::<InputAbsInfo>()),
+                &mut info,
+            )?;
+            abs_ranges.push((code as u16, info.minimum, info.maximum));
+        }
+
+        Ok(Some(EvdevDevice {
+            file,
+            abs_ranges,
+            buffer: vec![0; mem::size_of::<InputEvent>() * 64],
+        }))
+    }
+}
+
+impl GamepadDevice for EvdevDevice {
+    fn poll(&mut self, events: &mut Vec<RawGamepadEvent>) -> io::Result<()> {
+        let size = mem::size_of::<InputEvent>();
+        loop {
+            let read = match self.file.read(&mut self.buffer) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(read) => read,
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => return Err(e),
+            };
+            for chunk in self.buffer[..read].chunks_exact(size) {
+                // Safe because the kernel only writes whole `input_event`s.
+                let event = unsafe { ptr::read_unaligned(chunk.as_ptr() as *const InputEvent) };
+                match event.kind {
+                    EV_KEY => events.push(RawGamepadEvent::Key {
+                        code: event.code,
+                        // 2 means auto-repeat, the button is still pressed.
+                        pressed: event.value != 0,
+                    }),
+                    EV_ABS => events.push(RawGamepadEvent::Abs {
+                        code: event.code,
+                        value: event.value,
+                    }),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn abs_range(&self, code: u16) -> Option<(i32, i32)> {
+        self.abs_ranges
+            .iter()
+            .find(|(c, _, _)| *c == code)
+            .map(|(_, min, max)| (*min, *max))
+    }
+}
@@ -7,6 +7,7 @@ use super::{
     button::Button,
     controller::{ControllerAxis, ControllerButton},
     scroll_direction::ScrollDirection,
+    touch::Gesture,
 };
 
 /// Events generated by the input system
@@ -60,6 +61,46 @@ where
     },
     /// The mousewheel was moved in either direction
     MouseWheelMoved(ScrollDirection),
+    /// A finger touched the screen.
+    TouchStarted {
+        /// The id of the touch, unique while the finger stays on the screen.
+        id: u64,
+        /// The horizontal position of the touch in pixels.
+        x: f32,
+        /// The vertical position of the touch in pixels.
+        y: f32,
+    },
+    /// A finger moved on the screen.
+    TouchMoved {
+        /// The id of the touch.
+        id: u64,
+        /// The horizontal position of the touch in pixels.
+        x: f32,
+        /// The vertical position of the touch in pixels.
+        y: f32,
+        /// The amount the touch moved horizontally in pixels.
+        delta_x: f32,
+        /// The amount the touch moved vertically in pixels.
+        delta_y: f32,
+    },
+    /// A finger was lifted from the screen.
+    TouchEnded {
+        /// The id of the touch, it might be reused later.
+        id: u64,
+        /// The horizontal position of the touch in pixels.
+        x: f32,
+        /// The vertical position of the touch in pixels.
+        y: f32,
+    },
+    /// The system cancelled a touch, e.g. because the window lost focus.
+    TouchCancelled {
+        /// The id of the touch, it might be reused later.
+        id: u64,
+    },
+    /// A touch gesture was recognized.
+    ///
+    /// Sent by the `GestureSystem`.
+    Gesture(Gesture),
     /// An axis value changed.
     ///
     /// Note that this variant is used for `BindingTypes::Axis`, not a `ControllerAxis`.
@@ -116,4 +157,8 @@ where
     ActionReleased(T::Action),
     /// The associated action has its mouse wheel moved.
     ActionWheelMoved(T::Action),
+    /// The input sequence bound to the associated action was completed.
+    ///
+    /// Sent by the `SequenceSystem`, see `InputSequence`.
+    SequenceCompleted(T::Action),
 }
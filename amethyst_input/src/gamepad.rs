@@ -0,0 +1,666 @@
+//! Controller support implemented in Rust, as an alternative to the SDL backend.
+//!
+//! Devices are reached through the `GamepadProvider` and `GamepadDevice` traits, so the
+//! translation into `ControllerEvent`s can be tested without hardware. Enable the
+//! `evdev_controller` feature for the Linux evdev implementation.
+
+use std::{
+    fmt, io,
+    marker::PhantomData,
+    sync::{
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use amethyst_core::{
+    ecs::prelude::{System, SystemData, World, Write},
+    shrev::EventChannel,
+    SystemDesc,
+};
+
+use crate::{
+    bindings::BindingTypes,
+    controller::{ControllerAxis, ControllerButton, ControllerEvent},
+    event::InputEvent,
+    input_handler::InputHandler,
+};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+/// A raw input reported by a `GamepadDevice`, using the codes of the Linux input subsystem.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum RawGamepadEvent {
+    /// A digital button changed state.
+    Key {
+        /// The button code, e.g. `0x130` for `BTN_SOUTH`.
+        code: u16,
+        /// Whether the button is now pressed.
+        pressed: bool,
+    },
+    /// An absolute axis changed value.
+    Abs {
+        /// The axis code, e.g. `0x00` for `ABS_X`.
+        code: u16,
+        /// The new raw value, within the range given by `GamepadDevice::abs_range`.
+        value: i32,
+    },
+}
+
+/// A single opened controller.
+pub trait GamepadDevice: Send {
+    /// Pushes every input received since the last call into `events`.
+    ///
+    /// Returning an error means the device is gone; it is then closed and reported as
+    /// disconnected.
+    fn poll(&mut self, events: &mut Vec<RawGamepadEvent>) -> io::Result<()>;
+
+    /// Returns the minimum and maximum raw values of an absolute axis.
+    fn abs_range(&self, code: u16) -> Option<(i32, i32)>;
+}
+
+/// Finds controllers plugged into the system.
+pub trait GamepadProvider: Send {
+    /// Opens every controller not opened yet, returning them along with a key unique to the
+    /// physical device (e.g. its path).
+    ///
+    /// `is_open` tells whether the device with the given key is already opened.
+    fn discover(&mut self, is_open: &dyn Fn(&str) -> bool)
+        -> Vec<(String, Box<dyn GamepadDevice>)>;
+}
+
+/// What a raw button code of a `GamepadDevice` stands for.
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum MappedInput {
+    /// A controller button.
+    Button(ControllerButton),
+    /// A stick axis, reported from -1 to 1.
+    Stick(ControllerAxis),
+    /// A trigger axis, reported from 0 to 1.
+    Trigger(ControllerAxis),
+    /// A hat axis emulating a pair of buttons, negative then positive (e.g. dpad left and right).
+    Hat(ControllerButton, ControllerButton),
+}
+
+/// Translation of raw codes into controller inputs.
+///
+/// The default follows the Linux gamepad specification; override entries for controllers
+/// whose drivers don't.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct GamepadMapping {
+    /// Mappings of `RawGamepadEvent::Key` codes.
+    pub keys: Vec<(u16, MappedInput)>,
+    /// Mappings of `RawGamepadEvent::Abs` codes.
+    pub axes: Vec<(u16, MappedInput)>,
+}
+
+impl Default for GamepadMapping {
+    fn default() -> Self {
+        use self::MappedInput::*;
+        use crate::controller::{ControllerAxis as A, ControllerButton as B};
+
+        GamepadMapping {
+            keys: vec![
+                (0x130, Button(B::A)),
+                (0x131, Button(B::B)),
+                (0x133, Button(B::Y)),
+                (0x134, Button(B::X)),
+                (0x136, Button(B::LeftShoulder)),
+                (0x137, Button(B::RightShoulder)),
+                (0x13a, Button(B::Back)),
+                (0x13b, Button(B::Start)),
+                (0x13c, Button(B::Guide)),
+                (0x13d, Button(B::LeftStick)),
+                (0x13e, Button(B::RightStick)),
+                (0x220, Button(B::DPadUp)),
+                (0x221, Button(B::DPadDown)),
+                (0x222, Button(B::DPadLeft)),
+                (0x223, Button(B::DPadRight)),
+            ],
+            axes: vec![
+                (0x00, Stick(A::LeftX)),
+                (0x01, Stick(A::LeftY)),
+                (0x02, Trigger(A::LeftTrigger)),
+                (0x03, Stick(A::RightX)),
+                (0x04, Stick(A::RightY)),
+                (0x05, Trigger(A::RightTrigger)),
+                (0x10, Hat(B::DPadLeft, B::DPadRight)),
+                (0x11, Hat(B::DPadUp, B::DPadDown)),
+            ],
+        }
+    }
+}
+
+struct OpenedGamepad {
+    which: u32,
+    key: String,
+    device: Box<dyn GamepadDevice>,
+    /// Buttons currently pressed through hat axes.
+    hat_buttons: Vec<ControllerButton>,
+}
+
+type FoundGamepad = (String, Box<dyn GamepadDevice>);
+
+enum Discovery {
+    /// The provider is called on the thread of `GamepadManager::discover`.
+    Inline(Box<dyn GamepadProvider>),
+    /// The provider is called on a background thread, sending the devices it finds.
+    Background {
+        found: Receiver<FoundGamepad>,
+        /// Keys of the opened devices, including the ones sent but not received yet.
+        open: Arc<Mutex<Vec<String>>>,
+    },
+}
+
+/// Keeps track of the opened controllers of a `GamepadProvider`, and translates their raw
+/// inputs into `ControllerEvent`s.
+pub struct GamepadManager {
+    discovery: Discovery,
+    mapping: GamepadMapping,
+    opened: Vec<OpenedGamepad>,
+    next_which: u32,
+    raw_events: Vec<RawGamepadEvent>,
+}
+
+impl fmt::Debug for GamepadManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GamepadManager")
+            .field("mapping", &self.mapping)
+            .field(
+                "opened",
+                &self.opened.iter().map(|g| &g.key).collect::<Vec<_>>(),
+            )
+            .field("next_which", &self.next_which)
+            .finish()
+    }
+}
+
+impl GamepadManager {
+    /// Creates a new manager opening devices from the given provider.
+    ///
+    /// The provider is called on the thread of `discover`, which may block on the file system;
+    /// see `with_background_discovery` to keep it off the frame.
+    pub fn new(provider: Box<dyn GamepadProvider>, mapping: GamepadMapping) -> Self {
+        GamepadManager::with_discovery(Discovery::Inline(provider), mapping)
+    }
+
+    /// Creates a new manager searching for controllers of the given provider on a background
+    /// thread, every `interval`.
+    ///
+    /// The thread stops once the manager is dropped.
+    pub fn with_background_discovery(
+        mut provider: Box<dyn GamepadProvider>,
+        mapping: GamepadMapping,
+        interval: Duration,
+    ) -> Self {
+        let (sender, found) = mpsc::channel();
+        let open = Arc::new(Mutex::new(Vec::<String>::new()));
+        let thread_open = Arc::clone(&open);
+        thread::spawn(move || loop {
+            // The manager was dropped
+            if Arc::strong_count(&thread_open) == 1 {
+                return;
+            }
+            // Scanning on a copy of the keys, so `discover` never waits for the provider.
+            let known = match thread_open.lock() {
+                Ok(open) => open.clone(),
+                Err(_) => return,
+            };
+            let devices = provider.discover(&|key| known.iter().any(|k| k == key));
+            if !devices.is_empty() {
+                let mut open = match thread_open.lock() {
+                    Ok(open) => open,
+                    Err(_) => return,
+                };
+                for (key, device) in devices {
+                    open.push(key.clone());
+                    if sender.send((key, device)).is_err() {
+                        return;
+                    }
+                }
+            }
+            thread::sleep(interval);
+        });
+        GamepadManager::with_discovery(Discovery::Background { found, open }, mapping)
+    }
+
+    fn with_discovery(discovery: Discovery, mapping: GamepadMapping) -> Self {
+        GamepadManager {
+            discovery,
+            mapping,
+            opened: Vec::new(),
+            next_which: 0,
+            raw_events: Vec::new(),
+        }
+    }
+
+    /// Opens newly plugged controllers, reporting them as connected.
+    ///
+    /// With background discovery, this only picks up the controllers found by the background
+    /// thread and never blocks for long.
+    pub fn discover(&mut self, events: &mut Vec<ControllerEvent>) {
+        let opened = &self.opened;
+        let found = match self.discovery {
+            Discovery::Inline(ref mut provider) => {
+                provider.discover(&|key| opened.iter().any(|g| g.key == key))
+            }
+            Discovery::Background {
+                ref found,
+                ref open,
+            } => {
+                // Draining while holding the lock, so the keys sent meanwhile aren't forgotten.
+                let mut open = match open.lock() {
+                    Ok(open) => open,
+                    Err(_) => return,
+                };
+                let found = found.try_iter().collect::<Vec<_>>();
+                open.clear();
+                open.extend(opened.iter().map(|g| g.key.clone()));
+                open.extend(found.iter().map(|(key, _)| key.clone()));
+                found
+            }
+        };
+        for (key, device) in found {
+            let which = self.next_which;
+            self.next_which += 1;
+            self.opened.push(OpenedGamepad {
+                which,
+                key,
+                device,
+                hat_buttons: Vec::new(),
+            });
+            events.push(ControllerEvent::ControllerConnected { which });
+        }
+    }
+
+    /// Polls every opened controller, translating its inputs. Controllers failing to be polled
+    /// are closed and reported as disconnected.
+    pub fn poll(&mut self, events: &mut Vec<ControllerEvent>) {
+        let mapping = &self.mapping;
+        let raw_events = &mut self.raw_events;
+        let mut index = 0;
+        while index < self.opened.len() {
+            raw_events.clear();
+            let gamepad = &mut self.opened[index];
+            match gamepad.device.poll(raw_events) {
+                Ok(()) => {
+                    for raw in raw_events.iter() {
+                        translate(mapping, gamepad, *raw, events);
+                    }
+                    index += 1;
+                }
+                Err(_) => {
+                    let which = gamepad.which;
+                    self.opened.swap_remove(index);
+                    events.push(ControllerEvent::ControllerDisconnected { which });
+                }
+            }
+        }
+    }
+}
+
+fn translate(
+    mapping: &GamepadMapping,
+    gamepad: &mut OpenedGamepad,
+    raw: RawGamepadEvent,
+    events: &mut Vec<ControllerEvent>,
+) {
+    let which = gamepad.which;
+    match raw {
+        RawGamepadEvent::Key { code, pressed } => {
+            if let Some((_, MappedInput::Button(button))) =
+                mapping.keys.iter().find(|(c, _)| *c == code)
+            {
+                events.push(if pressed {
+                    ControllerEvent::ControllerButtonPressed {
+                        which,
+                        button: *button,
+                    }
+                } else {
+                    ControllerEvent::ControllerButtonReleased {
+                        which,
+                        button: *button,
+                    }
+                });
+            }
+        }
+        RawGamepadEvent::Abs { code, value } => {
+            let input = match mapping.axes.iter().find(|(c, _)| *c == code) {
+                Some((_, input)) => *input,
+                None => return,
+            };
+            let (min, max) = gamepad.device.abs_range(code).unwrap_or((-1, 1));
+            let range = (max - min).max(1) as f32;
+            let unit = (value - min) as f32 / range;
+            match input {
+                MappedInput::Stick(axis) => events.push(ControllerEvent::ControllerAxisMoved {
+                    which,
+                    axis,
+                    value: (unit * 2.0 - 1.0).max(-1.0).min(1.0),
+                }),
+                MappedInput::Trigger(axis) => events.push(ControllerEvent::ControllerAxisMoved {
+                    which,
+                    axis,
+                    value: unit.max(0.0).min(1.0),
+                }),
+                MappedInput::Hat(negative, positive) => {
+                    let center = min + (max - min) / 2;
+                    let pressed = if value < center {
+                        Some(negative)
+                    } else if value > center {
+                        Some(positive)
+                    } else {
+                        None
+                    };
+                    for button in [negative, positive].iter() {
+                        let was_down = gamepad.hat_buttons.contains(button);
+                        let is_down = pressed == Some(*button);
+                        if was_down && !is_down {
+                            gamepad.hat_buttons.retain(|b| b != button);
+                            events.push(ControllerEvent::ControllerButtonReleased {
+                                which,
+                                button: *button,
+                            });
+                        } else if is_down && !was_down {
+                            gamepad.hat_buttons.push(*button);
+                            events.push(ControllerEvent::ControllerButtonPressed {
+                                which,
+                                button: *button,
+                            });
+                        }
+                    }
+                }
+                MappedInput::Button(_) => {}
+            }
+        }
+    }
+}
+
+/// Builds a `GamepadEventsSystem`.
+pub struct GamepadEventsSystemDesc<T>
+where
+    T: BindingTypes,
+{
+    provider: Box<dyn GamepadProvider>,
+    mapping: GamepadMapping,
+    marker: PhantomData<T>,
+}
+
+impl<T: BindingTypes> fmt::Debug for GamepadEventsSystemDesc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GamepadEventsSystemDesc")
+            .field("mapping", &self.mapping)
+            .finish()
+    }
+}
+
+impl<T: BindingTypes> GamepadEventsSystemDesc<T> {
+    /// Creates a new builder opening controllers from the given provider.
+    pub fn new(provider: Box<dyn GamepadProvider>, mapping: GamepadMapping) -> Self {
+        GamepadEventsSystemDesc {
+            provider,
+            mapping,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b, T> SystemDesc<'a, 'b, GamepadEventsSystem<T>> for GamepadEventsSystemDesc<T>
+where
+    T: BindingTypes,
+{
+    fn build(self, world: &mut World) -> GamepadEventsSystem<T> {
+        <GamepadEventsSystem<T> as System<'_>>::SystemData::setup(world);
+
+        GamepadEventsSystem::new(GamepadManager::with_background_discovery(
+            self.provider,
+            self.mapping,
+            DISCOVER_INTERVAL,
+        ))
+    }
+}
+
+/// Time between two searches for newly plugged controllers.
+const DISCOVER_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A system that pumps controller events of a `GamepadProvider` into the `amethyst_input` APIs.
+///
+/// Produces the same `ControllerEvent`s as the `SdlEventsSystem`, without depending on SDL.
+/// Controllers are discovered every frame, so the manager should use background discovery.
+#[derive(Debug)]
+pub struct GamepadEventsSystem<T: BindingTypes> {
+    manager: GamepadManager,
+    events: Vec<ControllerEvent>,
+    marker: PhantomData<T>,
+}
+
+impl<T: BindingTypes> GamepadEventsSystem<T> {
+    /// Creates a new instance of this system driving the given manager.
+    pub fn new(manager: GamepadManager) -> Self {
+        GamepadEventsSystem {
+            manager,
+            events: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: BindingTypes> System<'a> for GamepadEventsSystem<T> {
+    type SystemData = (
+        Write<'a, InputHandler<T>>,
+        Write<'a, EventChannel<InputEvent<T>>>,
+    );
+
+    fn run(&mut self, (mut handler, mut output): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("gamepad_events_system");
+
+        self.manager.discover(&mut self.events);
+        self.manager.poll(&mut self.events);
+        for event in self.events.drain(..) {
+            handler.send_controller_event(&event, &mut output);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::SyncSender;
+
+    type Inbox = Arc<Mutex<Option<Vec<RawGamepadEvent>>>>;
+
+    /// A device whose inputs are pushed by the test, `None` meaning unplugged.
+    struct MockDevice(Inbox);
+
+    impl GamepadDevice for MockDevice {
+        fn poll(&mut self, events: &mut Vec<RawGamepadEvent>) -> io::Result<()> {
+            match *self.0.lock().unwrap() {
+                Some(ref mut inbox) => {
+                    events.extend(inbox.drain(..));
+                    Ok(())
+                }
+                None => Err(io::Error::new(io::ErrorKind::NotFound, "unplugged")),
+            }
+        }
+
+        fn abs_range(&self, code: u16) -> Option<(i32, i32)> {
+            match code {
+                0x02 | 0x05 => Some((0, 255)),
+                0x10 | 0x11 => Some((-1, 1)),
+                _ => Some((-32768, 32767)),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct MockProvider(Arc<Mutex<Vec<(String, Inbox)>>>);
+
+    impl GamepadProvider for MockProvider {
+        fn discover(
+            &mut self,
+            is_open: &dyn Fn(&str) -> bool,
+        ) -> Vec<(String, Box<dyn GamepadDevice>)> {
+            self.0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(key, inbox)| !is_open(key) && inbox.lock().unwrap().is_some())
+                .map(|(key, inbox)| {
+                    (
+                        key.clone(),
+                        Box::new(MockDevice(inbox.clone())) as Box<dyn GamepadDevice>,
+                    )
+                })
+                .collect()
+        }
+    }
+
+    /// A provider telling the test every time it starts a scan, waiting for it to listen.
+    struct SteppedProvider(MockProvider, SyncSender<()>);
+
+    impl GamepadProvider for SteppedProvider {
+        fn discover(
+            &mut self,
+            is_open: &dyn Fn(&str) -> bool,
+        ) -> Vec<(String, Box<dyn GamepadDevice>)> {
+            let _ = self.1.send(());
+            self.0.discover(is_open)
+        }
+    }
+
+    fn setup() -> (GamepadManager, Inbox) {
+        let provider = MockProvider::default();
+        let inbox: Inbox = Arc::new(Mutex::new(Some(Vec::new())));
+        provider
+            .0
+            .lock()
+            .unwrap()
+            .push((String::from("/dev/input/event3"), inbox.clone()));
+        (
+            GamepadManager::new(Box::new(provider), GamepadMapping::default()),
+            inbox,
+        )
+    }
+
+    #[test]
+    fn connects_and_translates_inputs() {
+        let (mut manager, inbox) = setup();
+        let mut events = Vec::new();
+        manager.discover(&mut events);
+        manager.discover(&mut events);
+        assert_eq!(
+            events,
+            vec![ControllerEvent::ControllerConnected { which: 0 }]
+        );
+
+        events.clear();
+        inbox.lock().unwrap().as_mut().unwrap().extend(vec![
+            RawGamepadEvent::Key {
+                code: 0x130,
+                pressed: true,
+            },
+            RawGamepadEvent::Abs {
+                code: 0x02,
+                value: 255,
+            },
+            RawGamepadEvent::Abs {
+                code: 0x00,
+                value: -32768,
+            },
+            RawGamepadEvent::Abs {
+                code: 0x10,
+                value: 1,
+            },
+            RawGamepadEvent::Abs {
+                code: 0x10,
+                value: 0,
+            },
+        ]);
+        manager.poll(&mut events);
+        assert_eq!(
+            events,
+            vec![
+                ControllerEvent::ControllerButtonPressed {
+                    which: 0,
+                    button: ControllerButton::A,
+                },
+                ControllerEvent::ControllerAxisMoved {
+                    which: 0,
+                    axis: ControllerAxis::LeftTrigger,
+                    value: 1.0,
+                },
+                ControllerEvent::ControllerAxisMoved {
+                    which: 0,
+                    axis: ControllerAxis::LeftX,
+                    value: -1.0,
+                },
+                ControllerEvent::ControllerButtonPressed {
+                    which: 0,
+                    button: ControllerButton::DPadRight,
+                },
+                ControllerEvent::ControllerButtonReleased {
+                    which: 0,
+                    button: ControllerButton::DPadRight,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unplugged_device_disconnects() {
+        let (mut manager, inbox) = setup();
+        let mut events = Vec::new();
+        manager.discover(&mut events);
+        *inbox.lock().unwrap() = None;
+        events.clear();
+        manager.poll(&mut events);
+        assert_eq!(
+            events,
+            vec![ControllerEvent::ControllerDisconnected { which: 0 }]
+        );
+
+        *inbox.lock().unwrap() = Some(Vec::new());
+        events.clear();
+        manager.discover(&mut events);
+        assert_eq!(
+            events,
+            vec![ControllerEvent::ControllerConnected { which: 1 }]
+        );
+    }
+
+    #[test]
+    fn background_discovery_connects_once() {
+        let provider = MockProvider::default();
+        let inbox: Inbox = Arc::new(Mutex::new(Some(Vec::new())));
+        provider
+            .0
+            .lock()
+            .unwrap()
+            .push((String::from("/dev/input/event3"), inbox.clone()));
+        let (scans, scan_started) = mpsc::sync_channel(0);
+        let mut manager = GamepadManager::with_background_discovery(
+            Box::new(SteppedProvider(provider, scans)),
+            GamepadMapping::default(),
+            Duration::from_millis(1),
+        );
+
+        // Once a scan starts, the devices found by the previous one have been sent.
+        let mut events = Vec::new();
+        scan_started.recv().unwrap();
+        scan_started.recv().unwrap();
+        manager.discover(&mut events);
+        scan_started.recv().unwrap();
+        scan_started.recv().unwrap();
+        manager.discover(&mut events);
+        assert_eq!(
+            events,
+            vec![ControllerEvent::ControllerConnected { which: 0 }]
+        );
+    }
+}
@@ -4,6 +4,8 @@ use super::{
     controller::{ControllerButton, ControllerEvent},
     event::InputEvent::{self, *},
     scroll_direction::ScrollDirection,
+    touch::TouchPoint,
+    virtual_input::VirtualEvent,
     *,
 };
 use amethyst_core::shrev::EventChannel;
@@ -12,7 +14,7 @@ use smallvec::SmallVec;
 use std::{borrow::Borrow, hash::Hash};
 use winit::{
     dpi::LogicalPosition, DeviceEvent, ElementState, Event, KeyboardInput, MouseButton,
-    MouseScrollDelta, VirtualKeyCode, WindowEvent,
+    MouseScrollDelta, Touch, TouchPhase, VirtualKeyCode, WindowEvent,
 };
 
 /// This struct holds state information about input devices.
@@ -40,6 +42,10 @@ where
     mouse_position: Option<(f32, f32)>,
     mouse_wheel_vertical: f32,
     mouse_wheel_horizontal: f32,
+    /// Fingers currently on the screen, in the order they touched it.
+    touches: SmallVec<[TouchPoint; 10]>,
+    /// Buttons held down by a virtual device, see `VirtualInput`.
+    virtual_buttons: SmallVec<[Button; 8]>,
 }
 
 impl<T> InputHandler<T>
@@ -77,21 +83,33 @@ where
                     ..
                 } => {
                     if self.pressed_keys.iter().all(|&k| k.0 != key_code) {
+                        // Buttons already held by a virtual device don't send events
+                        let key_was_down = self.key_is_down(key_code);
+                        let scancode_was_down = self.scan_code_is_down(scancode);
                         self.pressed_keys.push((key_code, scancode));
-                        event_handler.iter_write(
-                            [
-                                KeyPressed { key_code, scancode },
-                                ButtonPressed(Button::Key(key_code)),
-                                ButtonPressed(Button::ScanCode(scancode)),
-                            ]
-                            .iter()
-                            .cloned(),
-                        );
-                        self.send_axis_moved_events_key(event_handler, key_code, scancode);
+                        if key_was_down && scancode_was_down {
+                            return;
+                        }
+                        event_handler.single_write(KeyPressed { key_code, scancode });
+                        if !key_was_down {
+                            event_handler.single_write(ButtonPressed(Button::Key(key_code)));
+                            self.send_axis_moved_events_button(
+                                event_handler,
+                                Button::Key(key_code),
+                            );
+                        }
+                        if !scancode_was_down {
+                            event_handler.single_write(ButtonPressed(Button::ScanCode(scancode)));
+                            self.send_axis_moved_events_button(
+                                event_handler,
+                                Button::ScanCode(scancode),
+                            );
+                        }
                         for (action, combinations) in self.bindings.actions.iter() {
                             for combination in combinations.iter().filter(|c| {
-                                c.contains(&Button::Key(key_code))
-                                    || c.contains(&Button::ScanCode(scancode))
+                                (!key_was_down && c.contains(&Button::Key(key_code)))
+                                    || (!scancode_was_down
+                                        && c.contains(&Button::ScanCode(scancode)))
                             }) {
                                 if combination
                                     .iter()
@@ -116,19 +134,31 @@ where
                     let index = self.pressed_keys.iter().position(|&k| k.0 == key_code);
                     if let Some(i) = index {
                         self.pressed_keys.swap_remove(i);
-                        event_handler.iter_write(
-                            [
-                                KeyReleased { key_code, scancode },
-                                ButtonReleased(Button::Key(key_code)),
-                                ButtonReleased(Button::ScanCode(scancode)),
-                            ]
-                            .iter()
-                            .cloned(),
-                        );
-                        self.send_axis_moved_events_key(event_handler, key_code, scancode);
+                        // Buttons still held by a virtual device don't send events
+                        let key_down = self.key_is_down(key_code);
+                        let scancode_down = self.scan_code_is_down(scancode);
+                        if key_down && scancode_down {
+                            return;
+                        }
+                        event_handler.single_write(KeyReleased { key_code, scancode });
+                        if !key_down {
+                            event_handler.single_write(ButtonReleased(Button::Key(key_code)));
+                            self.send_axis_moved_events_button(
+                                event_handler,
+                                Button::Key(key_code),
+                            );
+                        }
+                        if !scancode_down {
+                            event_handler.single_write(ButtonReleased(Button::ScanCode(scancode)));
+                            self.send_axis_moved_events_button(
+                                event_handler,
+                                Button::ScanCode(scancode),
+                            );
+                        }
                         for (action, combinations) in self.bindings.actions.iter() {
                             for combination in combinations {
-                                if combination.contains(&Button::Key(key_code))
+                                if !key_down
+                                    && combination.contains(&Button::Key(key_code))
                                     && combination
                                         .iter()
                                         .filter(|b| b != &&Button::Key(key_code))
@@ -136,7 +166,8 @@ where
                                 {
                                     event_handler.single_write(ActionReleased(action.clone()));
                                 }
-                                if combination.contains(&Button::ScanCode(scancode))
+                                if !scancode_down
+                                    && combination.contains(&Button::ScanCode(scancode))
                                     && combination
                                         .iter()
                                         .filter(|b| b != &&Button::ScanCode(scancode))
@@ -159,7 +190,12 @@ where
                         .iter()
                         .all(|&b| b != mouse_button)
                     {
+                        // No events if the button is already held by a virtual device
+                        let was_down = self.mouse_button_is_down(mouse_button);
                         self.pressed_mouse_buttons.push(mouse_button);
+                        if was_down {
+                            return;
+                        }
                         event_handler.iter_write(
                             [
                                 MouseButtonPressed(mouse_button),
@@ -196,6 +232,10 @@ where
                         .position(|&b| b == mouse_button);
                     if let Some(i) = index {
                         self.pressed_mouse_buttons.swap_remove(i);
+                        // No events if the button is still held by a virtual device
+                        if self.mouse_button_is_down(mouse_button) {
+                            return;
+                        }
                         event_handler.iter_write(
                             [
                                 MouseButtonReleased(mouse_button),
@@ -231,10 +271,55 @@ where
                     }
                     self.mouse_position = Some(((x as f32) * hidpi, (y as f32) * hidpi));
                 }
+                WindowEvent::Touch(Touch {
+                    phase,
+                    location: LogicalPosition { x, y },
+                    id,
+                    ..
+                }) => {
+                    let (x, y) = ((x as f32) * hidpi, (y as f32) * hidpi);
+                    let index = self.touches.iter().position(|t| t.id == id);
+                    match (phase, index) {
+                        (TouchPhase::Started, None) => {
+                            self.touches.push(TouchPoint {
+                                id,
+                                position: (x, y),
+                                start_position: (x, y),
+                            });
+                            event_handler.single_write(TouchStarted { id, x, y });
+                        }
+                        (TouchPhase::Moved, Some(i)) => {
+                            let (old_x, old_y) = self.touches[i].position;
+                            self.touches[i].position = (x, y);
+                            event_handler.single_write(TouchMoved {
+                                id,
+                                x,
+                                y,
+                                delta_x: x - old_x,
+                                delta_y: y - old_y,
+                            });
+                        }
+                        (TouchPhase::Ended, Some(i)) => {
+                            self.touches.remove(i);
+                            event_handler.single_write(TouchEnded { id, x, y });
+                        }
+                        (TouchPhase::Cancelled, Some(i)) => {
+                            self.touches.remove(i);
+                            event_handler.single_write(TouchCancelled { id });
+                        }
+                        _ => {}
+                    }
+                }
                 WindowEvent::Focused(false) => {
                     self.pressed_keys.clear();
                     self.pressed_mouse_buttons.clear();
                     self.mouse_position = None;
+                    event_handler.iter_write(
+                        self.touches
+                            .drain()
+                            .map(|t| TouchCancelled { id: t.id })
+                            .collect::<Vec<_>>(),
+                    );
                 }
                 _ => {}
             },
@@ -307,8 +392,13 @@ where
                         .iter()
                         .all(|&(id, b)| id != controller_id || b != button)
                     {
+                        // No events if the button is already held by a virtual device
+                        let was_down = self.controller_button_is_down(controller_id, button);
                         self.pressed_controller_buttons
                             .push((controller_id, button));
+                        if was_down {
+                            return;
+                        }
                         event_handler.iter_write(
                             [
                                 event.into(),
@@ -341,6 +431,10 @@ where
                         .position(|&(id, b)| id == controller_id && b == button);
                     if let Some(i) = index {
                         self.pressed_controller_buttons.swap_remove(i);
+                        // No events if the button is still held by a virtual device
+                        if self.controller_button_is_down(controller_id, button) {
+                            return;
+                        }
                         event_handler.iter_write(
                             [
                                 event.into(),
@@ -397,6 +491,87 @@ where
         }
     }
 
+    /// Updates the input handler with an event produced by a virtual device.
+    ///
+    /// Called internally from the `InputSystem` for every event queued in the `VirtualInput`
+    /// resource. Call it yourself only if you don't use that system.
+    pub fn send_virtual_event(
+        &mut self,
+        event: &VirtualEvent,
+        event_handler: &mut EventChannel<InputEvent<T>>,
+    ) {
+        match *event {
+            VirtualEvent::ButtonPressed(Button::MouseWheel(dir)) => {
+                let (delta_x, delta_y) = match dir {
+                    ScrollDirection::ScrollUp => (0.0, 1.0),
+                    ScrollDirection::ScrollDown => (0.0, -1.0),
+                    ScrollDirection::ScrollLeft => (-1.0, 0.0),
+                    ScrollDirection::ScrollRight => (1.0, 0.0),
+                };
+                if delta_x != 0.0 {
+                    self.mouse_wheel_horizontal = delta_x;
+                }
+                if delta_y != 0.0 {
+                    self.mouse_wheel_vertical = delta_y;
+                }
+                self.invoke_wheel_moved(delta_x, delta_y, event_handler);
+            }
+            VirtualEvent::ButtonPressed(button) => {
+                if self.virtual_buttons.iter().all(|&b| b != button) {
+                    // No events if the button is already held on a physical device
+                    let was_down = self.button_is_down(button);
+                    self.virtual_buttons.push(button);
+                    if !was_down {
+                        event_handler.single_write(ButtonPressed(button));
+                        self.send_axis_moved_events_button(event_handler, button);
+                        for (action, combinations) in self.bindings.actions.iter() {
+                            for combination in combinations.iter().filter(|c| c.contains(&button)) {
+                                if combination.iter().all(|b| self.button_is_down(*b)) {
+                                    event_handler.single_write(ActionPressed(action.clone()));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            VirtualEvent::ButtonReleased(button) => {
+                let index = self.virtual_buttons.iter().position(|&b| b == button);
+                if let Some(i) = index {
+                    self.virtual_buttons.swap_remove(i);
+                    // No events if the button is still held on a physical device
+                    if !self.button_is_down(button) {
+                        event_handler.single_write(ButtonReleased(button));
+                        self.send_axis_moved_events_button(event_handler, button);
+                        for (action, combinations) in self.bindings.actions.iter() {
+                            for combination in combinations {
+                                if combination.contains(&button)
+                                    && combination
+                                        .iter()
+                                        .filter(|b| **b != button)
+                                        .all(|b| self.button_is_down(*b))
+                                {
+                                    event_handler.single_write(ActionReleased(action.clone()));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            VirtualEvent::CursorMoved { x, y } => {
+                if let Some((old_x, old_y)) = self.mouse_position {
+                    event_handler.single_write(CursorMoved {
+                        delta_x: x - old_x,
+                        delta_y: y - old_y,
+                    });
+                }
+                self.mouse_position = Some((x, y));
+            }
+            VirtualEvent::Controller(ref event) => {
+                self.send_controller_event(event, event_handler);
+            }
+        }
+    }
+
     /// This function is to be called whenever a frame begins. It resets some input values.
     ///
     /// The `InputSystem` will call this automatically. If you're using that system, you
@@ -414,6 +589,7 @@ where
     /// Checks if a key is down.
     pub fn key_is_down(&self, key: VirtualKeyCode) -> bool {
         self.pressed_keys.iter().any(|&k| k.0 == key)
+            || self.virtual_buttons.contains(&Button::Key(key))
     }
 
     /// Returns an iterator over all pressed mouse buttons
@@ -426,6 +602,7 @@ where
         self.pressed_mouse_buttons
             .iter()
             .any(|&mb| mb == mouse_button)
+            || self.virtual_buttons.contains(&Button::Mouse(mouse_button))
     }
 
     /// If the mouse wheel was scrolled this frame this function will return the direction it was scrolled.
@@ -448,6 +625,7 @@ where
     /// Checks if the key corresponding to a scan code is down.
     pub fn scan_code_is_down(&self, scan_code: u32) -> bool {
         self.pressed_keys.iter().any(|&k| k.1 == scan_code)
+            || self.virtual_buttons.contains(&Button::ScanCode(scan_code))
     }
 
     /// Returns an iterator over all pressed controller buttons on all controllers.
@@ -466,6 +644,9 @@ where
         self.pressed_controller_buttons
             .iter()
             .any(|&(id, b)| id == controller_id && b == controller_button)
+            || self
+                .virtual_buttons
+                .contains(&Button::Controller(controller_id, controller_button))
     }
 
     /// List controller ids of all currently connected controllers.
@@ -490,7 +671,27 @@ where
         self.mouse_position
     }
 
+    /// Returns an iterator over all fingers currently touching the screen,
+    /// in the order they touched it.
+    pub fn touches(&self) -> impl Iterator<Item = &TouchPoint> {
+        self.touches.iter()
+    }
+
+    /// Returns the touch with the given id, if that finger is still on the screen.
+    pub fn touch(&self, id: u64) -> Option<&TouchPoint> {
+        self.touches.iter().find(|t| t.id == id)
+    }
+
+    /// Returns the oldest touch still on the screen.
+    ///
+    /// This is the touch that should be treated like a mouse pointer.
+    pub fn primary_touch(&self) -> Option<&TouchPoint> {
+        self.touches.first()
+    }
+
     /// Returns an iterator over all buttons that are down.
+    ///
+    /// Buttons held by both a physical and a virtual device are returned twice.
     pub fn buttons_that_are_down(&self) -> impl Iterator<Item = Button> + '_ {
         let mouse_buttons = self
             .pressed_mouse_buttons
@@ -502,7 +703,10 @@ where
             .iter()
             .map(|&gb| Button::Controller(gb.0, gb.1));
 
-        mouse_buttons.chain(keys).chain(controller_buttons)
+        mouse_buttons
+            .chain(keys)
+            .chain(controller_buttons)
+            .chain(self.virtual_buttons.iter().cloned())
     }
 
     /// Checks if a button is down.
@@ -522,7 +726,17 @@ where
         T::Axis: Borrow<A>,
         A: Hash + Eq + ?Sized,
     {
-        self.bindings.axes.get(id).map(|a| match *a {
+        self.axis_value_with(&self.bindings, id)
+    }
+
+    /// Returns the value of an axis by the id using the given bindings instead of the handler's
+    /// own, if the id doesn't exist this returns None.
+    pub fn axis_value_with<A>(&self, bindings: &Bindings<T>, id: &A) -> Option<f32>
+    where
+        T::Axis: Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        bindings.axes.get(id).map(|a| match *a {
             Axis::Emulated { pos, neg, .. } => {
                 match (self.button_is_down(pos), self.button_is_down(neg)) {
                     (true, false) => 1.0,
@@ -564,7 +778,19 @@ where
         T::Action: Borrow<A>,
         A: Hash + Eq + ?Sized,
     {
-        self.bindings.actions.get(action).map(|combinations| {
+        self.action_is_down_with(&self.bindings, action)
+    }
+
+    /// Returns true if any of the actions bindings is down, using the given bindings instead of
+    /// the handler's own.
+    ///
+    /// If a binding represents a combination of buttons, all of them need to be down.
+    pub fn action_is_down_with<A>(&self, bindings: &Bindings<T>, action: &A) -> Option<bool>
+    where
+        T::Action: Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        bindings.actions.get(action).map(|combinations| {
             combinations.iter().any(|combination| {
                 combination
                     .iter()
@@ -662,47 +888,21 @@ where
         event_handler.iter_write(events);
     }
 
-    fn send_axis_moved_events_key(
+    fn send_axis_moved_events_button(
         &self,
         event_handler: &mut EventChannel<InputEvent<T>>,
-        key_code: VirtualKeyCode,
-        scancode: u32,
+        button: Button,
     ) {
         for (axis, input_axis) in self.bindings.axes.iter() {
             if let Axis::Emulated { pos, neg } = input_axis {
-                let value = self
-                    .axis_value(axis)
-                    .expect("Unreachable: `axis` is from bindings axes.");
-                match *pos {
-                    Button::Key(key_code_pos) if key_code_pos == key_code => {
-                        event_handler.single_write(AxisMoved {
-                            axis: axis.clone(),
-                            value,
-                        });
-                    }
-                    Button::ScanCode(scancode_pos) if scancode_pos == scancode => {
-                        event_handler.single_write(AxisMoved {
-                            axis: axis.clone(),
-                            value,
-                        });
-                    }
-                    _ => {}
-                }
-
-                match *neg {
-                    Button::Key(key_code_neg) if key_code_neg == key_code => {
-                        event_handler.single_write(AxisMoved {
-                            axis: axis.clone(),
-                            value,
-                        });
-                    }
-                    Button::ScanCode(scancode_neg) if scancode_neg == scancode => {
-                        event_handler.single_write(AxisMoved {
-                            axis: axis.clone(),
-                            value,
-                        });
-                    }
-                    _ => {}
+                if *pos == button || *neg == button {
+                    let value = self
+                        .axis_value(axis)
+                        .expect("Unreachable: `axis` is from bindings axes.");
+                    event_handler.single_write(AxisMoved {
+                        axis: axis.clone(),
+                        value,
+                    });
                 }
             }
         }
@@ -1201,6 +1401,71 @@ mod tests {
         assert_ulps_eq!(handler.mouse_wheel_value(true), -1.0);
     }
 
+    #[test]
+    fn touch_tracking() {
+        let mut handler = InputHandler::<StringBindings>::new();
+        let mut events = EventChannel::<InputEvent<StringBindings>>::new();
+        let mut reader = events.register_reader();
+        assert_eq!(handler.primary_touch(), None);
+
+        handler.send_event(&touch(0, TouchPhase::Started, 10.0, 20.0), &mut events, 2.0);
+        handler.send_event(&touch(1, TouchPhase::Started, 50.0, 50.0), &mut events, 2.0);
+        handler.send_event(&touch(0, TouchPhase::Moved, 15.0, 20.0), &mut events, 2.0);
+        assert_eq!(handler.touches().count(), 2);
+        assert_eq!(
+            handler.primary_touch(),
+            Some(&TouchPoint {
+                id: 0,
+                position: (30.0, 40.0),
+                start_position: (20.0, 40.0),
+            })
+        );
+        let event_vec = events.read(&mut reader).cloned().collect::<Vec<_>>();
+        assert_eq!(
+            event_vec,
+            vec![
+                InputEvent::TouchStarted {
+                    id: 0,
+                    x: 20.0,
+                    y: 40.0
+                },
+                InputEvent::TouchStarted {
+                    id: 1,
+                    x: 100.0,
+                    y: 100.0
+                },
+                InputEvent::TouchMoved {
+                    id: 0,
+                    x: 30.0,
+                    y: 40.0,
+                    delta_x: 10.0,
+                    delta_y: 0.0,
+                },
+            ]
+        );
+
+        handler.send_event(&touch(0, TouchPhase::Ended, 15.0, 20.0), &mut events, 2.0);
+        handler.send_event(
+            &touch(1, TouchPhase::Cancelled, 50.0, 50.0),
+            &mut events,
+            2.0,
+        );
+        assert_eq!(handler.touch(0), None);
+        assert_eq!(handler.primary_touch(), None);
+        let event_vec = events.read(&mut reader).cloned().collect::<Vec<_>>();
+        assert_eq!(
+            event_vec,
+            vec![
+                InputEvent::TouchEnded {
+                    id: 0,
+                    x: 30.0,
+                    y: 40.0
+                },
+                InputEvent::TouchCancelled { id: 1 },
+            ]
+        );
+    }
+
     /// Compares two sets for equality, but not the order
     fn sets_are_equal<T>(a: &[T], b: &[T])
     where
@@ -1286,6 +1551,18 @@ right: `{:?}`",
         }
     }
 
+    fn touch(id: u64, phase: TouchPhase, x: f64, y: f64) -> Event {
+        Event::WindowEvent {
+            window_id: unsafe { WindowId::dummy() },
+            event: WindowEvent::Touch(Touch {
+                device_id: unsafe { DeviceId::dummy() },
+                phase,
+                location: LogicalPosition { x, y },
+                id,
+            }),
+        }
+    }
+
     fn mouse_wheel(x: f32, y: f32) -> Event {
         Event::DeviceEvent {
             device_id: unsafe { DeviceId::dummy() },
@@ -9,6 +9,8 @@
 #![warn(clippy::all)]
 #![allow(clippy::new_without_default)]
 
+#[cfg(all(feature = "evdev_controller", target_os = "linux"))]
+pub use self::evdev::{EvdevDevice, EvdevProvider};
 #[cfg(feature = "sdl_controller")]
 pub use self::sdl_events_system::SdlEventsSystem;
 pub use self::{
@@ -18,13 +20,29 @@ pub use self::{
     button::Button,
     controller::{ControllerAxis, ControllerButton, ControllerEvent},
     event::InputEvent,
+    gamepad::{
+        GamepadDevice, GamepadEventsSystem, GamepadEventsSystemDesc, GamepadManager,
+        GamepadMapping, GamepadProvider, MappedInput, RawGamepadEvent,
+    },
     input_handler::InputHandler,
+    player::{
+        InputDevice, KeyboardLayout, PlayerEvent, PlayerInput, PlayerInputSystem,
+        PlayerInputSystemDesc,
+    },
     scroll_direction::ScrollDirection,
+    sequence::{
+        InputSequence, SequenceStep, SequenceSystem, SequenceSystemDesc, SequenceTracker,
+        DEFAULT_STEP_WINDOW,
+    },
     system::{InputSystem, InputSystemDesc},
+    touch::{
+        Gesture, GestureConfig, GestureRecognizer, GestureSystem, GestureSystemDesc, TouchPoint,
+    },
     util::{
         get_input_axis_simple, get_key, get_mouse_button, is_close_requested, is_key_down,
         is_key_up, is_mouse_button_down,
     },
+    virtual_input::{VirtualEvent, VirtualInput},
 };
 pub use winit::{ElementState, VirtualKeyCode};
 
@@ -38,11 +56,18 @@ mod bundle;
 mod button;
 mod controller;
 mod event;
+mod gamepad;
 mod input_handler;
+mod player;
 mod scroll_direction;
+mod sequence;
 mod system;
+mod touch;
 mod util;
+mod virtual_input;
 
+#[cfg(all(feature = "evdev_controller", target_os = "linux"))]
+mod evdev;
 #[cfg(feature = "sdl_controller")]
 mod sdl_events_system;
 
@@ -0,0 +1,683 @@
+//! Routing of input devices to player slots for local multiplayer.
+
+use std::{borrow::Borrow, hash::Hash};
+
+use derivative::Derivative;
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use amethyst_core::{
+    ecs::prelude::{Read, System, SystemData, World, Write},
+    shrev::{EventChannel, ReaderId},
+    SystemDesc,
+};
+
+use crate::{
+    axis::Axis,
+    bindings::{BindingTypes, Bindings},
+    button::Button,
+    controller::{ControllerAxis, ControllerButton},
+    event::InputEvent,
+    input_handler::InputHandler,
+    sequence::InputSequence,
+};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+/// A device a player can be assigned to.
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum InputDevice {
+    /// A controller, by the controller id allocated by the `InputHandler`.
+    Controller(u32),
+    /// A part of the keyboard, by index into `PlayerInput::keyboard_layouts`.
+    Keyboard(usize),
+}
+
+/// Maps the controller inputs used in a bindings template onto keyboard (or mouse) buttons,
+/// so a part of the keyboard can act as a controller.
+///
+/// Example Ron config for the left half of the keyboard:
+/// ```ron
+/// (
+///     buttons: [(A, Key(Space)), (B, Key(LShift)), (Start, Key(Key1))],
+///     axes: [(LeftX, Key(D), Key(A)), (LeftY, Key(W), Key(S))],
+/// )
+/// ```
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct KeyboardLayout {
+    /// The button replacing each controller button.
+    pub buttons: Vec<(ControllerButton, Button)>,
+    /// The positive and negative buttons emulating each controller axis.
+    pub axes: Vec<(ControllerAxis, Button, Button)>,
+}
+
+impl KeyboardLayout {
+    fn button(&self, button: ControllerButton) -> Option<Button> {
+        self.buttons
+            .iter()
+            .find(|(b, _)| *b == button)
+            .map(|(_, replacement)| *replacement)
+    }
+
+    fn axis(&self, axis: ControllerAxis) -> Option<(Button, Button)> {
+        self.axes
+            .iter()
+            .find(|(a, _, _)| *a == axis)
+            .map(|(_, pos, neg)| (*pos, *neg))
+    }
+}
+
+/// Events sent by the `PlayerInputSystem` when players join or lose their device.
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum PlayerEvent {
+    /// A device pressed the join button and was assigned to a free player slot.
+    Joined {
+        /// The index of the player.
+        player: usize,
+        /// The device the player is using.
+        device: InputDevice,
+    },
+    /// The controller of a player was disconnected. The slot is kept, and the next controller
+    /// connected is assigned to it.
+    DeviceLost {
+        /// The index of the player.
+        player: usize,
+    },
+    /// A controller was connected and assigned to a player who had lost theirs.
+    DeviceRestored {
+        /// The index of the player.
+        player: usize,
+        /// The device the player is now using.
+        device: InputDevice,
+    },
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
+struct PlayerSlot<T: BindingTypes> {
+    device: InputDevice,
+    bindings: Bindings<T>,
+    connected: bool,
+}
+
+/// Resource assigning input devices to player slots for local multiplayer.
+///
+/// All players share a single bindings `template`, written for a controller: the controller
+/// ids in it are replaced with the id of each player's controller, and keyboard players use
+/// a `KeyboardLayout` to replace controller buttons and axes with keys. Any keyboard or mouse
+/// binding in the template is ignored.
+///
+/// Devices join by pressing the join button ("press start to join") while `accepting_joins`
+/// is set, and players whose controller disconnects get the next connected controller.
+/// The `PlayerInputSystem` takes care of both; query the players' input with
+/// `action_is_down` and `axis_value`.
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""), Default(bound = ""))]
+pub struct PlayerInput<T: BindingTypes> {
+    /// Bindings shared by all players, written for a controller.
+    pub template: Bindings<T>,
+    /// Parts of the keyboard usable as devices.
+    pub keyboard_layouts: Vec<KeyboardLayout>,
+    /// Maximum amount of players.
+    pub max_players: usize,
+    /// Button that assigns the device to a free slot when pressed.
+    ///
+    /// On keyboards, the button replacing it in the layout is used.
+    #[derivative(Default(value = "ControllerButton::Start"))]
+    pub join_button: ControllerButton,
+    /// Whether pressing the join button on an unassigned device assigns it to a slot.
+    #[derivative(Default(value = "true"))]
+    pub accepting_joins: bool,
+    slots: Vec<Option<PlayerSlot<T>>>,
+    /// Players who lost their controller, in the order they lost it.
+    lost: Vec<usize>,
+}
+
+impl<T: BindingTypes> PlayerInput<T> {
+    /// Creates a new player assignment layer for up to `max_players` players.
+    pub fn new(template: Bindings<T>, max_players: usize) -> Self {
+        PlayerInput {
+            template,
+            max_players,
+            ..Default::default()
+        }
+    }
+
+    /// Adds a part of the keyboard usable as a device, e.g. WASD or the arrow keys.
+    pub fn with_keyboard_layout(mut self, layout: KeyboardLayout) -> Self {
+        self.keyboard_layouts.push(layout);
+        self
+    }
+
+    /// Sets the button that assigns a device to a free slot.
+    pub fn with_join_button(mut self, button: ControllerButton) -> Self {
+        self.join_button = button;
+        self
+    }
+
+    /// Assigns a device to the lowest free player slot, returning the player index.
+    ///
+    /// Returns `None` if all slots are taken, or if the device is already assigned.
+    pub fn join(&mut self, device: InputDevice) -> Option<usize> {
+        if self.player_for_device(device).is_some() {
+            return None;
+        }
+        let player = match self.slots.iter().position(Option::is_none) {
+            Some(player) => player,
+            None if self.slots.len() < self.max_players => {
+                self.slots.push(None);
+                self.slots.len() - 1
+            }
+            None => return None,
+        };
+        let bindings = self.bindings_for(device);
+        self.slots[player] = Some(PlayerSlot {
+            device,
+            bindings,
+            connected: true,
+        });
+        Some(player)
+    }
+
+    /// Frees the slot of a player, returning the device it was using.
+    pub fn leave(&mut self, player: usize) -> Option<InputDevice> {
+        self.lost.retain(|&p| p != player);
+        self.slots
+            .get_mut(player)
+            .and_then(Option::take)
+            .map(|slot| slot.device)
+    }
+
+    /// Reassigns a player to another device.
+    pub fn reassign(&mut self, player: usize, device: InputDevice) {
+        let bindings = self.bindings_for(device);
+        if let Some(Some(slot)) = self.slots.get_mut(player) {
+            slot.device = device;
+            slot.bindings = bindings;
+            slot.connected = true;
+            self.lost.retain(|&p| p != player);
+        }
+    }
+
+    /// Regenerates the bindings of every player, call this after changing the `template` or
+    /// the `keyboard_layouts`.
+    pub fn rebuild_bindings(&mut self) {
+        for player in 0..self.slots.len() {
+            if let Some(device) = self.player_device(player) {
+                let bindings = self.bindings_for(device);
+                if let Some(slot) = self.slots[player].as_mut() {
+                    slot.bindings = bindings;
+                }
+            }
+        }
+    }
+
+    /// Returns the indices of every player that joined.
+    pub fn players(&self) -> impl Iterator<Item = usize> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.is_some())
+            .map(|(player, _)| player)
+    }
+
+    /// Returns the device of a player, if it joined.
+    pub fn player_device(&self, player: usize) -> Option<InputDevice> {
+        self.slot(player).map(|slot| slot.device)
+    }
+
+    /// Returns the player a device is assigned to.
+    pub fn player_for_device(&self, device: InputDevice) -> Option<usize> {
+        self.slots.iter().position(|slot| {
+            slot.as_ref()
+                .map(|slot| slot.connected && slot.device == device)
+                .unwrap_or(false)
+        })
+    }
+
+    /// Returns true if the player joined and its device is connected.
+    pub fn is_player_connected(&self, player: usize) -> bool {
+        self.slot(player)
+            .map(|slot| slot.connected)
+            .unwrap_or(false)
+    }
+
+    /// Returns the bindings generated for a player.
+    pub fn player_bindings(&self, player: usize) -> Option<&Bindings<T>> {
+        self.slot(player).map(|slot| &slot.bindings)
+    }
+
+    /// Returns true if any of the action's bindings is down for the player.
+    ///
+    /// Returns `None` if the player didn't join or the action isn't bound for its device.
+    pub fn action_is_down<A>(
+        &self,
+        player: usize,
+        action: &A,
+        handler: &InputHandler<T>,
+    ) -> Option<bool>
+    where
+        T::Action: Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        self.slot(player)
+            .and_then(|slot| handler.action_is_down_with(&slot.bindings, action))
+    }
+
+    /// Returns the value of an axis for the player.
+    ///
+    /// Returns `None` if the player didn't join or the axis isn't bound for its device.
+    pub fn axis_value<A>(&self, player: usize, axis: &A, handler: &InputHandler<T>) -> Option<f32>
+    where
+        T::Axis: Borrow<A>,
+        A: Hash + Eq + ?Sized,
+    {
+        self.slot(player)
+            .and_then(|slot| handler.axis_value_with(&slot.bindings, axis))
+    }
+
+    /// Processes an input event produced by `handler`, handling joins, disconnects and
+    /// reconnects. Returns the resulting player events.
+    ///
+    /// The `PlayerInputSystem` calls this automatically.
+    pub fn process_event(
+        &mut self,
+        event: &InputEvent<T>,
+        handler: &InputHandler<T>,
+    ) -> SmallVec<[PlayerEvent; 2]> {
+        let mut events = SmallVec::new();
+        match *event {
+            InputEvent::ButtonPressed(button) if self.accepting_joins => {
+                if let Some(device) = self.joining_device(button) {
+                    if let Some(player) = self.join(device) {
+                        events.push(PlayerEvent::Joined { player, device });
+                    }
+                }
+            }
+            InputEvent::ControllerConnected { .. } | InputEvent::ControllerDisconnected { .. } => {
+                self.sync_controllers(handler, &mut events);
+            }
+            _ => {}
+        }
+        events
+    }
+
+    fn slot(&self, player: usize) -> Option<&PlayerSlot<T>> {
+        self.slots.get(player).and_then(Option::as_ref)
+    }
+
+    fn joining_device(&self, button: Button) -> Option<InputDevice> {
+        match button {
+            Button::Controller(controller_id, b) if b == self.join_button => {
+                Some(InputDevice::Controller(controller_id))
+            }
+            Button::Controller(..) => None,
+            _ => self
+                .keyboard_layouts
+                .iter()
+                .position(|layout| layout.button(self.join_button) == Some(button))
+                .map(InputDevice::Keyboard),
+        }
+    }
+
+    /// Marks players whose controller disappeared as lost, and hands newly connected
+    /// controllers to lost players.
+    fn sync_controllers(
+        &mut self,
+        handler: &InputHandler<T>,
+        events: &mut SmallVec<[PlayerEvent; 2]>,
+    ) {
+        for (player, slot) in self.slots.iter_mut().enumerate() {
+            if let Some(slot) = slot {
+                if let InputDevice::Controller(controller_id) = slot.device {
+                    if slot.connected && !handler.is_controller_connected(controller_id) {
+                        slot.connected = false;
+                        self.lost.push(player);
+                        events.push(PlayerEvent::DeviceLost { player });
+                    }
+                }
+            }
+        }
+
+        let free_controllers = handler
+            .connected_controllers()
+            .filter(|&id| {
+                self.player_for_device(InputDevice::Controller(id))
+                    .is_none()
+            })
+            .collect::<Vec<_>>();
+        for controller_id in free_controllers {
+            if self.lost.is_empty() {
+                break;
+            }
+            let player = self.lost.remove(0);
+            let device = InputDevice::Controller(controller_id);
+            self.reassign(player, device);
+            events.push(PlayerEvent::DeviceRestored { player, device });
+        }
+    }
+
+    fn bindings_for(&self, device: InputDevice) -> Bindings<T> {
+        let mut bindings = Bindings::new();
+        for (id, axis) in self.template.axes.iter() {
+            if let Some(axis) = self.remap_axis(axis, device) {
+                bindings.axes.insert(id.clone(), axis);
+            }
+        }
+        for (id, combinations) in self.template.actions.iter() {
+            let combinations = combinations
+                .iter()
+                .filter_map(|combination| {
+                    combination
+                        .iter()
+                        .map(|button| self.remap_button(*button, device))
+                        .collect::<Option<SmallVec<[Button; 2]>>>()
+                })
+                .collect::<SmallVec<[_; 4]>>();
+            if !combinations.is_empty() {
+                bindings.actions.insert(id.clone(), combinations);
+            }
+        }
+        for (id, sequence) in self.template.sequences.iter() {
+            let mut remapped = InputSequence::new(Vec::with_capacity(sequence.steps.len()))
+                .with_leniency(sequence.leniency);
+            for step in sequence.steps.iter() {
+                let buttons = step
+                    .buttons
+                    .iter()
+                    .map(|button| self.remap_button(*button, device))
+                    .collect::<Option<SmallVec<[Button; 2]>>>();
+                match buttons {
+                    Some(buttons) => {
+                        let mut step = step.clone();
+                        step.buttons = buttons;
+                        remapped.steps.push(step);
+                    }
+                    None => break,
+                }
+            }
+            if remapped.steps.len() == sequence.steps.len() {
+                bindings.sequences.insert(id.clone(), remapped);
+            }
+        }
+        bindings
+    }
+
+    fn remap_button(&self, button: Button, device: InputDevice) -> Option<Button> {
+        match (button, device) {
+            (Button::Controller(_, b), InputDevice::Controller(controller_id)) => {
+                Some(Button::Controller(controller_id, b))
+            }
+            (Button::Controller(_, b), InputDevice::Keyboard(layout)) => self
+                .keyboard_layouts
+                .get(layout)
+                .and_then(|layout| layout.button(b)),
+            _ => None,
+        }
+    }
+
+    fn remap_axis(&self, axis: &Axis, device: InputDevice) -> Option<Axis> {
+        match (axis, device) {
+            (
+                Axis::Controller {
+                    axis,
+                    invert,
+                    dead_zone,
+                    ..
+                },
+                InputDevice::Controller(controller_id),
+            ) => Some(Axis::Controller {
+                controller_id,
+                axis: *axis,
+                invert: *invert,
+                dead_zone: *dead_zone,
+            }),
+            (Axis::Controller { axis, invert, .. }, InputDevice::Keyboard(layout)) => self
+                .keyboard_layouts
+                .get(layout)
+                .and_then(|layout| layout.axis(*axis))
+                .map(|(pos, neg)| {
+                    if *invert {
+                        Axis::Emulated { pos: neg, neg: pos }
+                    } else {
+                        Axis::Emulated { pos, neg }
+                    }
+                }),
+            (Axis::Emulated { pos, neg }, _) => {
+                match (
+                    self.remap_button(*pos, device),
+                    self.remap_button(*neg, device),
+                ) {
+                    (Some(pos), Some(neg)) => Some(Axis::Emulated { pos, neg }),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Builds a `PlayerInputSystem`.
+#[derive(Debug, new)]
+pub struct PlayerInputSystemDesc<T>
+where
+    T: BindingTypes,
+{
+    #[new(default)]
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<'a, 'b, T> SystemDesc<'a, 'b, PlayerInputSystem<T>> for PlayerInputSystemDesc<T>
+where
+    T: BindingTypes,
+{
+    fn build(self, world: &mut World) -> PlayerInputSystem<T> {
+        <PlayerInputSystem<T> as System<'_>>::SystemData::setup(world);
+
+        let reader = world
+            .fetch_mut::<EventChannel<InputEvent<T>>>()
+            .register_reader();
+
+        PlayerInputSystem::new(reader)
+    }
+}
+
+/// Player input system
+///
+/// Will read `InputEvent`s to assign devices to the slots of the `PlayerInput<T>` resource,
+/// and push the resulting `PlayerEvent`s in `EventChannel<PlayerEvent>`.
+/// Must run after the `InputSystem`.
+#[derive(Debug, new)]
+pub struct PlayerInputSystem<T>
+where
+    T: BindingTypes,
+{
+    reader: ReaderId<InputEvent<T>>,
+}
+
+impl<'a, T: BindingTypes> System<'a> for PlayerInputSystem<T> {
+    type SystemData = (
+        Read<'a, EventChannel<InputEvent<T>>>,
+        Read<'a, InputHandler<T>>,
+        Write<'a, PlayerInput<T>>,
+        Write<'a, EventChannel<PlayerEvent>>,
+    );
+
+    fn run(&mut self, (input, handler, mut players, mut output): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("player_input_system");
+
+        for event in input.read(&mut self.reader) {
+            output.iter_write(players.process_event(event, &handler));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bindings::StringBindings, controller::ControllerEvent};
+    use winit::{
+        DeviceId, ElementState, Event, KeyboardInput, ModifiersState, VirtualKeyCode, WindowEvent,
+        WindowId,
+    };
+
+    const HIDPI: f32 = 1.0;
+
+    struct Harness {
+        handler: InputHandler<StringBindings>,
+        players: PlayerInput<StringBindings>,
+        events: EventChannel<InputEvent<StringBindings>>,
+        reader: ReaderId<InputEvent<StringBindings>>,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            let mut template = Bindings::<StringBindings>::new();
+            template
+                .insert_action_binding(
+                    String::from("jump"),
+                    [Button::Controller(0, ControllerButton::A)].iter().cloned(),
+                )
+                .unwrap();
+            template
+                .insert_axis(
+                    String::from("move"),
+                    Axis::Controller {
+                        controller_id: 0,
+                        axis: ControllerAxis::LeftX,
+                        invert: false,
+                        dead_zone: 0.0,
+                    },
+                )
+                .unwrap();
+            let players = PlayerInput::new(template, 2).with_keyboard_layout(KeyboardLayout {
+                buttons: vec![
+                    (ControllerButton::A, Button::Key(VirtualKeyCode::Space)),
+                    (ControllerButton::Start, Button::Key(VirtualKeyCode::Return)),
+                ],
+                axes: vec![(
+                    ControllerAxis::LeftX,
+                    Button::Key(VirtualKeyCode::D),
+                    Button::Key(VirtualKeyCode::A),
+                )],
+            });
+            let mut events = EventChannel::new();
+            let reader = events.register_reader();
+            Harness {
+                handler: InputHandler::new(),
+                players,
+                events,
+                reader,
+            }
+        }
+
+        fn controller(&mut self, event: ControllerEvent) -> Vec<PlayerEvent> {
+            self.handler.send_controller_event(&event, &mut self.events);
+            self.process()
+        }
+
+        fn key(&mut self, key: VirtualKeyCode, state: ElementState) -> Vec<PlayerEvent> {
+            self.handler
+                .send_event(&key_event(key, state), &mut self.events, HIDPI);
+            self.process()
+        }
+
+        fn process(&mut self) -> Vec<PlayerEvent> {
+            let events = self
+                .events
+                .read(&mut self.reader)
+                .cloned()
+                .collect::<Vec<_>>();
+            events
+                .iter()
+                .flat_map(|e| self.players.process_event(e, &self.handler))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn press_start_to_join() {
+        let mut h = Harness::new();
+        h.controller(ControllerEvent::ControllerConnected { which: 7 });
+        assert!(h
+            .controller(ControllerEvent::ControllerButtonPressed {
+                which: 7,
+                button: ControllerButton::A,
+            })
+            .is_empty());
+        assert_eq!(
+            h.key(VirtualKeyCode::Return, ElementState::Pressed),
+            vec![PlayerEvent::Joined {
+                player: 0,
+                device: InputDevice::Keyboard(0),
+            }]
+        );
+        assert_eq!(
+            h.controller(ControllerEvent::ControllerButtonPressed {
+                which: 7,
+                button: ControllerButton::Start,
+            }),
+            vec![PlayerEvent::Joined {
+                player: 1,
+                device: InputDevice::Controller(0),
+            }]
+        );
+
+        h.key(VirtualKeyCode::Space, ElementState::Pressed);
+        h.key(VirtualKeyCode::A, ElementState::Pressed);
+        assert_eq!(h.players.action_is_down(0, "jump", &h.handler), Some(true));
+        assert_eq!(h.players.axis_value(0, "move", &h.handler), Some(-1.0));
+        assert_eq!(h.players.action_is_down(1, "jump", &h.handler), Some(true));
+        assert_eq!(h.players.axis_value(1, "move", &h.handler), Some(0.0));
+    }
+
+    #[test]
+    fn reconnect_restores_player() {
+        let mut h = Harness::new();
+        h.controller(ControllerEvent::ControllerConnected { which: 3 });
+        h.controller(ControllerEvent::ControllerButtonPressed {
+            which: 3,
+            button: ControllerButton::Start,
+        });
+        assert_eq!(
+            h.controller(ControllerEvent::ControllerDisconnected { which: 3 }),
+            vec![PlayerEvent::DeviceLost { player: 0 }]
+        );
+        assert!(!h.players.is_player_connected(0));
+        assert_eq!(
+            h.controller(ControllerEvent::ControllerConnected { which: 4 }),
+            vec![PlayerEvent::DeviceRestored {
+                player: 0,
+                device: InputDevice::Controller(0),
+            }]
+        );
+        h.controller(ControllerEvent::ControllerButtonPressed {
+            which: 4,
+            button: ControllerButton::A,
+        });
+        assert_eq!(h.players.action_is_down(0, "jump", &h.handler), Some(true));
+    }
+
+    fn key_event(virtual_keycode: VirtualKeyCode, state: ElementState) -> Event {
+        Event::WindowEvent {
+            window_id: unsafe { WindowId::dummy() },
+            event: WindowEvent::KeyboardInput {
+                device_id: unsafe { DeviceId::dummy() },
+                input: KeyboardInput {
+                    scancode: virtual_keycode as u32,
+                    state,
+                    virtual_keycode: Some(virtual_keycode),
+                    modifiers: ModifiersState {
+                        shift: false,
+                        ctrl: false,
+                        alt: false,
+                        logo: false,
+                    },
+                },
+            },
+        }
+    }
+}
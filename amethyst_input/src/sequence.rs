@@ -0,0 +1,458 @@
+//! Recognition of ordered input sequences, such as fighting game special moves.
+
+use derivative::Derivative;
+use derive_new::new;
+use fnv::FnvHashMap as HashMap;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use amethyst_core::{
+    ecs::prelude::{Read, System, SystemData, World, Write},
+    shrev::{EventChannel, ReaderId},
+    timing::Time,
+    SystemDesc,
+};
+
+use crate::{
+    bindings::BindingTypes, button::Button, event::InputEvent, input_handler::InputHandler,
+};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+/// Default amount of seconds allowed between two consecutive steps of a sequence.
+pub const DEFAULT_STEP_WINDOW: f32 = 0.25;
+
+fn default_step_window() -> f32 {
+    DEFAULT_STEP_WINDOW
+}
+
+/// A single step of an `InputSequence`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SequenceStep {
+    /// Buttons that must all be down for this step to be matched.
+    ///
+    /// Use more than one button for diagonals or chords, e.g. `[Key(Down), Key(Right)]`.
+    pub buttons: SmallVec<[Button; 2]>,
+    /// Maximum amount of seconds between the previous step and this one.
+    ///
+    /// Ignored for the first step of a sequence.
+    #[serde(default = "default_step_window")]
+    pub window: f32,
+}
+
+impl SequenceStep {
+    /// Creates a new step using the default time window.
+    pub fn new<B: IntoIterator<Item = Button>>(buttons: B) -> Self {
+        SequenceStep {
+            buttons: buttons.into_iter().collect(),
+            window: DEFAULT_STEP_WINDOW,
+        }
+    }
+
+    /// Sets the maximum amount of seconds between the previous step and this one.
+    pub fn with_window(mut self, window: f32) -> Self {
+        self.window = window;
+        self
+    }
+}
+
+/// An ordered list of steps which must be performed in a limited amount of time,
+/// e.g. down, down-forward, forward + punch.
+///
+/// Sequences are declared in `Bindings` and recognized by the `SequenceSystem`,
+/// which emits `InputEvent::SequenceCompleted` once the last step is matched.
+///
+/// Example Ron config:
+/// ```ron
+/// (
+///     steps: [
+///         (buttons: [Key(Down)]),
+///         (buttons: [Key(Down), Key(Right)], window: 0.2),
+///         (buttons: [Key(Right)], window: 0.2),
+///         (buttons: [Key(Right), Key(X)], window: 0.3),
+///     ],
+///     leniency: 1,
+/// )
+/// ```
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct InputSequence {
+    /// The steps that make up this sequence, in order.
+    pub steps: Vec<SequenceStep>,
+    /// Amount of unrelated button presses tolerated between two steps before
+    /// the sequence is reset.
+    #[serde(default)]
+    pub leniency: u32,
+}
+
+impl InputSequence {
+    /// Creates a new sequence with no leniency.
+    pub fn new(steps: Vec<SequenceStep>) -> Self {
+        InputSequence { steps, leniency: 0 }
+    }
+
+    /// Sets the amount of unrelated button presses tolerated between two steps.
+    pub fn with_leniency(mut self, leniency: u32) -> Self {
+        self.leniency = leniency;
+        self
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct SequenceProgress {
+    /// Index of the next step to match.
+    next: usize,
+    /// Time at which the last step was matched.
+    last_time: f64,
+    /// Unrelated presses since the last step was matched.
+    mistakes: u32,
+}
+
+/// Tracks the progress of every sequence declared in the `InputHandler`'s bindings.
+///
+/// The `SequenceSystem` drives this automatically; use it directly only if you feed
+/// the `InputHandler` yourself.
+#[derive(Derivative)]
+#[derivative(Default(bound = ""), Debug(bound = ""))]
+pub struct SequenceTracker<T: BindingTypes> {
+    progress: HashMap<T::Action, SequenceProgress>,
+}
+
+impl<T: BindingTypes> SequenceTracker<T> {
+    /// Creates a new tracker with no progress recorded.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Forgets the progress of every sequence.
+    pub fn reset(&mut self) {
+        self.progress.clear();
+    }
+
+    /// Processes an input event that was produced by `handler`, returning the actions of every
+    /// sequence completed by it.
+    ///
+    /// `time` is the current time in seconds, usually `Time::absolute_time_seconds`.
+    /// Only button presses advance sequences; releases and other events are ignored.
+    pub fn process_event(
+        &mut self,
+        event: &InputEvent<T>,
+        handler: &InputHandler<T>,
+        time: f64,
+    ) -> SmallVec<[T::Action; 2]> {
+        let mut completed = SmallVec::new();
+        if let InputEvent::ButtonPressed(pressed) = *event {
+            self.advance(pressed, handler, time, &mut completed);
+        }
+        completed
+    }
+
+    fn advance(
+        &mut self,
+        pressed: Button,
+        handler: &InputHandler<T>,
+        time: f64,
+        completed: &mut SmallVec<[T::Action; 2]>,
+    ) {
+        for (action, sequence) in handler.bindings.sequences.iter() {
+            if sequence.steps.is_empty() {
+                continue;
+            }
+            let progress = self.progress.entry(action.clone()).or_default();
+
+            if progress.next > 0 {
+                let window = f64::from(sequence.steps[progress.next].window);
+                if time - progress.last_time > window {
+                    *progress = SequenceProgress::default();
+                }
+            }
+
+            if step_matched(&sequence.steps[progress.next], pressed, handler) {
+                progress.next += 1;
+                progress.last_time = time;
+                progress.mistakes = 0;
+            } else if progress.next > 0 {
+                if is_mistake(sequence, progress.next, pressed) {
+                    progress.mistakes += 1;
+                    if progress.mistakes > sequence.leniency {
+                        *progress = SequenceProgress::default();
+                        // The offending press might start the sequence over.
+                        if step_matched(&sequence.steps[0], pressed, handler) {
+                            progress.next = 1;
+                            progress.last_time = time;
+                        }
+                    }
+                }
+            }
+
+            if progress.next == sequence.steps.len() {
+                *progress = SequenceProgress::default();
+                completed.push(action.clone());
+            }
+        }
+    }
+}
+
+/// Builds a `SequenceSystem`.
+#[derive(Debug, new)]
+pub struct SequenceSystemDesc<T>
+where
+    T: BindingTypes,
+{
+    #[new(default)]
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<'a, 'b, T> SystemDesc<'a, 'b, SequenceSystem<T>> for SequenceSystemDesc<T>
+where
+    T: BindingTypes,
+{
+    fn build(self, world: &mut World) -> SequenceSystem<T> {
+        <SequenceSystem<T> as System<'_>>::SystemData::setup(world);
+
+        let reader = world
+            .fetch_mut::<EventChannel<InputEvent<T>>>()
+            .register_reader();
+
+        SequenceSystem::new(reader)
+    }
+}
+
+/// Sequence system
+///
+/// Will read button events from `EventChannel<InputEvent<T>>` and push
+/// `InputEvent::SequenceCompleted` back into it whenever a sequence declared in the
+/// `InputHandler`'s bindings is completed. Must run after the `InputSystem`.
+#[derive(Debug)]
+pub struct SequenceSystem<T>
+where
+    T: BindingTypes,
+{
+    reader: ReaderId<InputEvent<T>>,
+    tracker: SequenceTracker<T>,
+}
+
+impl<T: BindingTypes> SequenceSystem<T> {
+    /// Create a new sequence system. Needs a reader id for `EventChannel<InputEvent<T>>`.
+    pub fn new(reader: ReaderId<InputEvent<T>>) -> Self {
+        SequenceSystem {
+            reader,
+            tracker: SequenceTracker::new(),
+        }
+    }
+}
+
+impl<'a, T: BindingTypes> System<'a> for SequenceSystem<T> {
+    type SystemData = (
+        Read<'a, Time>,
+        Read<'a, InputHandler<T>>,
+        Write<'a, EventChannel<InputEvent<T>>>,
+    );
+
+    fn run(&mut self, (time, handler, mut events): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("sequence_system");
+
+        let now = time.absolute_time_seconds();
+        let mut completed = Vec::new();
+        for event in events.read(&mut self.reader) {
+            completed.extend(self.tracker.process_event(event, &handler, now));
+        }
+        events.iter_write(completed.into_iter().map(InputEvent::SequenceCompleted));
+    }
+}
+
+/// A step is matched when all of its buttons are down, and the triggering press is part of it.
+fn step_matched<T: BindingTypes>(
+    step: &SequenceStep,
+    pressed: Button,
+    handler: &InputHandler<T>,
+) -> bool {
+    step.buttons.contains(&pressed) && step.buttons.iter().all(|b| handler.button_is_down(*b))
+}
+
+/// Returns true if pressing the button while waiting for step `next` breaks the sequence.
+///
+/// Buttons of the previous and the next step are fine, so that building up a chord doesn't
+/// count as a mistake. Every key press is reported both as a `Key` and a `ScanCode`, so only
+/// the flavour the sequence is declared with is taken into account.
+fn is_mistake(sequence: &InputSequence, next: usize, button: Button) -> bool {
+    if sequence.steps[next - 1..=next]
+        .iter()
+        .any(|step| step.buttons.contains(&button))
+    {
+        return false;
+    }
+    let uses_scan_codes = sequence.steps.iter().any(|step| {
+        step.buttons.iter().any(|b| match b {
+            Button::ScanCode(_) => true,
+            _ => false,
+        })
+    });
+    match button {
+        Button::ScanCode(_) => uses_scan_codes,
+        Button::Key(_) => !uses_scan_codes,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bindings::StringBindings;
+    use amethyst_core::shrev::EventChannel;
+    use winit::{
+        DeviceId, ElementState, Event, KeyboardInput, ModifiersState, VirtualKeyCode, WindowEvent,
+        WindowId,
+    };
+
+    const HIDPI: f32 = 1.0;
+
+    struct Harness {
+        handler: InputHandler<StringBindings>,
+        tracker: SequenceTracker<StringBindings>,
+        events: EventChannel<InputEvent<StringBindings>>,
+        reader: amethyst_core::shrev::ReaderId<InputEvent<StringBindings>>,
+    }
+
+    impl Harness {
+        fn new(sequence: InputSequence) -> Self {
+            let mut handler = InputHandler::<StringBindings>::new();
+            handler
+                .bindings
+                .insert_sequence(String::from("hadouken"), sequence)
+                .unwrap();
+            let mut events = EventChannel::new();
+            let reader = events.register_reader();
+            Harness {
+                handler,
+                tracker: SequenceTracker::new(),
+                events,
+                reader,
+            }
+        }
+
+        fn key(&mut self, key: VirtualKeyCode, state: ElementState, time: f64) -> Vec<String> {
+            self.handler
+                .send_event(&key_event(key, state), &mut self.events, HIDPI);
+            let events = self
+                .events
+                .read(&mut self.reader)
+                .cloned()
+                .collect::<Vec<_>>();
+            events
+                .iter()
+                .flat_map(|e| self.tracker.process_event(e, &self.handler, time))
+                .collect()
+        }
+    }
+
+    fn quarter_circle() -> InputSequence {
+        use VirtualKeyCode::*;
+        InputSequence::new(vec![
+            SequenceStep::new(vec![Button::Key(Down)]),
+            SequenceStep::new(vec![Button::Key(Down), Button::Key(Right)]),
+            SequenceStep::new(vec![Button::Key(Right), Button::Key(X)]),
+        ])
+    }
+
+    #[test]
+    fn completes_sequence_in_time() {
+        use ElementState::*;
+        use VirtualKeyCode::*;
+
+        let mut h = Harness::new(quarter_circle());
+        assert!(h.key(Down, Pressed, 0.0).is_empty());
+        assert!(h.key(Right, Pressed, 0.1).is_empty());
+        assert!(h.key(Down, Released, 0.2).is_empty());
+        assert_eq!(h.key(X, Pressed, 0.3), vec![String::from("hadouken")]);
+    }
+
+    #[test]
+    fn step_window_expires() {
+        use ElementState::*;
+        use VirtualKeyCode::*;
+
+        let mut h = Harness::new(quarter_circle());
+        h.key(Down, Pressed, 0.0);
+        h.key(Right, Pressed, 0.1);
+        h.key(Down, Released, 0.2);
+        assert!(h.key(X, Pressed, 1.0).is_empty());
+    }
+
+    #[test]
+    fn unrelated_press_resets_without_leniency() {
+        use ElementState::*;
+        use VirtualKeyCode::*;
+
+        let mut h = Harness::new(quarter_circle());
+        h.key(Down, Pressed, 0.0);
+        h.key(Right, Pressed, 0.05);
+        h.key(Down, Released, 0.1);
+        h.key(Z, Pressed, 0.15);
+        assert!(h.key(X, Pressed, 0.2).is_empty());
+    }
+
+    #[test]
+    fn leniency_tolerates_unrelated_presses() {
+        use ElementState::*;
+        use VirtualKeyCode::*;
+
+        let mut h = Harness::new(quarter_circle().with_leniency(1));
+        h.key(Down, Pressed, 0.0);
+        h.key(Right, Pressed, 0.05);
+        h.key(Down, Released, 0.1);
+        h.key(Z, Pressed, 0.15);
+        assert_eq!(h.key(X, Pressed, 0.2), vec![String::from("hadouken")]);
+    }
+
+    #[test]
+    fn double_tap_requires_two_presses() {
+        use ElementState::*;
+        use VirtualKeyCode::*;
+
+        let mut h = Harness::new(InputSequence::new(vec![
+            SequenceStep::new(vec![Button::Key(Right)]),
+            SequenceStep::new(vec![Button::Key(Right)]),
+        ]));
+        assert!(h.key(Right, Pressed, 0.0).is_empty());
+        assert!(h.key(Right, Released, 0.05).is_empty());
+        assert_eq!(h.key(Right, Pressed, 0.1), vec![String::from("hadouken")]);
+    }
+
+    #[test]
+    fn release_does_not_advance_held_step() {
+        use ElementState::*;
+        use VirtualKeyCode::*;
+
+        let mut h = Harness::new(InputSequence::new(vec![
+            SequenceStep::new(vec![Button::Key(Right)]),
+            SequenceStep::new(vec![Button::Key(Right)]),
+        ]));
+        h.key(Z, Pressed, 0.0);
+        assert!(h.key(Right, Pressed, 0.05).is_empty());
+        assert!(h.key(Z, Released, 0.1).is_empty());
+        assert!(h.key(Right, Released, 0.15).is_empty());
+        assert_eq!(h.key(Right, Pressed, 0.2), vec![String::from("hadouken")]);
+    }
+
+    fn key_event(virtual_keycode: VirtualKeyCode, state: ElementState) -> Event {
+        Event::WindowEvent {
+            window_id: unsafe { WindowId::dummy() },
+            event: WindowEvent::KeyboardInput {
+                device_id: unsafe { DeviceId::dummy() },
+                input: KeyboardInput {
+                    scancode: virtual_keycode as u32,
+                    state,
+                    virtual_keycode: Some(virtual_keycode),
+                    modifiers: ModifiersState {
+                        shift: false,
+                        ctrl: false,
+                        alt: false,
+                        logo: false,
+                    },
+                },
+            },
+        }
+    }
+}
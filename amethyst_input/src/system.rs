@@ -2,7 +2,7 @@
 use derive_new::new;
 use winit::Event;
 
-use crate::{BindingTypes, Bindings, InputEvent, InputHandler};
+use crate::{BindingTypes, Bindings, InputEvent, InputHandler, VirtualInput};
 use amethyst_core::{
     ecs::{
         prelude::{Read, ReadExpect, System, World, Write},
@@ -43,8 +43,9 @@ where
 
 /// Input system
 ///
-/// Will read `winit::Event` from `EventHandler<winit::Event>`, process them with `InputHandler`,
-/// and push the results in `EventHandler<InputEvent>`.
+/// Will read `winit::Event` from `EventHandler<winit::Event>` and the events queued in
+/// `VirtualInput`, process them with `InputHandler`, and push the results in
+/// `EventHandler<InputEvent>`.
 #[derive(Debug)]
 pub struct InputSystem<T>
 where
@@ -76,9 +77,13 @@ impl<'a, T: BindingTypes> System<'a> for InputSystem<T> {
         Write<'a, InputHandler<T>>,
         Write<'a, EventChannel<InputEvent<T>>>,
         ReadExpect<'a, ScreenDimensions>,
+        Write<'a, VirtualInput>,
     );
 
-    fn run(&mut self, (input, mut handler, mut output, screen_dimensions): Self::SystemData) {
+    fn run(
+        &mut self,
+        (input, mut handler, mut output, screen_dimensions, mut virtual_input): Self::SystemData,
+    ) {
         #[cfg(feature = "profiler")]
         profile_scope!("input_system");
 
@@ -91,5 +96,8 @@ impl<'a, T: BindingTypes> System<'a> for InputSystem<T> {
                 screen_dimensions.hidpi_factor() as f32,
             );
         }
+        for event in virtual_input.drain() {
+            handler.send_virtual_event(&event, &mut *output);
+        }
     }
 }
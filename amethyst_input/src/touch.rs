@@ -0,0 +1,437 @@
+//! Touch points and gesture recognition.
+
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use amethyst_core::{
+    ecs::prelude::{Read, System, SystemData, World, Write},
+    shrev::{EventChannel, ReaderId},
+    timing::Time,
+    SystemDesc,
+};
+
+use crate::{bindings::BindingTypes, event::InputEvent};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+/// A finger (or stylus) currently touching the screen.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct TouchPoint {
+    /// Identifier of the touch, unique while the finger stays on the screen.
+    pub id: u64,
+    /// Current position in pixels.
+    pub position: (f32, f32),
+    /// Position in pixels where the touch started.
+    pub start_position: (f32, f32),
+}
+
+/// Gestures recognized from touch events by the `GestureSystem`.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum Gesture {
+    /// A single finger touched the screen briefly without moving.
+    Tap {
+        /// Horizontal position of the tap in pixels.
+        x: f32,
+        /// Vertical position of the tap in pixels.
+        y: f32,
+    },
+    /// A single finger moved quickly across the screen and was lifted.
+    Swipe {
+        /// Horizontal position where the swipe started in pixels.
+        start_x: f32,
+        /// Vertical position where the swipe started in pixels.
+        start_y: f32,
+        /// The horizontal distance covered in pixels.
+        delta_x: f32,
+        /// The vertical distance covered in pixels.
+        delta_y: f32,
+    },
+    /// Two fingers moved closer together or further apart.
+    Pinch {
+        /// Horizontal position of the point halfway between the fingers in pixels.
+        center_x: f32,
+        /// Vertical position of the point halfway between the fingers in pixels.
+        center_y: f32,
+        /// Ratio between the current and the previous distance of the fingers.
+        /// Values above 1 mean the fingers are spreading apart.
+        scale: f32,
+    },
+    /// Two fingers rotated around each other.
+    Rotate {
+        /// Horizontal position of the point halfway between the fingers in pixels.
+        center_x: f32,
+        /// Vertical position of the point halfway between the fingers in pixels.
+        center_y: f32,
+        /// Rotation since the previous event in radians.
+        angle: f32,
+    },
+}
+
+/// Thresholds used to tell gestures apart.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GestureConfig {
+    /// Maximum duration of a tap in seconds.
+    pub tap_max_duration: f32,
+    /// Maximum distance a finger can move during a tap in pixels.
+    pub tap_max_distance: f32,
+    /// Maximum duration of a swipe in seconds.
+    pub swipe_max_duration: f32,
+    /// Minimum distance a finger has to move for a swipe in pixels.
+    pub swipe_min_distance: f32,
+    /// Minimum change of the fingers' distance ratio reported as a pinch.
+    pub pinch_threshold: f32,
+    /// Minimum change of the fingers' angle reported as a rotation, in radians.
+    pub rotate_threshold: f32,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        GestureConfig {
+            tap_max_duration: 0.3,
+            tap_max_distance: 10.0,
+            swipe_max_duration: 0.5,
+            swipe_min_distance: 50.0,
+            pinch_threshold: 0.01,
+            rotate_threshold: 0.01,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TrackedTouch {
+    id: u64,
+    start_time: f64,
+    start: (f32, f32),
+    position: (f32, f32),
+    /// Set once a second finger joined, so the touch is no longer a tap or swipe candidate.
+    multi: bool,
+}
+
+/// Recognizes gestures from the touch events sent by the `InputHandler`.
+///
+/// The `GestureSystem` drives this automatically; use it directly only if you feed
+/// the `InputHandler` yourself.
+#[derive(Debug, Default)]
+pub struct GestureRecognizer {
+    /// Thresholds used to tell gestures apart.
+    pub config: GestureConfig,
+    touches: SmallVec<[TrackedTouch; 4]>,
+    /// Distance and angle between the first two fingers when last processed.
+    pair: Option<(f32, f32)>,
+}
+
+impl GestureRecognizer {
+    /// Creates a new recognizer using the given thresholds.
+    pub fn new(config: GestureConfig) -> Self {
+        GestureRecognizer {
+            config,
+            touches: SmallVec::new(),
+            pair: None,
+        }
+    }
+
+    /// Processes an input event, returning the gestures it completed.
+    ///
+    /// `time` is the current time in seconds, usually `Time::absolute_time_seconds`.
+    /// Events that aren't touch events are ignored.
+    pub fn process_event<T: BindingTypes>(
+        &mut self,
+        event: &InputEvent<T>,
+        time: f64,
+    ) -> SmallVec<[Gesture; 2]> {
+        let mut gestures = SmallVec::new();
+        match *event {
+            InputEvent::TouchStarted { id, x, y } => {
+                self.touches.retain(|t| t.id != id);
+                let multi = !self.touches.is_empty();
+                for touch in self.touches.iter_mut() {
+                    touch.multi = true;
+                }
+                self.touches.push(TrackedTouch {
+                    id,
+                    start_time: time,
+                    start: (x, y),
+                    position: (x, y),
+                    multi,
+                });
+                self.pair = self.pair_metrics();
+            }
+            InputEvent::TouchMoved { id, x, y, .. } => {
+                if let Some(touch) = self.touches.iter_mut().find(|t| t.id == id) {
+                    touch.position = (x, y);
+                }
+                self.recognize_pair(&mut gestures);
+            }
+            InputEvent::TouchEnded { id, x, y } => {
+                if let Some(index) = self.touches.iter().position(|t| t.id == id) {
+                    let touch = self.touches.remove(index);
+                    if !touch.multi {
+                        self.recognize_single(&touch, (x, y), time, &mut gestures);
+                    }
+                }
+                self.pair = self.pair_metrics();
+            }
+            InputEvent::TouchCancelled { id } => {
+                self.touches.retain(|t| t.id != id);
+                self.pair = self.pair_metrics();
+            }
+            _ => {}
+        }
+        gestures
+    }
+
+    fn recognize_single(
+        &self,
+        touch: &TrackedTouch,
+        end: (f32, f32),
+        time: f64,
+        gestures: &mut SmallVec<[Gesture; 2]>,
+    ) {
+        let duration = (time - touch.start_time) as f32;
+        let (delta_x, delta_y) = (end.0 - touch.start.0, end.1 - touch.start.1);
+        let distance = (delta_x * delta_x + delta_y * delta_y).sqrt();
+        if duration <= self.config.tap_max_duration && distance <= self.config.tap_max_distance {
+            gestures.push(Gesture::Tap { x: end.0, y: end.1 });
+        } else if duration <= self.config.swipe_max_duration
+            && distance >= self.config.swipe_min_distance
+        {
+            gestures.push(Gesture::Swipe {
+                start_x: touch.start.0,
+                start_y: touch.start.1,
+                delta_x,
+                delta_y,
+            });
+        }
+    }
+
+    fn recognize_pair(&mut self, gestures: &mut SmallVec<[Gesture; 2]>) {
+        let (previous, current) = match (self.pair, self.pair_metrics()) {
+            (Some(previous), Some(current)) => (previous, current),
+            (_, current) => {
+                self.pair = current;
+                return;
+            }
+        };
+        let (a, b) = (self.touches[0].position, self.touches[1].position);
+        let (center_x, center_y) = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+        let mut updated = previous;
+
+        if previous.0 > 0.0 {
+            let scale = current.0 / previous.0;
+            if (scale - 1.0).abs() >= self.config.pinch_threshold {
+                gestures.push(Gesture::Pinch {
+                    center_x,
+                    center_y,
+                    scale,
+                });
+                updated.0 = current.0;
+            }
+        }
+
+        let angle = normalize_angle(current.1 - previous.1);
+        if angle.abs() >= self.config.rotate_threshold {
+            gestures.push(Gesture::Rotate {
+                center_x,
+                center_y,
+                angle,
+            });
+            updated.1 = current.1;
+        }
+
+        self.pair = Some(updated);
+    }
+
+    /// Distance and angle between the first two fingers, if at least two are down.
+    fn pair_metrics(&self) -> Option<(f32, f32)> {
+        if self.touches.len() < 2 {
+            return None;
+        }
+        let (a, b) = (self.touches[0].position, self.touches[1].position);
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        Some(((dx * dx + dy * dy).sqrt(), dy.atan2(dx)))
+    }
+}
+
+/// Wraps an angle into the `[-PI, PI]` range.
+fn normalize_angle(angle: f32) -> f32 {
+    use std::f32::consts::PI;
+
+    let mut angle = angle % (2.0 * PI);
+    if angle > PI {
+        angle -= 2.0 * PI;
+    } else if angle < -PI {
+        angle += 2.0 * PI;
+    }
+    angle
+}
+
+/// Builds a `GestureSystem`.
+#[derive(Debug, new)]
+pub struct GestureSystemDesc<T>
+where
+    T: BindingTypes,
+{
+    config: GestureConfig,
+    #[new(default)]
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<'a, 'b, T> SystemDesc<'a, 'b, GestureSystem<T>> for GestureSystemDesc<T>
+where
+    T: BindingTypes,
+{
+    fn build(self, world: &mut World) -> GestureSystem<T> {
+        <GestureSystem<T> as System<'_>>::SystemData::setup(world);
+
+        let reader = world
+            .fetch_mut::<EventChannel<InputEvent<T>>>()
+            .register_reader();
+
+        GestureSystem::new(reader, GestureRecognizer::new(self.config))
+    }
+}
+
+/// Gesture system
+///
+/// Will read touch events from `EventChannel<InputEvent<T>>` and push `InputEvent::Gesture`
+/// back into it whenever a gesture is recognized. Must run after the `InputSystem`.
+#[derive(Debug)]
+pub struct GestureSystem<T>
+where
+    T: BindingTypes,
+{
+    reader: ReaderId<InputEvent<T>>,
+    recognizer: GestureRecognizer,
+}
+
+impl<T: BindingTypes> GestureSystem<T> {
+    /// Create a new gesture system. Needs a reader id for `EventChannel<InputEvent<T>>`.
+    pub fn new(reader: ReaderId<InputEvent<T>>, recognizer: GestureRecognizer) -> Self {
+        GestureSystem { reader, recognizer }
+    }
+}
+
+impl<'a, T: BindingTypes> System<'a> for GestureSystem<T> {
+    type SystemData = (Read<'a, Time>, Write<'a, EventChannel<InputEvent<T>>>);
+
+    fn run(&mut self, (time, mut events): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("gesture_system");
+
+        let now = time.absolute_time_seconds();
+        let mut gestures = Vec::new();
+        for event in events.read(&mut self.reader) {
+            gestures.extend(self.recognizer.process_event(event, now));
+        }
+        events.iter_write(gestures.into_iter().map(InputEvent::Gesture));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bindings::StringBindings;
+
+    type Event = InputEvent<StringBindings>;
+
+    fn start(id: u64, x: f32, y: f32) -> Event {
+        InputEvent::TouchStarted { id, x, y }
+    }
+
+    fn moved(id: u64, x: f32, y: f32) -> Event {
+        InputEvent::TouchMoved {
+            id,
+            x,
+            y,
+            delta_x: 0.0,
+            delta_y: 0.0,
+        }
+    }
+
+    fn end(id: u64, x: f32, y: f32) -> Event {
+        InputEvent::TouchEnded { id, x, y }
+    }
+
+    #[test]
+    fn tap() {
+        let mut recognizer = GestureRecognizer::default();
+        assert!(recognizer
+            .process_event(&start(0, 10.0, 10.0), 0.0)
+            .is_empty());
+        assert_eq!(
+            recognizer
+                .process_event(&end(0, 12.0, 10.0), 0.1)
+                .as_slice(),
+            &[Gesture::Tap { x: 12.0, y: 10.0 }]
+        );
+    }
+
+    #[test]
+    fn long_press_is_not_a_tap() {
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.process_event(&start(0, 10.0, 10.0), 0.0);
+        assert!(recognizer
+            .process_event(&end(0, 10.0, 10.0), 1.0)
+            .is_empty());
+    }
+
+    #[test]
+    fn swipe() {
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.process_event(&start(0, 10.0, 10.0), 0.0);
+        recognizer.process_event(&moved(0, 60.0, 12.0), 0.1);
+        assert_eq!(
+            recognizer
+                .process_event(&end(0, 110.0, 14.0), 0.2)
+                .as_slice(),
+            &[Gesture::Swipe {
+                start_x: 10.0,
+                start_y: 10.0,
+                delta_x: 100.0,
+                delta_y: 4.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn pinch() {
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.process_event(&start(0, 0.0, 0.0), 0.0);
+        recognizer.process_event(&start(1, 100.0, 0.0), 0.0);
+        assert_eq!(
+            recognizer
+                .process_event(&moved(1, 200.0, 0.0), 0.1)
+                .as_slice(),
+            &[Gesture::Pinch {
+                center_x: 100.0,
+                center_y: 0.0,
+                scale: 2.0,
+            }]
+        );
+        // Neither finger produces a tap or a swipe once a second one joined.
+        assert!(recognizer
+            .process_event(&end(1, 200.0, 0.0), 0.2)
+            .is_empty());
+        assert!(recognizer.process_event(&end(0, 0.0, 0.0), 0.2).is_empty());
+    }
+
+    #[test]
+    fn rotate() {
+        use approx::assert_ulps_eq;
+
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.process_event(&start(0, 0.0, 0.0), 0.0);
+        recognizer.process_event(&start(1, 100.0, 0.0), 0.0);
+        let gestures = recognizer.process_event(&moved(1, 0.0, 100.0), 0.1);
+        assert_eq!(gestures.len(), 1);
+        match gestures[0] {
+            Gesture::Rotate { angle, .. } => {
+                assert_ulps_eq!(angle, std::f32::consts::FRAC_PI_2);
+            }
+            ref other => panic!("Expected a rotation, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! Programmatic input, for AI-controlled players and scripted tests.
+
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use crate::{
+    button::Button,
+    controller::{ControllerAxis, ControllerEvent},
+};
+
+/// An input event that didn't originate from a physical device.
+///
+/// Queued through the `VirtualInput` resource, and applied by the `InputSystem`
+/// after the window events of the frame.
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum VirtualEvent {
+    /// The button is held down until a matching `ButtonReleased` is sent.
+    ///
+    /// Pressing a `Button::MouseWheel` scrolls the wheel once in that direction.
+    ButtonPressed(Button),
+    /// The button is released.
+    ButtonReleased(Button),
+    /// The mouse pointer is moved to the given position in pixels.
+    CursorMoved {
+        /// The horizontal position of the cursor.
+        x: f32,
+        /// The vertical position of the cursor.
+        y: f32,
+    },
+    /// A controller event, as if it was produced by a controller backend.
+    ///
+    /// Use this to connect virtual controllers and move their axes.
+    Controller(ControllerEvent),
+}
+
+/// Resource used to drive the `InputHandler` without a physical device.
+///
+/// Every event queued during a frame is applied by the `InputSystem` at the start of the next
+/// one, producing the same `InputEvent`s, actions and axis values as real input would. This
+/// lets AI-controlled players share the action code of human players, and tests script input:
+///
+/// ```rust,edition2018,no_run,noplaypen
+/// # use amethyst_input::{Button, VirtualInput, VirtualKeyCode};
+/// # use amethyst_core::ecs::prelude::World;
+/// # fn effect(world: &mut World) {
+/// world
+///     .write_resource::<VirtualInput>()
+///     .tap(Button::Key(VirtualKeyCode::Space));
+/// # }
+/// ```
+///
+/// Virtual buttons are tracked separately from physical ones: a button is down if either
+/// holds it. Key presses don't carry a scan code, so they don't emit `InputEvent::KeyPressed`.
+#[derive(Debug, Default)]
+pub struct VirtualInput {
+    queue: Vec<VirtualEvent>,
+    /// Buttons tapped this frame, released on the next one.
+    tapped: SmallVec<[Button; 4]>,
+}
+
+impl VirtualInput {
+    /// Creates an empty virtual input queue.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Queues a raw virtual event.
+    pub fn send(&mut self, event: VirtualEvent) {
+        self.queue.push(event);
+    }
+
+    /// Presses a button, holding it until `release` is called.
+    pub fn press(&mut self, button: Button) {
+        self.send(VirtualEvent::ButtonPressed(button));
+    }
+
+    /// Releases a button pressed with `press`.
+    pub fn release(&mut self, button: Button) {
+        self.send(VirtualEvent::ButtonReleased(button));
+    }
+
+    /// Presses a button for exactly one frame.
+    pub fn tap(&mut self, button: Button) {
+        self.press(button);
+        self.tapped.push(button);
+    }
+
+    /// Moves the mouse pointer to the given position in pixels.
+    pub fn move_cursor(&mut self, x: f32, y: f32) {
+        self.send(VirtualEvent::CursorMoved { x, y });
+    }
+
+    /// Connects a virtual controller.
+    ///
+    /// `which` is the raw id used by the controller events, pick one that doesn't collide with
+    /// the instance ids of the controller backend (e.g. starting from `u32::max_value()` and
+    /// counting down). The `InputHandler` allocates a controller id for it like for any other
+    /// controller.
+    pub fn connect_controller(&mut self, which: u32) {
+        self.send(VirtualEvent::Controller(
+            ControllerEvent::ControllerConnected { which },
+        ));
+    }
+
+    /// Disconnects a virtual controller.
+    pub fn disconnect_controller(&mut self, which: u32) {
+        self.send(VirtualEvent::Controller(
+            ControllerEvent::ControllerDisconnected { which },
+        ));
+    }
+
+    /// Sets the value of an axis of a virtual controller, from -1 to 1.
+    pub fn set_controller_axis(&mut self, which: u32, axis: ControllerAxis, value: f32) {
+        self.send(VirtualEvent::Controller(
+            ControllerEvent::ControllerAxisMoved { which, axis, value },
+        ));
+    }
+
+    /// Returns the events to apply this frame, queueing the releases of tapped buttons
+    /// for the next one.
+    pub(crate) fn drain(&mut self) -> Vec<VirtualEvent> {
+        let events = std::mem::replace(&mut self.queue, Vec::new());
+        self.queue
+            .extend(self.tapped.drain().map(VirtualEvent::ButtonReleased));
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        axis::Axis, bindings::StringBindings, controller::ControllerButton, event::InputEvent,
+        input_handler::InputHandler,
+    };
+    use amethyst_core::shrev::EventChannel;
+    use winit::{
+        DeviceId, ElementState, Event, ModifiersState, MouseButton, VirtualKeyCode, WindowEvent,
+        WindowId,
+    };
+
+    fn apply(
+        input: &mut VirtualInput,
+        handler: &mut InputHandler<StringBindings>,
+        events: &mut EventChannel<InputEvent<StringBindings>>,
+    ) {
+        for event in input.drain() {
+            handler.send_virtual_event(&event, events);
+        }
+    }
+
+    fn mouse_event(state: ElementState) -> Event {
+        Event::WindowEvent {
+            window_id: unsafe { WindowId::dummy() },
+            event: WindowEvent::MouseInput {
+                device_id: unsafe { DeviceId::dummy() },
+                state,
+                button: MouseButton::Left,
+                modifiers: ModifiersState {
+                    shift: false,
+                    ctrl: false,
+                    alt: false,
+                    logo: false,
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn virtual_buttons_trigger_actions() {
+        let mut input = VirtualInput::new();
+        let mut handler = InputHandler::<StringBindings>::new();
+        let mut events = EventChannel::<InputEvent<StringBindings>>::new();
+        let mut reader = events.register_reader();
+        handler
+            .bindings
+            .insert_action_binding(
+                String::from("jump"),
+                [Button::Key(VirtualKeyCode::Space)].iter().cloned(),
+            )
+            .unwrap();
+
+        input.press(Button::Key(VirtualKeyCode::Space));
+        apply(&mut input, &mut handler, &mut events);
+        assert_eq!(handler.action_is_down("jump"), Some(true));
+        assert!(handler.key_is_down(VirtualKeyCode::Space));
+        assert_eq!(
+            events.read(&mut reader).cloned().collect::<Vec<_>>(),
+            vec![
+                InputEvent::ButtonPressed(Button::Key(VirtualKeyCode::Space)),
+                InputEvent::ActionPressed(String::from("jump")),
+            ]
+        );
+
+        input.release(Button::Key(VirtualKeyCode::Space));
+        apply(&mut input, &mut handler, &mut events);
+        assert_eq!(handler.action_is_down("jump"), Some(false));
+        assert_eq!(
+            events.read(&mut reader).cloned().collect::<Vec<_>>(),
+            vec![
+                InputEvent::ButtonReleased(Button::Key(VirtualKeyCode::Space)),
+                InputEvent::ActionReleased(String::from("jump")),
+            ]
+        );
+    }
+
+    #[test]
+    fn virtual_press_of_held_button_sends_no_events() {
+        let mut input = VirtualInput::new();
+        let mut handler = InputHandler::<StringBindings>::new();
+        let mut events = EventChannel::<InputEvent<StringBindings>>::new();
+        handler
+            .bindings
+            .insert_action_binding(
+                String::from("fire"),
+                [Button::Mouse(MouseButton::Left)].iter().cloned(),
+            )
+            .unwrap();
+        handler.send_event(&mouse_event(ElementState::Pressed), &mut events, 1.0);
+        let mut reader = events.register_reader();
+
+        input.press(Button::Mouse(MouseButton::Left));
+        apply(&mut input, &mut handler, &mut events);
+        input.release(Button::Mouse(MouseButton::Left));
+        apply(&mut input, &mut handler, &mut events);
+        assert_eq!(handler.action_is_down("fire"), Some(true));
+        assert_eq!(events.read(&mut reader).count(), 0);
+
+        input.press(Button::Mouse(MouseButton::Left));
+        apply(&mut input, &mut handler, &mut events);
+        handler.send_event(&mouse_event(ElementState::Released), &mut events, 1.0);
+        assert_eq!(handler.action_is_down("fire"), Some(true));
+        assert_eq!(events.read(&mut reader).count(), 0);
+
+        input.release(Button::Mouse(MouseButton::Left));
+        apply(&mut input, &mut handler, &mut events);
+        assert_eq!(handler.action_is_down("fire"), Some(false));
+        assert_eq!(
+            events.read(&mut reader).cloned().collect::<Vec<_>>(),
+            vec![
+                InputEvent::ButtonReleased(Button::Mouse(MouseButton::Left)),
+                InputEvent::ActionReleased(String::from("fire")),
+            ]
+        );
+    }
+
+    #[test]
+    fn tap_releases_on_next_frame() {
+        let mut input = VirtualInput::new();
+        let mut handler = InputHandler::<StringBindings>::new();
+        let mut events = EventChannel::<InputEvent<StringBindings>>::new();
+
+        input.tap(Button::Key(VirtualKeyCode::E));
+        apply(&mut input, &mut handler, &mut events);
+        assert!(handler.button_is_down(Button::Key(VirtualKeyCode::E)));
+        apply(&mut input, &mut handler, &mut events);
+        assert!(!handler.button_is_down(Button::Key(VirtualKeyCode::E)));
+    }
+
+    #[test]
+    fn virtual_controller() {
+        let mut input = VirtualInput::new();
+        let mut handler = InputHandler::<StringBindings>::new();
+        let mut events = EventChannel::<InputEvent<StringBindings>>::new();
+        handler
+            .bindings
+            .insert_axis(
+                String::from("steer"),
+                Axis::Controller {
+                    controller_id: 0,
+                    axis: ControllerAxis::LeftX,
+                    invert: false,
+                    dead_zone: 0.0,
+                },
+            )
+            .unwrap();
+
+        let which = u32::max_value();
+        input.connect_controller(which);
+        input.set_controller_axis(which, ControllerAxis::LeftX, 0.5);
+        input.press(Button::Controller(0, ControllerButton::A));
+        apply(&mut input, &mut handler, &mut events);
+        assert!(handler.is_controller_connected(0));
+        assert_eq!(handler.axis_value("steer"), Some(0.5));
+        assert!(handler.controller_button_is_down(0, ControllerButton::A));
+    }
+}
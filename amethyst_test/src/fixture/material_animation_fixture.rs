@@ -48,6 +48,7 @@ impl MaterialAnimationFixture {
                     (0, MaterialChannel::AlbedoTexture, texture_animation_handle),
                     (0, MaterialChannel::UvOffset, sampler_animation_handle),
                 ],
+                events: Vec::new(),
             };
 
             loader.load_from_data::<Animation<Material>, ()>(animation, (), &world.read_resource())
@@ -64,6 +64,7 @@ impl SpriteRenderAnimationFixture {
                         sprite_index_animation_handle,
                     ),
                 ],
+                events: Vec::new(),
             };
 
             loader.load_from_data::<Animation<SpriteRender>, ()>(
@@ -0,0 +1,614 @@
+//! Data binding between the fields of resources or components and widgets.
+
+use std::{collections::HashMap, fmt, marker::PhantomData};
+
+use amethyst_core::{
+    ecs::{
+        prelude::{
+            Component, DenseVecStorage, Entities, Entity, Join, Read, ReadStorage, ReaderId,
+            System, World, Write, WriteStorage,
+        },
+        shred::{Resource, ResourceId, SystemData},
+    },
+    shrev::EventChannel,
+    Named, SystemDesc,
+};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use crate::{UiCheckbox, UiDropdown, UiEvent, UiEventType, UiProgressBar, UiSlider, UiText};
+
+/// A value shown by a bound widget, or written back by it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum UiValue {
+    /// A boolean, shown by checkboxes.
+    Bool(bool),
+    /// A number, shown by sliders, progress bars and dropdowns as the selected index.
+    Number(f32),
+    /// A text, shown by texts.
+    Text(String),
+}
+
+impl UiValue {
+    /// The value as a number, parsing texts and with booleans as 0 or 1.
+    pub fn as_number(&self) -> Option<f32> {
+        match self {
+            UiValue::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+            UiValue::Number(value) => Some(*value),
+            UiValue::Text(text) => text.trim().parse().ok(),
+        }
+    }
+
+    /// The value as a boolean, with numbers true when not zero.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            UiValue::Bool(value) => Some(*value),
+            UiValue::Number(value) => Some(*value != 0.0),
+            UiValue::Text(text) => text.trim().parse().ok(),
+        }
+    }
+}
+
+impl fmt::Display for UiValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UiValue::Bool(value) => value.fmt(f),
+            UiValue::Number(value) => value.fmt(f),
+            UiValue::Text(text) => text.fmt(f),
+        }
+    }
+}
+
+impl From<bool> for UiValue {
+    fn from(value: bool) -> Self {
+        UiValue::Bool(value)
+    }
+}
+
+impl From<f32> for UiValue {
+    fn from(value: f32) -> Self {
+        UiValue::Number(value)
+    }
+}
+
+impl From<i32> for UiValue {
+    fn from(value: i32) -> Self {
+        UiValue::Number(value as f32)
+    }
+}
+
+impl From<u32> for UiValue {
+    fn from(value: u32) -> Self {
+        UiValue::Number(value as f32)
+    }
+}
+
+impl From<usize> for UiValue {
+    fn from(value: usize) -> Self {
+        UiValue::Number(value as f32)
+    }
+}
+
+impl From<String> for UiValue {
+    fn from(value: String) -> Self {
+        UiValue::Text(value)
+    }
+}
+
+impl<'a> From<&'a str> for UiValue {
+    fn from(value: &'a str) -> Self {
+        UiValue::Text(value.to_string())
+    }
+}
+
+/// Formats a value for a bound `UiText`.
+///
+/// `{}` is replaced by the value and `{:.N}` by the value with N decimals if it is a number.
+/// Without a format, the value is shown as is.
+pub fn format_value(format: Option<&str>, value: &UiValue) -> String {
+    let format = match format {
+        Some(format) => format,
+        None => return value.to_string(),
+    };
+    let mut formatted = String::with_capacity(format.len());
+    let mut rest = format;
+    while let Some(open) = rest.find('{') {
+        formatted.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let close = match rest.find('}') {
+            Some(close) => close,
+            None => break,
+        };
+        let spec = &rest[1..close];
+        let precision = if spec.starts_with(":.") {
+            spec[2..].parse::<usize>().ok()
+        } else {
+            None
+        };
+        match (spec, precision, value.as_number()) {
+            ("", _, _) => formatted.push_str(&value.to_string()),
+            (_, Some(precision), Some(number)) => {
+                formatted.push_str(&format!("{:.*}", precision, number))
+            }
+            (_, Some(_), None) => formatted.push_str(&value.to_string()),
+            _ => formatted.push_str(&rest[..=close]),
+        }
+        rest = &rest[close + 1..];
+    }
+    formatted.push_str(rest);
+    formatted
+}
+
+/// Exposes named fields of a resource or component to the widgets bound to it.
+///
+/// ```rust,ignore
+/// #[derive(Default)]
+/// struct Settings {
+///     volume: f32,
+/// }
+///
+/// impl UiBindingSource for Settings {
+///     fn read(&self, field: &str) -> Option<UiValue> {
+///         match field {
+///             "volume" => Some(self.volume.into()),
+///             _ => None,
+///         }
+///     }
+///
+///     fn write(&mut self, field: &str, value: UiValue) -> bool {
+///         match (field, value.as_number()) {
+///             ("volume", Some(volume)) => self.volume = volume,
+///             _ => return false,
+///         }
+///         true
+///     }
+/// }
+/// ```
+pub trait UiBindingSource {
+    /// Reads a field, `None` if there is no such field.
+    fn read(&self, field: &str) -> Option<UiValue>;
+
+    /// Writes a field from the value of a widget bound both ways, returns false if the field
+    /// can't be written. Fields are read only by default.
+    fn write(&mut self, _field: &str, _value: UiValue) -> bool {
+        false
+    }
+}
+
+/// The direction in which the values of a `UiBinding` flow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UiBindingMode {
+    /// The widget shows the value of the field.
+    OneWay,
+    /// The widget shows the value of the field, and its changes are written to the field.
+    TwoWay,
+}
+
+impl Default for UiBindingMode {
+    fn default() -> Self {
+        UiBindingMode::OneWay
+    }
+}
+
+/// Component binding a widget to a field of a resource or component.
+///
+/// Bound `UiSlider`s, `UiProgressBar`s, `UiCheckbox`es and `UiDropdown`s show the value of the
+/// field, other widgets show it as their `UiText`. The widgets are only touched when the value
+/// changed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiBinding {
+    /// The name the source was registered with, by its `UiResourceBindingSystemDesc` or
+    /// `UiComponentBindingSystemDesc`.
+    pub source: String,
+    /// The field of the source, see `UiBindingSource`.
+    pub field: String,
+    /// For component sources, the name of the `Named` entity holding the component. The
+    /// component of the widget entity itself is used if not set.
+    pub entity: Option<String>,
+    /// The format of bound texts, see `format_value`.
+    pub format: Option<String>,
+    /// Whether the changes of the widget are written to the field.
+    pub mode: UiBindingMode,
+}
+
+impl UiBinding {
+    /// Binds a widget to the field of the source registered as `source`.
+    pub fn new<S: Into<String>, F: Into<String>>(source: S, field: F) -> Self {
+        UiBinding {
+            source: source.into(),
+            field: field.into(),
+            ..UiBinding::default()
+        }
+    }
+
+    /// Reads the component of the `Named` entity with this name.
+    pub fn with_entity<S: Into<String>>(mut self, entity: S) -> Self {
+        self.entity = Some(entity.into());
+        self
+    }
+
+    /// Sets the format of the bound text, see `format_value`.
+    pub fn with_format<S: Into<String>>(mut self, format: S) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    /// Writes the changes of the widget to the field.
+    pub fn two_way(mut self) -> Self {
+        self.mode = UiBindingMode::TwoWay;
+        self
+    }
+}
+
+impl Component for UiBinding {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// The widgets a binding system updates.
+#[derive(SystemData)]
+#[allow(missing_debug_implementations)]
+pub struct UiBoundWidgets<'a> {
+    entities: Entities<'a>,
+    bindings: ReadStorage<'a, UiBinding>,
+    texts: WriteStorage<'a, UiText>,
+    sliders: WriteStorage<'a, UiSlider>,
+    progress_bars: WriteStorage<'a, UiProgressBar>,
+    checkboxes: WriteStorage<'a, UiCheckbox>,
+    dropdowns: WriteStorage<'a, UiDropdown>,
+    ui_events: Read<'a, EventChannel<UiEvent>>,
+}
+
+impl<'a> UiBoundWidgets<'a> {
+    /// The value of a widget, to be written to its field.
+    fn value(&self, entity: Entity) -> Option<UiValue> {
+        if let Some(slider) = self.sliders.get(entity) {
+            Some(UiValue::Number(slider.value))
+        } else if let Some(checkbox) = self.checkboxes.get(entity) {
+            Some(UiValue::Bool(checkbox.checked))
+        } else if let Some(dropdown) = self.dropdowns.get(entity) {
+            Some(UiValue::from(dropdown.selected))
+        } else {
+            self.texts
+                .get(entity)
+                .map(|text| UiValue::Text(text.text.clone()))
+        }
+    }
+
+    /// Shows a value on a widget.
+    fn show(&mut self, entity: Entity, value: &UiValue) {
+        if let Some(slider) = self.sliders.get_mut(entity) {
+            if let Some(number) = value.as_number() {
+                slider.set_value(number);
+            }
+        } else if let Some(progress_bar) = self.progress_bars.get_mut(entity) {
+            if let Some(number) = value.as_number() {
+                progress_bar.value = number;
+            }
+        } else if let Some(checkbox) = self.checkboxes.get_mut(entity) {
+            if let Some(checked) = value.as_bool() {
+                checkbox.checked = checked;
+            }
+        } else if let Some(dropdown) = self.dropdowns.get_mut(entity) {
+            if let Some(index) = value.as_number() {
+                dropdown.select(index.max(0.0) as usize);
+            }
+        } else if let Some(text) = self.texts.get_mut(entity) {
+            let format = self
+                .bindings
+                .get(entity)
+                .and_then(|binding| binding.format.as_ref());
+            text.text = format_value(format.map(String::as_str), value);
+        }
+    }
+}
+
+/// The state shared by the binding systems.
+#[derive(Debug)]
+struct BindingState {
+    name: String,
+    ui_reader_id: ReaderId<UiEvent>,
+    /// The last value shown by each widget.
+    shown: HashMap<Entity, UiValue>,
+}
+
+impl BindingState {
+    fn new(name: String, world: &mut World) -> Self {
+        let ui_reader_id = world.fetch_mut::<EventChannel<UiEvent>>().register_reader();
+        BindingState {
+            name,
+            ui_reader_id,
+            shown: HashMap::new(),
+        }
+    }
+
+    /// Writes the changes of the widgets bound both ways to this source with `write`.
+    fn write_back<F>(&mut self, widgets: &UiBoundWidgets<'_>, mut write: F)
+    where
+        F: FnMut(&UiBinding, Entity, UiValue) -> bool,
+    {
+        for event in widgets.ui_events.read(&mut self.ui_reader_id) {
+            if event.event_type != UiEventType::ValueChange {
+                continue;
+            }
+            let binding = match widgets.bindings.get(event.target) {
+                Some(binding) if binding.source == self.name => binding,
+                _ => continue,
+            };
+            if binding.mode != UiBindingMode::TwoWay {
+                continue;
+            }
+            if let Some(value) = widgets.value(event.target) {
+                if write(binding, event.target, value.clone()) {
+                    // The widget already shows the value.
+                    self.shown.insert(event.target, value);
+                }
+            }
+        }
+    }
+
+    /// Shows the values read with `read` on the widgets bound to this source, if they changed.
+    fn update<F>(&mut self, widgets: &mut UiBoundWidgets<'_>, read: F)
+    where
+        F: Fn(&UiBinding, Entity) -> Option<UiValue>,
+    {
+        let entities = &widgets.entities;
+        self.shown.retain(|entity, _| entities.is_alive(*entity));
+
+        let mut changed = Vec::new();
+        for (entity, binding) in (&*widgets.entities, &widgets.bindings).join() {
+            if binding.source != self.name {
+                continue;
+            }
+            if let Some(value) = read(binding, entity) {
+                if self.shown.get(&entity) != Some(&value) {
+                    changed.push((entity, value));
+                }
+            }
+        }
+        for (entity, value) in changed {
+            widgets.show(entity, &value);
+            self.shown.insert(entity, value);
+        }
+    }
+}
+
+/// Builds a `UiResourceBindingSystem`.
+#[derive(Debug)]
+pub struct UiResourceBindingSystemDesc<R> {
+    name: String,
+    phantom: PhantomData<R>,
+}
+
+impl<R> UiResourceBindingSystemDesc<R> {
+    /// Registers the resource `R` as the binding source named `name`.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        UiResourceBindingSystemDesc {
+            name: name.into(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b, R> SystemDesc<'a, 'b, UiResourceBindingSystem<R>> for UiResourceBindingSystemDesc<R>
+where
+    R: Resource + UiBindingSource,
+{
+    fn build(self, world: &mut World) -> UiResourceBindingSystem<R> {
+        <UiResourceBindingSystem<R> as System<'_>>::SystemData::setup(world);
+        UiResourceBindingSystem {
+            state: BindingState::new(self.name, world),
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// System keeping the widgets bound to the resource `R` in sync with its fields.
+///
+/// ```rust,ignore
+/// let game_data = GameDataBuilder::default().with_system_desc(
+///     UiResourceBindingSystemDesc::<Settings>::new("settings"),
+///     "settings_binding",
+///     &["ui_form_system"],
+/// );
+/// ```
+#[derive(Debug)]
+pub struct UiResourceBindingSystem<R> {
+    state: BindingState,
+    phantom: PhantomData<R>,
+}
+
+impl<'a, R> System<'a> for UiResourceBindingSystem<R>
+where
+    R: Resource + UiBindingSource,
+{
+    type SystemData = (Option<Write<'a, R>>, UiBoundWidgets<'a>);
+
+    fn run(&mut self, (mut resource, mut widgets): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("ui_resource_binding_system");
+
+        self.state.write_back(&widgets, |binding, _, value| {
+            resource
+                .as_mut()
+                .map_or(false, |resource| resource.write(&binding.field, value))
+        });
+        if let Some(resource) = resource {
+            self.state
+                .update(&mut widgets, |binding, _| resource.read(&binding.field));
+        }
+    }
+}
+
+/// Builds a `UiComponentBindingSystem`.
+#[derive(Debug)]
+pub struct UiComponentBindingSystemDesc<C> {
+    name: String,
+    phantom: PhantomData<C>,
+}
+
+impl<C> UiComponentBindingSystemDesc<C> {
+    /// Registers the component `C` as the binding source named `name`.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        UiComponentBindingSystemDesc {
+            name: name.into(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, 'b, C> SystemDesc<'a, 'b, UiComponentBindingSystem<C>> for UiComponentBindingSystemDesc<C>
+where
+    C: Component + UiBindingSource,
+{
+    fn build(self, world: &mut World) -> UiComponentBindingSystem<C> {
+        <UiComponentBindingSystem<C> as System<'_>>::SystemData::setup(world);
+        UiComponentBindingSystem {
+            state: BindingState::new(self.name, world),
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// System keeping the widgets bound to the component `C` in sync with its fields, either on
+/// the widget entity or on the `Named` entity of the `UiBinding`.
+#[derive(Debug)]
+pub struct UiComponentBindingSystem<C> {
+    state: BindingState,
+    phantom: PhantomData<C>,
+}
+
+impl<'a, C> System<'a> for UiComponentBindingSystem<C>
+where
+    C: Component + UiBindingSource,
+{
+    type SystemData = (
+        WriteStorage<'a, C>,
+        ReadStorage<'a, Named>,
+        UiBoundWidgets<'a>,
+    );
+
+    fn run(&mut self, (mut components, names, mut widgets): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("ui_component_binding_system");
+
+        let named = (&*widgets.entities, &names)
+            .join()
+            .map(|(entity, named)| (named.name.to_string(), entity))
+            .collect::<HashMap<_, _>>();
+        let source = |binding: &UiBinding, widget: Entity| match binding.entity {
+            Some(ref name) => named.get(name).cloned(),
+            None => Some(widget),
+        };
+
+        self.state.write_back(&widgets, |binding, widget, value| {
+            source(binding, widget)
+                .and_then(|entity| components.get_mut(entity))
+                .map_or(false, |component| component.write(&binding.field, value))
+        });
+        self.state.update(&mut widgets, |binding, widget| {
+            source(binding, widget)
+                .and_then(|entity| components.get(entity))
+                .and_then(|component| component.read(&binding.field))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use amethyst_assets::AssetStorage;
+    use amethyst_core::ecs::{Builder, RunNow, WorldExt};
+    use glyph_brush::rusttype::Font;
+
+    use crate::FontAsset;
+
+    #[derive(Default)]
+    struct Settings {
+        volume: f32,
+        name: String,
+    }
+
+    impl UiBindingSource for Settings {
+        fn read(&self, field: &str) -> Option<UiValue> {
+            match field {
+                "volume" => Some(self.volume.into()),
+                "name" => Some(self.name.as_str().into()),
+                _ => None,
+            }
+        }
+
+        fn write(&mut self, field: &str, value: UiValue) -> bool {
+            match (field, value.as_number()) {
+                ("volume", Some(volume)) => self.volume = volume,
+                _ => return false,
+            }
+            true
+        }
+    }
+
+    #[test]
+    fn formatting() {
+        let value = UiValue::Number(2.75);
+        assert_eq!(format_value(None, &value), "2.75");
+        assert_eq!(format_value(Some("Volume: {}"), &value), "Volume: 2.75");
+        assert_eq!(format_value(Some("{:.3} ({:.0})"), &value), "2.750 (3)");
+        assert_eq!(format_value(Some("{x} {"), &value), "{x} {");
+        let value = UiValue::Text("Ann".to_string());
+        assert_eq!(format_value(Some("Hi {:.1}!"), &value), "Hi Ann!");
+    }
+
+    #[test]
+    fn resource_binding() {
+        let mut world = World::new();
+        world.insert(Settings {
+            volume: 0.5,
+            name: "Ann".to_string(),
+        });
+        let mut system = UiResourceBindingSystemDesc::<Settings>::new("settings").build(&mut world);
+
+        let font = Font::from_bytes(&include_bytes!("font/square.ttf")[..])
+            .expect("Failed to load the default font");
+        let font = AssetStorage::<FontAsset>::new().insert(FontAsset(font));
+        let label = world
+            .create_entity()
+            .with(UiText::new(font, String::new(), [1.0; 4], 20.0))
+            .with(UiBinding::new("settings", "name").with_format("Name: {}"))
+            .build();
+        let handle = world.create_entity().build();
+        let slider = world
+            .create_entity()
+            .with(UiSlider::new(handle, 0.0, 1.0))
+            .with(UiBinding::new("settings", "volume").two_way())
+            .build();
+
+        system.run_now(&world);
+        assert_eq!(
+            world.read_storage::<UiText>().get(label).unwrap().text,
+            "Name: Ann"
+        );
+        assert_eq!(
+            world.read_storage::<UiSlider>().get(slider).unwrap().value,
+            0.5
+        );
+
+        world.write_resource::<Settings>().name = "Bob".to_string();
+        world
+            .write_storage::<UiSlider>()
+            .get_mut(slider)
+            .unwrap()
+            .value = 0.75;
+        world
+            .write_resource::<EventChannel<UiEvent>>()
+            .single_write(UiEvent::new(UiEventType::ValueChange, slider));
+        system.run_now(&world);
+        assert_eq!(
+            world.read_storage::<UiText>().get(label).unwrap().text,
+            "Name: Bob"
+        );
+        assert_eq!(world.read_resource::<Settings>().volume, 0.75);
+    }
+}
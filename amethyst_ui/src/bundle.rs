@@ -2,10 +2,12 @@
 
 use crate::{
     BlinkSystem, CacheSelectionOrderSystem, FontAsset, NoCustomUi, ResizeSystemDesc,
-    SelectionKeyboardSystemDesc, SelectionMouseSystemDesc, TextEditingInputSystemDesc,
-    TextEditingMouseSystemDesc, ToNativeWidget, UiButtonActionRetriggerSystemDesc,
-    UiButtonSystemDesc, UiLoaderSystemDesc, UiMouseSystem, UiSoundRetriggerSystemDesc,
-    UiSoundSystemDesc, UiTransformSystemDesc, WidgetId,
+    SelectionKeyboardSystemDesc, SelectionMouseSystemDesc, SelectionNavigationSystem,
+    TextEditingInputSystemDesc, TextEditingMouseSystemDesc, ToNativeWidget,
+    UiButtonActionRetriggerSystemDesc, UiButtonSystemDesc, UiDragSystem, UiFormSystemDesc,
+    UiLayoutSystem, UiLoaderSystemDesc, UiMouseSystem, UiScrollSystemDesc,
+    UiSoundRetriggerSystemDesc, UiSoundSystemDesc, UiTheme, UiThemeSystemDesc, UiTooltipSystemDesc,
+    UiTransformSystemDesc, WidgetId,
 };
 use amethyst_assets::Processor;
 use amethyst_core::{
@@ -47,16 +49,31 @@ where
             "ui_loader",
             &[],
         );
+        builder.add(
+            UiScrollSystemDesc::<T>::default().build(world),
+            "ui_scroll_system",
+            &[],
+        );
+        builder.add(UiLayoutSystem, "ui_layout", &["ui_scroll_system"]);
         builder.add(
             UiTransformSystemDesc::default().build(world),
             "ui_transform",
-            &["transform_system"],
+            // The scrolled content and the children of layouts are moved before computing their
+            // position.
+            &["transform_system", "ui_scroll_system", "ui_layout"],
         );
         builder.add(
             Processor::<FontAsset>::new(),
             "font_processor",
             &["ui_loader"],
         );
+        builder.add(Processor::<UiTheme>::new(), "ui_theme_processor", &[]);
+        builder.add(
+            UiThemeSystemDesc::default().build(world),
+            "ui_theme_system",
+            // The labels of the styled buttons are found through the hierarchy.
+            &["ui_loader", "ui_theme_processor", "parent_hierarchy_system"],
+        );
         builder.add(
             CacheSelectionOrderSystem::<G>::new(),
             "selection_order_cache",
@@ -73,16 +90,29 @@ where
             // Because when you press tab, you want to override the previously selected elements.
             &["ui_mouse_selection"],
         );
+        builder.add(
+            SelectionNavigationSystem::<G, T>::new(),
+            "ui_navigation_selection",
+            &["ui_keyboard_selection", "ui_transform"],
+        );
         builder.add(
             TextEditingMouseSystemDesc::default().build(world),
             "ui_text_editing_mouse_system",
-            &["ui_mouse_selection", "ui_keyboard_selection"],
+            &[
+                "ui_mouse_selection",
+                "ui_keyboard_selection",
+                "ui_navigation_selection",
+            ],
         );
         builder.add(
             TextEditingInputSystemDesc::default().build(world),
             "ui_text_editing_input_system",
             // Hard requirement. The system assumes the text to edit is selected.
-            &["ui_mouse_selection", "ui_keyboard_selection"],
+            &[
+                "ui_mouse_selection",
+                "ui_keyboard_selection",
+                "ui_navigation_selection",
+            ],
         );
         builder.add(
             ResizeSystemDesc::default().build(world),
@@ -94,6 +124,25 @@ where
             "ui_mouse_system",
             &["ui_transform"],
         );
+        builder.add(
+            UiTooltipSystemDesc::<T>::default().build(world),
+            "ui_tooltip_system",
+            &["ui_mouse_system"],
+        );
+        builder.add(
+            UiDragSystem::<T>::new(),
+            "ui_drag_system",
+            &["ui_transform"],
+        );
+        builder.add(
+            UiFormSystemDesc::<T>::default().build(world),
+            "ui_form_system",
+            &[
+                "ui_mouse_system",
+                "ui_keyboard_selection",
+                "ui_navigation_selection",
+            ],
+        );
         builder.add(
             UiButtonSystemDesc::default().build(world),
             "ui_button_system",
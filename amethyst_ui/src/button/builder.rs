@@ -16,7 +16,7 @@ use crate::{
     Anchor, FontAsset, FontHandle, Interactable, Selectable, Stretch, UiButton, UiButtonAction,
     UiButtonActionRetrigger,
     UiButtonActionType::{self, *},
-    UiImage, UiPlaySoundAction, UiSoundRetrigger, UiText, UiTransform, WidgetId, Widgets,
+    UiImage, UiPlaySoundAction, UiSoundRetrigger, UiStyled, UiText, UiTransform, WidgetId, Widgets,
 };
 
 use std::marker::PhantomData;
@@ -45,6 +45,7 @@ pub struct UiButtonBuilderResources<'a, G: PartialEq + Send + Sync + 'static, I:
     sound_retrigger: WriteStorage<'a, UiSoundRetrigger>,
     button_action_retrigger: WriteStorage<'a, UiButtonActionRetrigger>,
     selectables: WriteStorage<'a, Selectable<G>>,
+    styled: WriteStorage<'a, UiStyled>,
 }
 
 /// Convenience structure for building a button
@@ -65,6 +66,7 @@ pub struct UiButtonBuilder<G, I: WidgetId> {
     font_size: f32,
     image: Option<Handle<Texture>>,
     parent: Option<Entity>,
+    style: Option<String>,
     on_click_start_sound: Option<UiPlaySoundAction>,
     on_click_stop_sound: Option<UiPlaySoundAction>,
     on_hover_sound: Option<UiPlaySoundAction>,
@@ -98,6 +100,7 @@ where
             font_size: 32.,
             image: None,
             parent: None,
+            style: None,
             on_click_start_sound: None,
             on_click_stop_sound: None,
             on_hover_sound: None,
@@ -258,6 +261,13 @@ impl<'a, G: PartialEq + Send + Sync + 'static, I: WidgetId> UiButtonBuilder<G, I
         self
     }
 
+    /// Style the button with the style named `style` of the `ActiveUiTheme`, overriding the
+    /// properties of the builder the style sets.
+    pub fn with_style<S: Into<String>>(mut self, style: S) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+
     /// Build this with the `UiButtonBuilderResources`.
     pub fn build(mut self, mut res: UiButtonBuilderResources<'a, G, I>) -> (I, UiButton) {
         let image_entity = res.entities.create();
@@ -365,6 +375,11 @@ impl<'a, G: PartialEq + Send + Sync + 'static, I: WidgetId> UiButtonBuilder<G, I
                 .insert(image_entity, Parent { entity: parent })
                 .expect("Unreachable: Inserting newly created entity");
         }
+        if let Some(style) = self.style.take() {
+            res.styled
+                .insert(image_entity, UiStyled::new(style))
+                .expect("Unreachable: Inserting newly created entity");
+        }
 
         res.transform
             .insert(
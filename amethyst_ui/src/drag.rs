@@ -0,0 +1,385 @@
+//! Drag and drop of ui elements.
+
+use std::marker::PhantomData;
+
+use amethyst_core::{
+    ecs::prelude::{
+        Component, DenseVecStorage, Entities, Entity, Join, Read, ReadExpect, ReadStorage, System,
+        Write, WriteStorage,
+    },
+    math::Vector2,
+    shrev::EventChannel,
+    Hidden, HiddenPropagate, Parent, ParentHierarchy,
+};
+use amethyst_input::{BindingTypes, InputHandler};
+use amethyst_window::ScreenDimensions;
+use serde::{Deserialize, Serialize};
+use winit::MouseButton;
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use crate::{
+    modal::{active_modal, is_inside},
+    targeted, Interactable, ScaleMode, UiEvent, UiEventType, UiModal, UiTransform,
+};
+
+/// The axes along which a `Draggable` element can move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DragAxis {
+    /// The element follows the pointer freely.
+    Both,
+    /// The element only moves left and right.
+    Horizontal,
+    /// The element only moves up and down.
+    Vertical,
+}
+
+impl Default for DragAxis {
+    fn default() -> Self {
+        DragAxis::Both
+    }
+}
+
+/// A component that allows the user to move a ui element by holding the pointer over it.
+///
+/// The entity also needs to be `Interactable` for the drag to start. While it is moved,
+/// `UiEventType::Dragging` events are emitted for it. When it is released over another
+/// interactable element, a `UiEventType::Dropped` event is emitted for the dragged entity.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Draggable {
+    /// Restricts the movement to a single axis.
+    pub axis: DragAxis,
+    /// Keeps the element inside of its parent, or inside of the screen if it has no parent.
+    pub bounded_by_parent: bool,
+}
+
+impl Draggable {
+    /// Creates a `Draggable` moving freely.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Restricts the movement to the given axis.
+    pub fn with_axis(mut self, axis: DragAxis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    /// Keeps the element inside of its parent, or inside of the screen if it has no parent.
+    pub fn bounded(mut self) -> Self {
+        self.bounded_by_parent = true;
+        self
+    }
+
+    /// Returns where the center of an element of the given size should go when dragged from
+    /// `center` towards `target`.
+    /// `bounds` is the (left, bottom, right, top) rectangle the element may be kept in.
+    fn constrain(
+        &self,
+        center: (f32, f32),
+        target: (f32, f32),
+        size: (f32, f32),
+        bounds: (f32, f32, f32, f32),
+    ) -> (f32, f32) {
+        let mut x = if self.axis == DragAxis::Vertical {
+            center.0
+        } else {
+            target.0
+        };
+        let mut y = if self.axis == DragAxis::Horizontal {
+            center.1
+        } else {
+            target.1
+        };
+        if self.bounded_by_parent {
+            let (left, bottom, right, top) = bounds;
+            x = x.max(left + size.0 / 2.0).min(right - size.0 / 2.0);
+            y = y.max(bottom + size.1 / 2.0).min(top - size.1 / 2.0);
+        }
+        (x, y)
+    }
+}
+
+impl Component for Draggable {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[derive(Debug)]
+struct DragState {
+    entity: Entity,
+    element_offset: Vector2<f32>,
+    last_position: (f32, f32),
+}
+
+/// The system moving `Draggable` entities and generating the drag and drop events.
+/// The generic type T represent the T generic parameter of the InputHandler<T>.
+///
+/// Like the `UiMouseSystem`, the primary touch is treated like a mouse pointer holding
+/// the left button.
+#[derive(Debug)]
+pub struct UiDragSystem<T: BindingTypes> {
+    was_down: bool,
+    dragging: Option<DragState>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: BindingTypes> UiDragSystem<T> {
+    /// Creates a new UiDragSystem.
+    pub fn new() -> Self {
+        UiDragSystem {
+            was_down: false,
+            dragging: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: BindingTypes> System<'a> for UiDragSystem<T> {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, UiTransform>,
+        ReadStorage<'a, Draggable>,
+        ReadStorage<'a, Interactable>,
+        ReadStorage<'a, Parent>,
+        ReadStorage<'a, UiModal>,
+        ReadStorage<'a, Hidden>,
+        ReadStorage<'a, HiddenPropagate>,
+        ReadExpect<'a, ParentHierarchy>,
+        Read<'a, InputHandler<T>>,
+        ReadExpect<'a, ScreenDimensions>,
+        Write<'a, EventChannel<UiEvent>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut transforms,
+            draggables,
+            interactables,
+            parents,
+            modals,
+            hiddens,
+            hidden_propagates,
+            hierarchy,
+            input,
+            screen_dimensions,
+            mut events,
+        ): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("ui_drag_system");
+
+        let touch_position = input.primary_touch().map(|touch| touch.position);
+        let down = input.mouse_button_is_down(MouseButton::Left) || touch_position.is_some();
+        let pointer_position = touch_position
+            .or_else(|| input.mouse_position())
+            .map(|(x, y)| (x as f32, screen_dimensions.height() - y as f32));
+
+        if down && !self.was_down {
+            if let Some(pos) = pointer_position {
+                // Elements behind the active modal element can't be dragged.
+                let modal = active_modal(
+                    &entities,
+                    &modals,
+                    &transforms,
+                    &hiddens,
+                    &hidden_propagates,
+                );
+                let target = targeted(pos, (&*entities, &transforms, interactables.maybe()).join())
+                    .filter(|target| draggables.contains(*target))
+                    .filter(|target| {
+                        modal.map_or(true, |modal| is_inside(*target, modal, &parents))
+                    });
+                if let Some(entity) = target {
+                    let transform = transforms
+                        .get(entity)
+                        .expect("Targeted entities have a UiTransform");
+                    self.dragging = Some(DragState {
+                        entity,
+                        element_offset: Vector2::new(
+                            pos.0 - transform.pixel_x,
+                            pos.1 - transform.pixel_y,
+                        ),
+                        last_position: pos,
+                    });
+                }
+            }
+        } else if down {
+            if let (Some(state), Some(pos)) = (self.dragging.as_mut(), pointer_position) {
+                if pos != state.last_position {
+                    state.last_position = pos;
+                    if let Some(draggable) = draggables.get(state.entity) {
+                        let bounds = parents
+                            .get(state.entity)
+                            .and_then(|parent| transforms.get(parent.entity))
+                            .map(|parent| {
+                                (
+                                    parent.pixel_x - parent.pixel_width / 2.0,
+                                    parent.pixel_y - parent.pixel_height / 2.0,
+                                    parent.pixel_x + parent.pixel_width / 2.0,
+                                    parent.pixel_y + parent.pixel_height / 2.0,
+                                )
+                            })
+                            .unwrap_or((
+                                0.0,
+                                0.0,
+                                screen_dimensions.width(),
+                                screen_dimensions.height(),
+                            ));
+                        if let Some(transform) = transforms.get_mut(state.entity) {
+                            drag_to(transform, draggable, state, bounds);
+                            events.single_write(UiEvent::new(
+                                UiEventType::Dragging {
+                                    element_offset: state.element_offset,
+                                },
+                                state.entity,
+                            ));
+                        }
+                    }
+                }
+            }
+        } else if let Some(state) = self.dragging.take() {
+            // A lifted finger has no position anymore, use the last one.
+            let pos = pointer_position.unwrap_or(state.last_position);
+            let dragged = hierarchy.all_children(state.entity);
+            let target = targeted(
+                pos,
+                (&*entities, &transforms, interactables.maybe())
+                    .join()
+                    .filter(|(entity, _, _)| {
+                        *entity != state.entity && !dragged.contains(entity.id())
+                    }),
+            );
+            if let Some(dropped_on) = target {
+                events.single_write(UiEvent::new(
+                    UiEventType::Dropped { dropped_on },
+                    state.entity,
+                ));
+            }
+        }
+
+        self.was_down = down;
+    }
+}
+
+/// Moves the dragged element under the pointer, `bounds` being the (left, bottom, right, top)
+/// rectangle of its parent.
+fn drag_to(
+    transform: &mut UiTransform,
+    draggable: &Draggable,
+    state: &DragState,
+    bounds: (f32, f32, f32, f32),
+) {
+    let center = (transform.pixel_x, transform.pixel_y);
+    let (x, y) = draggable.constrain(
+        center,
+        (
+            state.last_position.0 - state.element_offset.x,
+            state.last_position.1 - state.element_offset.y,
+        ),
+        (transform.pixel_width, transform.pixel_height),
+        bounds,
+    );
+    match transform.scale_mode {
+        ScaleMode::Pixel => {
+            transform.local_x += x - center.0;
+            transform.local_y += y - center.1;
+        }
+        ScaleMode::Percent => {
+            // A collapsed parent has no percent to move by
+            let (width, height) = (bounds.2 - bounds.0, bounds.3 - bounds.1);
+            if width > 0.0 {
+                transform.local_x += (x - center.0) / width;
+            }
+            if height > 0.0 {
+                transform.local_y += (y - center.1) / height;
+            }
+        }
+    }
+    // The `UiTransformSystem` only catches up next frame, keep the element under the pointer
+    // in the meantime.
+    transform.pixel_x = x;
+    transform.pixel_y = y;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Anchor;
+    use amethyst_core::ecs::prelude::{Builder, World, WorldExt};
+
+    const BOUNDS: (f32, f32, f32, f32) = (0.0, 0.0, 100.0, 50.0);
+
+    #[test]
+    fn axis_lock() {
+        let draggable = Draggable::new().with_axis(DragAxis::Horizontal);
+        assert_eq!(
+            draggable.constrain((10.0, 10.0), (30.0, 40.0), (4.0, 4.0), BOUNDS),
+            (30.0, 10.0)
+        );
+        let draggable = Draggable::new().with_axis(DragAxis::Vertical);
+        assert_eq!(
+            draggable.constrain((10.0, 10.0), (30.0, 40.0), (4.0, 4.0), BOUNDS),
+            (10.0, 40.0)
+        );
+        let draggable = Draggable::new();
+        assert_eq!(
+            draggable.constrain((10.0, 10.0), (30.0, 40.0), (4.0, 4.0), BOUNDS),
+            (30.0, 40.0)
+        );
+    }
+
+    #[test]
+    fn bounded_by_parent() {
+        let draggable = Draggable::new();
+        assert_eq!(
+            draggable.constrain((10.0, 10.0), (-30.0, 80.0), (10.0, 20.0), BOUNDS),
+            (-30.0, 80.0)
+        );
+        let draggable = draggable.bounded();
+        assert_eq!(
+            draggable.constrain((10.0, 10.0), (-30.0, 80.0), (10.0, 20.0), BOUNDS),
+            (5.0, 40.0)
+        );
+        assert_eq!(
+            draggable.constrain((10.0, 10.0), (120.0, -5.0), (10.0, 20.0), BOUNDS),
+            (95.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn percent_drag_in_collapsed_parent() {
+        let mut world = World::new();
+        let state = DragState {
+            entity: world.create_entity().build(),
+            element_offset: Vector2::new(0.0, 0.0),
+            last_position: (30.0, 40.0),
+        };
+        let mut transform = UiTransform::new(
+            String::from("drag"),
+            Anchor::Middle,
+            Anchor::Middle,
+            0.1,
+            0.2,
+            0.0,
+            0.0,
+            0.0,
+        )
+        .into_percent();
+        transform.pixel_x = 10.0;
+        transform.pixel_y = 10.0;
+
+        drag_to(
+            &mut transform,
+            &Draggable::new(),
+            &state,
+            (0.0, 0.0, 100.0, 0.0),
+        );
+        assert!((transform.local_x - 0.3).abs() < 1.0e-5);
+        assert!((transform.local_y - 0.2).abs() < 1.0e-5);
+        assert_eq!((transform.pixel_x, transform.pixel_y), (30.0, 40.0));
+    }
+}
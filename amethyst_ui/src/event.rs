@@ -1,4 +1,9 @@
-use crate::transform::UiTransform;
+use crate::{
+    markup::UiRichText,
+    modal::{active_modal, is_inside, UiModal},
+    scroll::{clip_rect, UiScrollView},
+    transform::UiTransform,
+};
 use amethyst_core::{
     ecs::{
         prelude::{
@@ -8,6 +13,7 @@ use amethyst_core::{
     },
     math::Vector2,
     shrev::EventChannel,
+    Hidden, HiddenPropagate, Parent,
 };
 use amethyst_input::{BindingTypes, InputHandler};
 use amethyst_window::ScreenDimensions;
@@ -46,14 +52,21 @@ pub enum UiEventType {
         /// The entity on which the dragged object was dropped.
         dropped_on: Entity,
     },
-    /// When the value of a UiText element has been changed by user input.
+    /// When the value of a UiText element or of a form widget has been changed by user input.
     ValueChange,
-    /// When the value of a UiText element has been committed by user action.
+    /// When the value of a UiText element or of a `UiSlider` has been committed by user action.
     ValueCommit,
     /// When an editable UiText element has gained focus.
     Focus,
     /// When an editable UiText element has lost focus.
     Blur,
+    /// When a link of a `UiRichText` element is clicked, after the `Click` event.
+    LinkClick {
+        /// The target of the link, as written in the markup.
+        link: String,
+    },
+    /// When a click starts outside of the active `UiModal` element, targeting it.
+    ClickOutside,
 }
 
 /// A ui event instance.
@@ -90,11 +103,16 @@ impl Component for Interactable {
 
 /// The system that generates events for `Interactable` enabled entities.
 /// The generic types A and B represent the A and B generic parameter of the InputHandler<A,B>.
+///
+/// The primary touch of the `InputHandler` is treated like a mouse pointer holding
+/// the left button.
 #[derive(Default, Debug)]
 pub struct UiMouseSystem<T: BindingTypes> {
     was_down: bool,
     click_started_on: Option<Entity>,
     last_target: Option<Entity>,
+    /// Position of the primary touch last frame, used to locate the click when the finger is lifted.
+    last_touch_position: Option<(f32, f32)>,
     _marker: PhantomData<T>,
 }
 
@@ -105,6 +123,7 @@ impl<T: BindingTypes> UiMouseSystem<T> {
             was_down: false,
             click_started_on: None,
             last_target: None,
+            last_touch_position: None,
             _marker: PhantomData,
         }
     }
@@ -115,6 +134,12 @@ impl<'a, T: BindingTypes> System<'a> for UiMouseSystem<T> {
         Entities<'a>,
         ReadStorage<'a, UiTransform>,
         ReadStorage<'a, Interactable>,
+        ReadStorage<'a, Parent>,
+        ReadStorage<'a, UiScrollView>,
+        ReadStorage<'a, Hidden>,
+        ReadStorage<'a, HiddenPropagate>,
+        ReadStorage<'a, UiRichText>,
+        ReadStorage<'a, UiModal>,
         Read<'a, InputHandler<T>>,
         ReadExpect<'a, ScreenDimensions>,
         Write<'a, EventChannel<UiEvent>>,
@@ -122,19 +147,64 @@ impl<'a, T: BindingTypes> System<'a> for UiMouseSystem<T> {
 
     fn run(
         &mut self,
-        (entities, transform, react, input, screen_dimensions, mut events): Self::SystemData,
+        (
+            entities,
+            transform,
+            react,
+            parents,
+            scroll_views,
+            hiddens,
+            hidden_props,
+            rich_texts,
+            modals,
+            input,
+            screen_dimensions,
+            mut events,
+        ): Self::SystemData,
     ) {
-        let down = input.mouse_button_is_down(MouseButton::Left);
+        let touch_position = input.primary_touch().map(|touch| touch.position);
+        let down = input.mouse_button_is_down(MouseButton::Left) || touch_position.is_some();
 
         // TODO: To replace on InputHandler generate OnMouseDown and OnMouseUp events
         let click_started = down && !self.was_down;
         let click_stopped = !down && self.was_down;
 
-        if let Some((pos_x, pos_y)) = input.mouse_position() {
+        let pointer_position = touch_position
+            .or_else(|| self.last_touch_position.take())
+            .or_else(|| input.mouse_position());
+        self.last_touch_position = touch_position;
+
+        if let Some((pos_x, pos_y)) = pointer_position {
             let x = pos_x as f32;
             let y = screen_dimensions.height() - pos_y as f32;
 
-            let target = targeted((x, y), (&*entities, &transform, react.maybe()).join());
+            let modal = active_modal(&entities, &modals, &transform, &hiddens, &hidden_props);
+            if let Some(modal) = modal {
+                let inside = transform
+                    .get(modal)
+                    .map_or(false, |t| t.position_inside(x, y));
+                if click_started && !inside {
+                    events.single_write(UiEvent::new(UiEventType::ClickOutside, modal));
+                }
+            }
+
+            // Hidden elements, parts of scroll views' content outside of their viewport and
+            // elements behind the active modal element can't be clicked.
+            let visible = (
+                &*entities,
+                &transform,
+                react.maybe(),
+                !&hiddens,
+                !&hidden_props,
+            )
+                .join()
+                .map(|(entity, t, m, _, _)| (entity, t, m))
+                .filter(|(entity, _, _)| {
+                    modal.map_or(true, |modal| is_inside(*entity, modal, &parents))
+                        && clip_rect(*entity, &transform, &parents, &scroll_views)
+                            .map_or(true, |clip| clip.contains(x, y))
+                });
+            let target = targeted((x, y), visible);
             if target != self.last_target {
                 if let Some(last_target) = self.last_target {
                     events.single_write(UiEvent::new(UiEventType::HoverStop, last_target));
@@ -152,6 +222,12 @@ impl<'a, T: BindingTypes> System<'a> for UiMouseSystem<T> {
                     if let Some(e2) = self.click_started_on {
                         if e2 == e {
                             events.single_write(UiEvent::new(UiEventType::Click, e2));
+                            let link = rich_texts.get(e).and_then(|rich| rich.link_at(x, y));
+                            if let Some(link) = link {
+                                let link = link.to_string();
+                                events
+                                    .single_write(UiEvent::new(UiEventType::LinkClick { link }, e));
+                            }
                         }
                     }
                 }
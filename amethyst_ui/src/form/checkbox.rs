@@ -0,0 +1,672 @@
+use amethyst_assets::{AssetStorage, Loader};
+use amethyst_core::{
+    ecs::{
+        prelude::{
+            Component, DenseVecStorage, Entities, Entity, Read, ReadExpect, World, Write,
+            WriteStorage,
+        },
+        shred::{ResourceId, SystemData},
+    },
+    HiddenPropagate, Parent,
+};
+
+use crate::{
+    define_widget, font::default::get_default_font, Anchor, FontAsset, FontHandle, Interactable,
+    Selectable, UiImage, UiLayout, UiText, UiTransform, WidgetId, Widgets,
+};
+
+use std::marker::PhantomData;
+
+const DEFAULT_Z: f32 = 1.0;
+const DEFAULT_SIZE: f32 = 24.0;
+const DEFAULT_LABEL_WIDTH: f32 = 128.0;
+const DEFAULT_SPACING: f32 = 8.0;
+const DEFAULT_TAB_ORDER: u32 = 9;
+const DEFAULT_BOX_COLOR: [f32; 4] = [0.82, 0.83, 0.83, 1.0];
+const DEFAULT_CHECK_COLOR: [f32; 4] = [0.2, 0.2, 0.2, 1.0];
+const DEFAULT_TXT_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+/// A box the user can check and uncheck by clicking it, or by pressing space or enter while
+/// it is `Selected`.
+///
+/// The check mark is hidden by the `UiFormSystem` while the box is unchecked. Every time the
+/// user toggles the box, a `UiEventType::ValueChange` is emitted for it. Clicking a child of the
+/// box, like its label, toggles it too.
+#[derive(Debug, Clone)]
+pub struct UiCheckbox {
+    /// Whether the box is checked.
+    pub checked: bool,
+    /// The entity shown while the box is checked.
+    pub check: Entity,
+}
+
+impl UiCheckbox {
+    /// Creates an unchecked box.
+    pub fn new(check: Entity) -> Self {
+        UiCheckbox {
+            checked: false,
+            check,
+        }
+    }
+
+    /// Sets the initial state of the box.
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// Checks the box if it was unchecked and the other way around.
+    pub fn toggle(&mut self) {
+        self.checked = !self.checked;
+    }
+}
+
+impl Component for UiCheckbox {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Turns a list of `UiCheckbox`es into radio buttons: exactly one of them is checked at a time.
+///
+/// The checked state of the options is driven by the group. Clicking an option selects it,
+/// the arrow keys select the previous or next option while one of them is `Selected`.
+/// A `UiEventType::ValueChange` is emitted for the group entity when the user changes its
+/// selection.
+#[derive(Debug, Clone)]
+pub struct UiToggleGroup {
+    /// The checkbox entities, in order.
+    pub options: Vec<Entity>,
+    /// The index of the checked option, if any.
+    pub selected: Option<usize>,
+}
+
+impl UiToggleGroup {
+    /// Creates a group of options without selection.
+    pub fn new(options: Vec<Entity>) -> Self {
+        UiToggleGroup {
+            options,
+            selected: None,
+        }
+    }
+
+    /// Sets the initially checked option.
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.select(index);
+        self
+    }
+
+    /// Checks the option at `index`.
+    /// Returns `true` if the selection changed.
+    pub fn select(&mut self, index: usize) -> bool {
+        if index >= self.options.len() || self.selected == Some(index) {
+            return false;
+        }
+        self.selected = Some(index);
+        true
+    }
+
+    /// The index of an option entity in the group.
+    pub fn position(&self, option: Entity) -> Option<usize> {
+        self.options.iter().position(|entity| *entity == option)
+    }
+
+    /// The entity of the checked option, if any.
+    pub fn selected_option(&self) -> Option<Entity> {
+        self.selected
+            .and_then(|index| self.options.get(index).cloned())
+    }
+}
+
+impl Component for UiToggleGroup {
+    type Storage = DenseVecStorage<Self>;
+}
+
+define_widget!(UiCheckboxWidget =>
+    entities: [box_entity, check_entity, text_entity]
+    components: [
+        (has UiTransform as position on box_entity),
+        (has UiCheckbox as checkbox on box_entity),
+        (has UiImage as box_image on box_entity),
+        (has UiImage as check_image on check_entity),
+        (has UiText as text on text_entity),
+
+        (maybe_has Parent as parent on box_entity)
+    ]
+);
+
+define_widget!(UiToggleGroupWidget =>
+    entities: [group_entity]
+    components: [
+        (has UiTransform as position on group_entity),
+        (has UiToggleGroup as group on group_entity),
+
+        (maybe_has UiLayout as layout on group_entity),
+        (maybe_has Parent as parent on group_entity)
+    ]
+);
+
+/// Container for all the resources the builder needs to make a new checkbox.
+#[derive(SystemData)]
+#[allow(missing_debug_implementations)]
+pub struct UiCheckboxBuilderResources<'a, G: PartialEq + Send + Sync + 'static, I: WidgetId = u32> {
+    font_asset: Read<'a, AssetStorage<FontAsset>>,
+    loader: ReadExpect<'a, Loader>,
+    entities: Entities<'a>,
+    image: WriteStorage<'a, UiImage>,
+    mouse_reactive: WriteStorage<'a, Interactable>,
+    parent: WriteStorage<'a, Parent>,
+    hidden: WriteStorage<'a, HiddenPropagate>,
+    text: WriteStorage<'a, UiText>,
+    transform: WriteStorage<'a, UiTransform>,
+    checkbox: WriteStorage<'a, UiCheckbox>,
+    checkbox_widgets: Write<'a, Widgets<UiCheckboxWidget, I>>,
+    selectables: WriteStorage<'a, Selectable<G>>,
+}
+
+/// Convenience structure for building a checkbox followed by its label
+#[derive(Debug, Clone)]
+pub struct UiCheckboxBuilder<G, I: WidgetId> {
+    id: Option<I>,
+    x: f32,
+    y: f32,
+    z: f32,
+    size: f32,
+    label_width: f32,
+    tab_order: u32,
+    anchor: Anchor,
+    text: String,
+    text_color: [f32; 4],
+    font: Option<FontHandle>,
+    font_size: f32,
+    checked: bool,
+    box_image: Option<UiImage>,
+    check_image: Option<UiImage>,
+    parent: Option<Entity>,
+    _phantom: PhantomData<G>,
+}
+
+impl<'a, G: PartialEq + Send + Sync + 'static, I: WidgetId> UiCheckboxBuilder<G, I> {
+    /// Construct a new UiCheckboxBuilder with the given label.
+    pub fn new<S: ToString>(text: S) -> UiCheckboxBuilder<G, I> {
+        UiCheckboxBuilder {
+            id: None,
+            x: 0.,
+            y: 0.,
+            z: DEFAULT_Z,
+            size: DEFAULT_SIZE,
+            label_width: DEFAULT_LABEL_WIDTH,
+            tab_order: DEFAULT_TAB_ORDER,
+            anchor: Anchor::TopLeft,
+            text: text.to_string(),
+            text_color: DEFAULT_TXT_COLOR,
+            font: None,
+            font_size: 20.,
+            checked: false,
+            box_image: None,
+            check_image: None,
+            parent: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets an ID for this widget. The type of this ID will determine which `Widgets`
+    /// resource this widget will be added to, see [`Widgets`](../../struct.Widgets.html).
+    pub fn with_id(mut self, id: I) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Add a parent to the checkbox.
+    pub fn with_parent(mut self, parent: Entity) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Add an anchor to the checkbox.
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Provide an X and Y position for the box.
+    pub fn with_position(mut self, x: f32, y: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Provide a Z position, i.e UI layer
+    pub fn with_layer(mut self, z: f32) -> Self {
+        self.z = z;
+        self
+    }
+
+    /// Set the width and height of the box
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the width of the label following the box
+    pub fn with_label_width(mut self, width: f32) -> Self {
+        self.label_width = width;
+        self
+    }
+
+    /// Set checkbox tab order
+    pub fn with_tab_order(mut self, tab_order: u32) -> Self {
+        self.tab_order = tab_order;
+        self
+    }
+
+    /// Use a different font for the label.
+    pub fn with_font(mut self, font: FontHandle) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Set font size
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Set text color
+    pub fn with_text_color(mut self, text_color: [f32; 4]) -> Self {
+        self.text_color = text_color;
+        self
+    }
+
+    /// Set the initial state of the box
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// Replace the default box image with `image`.
+    pub fn with_box_image(mut self, image: UiImage) -> Self {
+        self.box_image = Some(image);
+        self
+    }
+
+    /// Replace the default check mark image with `image`.
+    pub fn with_check_image(mut self, image: UiImage) -> Self {
+        self.check_image = Some(image);
+        self
+    }
+
+    /// Adds the components of the checkbox to the entities of `widget`.
+    ///
+    /// The options of a toggle group consume the arrow keys, which step through the group.
+    fn build_entities(
+        self,
+        id: String,
+        widget: &UiCheckboxWidget,
+        in_group: bool,
+        res: &mut UiCheckboxBuilderResources<'a, G, I>,
+    ) {
+        let UiCheckboxWidget {
+            box_entity,
+            check_entity,
+            text_entity,
+        } = *widget;
+
+        res.transform
+            .insert(
+                box_entity,
+                UiTransform::new(
+                    format!("{}_checkbox", id),
+                    self.anchor,
+                    Anchor::Middle,
+                    self.x,
+                    self.y,
+                    self.z,
+                    self.size,
+                    self.size,
+                ),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.image
+            .insert(
+                box_entity,
+                self.box_image
+                    .unwrap_or(UiImage::SolidColor(DEFAULT_BOX_COLOR)),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.mouse_reactive
+            .insert(box_entity, Interactable)
+            .expect("Unreachable: Inserting newly created entity");
+        let mut selectable = Selectable::<G>::new(self.tab_order);
+        selectable.consumes_inputs = in_group;
+        res.selectables
+            .insert(box_entity, selectable)
+            .expect("Unreachable: Inserting newly created entity");
+        res.checkbox
+            .insert(
+                box_entity,
+                UiCheckbox::new(check_entity).with_checked(self.checked),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        if let Some(parent) = self.parent {
+            res.parent
+                .insert(box_entity, Parent { entity: parent })
+                .expect("Unreachable: Inserting newly created entity");
+        }
+
+        res.transform
+            .insert(
+                check_entity,
+                UiTransform::new(
+                    format!("{}_checkbox_check", id),
+                    Anchor::Middle,
+                    Anchor::Middle,
+                    0.,
+                    0.,
+                    0.01,
+                    self.size / 2.,
+                    self.size / 2.,
+                )
+                .into_transparent(),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.image
+            .insert(
+                check_entity,
+                self.check_image
+                    .unwrap_or(UiImage::SolidColor(DEFAULT_CHECK_COLOR)),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        if !self.checked {
+            res.hidden
+                .insert(check_entity, HiddenPropagate)
+                .expect("Unreachable: Inserting newly created entity");
+        }
+        res.parent
+            .insert(check_entity, Parent { entity: box_entity })
+            .expect("Unreachable: Inserting newly created entity");
+
+        // The label is interactable so that clicking it toggles the box too.
+        res.transform
+            .insert(
+                text_entity,
+                UiTransform::new(
+                    format!("{}_checkbox_text", id),
+                    Anchor::MiddleRight,
+                    Anchor::MiddleLeft,
+                    DEFAULT_SPACING,
+                    0.,
+                    0.01,
+                    self.label_width,
+                    self.size,
+                ),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.mouse_reactive
+            .insert(text_entity, Interactable)
+            .expect("Unreachable: Inserting newly created entity");
+        let font_handle = self
+            .font
+            .unwrap_or_else(|| get_default_font(&res.loader, &res.font_asset));
+        let mut text = UiText::new(font_handle, self.text, self.text_color, self.font_size);
+        text.align = Anchor::MiddleLeft;
+        res.text
+            .insert(text_entity, text)
+            .expect("Unreachable: Inserting newly created entity");
+        res.parent
+            .insert(text_entity, Parent { entity: box_entity })
+            .expect("Unreachable: Inserting newly created entity");
+    }
+
+    /// Build this with the `UiCheckboxBuilderResources`.
+    pub fn build(mut self, mut res: UiCheckboxBuilderResources<'a, G, I>) -> (I, UiCheckboxWidget) {
+        let widget = UiCheckboxWidget::new(
+            res.entities.create(),
+            res.entities.create(),
+            res.entities.create(),
+        );
+
+        let id = {
+            let widget = widget.clone();
+
+            if let Some(id) = self.id.take() {
+                let added_id = id.clone();
+                res.checkbox_widgets.add_with_id(id, widget);
+                added_id
+            } else {
+                res.checkbox_widgets.add(widget)
+            }
+        };
+
+        self.build_entities(id.to_string(), &widget, false, &mut res);
+        (id, widget)
+    }
+
+    /// Create the checkbox based on provided configuration parameters.
+    pub fn build_from_world(self, world: &World) -> (I, UiCheckboxWidget) {
+        self.build(UiCheckboxBuilderResources::<G, I>::fetch(&world))
+    }
+}
+
+/// Container for all the resources the builder needs to make a new toggle group.
+#[derive(SystemData)]
+#[allow(missing_debug_implementations)]
+pub struct UiToggleGroupBuilderResources<
+    'a,
+    G: PartialEq + Send + Sync + 'static,
+    I: WidgetId = u32,
+> {
+    checkboxes: UiCheckboxBuilderResources<'a, G, I>,
+    group: WriteStorage<'a, UiToggleGroup>,
+    group_widgets: Write<'a, Widgets<UiToggleGroupWidget, I>>,
+}
+
+/// Convenience structure for building a group of options stacked vertically, of which only one
+/// can be checked at a time
+#[derive(Debug, Clone)]
+pub struct UiToggleGroupBuilder<G, I: WidgetId> {
+    id: Option<I>,
+    x: f32,
+    y: f32,
+    z: f32,
+    width: f32,
+    tab_order: u32,
+    anchor: Anchor,
+    spacing: f32,
+    selected: Option<usize>,
+    parent: Option<Entity>,
+    option: UiCheckboxBuilder<G, I>,
+    options: Vec<String>,
+}
+
+impl<'a, G: PartialEq + Send + Sync + 'static, I: WidgetId> UiToggleGroupBuilder<G, I> {
+    /// Construct a new UiToggleGroupBuilder with one option per label.
+    pub fn new<S: ToString>(options: &[S]) -> UiToggleGroupBuilder<G, I> {
+        UiToggleGroupBuilder {
+            id: None,
+            x: 0.,
+            y: 0.,
+            z: DEFAULT_Z,
+            width: DEFAULT_SIZE + DEFAULT_SPACING + DEFAULT_LABEL_WIDTH,
+            tab_order: DEFAULT_TAB_ORDER,
+            anchor: Anchor::TopLeft,
+            spacing: DEFAULT_SPACING,
+            selected: None,
+            parent: None,
+            option: UiCheckboxBuilder::new(""),
+            options: options.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    /// Sets an ID for this widget. The type of this ID will determine which `Widgets`
+    /// resource this widget will be added to, see [`Widgets`](../../struct.Widgets.html).
+    pub fn with_id(mut self, id: I) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Add a parent to the group.
+    pub fn with_parent(mut self, parent: Entity) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Add an anchor to the group.
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Provide an X and Y position for the group.
+    pub fn with_position(mut self, x: f32, y: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Provide a Z position, i.e UI layer
+    pub fn with_layer(mut self, z: f32) -> Self {
+        self.z = z;
+        self
+    }
+
+    /// Set the width of the group
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the tab order of the first option, the next ones follow it.
+    pub fn with_tab_order(mut self, tab_order: u32) -> Self {
+        self.tab_order = tab_order;
+        self
+    }
+
+    /// Set the space between two options
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Set the initially checked option
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.selected = Some(index);
+        self
+    }
+
+    /// Configure the look of the options, the label and position of `option` are ignored.
+    pub fn with_option_style(mut self, option: UiCheckboxBuilder<G, I>) -> Self {
+        self.option = option;
+        self
+    }
+
+    /// Build this with the `UiToggleGroupBuilderResources`.
+    pub fn build(
+        mut self,
+        mut res: UiToggleGroupBuilderResources<'a, G, I>,
+    ) -> (I, UiToggleGroupWidget) {
+        let group_entity = res.checkboxes.entities.create();
+        let widget = UiToggleGroupWidget::new(group_entity);
+
+        let id = {
+            let widget = widget.clone();
+
+            if let Some(id) = self.id.take() {
+                let added_id = id.clone();
+                res.group_widgets.add_with_id(id, widget);
+                added_id
+            } else {
+                res.group_widgets.add(widget)
+            }
+        };
+
+        let option_size = self.option.size;
+        let count = self.options.len() as f32;
+        let height = count * option_size + (count - 1.).max(0.) * self.spacing;
+        res.checkboxes
+            .transform
+            .insert(
+                group_entity,
+                UiTransform::new(
+                    format!("{}_toggle_group", id),
+                    self.anchor,
+                    Anchor::Middle,
+                    self.x,
+                    self.y,
+                    self.z,
+                    self.width,
+                    height,
+                )
+                .into_transparent(),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        if let Some(parent) = self.parent.take() {
+            res.checkboxes
+                .parent
+                .insert(group_entity, Parent { entity: parent })
+                .expect("Unreachable: Inserting newly created entity");
+        }
+
+        // Each option is a row with the box at its left.
+        let label_width = self.width - option_size - DEFAULT_SPACING;
+        let options = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(index, text)| {
+                let mut option = self.option.clone();
+                option.text = text.clone();
+                option.anchor = Anchor::TopLeft;
+                option.x = option_size / 2.;
+                option.y = -option_size / 2. - index as f32 * (option_size + self.spacing);
+                option.label_width = label_width;
+                option.z = 0.01;
+                option.tab_order = self.tab_order + index as u32;
+                option.parent = Some(group_entity);
+                let widget = UiCheckboxWidget::new(
+                    res.checkboxes.entities.create(),
+                    res.checkboxes.entities.create(),
+                    res.checkboxes.entities.create(),
+                );
+                option.build_entities(
+                    format!("{}_{}", id, index),
+                    &widget,
+                    true,
+                    &mut res.checkboxes,
+                );
+                widget.box_entity
+            })
+            .collect();
+        let mut group = UiToggleGroup::new(options);
+        if let Some(selected) = self.selected {
+            group.select(selected);
+        }
+        res.group
+            .insert(group_entity, group)
+            .expect("Unreachable: Inserting newly created entity");
+
+        (id, widget)
+    }
+
+    /// Create the toggle group based on provided configuration parameters.
+    pub fn build_from_world(self, world: &World) -> (I, UiToggleGroupWidget) {
+        self.build(UiToggleGroupBuilderResources::<G, I>::fetch(&world))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst_core::ecs::{Builder, WorldExt};
+
+    #[test]
+    fn toggle_group_selection() {
+        let mut world = World::new();
+        let options: Vec<_> = (0..3).map(|_| world.create_entity().build()).collect();
+        let mut group = UiToggleGroup::new(options.clone());
+        assert_eq!(group.selected_option(), None);
+        assert!(group.select(1));
+        assert!(!group.select(1));
+        assert!(!group.select(3));
+        assert_eq!(group.selected_option(), Some(options[1]));
+        assert_eq!(group.position(options[2]), Some(2));
+    }
+}
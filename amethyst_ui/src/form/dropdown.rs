@@ -0,0 +1,484 @@
+use amethyst_assets::{AssetStorage, Loader};
+use amethyst_core::{
+    ecs::{
+        prelude::{
+            Component, DenseVecStorage, Entities, Entity, Read, ReadExpect, World, Write,
+            WriteStorage,
+        },
+        shred::{ResourceId, SystemData},
+    },
+    HiddenPropagate, Parent,
+};
+
+use crate::{
+    define_widget, font::default::get_default_font, Anchor, Arrangement, FontAsset, FontHandle,
+    Interactable, Selectable, Stretch, UiImage, UiLayout, UiText, UiTransform, WidgetId, Widgets,
+};
+
+use std::marker::PhantomData;
+
+const DEFAULT_Z: f32 = 1.0;
+const DEFAULT_WIDTH: f32 = 128.0;
+const DEFAULT_HEIGHT: f32 = 32.0;
+const DEFAULT_TAB_ORDER: u32 = 9;
+/// Keeps the opened list above the widgets following the dropdown.
+const LIST_Z: f32 = 10.0;
+const DEFAULT_BKGD_COLOR: [f32; 4] = [0.82, 0.83, 0.83, 1.0];
+const DEFAULT_LIST_COLOR: [f32; 4] = [0.92, 0.92, 0.92, 1.0];
+const DEFAULT_TXT_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+/// A button showing the selected option of a list, which opens when the button is clicked.
+///
+/// The `UiFormSystem` writes the selected option into the `text` entity, and hides the `list`
+/// entity while the dropdown is closed. The options of the list are the entities having a
+/// `UiDropdownItem` pointing at it. Clicking one of them selects it and closes the list,
+/// clicking anywhere else closes it too. While the dropdown is `Selected`, the up and down
+/// arrows change the selection, and space or enter open and close the list.
+/// A `UiEventType::ValueChange` is emitted for the dropdown when the user selects an option.
+#[derive(Debug, Clone)]
+pub struct UiDropdown {
+    /// The text of each option.
+    pub options: Vec<String>,
+    /// The index of the selected option.
+    pub selected: usize,
+    /// Whether the list of options is shown.
+    pub open: bool,
+    /// The entity whose `UiText` shows the selected option.
+    pub text: Entity,
+    /// The entity containing the options.
+    pub list: Entity,
+}
+
+impl UiDropdown {
+    /// Creates a closed dropdown with the first option selected.
+    pub fn new(options: Vec<String>, text: Entity, list: Entity) -> Self {
+        UiDropdown {
+            options,
+            selected: 0,
+            open: false,
+            text,
+            list,
+        }
+    }
+
+    /// Sets the initially selected option.
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.select(index);
+        self
+    }
+
+    /// Selects the option at `index`.
+    /// Returns `true` if the selection changed.
+    pub fn select(&mut self, index: usize) -> bool {
+        if index >= self.options.len() || index == self.selected {
+            return false;
+        }
+        self.selected = index;
+        true
+    }
+
+    /// The text of the selected option, if there is one.
+    pub fn selected_option(&self) -> Option<&str> {
+        self.options.get(self.selected).map(String::as_str)
+    }
+}
+
+impl Component for UiDropdown {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// An option in the list of a `UiDropdown`.
+#[derive(Debug, Clone)]
+pub struct UiDropdownItem {
+    /// The `list` entity of the dropdown.
+    pub list: Entity,
+    /// The index of the option in the dropdown.
+    pub index: usize,
+}
+
+impl Component for UiDropdownItem {
+    type Storage = DenseVecStorage<Self>;
+}
+
+define_widget!(UiDropdownWidget =>
+    entities: [dropdown_entity, text_entity, list_entity]
+    components: [
+        (has UiTransform as position on dropdown_entity),
+        (has UiTransform as list_position on list_entity),
+        (has UiDropdown as dropdown on dropdown_entity),
+        (has UiImage as image on dropdown_entity),
+        (has UiText as text on text_entity),
+
+        (maybe_has UiImage as list_image on list_entity),
+        (maybe_has Parent as parent on dropdown_entity)
+    ]
+);
+
+/// Container for all the resources the builder needs to make a new dropdown.
+#[derive(SystemData)]
+#[allow(missing_debug_implementations)]
+pub struct UiDropdownBuilderResources<'a, G: PartialEq + Send + Sync + 'static, I: WidgetId = u32> {
+    font_asset: Read<'a, AssetStorage<FontAsset>>,
+    loader: ReadExpect<'a, Loader>,
+    entities: Entities<'a>,
+    image: WriteStorage<'a, UiImage>,
+    mouse_reactive: WriteStorage<'a, Interactable>,
+    parent: WriteStorage<'a, Parent>,
+    hidden: WriteStorage<'a, HiddenPropagate>,
+    text: WriteStorage<'a, UiText>,
+    transform: WriteStorage<'a, UiTransform>,
+    layout: WriteStorage<'a, UiLayout>,
+    dropdown: WriteStorage<'a, UiDropdown>,
+    item: WriteStorage<'a, UiDropdownItem>,
+    dropdown_widgets: Write<'a, Widgets<UiDropdownWidget, I>>,
+    selectables: WriteStorage<'a, Selectable<G>>,
+}
+
+/// Convenience structure for building a dropdown
+#[derive(Debug, Clone)]
+pub struct UiDropdownBuilder<G, I: WidgetId> {
+    id: Option<I>,
+    x: f32,
+    y: f32,
+    z: f32,
+    width: f32,
+    height: f32,
+    tab_order: u32,
+    anchor: Anchor,
+    options: Vec<String>,
+    selected: usize,
+    text_color: [f32; 4],
+    font: Option<FontHandle>,
+    font_size: f32,
+    image: Option<UiImage>,
+    list_image: Option<UiImage>,
+    parent: Option<Entity>,
+    _phantom: PhantomData<G>,
+}
+
+impl<'a, G: PartialEq + Send + Sync + 'static, I: WidgetId> UiDropdownBuilder<G, I> {
+    /// Construct a new UiDropdownBuilder choosing between `options`.
+    pub fn new<S: ToString>(options: &[S]) -> UiDropdownBuilder<G, I> {
+        UiDropdownBuilder {
+            id: None,
+            x: 0.,
+            y: 0.,
+            z: DEFAULT_Z,
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            tab_order: DEFAULT_TAB_ORDER,
+            anchor: Anchor::TopLeft,
+            options: options.iter().map(ToString::to_string).collect(),
+            selected: 0,
+            text_color: DEFAULT_TXT_COLOR,
+            font: None,
+            font_size: 20.,
+            image: None,
+            list_image: None,
+            parent: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets an ID for this widget. The type of this ID will determine which `Widgets`
+    /// resource this widget will be added to, see [`Widgets`](../../struct.Widgets.html).
+    pub fn with_id(mut self, id: I) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Add a parent to the dropdown.
+    pub fn with_parent(mut self, parent: Entity) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Add an anchor to the dropdown.
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Provide an X and Y position for the dropdown.
+    pub fn with_position(mut self, x: f32, y: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Provide a Z position, i.e UI layer
+    pub fn with_layer(mut self, z: f32) -> Self {
+        self.z = z;
+        self
+    }
+
+    /// Set the size of the dropdown, each option of the list has the same size
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Set dropdown tab order
+    pub fn with_tab_order(mut self, tab_order: u32) -> Self {
+        self.tab_order = tab_order;
+        self
+    }
+
+    /// Set the initially selected option
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.selected = index;
+        self
+    }
+
+    /// Use a different font for the options.
+    pub fn with_font(mut self, font: FontHandle) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Set font size
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+
+    /// Set text color
+    pub fn with_text_color(mut self, text_color: [f32; 4]) -> Self {
+        self.text_color = text_color;
+        self
+    }
+
+    /// Replace the default dropdown image with `image`.
+    pub fn with_image(mut self, image: UiImage) -> Self {
+        self.image = Some(image);
+        self
+    }
+
+    /// Replace the default image of the options with `image`.
+    pub fn with_list_image(mut self, image: UiImage) -> Self {
+        self.list_image = Some(image);
+        self
+    }
+
+    /// Build this with the `UiDropdownBuilderResources`.
+    pub fn build(mut self, mut res: UiDropdownBuilderResources<'a, G, I>) -> (I, UiDropdownWidget) {
+        let dropdown_entity = res.entities.create();
+        let text_entity = res.entities.create();
+        let list_entity = res.entities.create();
+        let widget = UiDropdownWidget::new(dropdown_entity, text_entity, list_entity);
+
+        let id = {
+            let widget = widget.clone();
+
+            if let Some(id) = self.id {
+                let added_id = id.clone();
+                res.dropdown_widgets.add_with_id(id, widget);
+                added_id
+            } else {
+                res.dropdown_widgets.add(widget)
+            }
+        };
+
+        res.transform
+            .insert(
+                dropdown_entity,
+                UiTransform::new(
+                    format!("{}_dropdown", id),
+                    self.anchor,
+                    Anchor::Middle,
+                    self.x,
+                    self.y,
+                    self.z,
+                    self.width,
+                    self.height,
+                ),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.image
+            .insert(
+                dropdown_entity,
+                self.image
+                    .unwrap_or(UiImage::SolidColor(DEFAULT_BKGD_COLOR)),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.mouse_reactive
+            .insert(dropdown_entity, Interactable)
+            .expect("Unreachable: Inserting newly created entity");
+        // The arrow keys change the selected option instead of moving the selection.
+        let mut selectable = Selectable::<G>::new(self.tab_order);
+        selectable.consumes_inputs = true;
+        res.selectables
+            .insert(dropdown_entity, selectable)
+            .expect("Unreachable: Inserting newly created entity");
+        res.dropdown
+            .insert(
+                dropdown_entity,
+                UiDropdown::new(self.options.clone(), text_entity, list_entity)
+                    .with_selected(self.selected),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        if let Some(parent) = self.parent.take() {
+            res.parent
+                .insert(dropdown_entity, Parent { entity: parent })
+                .expect("Unreachable: Inserting newly created entity");
+        }
+
+        let font_handle = self
+            .font
+            .unwrap_or_else(|| get_default_font(&res.loader, &res.font_asset));
+        let selected_text = self.options.get(self.selected).cloned().unwrap_or_default();
+        res.transform
+            .insert(
+                text_entity,
+                UiTransform::new(
+                    format!("{}_dropdown_text", id),
+                    Anchor::Middle,
+                    Anchor::Middle,
+                    0.,
+                    0.,
+                    0.01,
+                    0.,
+                    0.,
+                )
+                .into_transparent()
+                .with_stretch(Stretch::XY {
+                    x_margin: 0.,
+                    y_margin: 0.,
+                    keep_aspect_ratio: false,
+                }),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.text
+            .insert(
+                text_entity,
+                UiText::new(
+                    font_handle.clone(),
+                    selected_text,
+                    self.text_color,
+                    self.font_size,
+                ),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.parent
+            .insert(
+                text_entity,
+                Parent {
+                    entity: dropdown_entity,
+                },
+            )
+            .expect("Unreachable: Inserting newly created entity");
+
+        res.transform
+            .insert(
+                list_entity,
+                UiTransform::new(
+                    format!("{}_dropdown_list", id),
+                    Anchor::BottomMiddle,
+                    Anchor::TopMiddle,
+                    0.,
+                    0.,
+                    LIST_Z,
+                    self.width,
+                    self.height * self.options.len() as f32,
+                )
+                .into_transparent(),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.layout
+            .insert(list_entity, UiLayout::new(Arrangement::Vertical))
+            .expect("Unreachable: Inserting newly created entity");
+        res.hidden
+            .insert(list_entity, HiddenPropagate)
+            .expect("Unreachable: Inserting newly created entity");
+        res.parent
+            .insert(
+                list_entity,
+                Parent {
+                    entity: dropdown_entity,
+                },
+            )
+            .expect("Unreachable: Inserting newly created entity");
+
+        let list_image = self
+            .list_image
+            .unwrap_or(UiImage::SolidColor(DEFAULT_LIST_COLOR));
+        for (index, option) in self.options.into_iter().enumerate() {
+            let item_entity = res.entities.create();
+            res.transform
+                .insert(
+                    item_entity,
+                    UiTransform::new(
+                        format!("{}_dropdown_item_{}", id, index),
+                        Anchor::Middle,
+                        Anchor::Middle,
+                        0.,
+                        0.,
+                        0.01,
+                        self.width,
+                        self.height,
+                    ),
+                )
+                .expect("Unreachable: Inserting newly created entity");
+            res.image
+                .insert(item_entity, list_image.clone())
+                .expect("Unreachable: Inserting newly created entity");
+            res.text
+                .insert(
+                    item_entity,
+                    UiText::new(font_handle.clone(), option, self.text_color, self.font_size),
+                )
+                .expect("Unreachable: Inserting newly created entity");
+            res.mouse_reactive
+                .insert(item_entity, Interactable)
+                .expect("Unreachable: Inserting newly created entity");
+            res.item
+                .insert(
+                    item_entity,
+                    UiDropdownItem {
+                        list: list_entity,
+                        index,
+                    },
+                )
+                .expect("Unreachable: Inserting newly created entity");
+            res.hidden
+                .insert(item_entity, HiddenPropagate)
+                .expect("Unreachable: Inserting newly created entity");
+            res.parent
+                .insert(
+                    item_entity,
+                    Parent {
+                        entity: list_entity,
+                    },
+                )
+                .expect("Unreachable: Inserting newly created entity");
+        }
+
+        (id, widget)
+    }
+
+    /// Create the dropdown based on provided configuration parameters.
+    pub fn build_from_world(self, world: &World) -> (I, UiDropdownWidget) {
+        self.build(UiDropdownBuilderResources::<G, I>::fetch(&world))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst_core::ecs::{Builder, WorldExt};
+
+    #[test]
+    fn selection() {
+        let mut world = World::new();
+        let text = world.create_entity().build();
+        let list = world.create_entity().build();
+        let options = vec!["Low".to_string(), "High".to_string()];
+        let mut dropdown = UiDropdown::new(options, text, list);
+        assert_eq!(dropdown.selected_option(), Some("Low"));
+        assert!(!dropdown.select(0));
+        assert!(!dropdown.select(2));
+        assert!(dropdown.select(1));
+        assert_eq!(dropdown.selected_option(), Some("High"));
+    }
+}
@@ -0,0 +1,22 @@
+//! Standard form widgets: sliders, checkboxes, toggle groups, dropdowns and progress bars.
+
+pub use self::{
+    checkbox::{
+        UiCheckbox, UiCheckboxBuilder, UiCheckboxBuilderResources, UiCheckboxWidget, UiToggleGroup,
+        UiToggleGroupBuilder, UiToggleGroupBuilderResources, UiToggleGroupWidget,
+    },
+    dropdown::{
+        UiDropdown, UiDropdownBuilder, UiDropdownBuilderResources, UiDropdownItem, UiDropdownWidget,
+    },
+    progress::{
+        UiProgressBar, UiProgressBarBuilder, UiProgressBarBuilderResources, UiProgressBarWidget,
+    },
+    slider::{UiSlider, UiSliderBuilder, UiSliderBuilderResources, UiSliderWidget},
+    system::{UiFormSystem, UiFormSystemDesc},
+};
+
+mod checkbox;
+mod dropdown;
+mod progress;
+mod slider;
+mod system;
@@ -0,0 +1,269 @@
+use amethyst_core::{
+    ecs::{
+        prelude::{Component, DenseVecStorage, Entities, Entity, World, Write, WriteStorage},
+        shred::{ResourceId, SystemData},
+    },
+    Parent,
+};
+
+use crate::{define_widget, Anchor, Stretch, UiImage, UiTransform, WidgetId, Widgets};
+
+const DEFAULT_Z: f32 = 1.0;
+const DEFAULT_WIDTH: f32 = 128.0;
+const DEFAULT_HEIGHT: f32 = 16.0;
+const DEFAULT_BKGD_COLOR: [f32; 4] = [0.82, 0.83, 0.83, 1.0];
+const DEFAULT_FILL_COLOR: [f32; 4] = [0.3, 0.6, 0.3, 1.0];
+
+/// A bar filling up from left to right as the value goes from 0 to 1.
+///
+/// The `UiFormSystem` resizes the `fill` entity to cover the matching part of the bar. The value
+/// is only changed by the game, so no `UiEvent` is emitted for progress bars.
+#[derive(Debug, Clone)]
+pub struct UiProgressBar {
+    /// The filled fraction of the bar, clamped between 0 and 1 when displayed.
+    pub value: f32,
+    /// The entity covering the filled part of the bar, usually a child of the bar.
+    pub fill: Entity,
+}
+
+impl UiProgressBar {
+    /// Creates an empty progress bar.
+    pub fn new(fill: Entity) -> Self {
+        UiProgressBar { value: 0.0, fill }
+    }
+
+    /// Sets the initial value.
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// The width of the filled part of a bar as wide as `width`.
+    pub fn fill_width(&self, width: f32) -> f32 {
+        self.value.max(0.0).min(1.0) * width
+    }
+}
+
+impl Component for UiProgressBar {
+    type Storage = DenseVecStorage<Self>;
+}
+
+define_widget!(UiProgressBarWidget =>
+    entities: [bar_entity, fill_entity]
+    components: [
+        (has UiTransform as position on bar_entity),
+        (has UiProgressBar as progress_bar on bar_entity),
+        (has UiImage as image on bar_entity),
+        (has UiImage as fill_image on fill_entity),
+
+        (maybe_has Parent as parent on bar_entity)
+    ]
+);
+
+/// Container for all the resources the builder needs to make a new progress bar.
+#[derive(SystemData)]
+#[allow(missing_debug_implementations)]
+pub struct UiProgressBarBuilderResources<'a, I: WidgetId = u32> {
+    entities: Entities<'a>,
+    image: WriteStorage<'a, UiImage>,
+    parent: WriteStorage<'a, Parent>,
+    transform: WriteStorage<'a, UiTransform>,
+    progress_bar: WriteStorage<'a, UiProgressBar>,
+    progress_bar_widgets: Write<'a, Widgets<UiProgressBarWidget, I>>,
+}
+
+/// Convenience structure for building a progress bar
+#[derive(Debug, Clone)]
+pub struct UiProgressBarBuilder<I: WidgetId> {
+    id: Option<I>,
+    x: f32,
+    y: f32,
+    z: f32,
+    width: f32,
+    height: f32,
+    anchor: Anchor,
+    stretch: Stretch,
+    value: f32,
+    image: Option<UiImage>,
+    fill_image: Option<UiImage>,
+    parent: Option<Entity>,
+}
+
+impl<I: WidgetId> Default for UiProgressBarBuilder<I> {
+    fn default() -> Self {
+        UiProgressBarBuilder {
+            id: None,
+            x: 0.,
+            y: 0.,
+            z: DEFAULT_Z,
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            anchor: Anchor::TopLeft,
+            stretch: Stretch::NoStretch,
+            value: 0.,
+            image: None,
+            fill_image: None,
+            parent: None,
+        }
+    }
+}
+
+impl<'a, I: WidgetId> UiProgressBarBuilder<I> {
+    /// Construct a new UiProgressBarBuilder.
+    pub fn new() -> UiProgressBarBuilder<I> {
+        UiProgressBarBuilder::default()
+    }
+
+    /// Sets an ID for this widget. The type of this ID will determine which `Widgets`
+    /// resource this widget will be added to, see [`Widgets`](../../struct.Widgets.html).
+    pub fn with_id(mut self, id: I) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Add a parent to the progress bar.
+    pub fn with_parent(mut self, parent: Entity) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Add an anchor to the progress bar.
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Stretch the progress bar.
+    pub fn with_stretch(mut self, stretch: Stretch) -> Self {
+        self.stretch = stretch;
+        self
+    }
+
+    /// Provide an X and Y position for the progress bar.
+    pub fn with_position(mut self, x: f32, y: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Provide a Z position, i.e UI layer
+    pub fn with_layer(mut self, z: f32) -> Self {
+        self.z = z;
+        self
+    }
+
+    /// Set progress bar size
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Set the initial value, between 0 and 1
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Replace the default background image with `image`.
+    pub fn with_image(mut self, image: UiImage) -> Self {
+        self.image = Some(image);
+        self
+    }
+
+    /// Replace the default image of the filled part with `image`.
+    pub fn with_fill_image(mut self, image: UiImage) -> Self {
+        self.fill_image = Some(image);
+        self
+    }
+
+    /// Build this with the `UiProgressBarBuilderResources`.
+    pub fn build(
+        mut self,
+        mut res: UiProgressBarBuilderResources<'a, I>,
+    ) -> (I, UiProgressBarWidget) {
+        let bar_entity = res.entities.create();
+        let fill_entity = res.entities.create();
+        let widget = UiProgressBarWidget::new(bar_entity, fill_entity);
+
+        let id = {
+            let widget = widget.clone();
+
+            if let Some(id) = self.id {
+                let added_id = id.clone();
+                res.progress_bar_widgets.add_with_id(id, widget);
+                added_id
+            } else {
+                res.progress_bar_widgets.add(widget)
+            }
+        };
+
+        res.transform
+            .insert(
+                bar_entity,
+                UiTransform::new(
+                    format!("{}_progress_bar", id),
+                    self.anchor,
+                    Anchor::Middle,
+                    self.x,
+                    self.y,
+                    self.z,
+                    self.width,
+                    self.height,
+                )
+                .with_stretch(self.stretch),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.image
+            .insert(
+                bar_entity,
+                self.image
+                    .unwrap_or(UiImage::SolidColor(DEFAULT_BKGD_COLOR)),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        let progress_bar = UiProgressBar::new(fill_entity).with_value(self.value);
+        let fill_width = progress_bar.fill_width(self.width);
+        res.progress_bar
+            .insert(bar_entity, progress_bar)
+            .expect("Unreachable: Inserting newly created entity");
+        if let Some(parent) = self.parent.take() {
+            res.parent
+                .insert(bar_entity, Parent { entity: parent })
+                .expect("Unreachable: Inserting newly created entity");
+        }
+
+        res.transform
+            .insert(
+                fill_entity,
+                UiTransform::new(
+                    format!("{}_progress_bar_fill", id),
+                    Anchor::MiddleLeft,
+                    Anchor::MiddleLeft,
+                    0.,
+                    0.,
+                    0.01,
+                    fill_width,
+                    self.height,
+                )
+                .into_transparent(),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.image
+            .insert(
+                fill_entity,
+                self.fill_image
+                    .unwrap_or(UiImage::SolidColor(DEFAULT_FILL_COLOR)),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.parent
+            .insert(fill_entity, Parent { entity: bar_entity })
+            .expect("Unreachable: Inserting newly created entity");
+
+        (id, widget)
+    }
+
+    /// Create the progress bar based on provided configuration parameters.
+    pub fn build_from_world(self, world: &World) -> (I, UiProgressBarWidget) {
+        self.build(UiProgressBarBuilderResources::<I>::fetch(&world))
+    }
+}
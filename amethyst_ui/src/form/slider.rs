@@ -0,0 +1,510 @@
+use amethyst_core::{
+    ecs::{
+        prelude::{Component, DenseVecStorage, Entities, Entity, World, Write, WriteStorage},
+        shred::{ResourceId, SystemData},
+    },
+    Parent,
+};
+
+use crate::{
+    define_widget, Anchor, Interactable, Selectable, Stretch, UiImage, UiTransform, WidgetId,
+    Widgets,
+};
+
+use std::marker::PhantomData;
+
+const DEFAULT_Z: f32 = 1.0;
+const DEFAULT_WIDTH: f32 = 128.0;
+const DEFAULT_HEIGHT: f32 = 8.0;
+const DEFAULT_HANDLE_SIZE: f32 = 16.0;
+const DEFAULT_TAB_ORDER: u32 = 9;
+const DEFAULT_TRACK_COLOR: [f32; 4] = [0.82, 0.83, 0.83, 1.0];
+const DEFAULT_HANDLE_COLOR: [f32; 4] = [0.4, 0.4, 0.4, 1.0];
+
+/// A slider picking a number in a range by moving a handle along a track.
+///
+/// This component goes on the track entity, which has to be `Interactable` for the handle to
+/// be dragged and `Selectable` for the value to be changed with the arrow, home and end keys.
+/// The `UiFormSystem` emits a `UiEventType::ValueChange` on the track every time the user
+/// changes the value, and a `UiEventType::ValueCommit` when they let go of the handle.
+#[derive(Debug, Clone)]
+pub struct UiSlider {
+    /// The current value, between `min` and `max`.
+    pub value: f32,
+    /// The value at the left, or bottom, end of the track.
+    pub min: f32,
+    /// The value at the right, or top, end of the track.
+    pub max: f32,
+    /// The value is rounded to a multiple of `step` above `min`. Zero means no rounding.
+    pub step: f32,
+    /// Moves the handle from bottom to top instead of left to right.
+    pub vertical: bool,
+    /// The entity moved along the track to show the value, usually a child of the track.
+    pub handle: Entity,
+}
+
+impl UiSlider {
+    /// Creates a horizontal slider without step, set to `min`.
+    pub fn new(handle: Entity, min: f32, max: f32) -> Self {
+        UiSlider {
+            value: min,
+            min,
+            max,
+            step: 0.0,
+            vertical: false,
+            handle,
+        }
+    }
+
+    /// Sets the initial value.
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    /// Rounds the value to multiples of `step`.
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        let value = self.value;
+        self.set_value(value);
+        self
+    }
+
+    /// Moves the handle from bottom to top instead of left to right.
+    pub fn vertical(mut self) -> Self {
+        self.vertical = true;
+        self
+    }
+
+    /// Sets the value, clamped to the range and rounded to the step.
+    /// Returns `true` if the value changed.
+    pub fn set_value(&mut self, value: f32) -> bool {
+        let mut value = value.max(self.min).min(self.max);
+        if self.step > 0.0 {
+            value = self.min + ((value - self.min) / self.step).round() * self.step;
+            value = value.min(self.max);
+        }
+        let changed = (value - self.value).abs() > std::f32::EPSILON;
+        self.value = value;
+        changed
+    }
+
+    /// Where the value sits in the range, from 0 at `min` to 1 at `max`.
+    pub fn fraction(&self) -> f32 {
+        let range = self.max - self.min;
+        if range > std::f32::EPSILON {
+            (self.value - self.min) / range
+        } else {
+            0.0
+        }
+    }
+
+    /// Sets the value from where it sits in the range, see `fraction`.
+    /// Returns `true` if the value changed.
+    pub fn set_fraction(&mut self, fraction: f32) -> bool {
+        let fraction = fraction.max(0.0).min(1.0);
+        self.set_value(self.min + fraction * (self.max - self.min))
+    }
+
+    /// The amount a key press changes the value by: the step, or a twentieth of the range
+    /// for sliders without step.
+    pub fn key_step(&self) -> f32 {
+        if self.step > 0.0 {
+            self.step
+        } else {
+            (self.max - self.min) / 20.0
+        }
+    }
+}
+
+impl Component for UiSlider {
+    type Storage = DenseVecStorage<Self>;
+}
+
+define_widget!(UiSliderWidget =>
+    entities: [track_entity, handle_entity]
+    components: [
+        (has UiTransform as position on track_entity),
+        (has UiTransform as handle_position on handle_entity),
+        (has UiSlider as slider on track_entity),
+        (has UiImage as track_image on track_entity),
+        (has UiImage as handle_image on handle_entity),
+
+        (maybe_has Parent as parent on track_entity)
+    ]
+);
+
+/// Container for all the resources the builder needs to make a new slider.
+#[derive(SystemData)]
+#[allow(missing_debug_implementations)]
+pub struct UiSliderBuilderResources<'a, G: PartialEq + Send + Sync + 'static, I: WidgetId = u32> {
+    entities: Entities<'a>,
+    image: WriteStorage<'a, UiImage>,
+    mouse_reactive: WriteStorage<'a, Interactable>,
+    parent: WriteStorage<'a, Parent>,
+    transform: WriteStorage<'a, UiTransform>,
+    slider: WriteStorage<'a, UiSlider>,
+    slider_widgets: Write<'a, Widgets<UiSliderWidget, I>>,
+    selectables: WriteStorage<'a, Selectable<G>>,
+}
+
+/// Convenience structure for building a slider
+#[derive(Debug, Clone)]
+pub struct UiSliderBuilder<G, I: WidgetId> {
+    id: Option<I>,
+    x: f32,
+    y: f32,
+    z: f32,
+    width: f32,
+    height: f32,
+    handle_width: f32,
+    handle_height: f32,
+    tab_order: u32,
+    anchor: Anchor,
+    stretch: Stretch,
+    min: f32,
+    max: f32,
+    value: f32,
+    step: f32,
+    vertical: bool,
+    track_image: Option<UiImage>,
+    handle_image: Option<UiImage>,
+    parent: Option<Entity>,
+    _phantom: PhantomData<G>,
+}
+
+impl<'a, G: PartialEq + Send + Sync + 'static, I: WidgetId> UiSliderBuilder<G, I> {
+    /// Construct a new UiSliderBuilder for values between `min` and `max`.
+    pub fn new(min: f32, max: f32) -> UiSliderBuilder<G, I> {
+        UiSliderBuilder {
+            id: None,
+            x: 0.,
+            y: 0.,
+            z: DEFAULT_Z,
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            handle_width: DEFAULT_HANDLE_SIZE,
+            handle_height: DEFAULT_HANDLE_SIZE,
+            tab_order: DEFAULT_TAB_ORDER,
+            anchor: Anchor::TopLeft,
+            stretch: Stretch::NoStretch,
+            min,
+            max,
+            value: min,
+            step: 0.,
+            vertical: false,
+            track_image: None,
+            handle_image: None,
+            parent: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets an ID for this widget. The type of this ID will determine which `Widgets`
+    /// resource this widget will be added to, see [`Widgets`](../../struct.Widgets.html).
+    pub fn with_id(mut self, id: I) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Add a parent to the slider.
+    pub fn with_parent(mut self, parent: Entity) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Add an anchor to the slider.
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Stretch the slider track.
+    pub fn with_stretch(mut self, stretch: Stretch) -> Self {
+        self.stretch = stretch;
+        self
+    }
+
+    /// Provide an X and Y position for the slider.
+    pub fn with_position(mut self, x: f32, y: f32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Provide a Z position, i.e UI layer
+    pub fn with_layer(mut self, z: f32) -> Self {
+        self.z = z;
+        self
+    }
+
+    /// Set track size
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Set handle size
+    pub fn with_handle_size(mut self, width: f32, height: f32) -> Self {
+        self.handle_width = width;
+        self.handle_height = height;
+        self
+    }
+
+    /// Set slider tab order
+    pub fn with_tab_order(mut self, tab_order: u32) -> Self {
+        self.tab_order = tab_order;
+        self
+    }
+
+    /// Set the initial value
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Round the value to multiples of `step`
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Move the handle from bottom to top instead of left to right
+    pub fn vertical(mut self) -> Self {
+        self.vertical = true;
+        self
+    }
+
+    /// Replace the default track image with `image`.
+    pub fn with_track_image(mut self, image: UiImage) -> Self {
+        self.track_image = Some(image);
+        self
+    }
+
+    /// Replace the default handle image with `image`.
+    pub fn with_handle_image(mut self, image: UiImage) -> Self {
+        self.handle_image = Some(image);
+        self
+    }
+
+    /// Build this with the `UiSliderBuilderResources`.
+    pub fn build(mut self, mut res: UiSliderBuilderResources<'a, G, I>) -> (I, UiSliderWidget) {
+        let track_entity = res.entities.create();
+        let handle_entity = res.entities.create();
+        let widget = UiSliderWidget::new(track_entity, handle_entity);
+
+        let id = {
+            let widget = widget.clone();
+
+            if let Some(id) = self.id {
+                let added_id = id.clone();
+                res.slider_widgets.add_with_id(id, widget);
+                added_id
+            } else {
+                res.slider_widgets.add(widget)
+            }
+        };
+
+        res.transform
+            .insert(
+                track_entity,
+                UiTransform::new(
+                    format!("{}_slider", id),
+                    self.anchor,
+                    Anchor::Middle,
+                    self.x,
+                    self.y,
+                    self.z,
+                    self.width,
+                    self.height,
+                )
+                .with_stretch(self.stretch),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.image
+            .insert(
+                track_entity,
+                self.track_image
+                    .unwrap_or(UiImage::SolidColor(DEFAULT_TRACK_COLOR)),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.mouse_reactive
+            .insert(track_entity, Interactable)
+            .expect("Unreachable: Inserting newly created entity");
+        // The arrow keys change the value instead of moving the selection.
+        let mut selectable = Selectable::<G>::new(self.tab_order);
+        selectable.consumes_inputs = true;
+        res.selectables
+            .insert(track_entity, selectable)
+            .expect("Unreachable: Inserting newly created entity");
+        let mut slider = UiSlider::new(handle_entity, self.min, self.max)
+            .with_step(self.step)
+            .with_value(self.value);
+        slider.vertical = self.vertical;
+        res.slider
+            .insert(track_entity, slider)
+            .expect("Unreachable: Inserting newly created entity");
+        if let Some(parent) = self.parent.take() {
+            res.parent
+                .insert(track_entity, Parent { entity: parent })
+                .expect("Unreachable: Inserting newly created entity");
+        }
+
+        // The handle lets clicks through so that the track can be dragged from anywhere.
+        res.transform
+            .insert(
+                handle_entity,
+                UiTransform::new(
+                    format!("{}_slider_handle", id),
+                    Anchor::Middle,
+                    Anchor::Middle,
+                    0.,
+                    0.,
+                    0.01,
+                    self.handle_width,
+                    self.handle_height,
+                )
+                .into_transparent(),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.image
+            .insert(
+                handle_entity,
+                self.handle_image
+                    .unwrap_or(UiImage::SolidColor(DEFAULT_HANDLE_COLOR)),
+            )
+            .expect("Unreachable: Inserting newly created entity");
+        res.parent
+            .insert(
+                handle_entity,
+                Parent {
+                    entity: track_entity,
+                },
+            )
+            .expect("Unreachable: Inserting newly created entity");
+
+        (id, widget)
+    }
+
+    /// Create the slider based on provided configuration parameters.
+    pub fn build_from_world(self, world: &World) -> (I, UiSliderWidget) {
+        self.build(UiSliderBuilderResources::<G, I>::fetch(&world))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CacheSelectionOrderSystem, Selected, SelectionNavigationSystem, UiNavigationBindings,
+    };
+    use amethyst_core::{
+        ecs::{Builder, Join, ReadStorage, RunNow, System, WorldExt},
+        shrev::EventChannel,
+    };
+    use amethyst_input::{
+        Button, InputEvent, InputHandler, StringBindings, VirtualEvent, VirtualKeyCode,
+    };
+
+    fn slider(min: f32, max: f32) -> UiSlider {
+        let mut world = World::new();
+        let handle = world.create_entity().build();
+        UiSlider::new(handle, min, max)
+    }
+
+    #[test]
+    fn value_is_clamped_and_stepped() {
+        let mut slider = slider(-1.0, 9.0).with_step(2.0);
+        assert!(slider.set_value(2.4));
+        assert_eq!(slider.value, 3.0);
+        assert!(!slider.set_value(3.2));
+        assert!(slider.set_value(20.0));
+        assert_eq!(slider.value, 9.0);
+        assert!(slider.set_value(-5.0));
+        assert_eq!(slider.value, -1.0);
+    }
+
+    #[test]
+    fn fraction() {
+        let mut slider = slider(10.0, 20.0);
+        assert!(slider.set_fraction(0.25));
+        assert_eq!(slider.value, 12.5);
+        assert_eq!(slider.fraction(), 0.25);
+        assert!(slider.set_fraction(3.0));
+        assert_eq!(slider.value, 20.0);
+        assert_eq!(slider.key_step(), 0.5);
+        assert_eq!(slider.with_step(4.0).key_step(), 4.0);
+    }
+
+    #[test]
+    fn arrows_keep_the_focus() {
+        let mut world = World::new();
+        <UiSliderBuilderResources<'_, ()> as SystemData<'_>>::setup(&mut world);
+        <CacheSelectionOrderSystem<()> as System<'_>>::SystemData::setup(&mut world);
+        <SelectionNavigationSystem<(), StringBindings> as System<'_>>::SystemData::setup(
+            &mut world,
+        );
+        let mut system = SelectionNavigationSystem::<(), StringBindings>::new();
+        world.insert(
+            UiNavigationBindings::<StringBindings>::default()
+                .with_actions("ui_up", "ui_down", "ui_left", "ui_right"),
+        );
+        world
+            .write_resource::<InputHandler<StringBindings>>()
+            .bindings
+            .insert_action_binding(
+                String::from("ui_right"),
+                Some(Button::Key(VirtualKeyCode::Right)),
+            )
+            .unwrap();
+
+        let (_, widget) = UiSliderBuilder::<(), u32>::new(0.0, 1.0).build_from_world(&world);
+        world.maintain();
+        let neighbour = world
+            .create_entity()
+            .with(UiTransform::new(
+                "neighbour".to_string(),
+                Anchor::TopLeft,
+                Anchor::TopLeft,
+                300.0,
+                0.0,
+                0.0,
+                32.0,
+                32.0,
+            ))
+            .with(Selectable::<()>::new(1))
+            .build();
+        CacheSelectionOrderSystem::<()>::new().run_now(&world);
+        world
+            .write_storage::<Selected>()
+            .insert(widget.track_entity, Selected)
+            .unwrap();
+
+        let mut press_right = |world: &mut World| {
+            let mut events = EventChannel::<InputEvent<StringBindings>>::new();
+            for event in &[
+                VirtualEvent::ButtonPressed(Button::Key(VirtualKeyCode::Right)),
+                VirtualEvent::ButtonReleased(Button::Key(VirtualKeyCode::Right)),
+            ] {
+                world
+                    .write_resource::<InputHandler<StringBindings>>()
+                    .send_virtual_event(event, &mut events);
+                system.run_now(world);
+            }
+            let (entities, selecteds) = <(Entities<'_>, ReadStorage<'_, Selected>)>::fetch(world);
+            (&*entities, &selecteds)
+                .join()
+                .map(|(entity, _)| entity)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(press_right(&mut world), vec![widget.track_entity]);
+
+        world
+            .write_storage::<Selectable<()>>()
+            .get_mut(widget.track_entity)
+            .unwrap()
+            .consumes_inputs = false;
+        assert_eq!(press_right(&mut world), vec![neighbour]);
+    }
+}
@@ -0,0 +1,413 @@
+use std::marker::PhantomData;
+
+use amethyst_core::{
+    ecs::{
+        prelude::{
+            Entities, Entity, Join, Read, ReadExpect, ReadStorage, ReaderId, System, SystemData,
+            World, Write, WriteStorage,
+        },
+        world::EntitiesRes,
+    },
+    shrev::EventChannel,
+    HiddenPropagate, Parent, SystemDesc,
+};
+use amethyst_derive::SystemDesc;
+use amethyst_input::{BindingTypes, InputHandler};
+use amethyst_window::ScreenDimensions;
+use winit::{ElementState, Event, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use crate::{
+    scroll::{move_to, resize},
+    Selected, UiCheckbox, UiDropdown, UiDropdownItem, UiEvent, UiEventType, UiProgressBar,
+    UiSlider, UiText, UiToggleGroup, UiTransform,
+};
+
+/// System reacting to the user input on the form widgets (`UiSlider`, `UiCheckbox`,
+/// `UiToggleGroup`, `UiDropdown`) and keeping the look of all of them, `UiProgressBar`
+/// included, in sync with their value.
+/// The generic type T represent the T generic parameter of the InputHandler<T>.
+///
+/// It emits `UiEventType::ValueChange` events for the widgets changed by the user.
+#[derive(Debug, SystemDesc)]
+#[system_desc(name(UiFormSystemDesc))]
+pub struct UiFormSystem<T: BindingTypes> {
+    #[system_desc(event_channel_reader)]
+    window_reader_id: ReaderId<Event>,
+    #[system_desc(event_channel_reader)]
+    ui_reader_id: ReaderId<UiEvent>,
+    /// The slider whose handle follows the pointer.
+    #[system_desc(skip)]
+    dragged_slider: Option<Entity>,
+    #[system_desc(skip)]
+    was_down: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<T: BindingTypes> UiFormSystem<T> {
+    /// Creates a new `UiFormSystem`.
+    pub fn new(window_reader_id: ReaderId<Event>, ui_reader_id: ReaderId<UiEvent>) -> Self {
+        Self {
+            window_reader_id,
+            ui_reader_id,
+            dragged_slider: None,
+            was_down: false,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: BindingTypes> System<'a> for UiFormSystem<T> {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, UiTransform>,
+        WriteStorage<'a, UiSlider>,
+        WriteStorage<'a, UiCheckbox>,
+        WriteStorage<'a, UiToggleGroup>,
+        WriteStorage<'a, UiDropdown>,
+        ReadStorage<'a, UiDropdownItem>,
+        ReadStorage<'a, UiProgressBar>,
+        WriteStorage<'a, UiText>,
+        WriteStorage<'a, HiddenPropagate>,
+        ReadStorage<'a, Parent>,
+        ReadStorage<'a, Selected>,
+        Read<'a, InputHandler<T>>,
+        ReadExpect<'a, ScreenDimensions>,
+        Read<'a, EventChannel<Event>>,
+        Write<'a, EventChannel<UiEvent>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut transforms,
+            mut sliders,
+            mut checkboxes,
+            mut groups,
+            mut dropdowns,
+            items,
+            progress_bars,
+            mut texts,
+            mut hiddens,
+            parents,
+            selecteds,
+            input,
+            screen_dimensions,
+            window_events,
+            mut ui_events,
+        ): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("ui_form_system");
+
+        let touch_position = input.primary_touch().map(|touch| touch.position);
+        let down = input.mouse_button_is_down(MouseButton::Left) || touch_position.is_some();
+        let pointer_position = touch_position
+            .or_else(|| input.mouse_position())
+            .map(|(x, y)| (x as f32, screen_dimensions.height() - y as f32));
+        let pressed = down && !self.was_down;
+        self.was_down = down;
+
+        let mut changes = Vec::new();
+        let clicks = ui_events
+            .read(&mut self.ui_reader_id)
+            .filter(|event| {
+                event.event_type == UiEventType::Click
+                    || event.event_type == UiEventType::ClickStart
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // A press anywhere but on an opened dropdown or its options closes it.
+        if pressed {
+            for (entity, dropdown) in (&*entities, &mut dropdowns).join() {
+                let kept_open = clicks.iter().any(|event| {
+                    event.event_type == UiEventType::ClickStart
+                        && (event.target == entity
+                            || items.get(event.target).map(|item| item.list) == Some(dropdown.list))
+                });
+                if !kept_open {
+                    dropdown.open = false;
+                }
+            }
+        }
+
+        for event in clicks {
+            if event.event_type == UiEventType::ClickStart {
+                if sliders.contains(event.target) {
+                    self.dragged_slider = Some(event.target);
+                }
+                continue;
+            }
+
+            if let Some(checkbox) = checkbox_of(event.target, &checkboxes, &parents) {
+                activate(
+                    checkbox,
+                    &entities,
+                    &mut checkboxes,
+                    &mut groups,
+                    &mut changes,
+                );
+            } else if let Some(dropdown) = dropdowns.get_mut(event.target) {
+                dropdown.open = !dropdown.open;
+            } else if let Some(item) = items.get(event.target) {
+                for (entity, dropdown) in (&*entities, &mut dropdowns).join() {
+                    if dropdown.list == item.list {
+                        if dropdown.select(item.index) {
+                            changes.push((UiEventType::ValueChange, entity));
+                        }
+                        dropdown.open = false;
+                    }
+                }
+            }
+        }
+
+        if !down {
+            if let Some(slider) = self.dragged_slider.take() {
+                changes.push((UiEventType::ValueCommit, slider));
+            }
+        } else if let (Some(entity), Some(position)) = (self.dragged_slider, pointer_position) {
+            if let (Some(slider), Some(track)) = (sliders.get_mut(entity), transforms.get(entity)) {
+                let (position, start, length) = if slider.vertical {
+                    (
+                        position.1,
+                        track.pixel_y - track.pixel_height / 2.0,
+                        track.pixel_height,
+                    )
+                } else {
+                    (
+                        position.0,
+                        track.pixel_x - track.pixel_width / 2.0,
+                        track.pixel_width,
+                    )
+                };
+                if length > std::f32::EPSILON && slider.set_fraction((position - start) / length) {
+                    changes.push((UiEventType::ValueChange, entity));
+                }
+            }
+        }
+
+        let pressed_keys = window_events
+            .read(&mut self.window_reader_id)
+            .filter_map(|event| match *event {
+                Event::WindowEvent {
+                    event:
+                        WindowEvent::KeyboardInput {
+                            input:
+                                KeyboardInput {
+                                    state: ElementState::Pressed,
+                                    virtual_keycode: Some(key),
+                                    ..
+                                },
+                            ..
+                        },
+                    ..
+                } => Some(key),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !pressed_keys.is_empty() {
+            let selected = (&*entities, &selecteds)
+                .join()
+                .map(|(entity, _)| entity)
+                .collect::<Vec<_>>();
+            for key in pressed_keys {
+                for &entity in &selected {
+                    if let Some(slider) = sliders.get_mut(entity) {
+                        let value = match key {
+                            VirtualKeyCode::Left | VirtualKeyCode::Down => {
+                                slider.value - slider.key_step()
+                            }
+                            VirtualKeyCode::Right | VirtualKeyCode::Up => {
+                                slider.value + slider.key_step()
+                            }
+                            VirtualKeyCode::Home => slider.min,
+                            VirtualKeyCode::End => slider.max,
+                            _ => continue,
+                        };
+                        if slider.set_value(value) {
+                            changes.push((UiEventType::ValueChange, entity));
+                            changes.push((UiEventType::ValueCommit, entity));
+                        }
+                    } else if let Some(dropdown) = dropdowns.get_mut(entity) {
+                        let changed = match key {
+                            VirtualKeyCode::Up => {
+                                dropdown.select(dropdown.selected.saturating_sub(1))
+                            }
+                            VirtualKeyCode::Down => dropdown.select(dropdown.selected + 1),
+                            VirtualKeyCode::Space | VirtualKeyCode::Return => {
+                                dropdown.open = !dropdown.open;
+                                false
+                            }
+                            VirtualKeyCode::Escape => {
+                                dropdown.open = false;
+                                false
+                            }
+                            _ => false,
+                        };
+                        if changed {
+                            changes.push((UiEventType::ValueChange, entity));
+                        }
+                    } else if checkboxes.contains(entity) {
+                        match key {
+                            VirtualKeyCode::Space | VirtualKeyCode::Return => activate(
+                                entity,
+                                &entities,
+                                &mut checkboxes,
+                                &mut groups,
+                                &mut changes,
+                            ),
+                            VirtualKeyCode::Up | VirtualKeyCode::Left => {
+                                step_group(entity, -1, &entities, &mut groups, &mut changes)
+                            }
+                            VirtualKeyCode::Down | VirtualKeyCode::Right => {
+                                step_group(entity, 1, &entities, &mut groups, &mut changes)
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        // The options of a group are checked by the group.
+        for group in (&groups).join() {
+            for (index, option) in group.options.iter().enumerate() {
+                if let Some(checkbox) = checkboxes.get_mut(*option) {
+                    checkbox.checked = group.selected == Some(index);
+                }
+            }
+        }
+
+        for (entity, checkbox) in (&*entities, &checkboxes).join() {
+            let hidden = !checkbox.checked || hiddens.contains(entity);
+            set_hidden(checkbox.check, hidden, &mut hiddens);
+        }
+
+        for (entity, slider) in (&*entities, &sliders).join() {
+            let (center, size) = match transforms.get(entity) {
+                Some(track) => (
+                    (track.pixel_x, track.pixel_y),
+                    (track.pixel_width, track.pixel_height),
+                ),
+                None => continue,
+            };
+            if let Some(handle) = transforms.get_mut(slider.handle) {
+                let position = if slider.vertical {
+                    (center.0, center.1 + (slider.fraction() - 0.5) * size.1)
+                } else {
+                    (center.0 + (slider.fraction() - 0.5) * size.0, center.1)
+                };
+                move_to(handle, position, size);
+            }
+        }
+
+        let mut lists = Vec::new();
+        for (entity, dropdown) in (&*entities, &dropdowns).join() {
+            if let (Some(text), Some(option)) =
+                (texts.get_mut(dropdown.text), dropdown.selected_option())
+            {
+                if text.text != option {
+                    text.text = option.to_string();
+                }
+            }
+            let hidden = !dropdown.open || hiddens.contains(entity);
+            set_hidden(dropdown.list, hidden, &mut hiddens);
+            lists.push((dropdown.list, hidden));
+        }
+        for (entity, item) in (&*entities, &items).join() {
+            if let Some((_, hidden)) = lists.iter().find(|(list, _)| *list == item.list) {
+                set_hidden(entity, *hidden, &mut hiddens);
+            }
+        }
+
+        for (entity, progress_bar) in (&*entities, &progress_bars).join() {
+            let size = match transforms.get(entity) {
+                Some(bar) => (bar.pixel_width, bar.pixel_height),
+                None => continue,
+            };
+            if let Some(fill) = transforms.get_mut(progress_bar.fill) {
+                let height = fill.pixel_height;
+                resize(fill, (progress_bar.fill_width(size.0), height), size);
+            }
+        }
+
+        for (event_type, target) in changes {
+            ui_events.single_write(UiEvent::new(event_type, target));
+        }
+    }
+}
+
+/// Returns the checkbox clicked when `entity` is, which is either itself or its parent for
+/// the label of a checkbox.
+fn checkbox_of(
+    entity: Entity,
+    checkboxes: &WriteStorage<'_, UiCheckbox>,
+    parents: &ReadStorage<'_, Parent>,
+) -> Option<Entity> {
+    if checkboxes.contains(entity) {
+        return Some(entity);
+    }
+    parents
+        .get(entity)
+        .map(|parent| parent.entity)
+        .filter(|parent| checkboxes.contains(*parent))
+}
+
+/// Toggles a checkbox, or selects it if it belongs to a `UiToggleGroup`.
+fn activate(
+    checkbox: Entity,
+    entities: &EntitiesRes,
+    checkboxes: &mut WriteStorage<'_, UiCheckbox>,
+    groups: &mut WriteStorage<'_, UiToggleGroup>,
+    changes: &mut Vec<(UiEventType, Entity)>,
+) {
+    for (entity, group) in (entities, &mut *groups).join() {
+        if let Some(index) = group.position(checkbox) {
+            if group.select(index) {
+                changes.push((UiEventType::ValueChange, entity));
+            }
+            return;
+        }
+    }
+    if let Some(checkbox_component) = checkboxes.get_mut(checkbox) {
+        checkbox_component.toggle();
+        changes.push((UiEventType::ValueChange, checkbox));
+    }
+}
+
+/// Selects the option before or after `option` in its `UiToggleGroup`.
+fn step_group(
+    option: Entity,
+    step: isize,
+    entities: &EntitiesRes,
+    groups: &mut WriteStorage<'_, UiToggleGroup>,
+    changes: &mut Vec<(UiEventType, Entity)>,
+) {
+    for (entity, group) in (entities, &mut *groups).join() {
+        if let Some(index) = group.position(option) {
+            let current = group.selected.unwrap_or(index) as isize;
+            let next = (current + step).max(0) as usize;
+            if group.select(next) {
+                changes.push((UiEventType::ValueChange, entity));
+            }
+            return;
+        }
+    }
+}
+
+fn set_hidden(entity: Entity, hidden: bool, hiddens: &mut WriteStorage<'_, HiddenPropagate>) {
+    if hidden && !hiddens.contains(entity) {
+        hiddens.insert(entity, HiddenPropagate).unwrap_or_else(|_| {
+            panic!(
+                "Failed to insert HiddenPropagate component for {:?}",
+                entity
+            )
+        });
+    } else if !hidden && hiddens.contains(entity) {
+        hiddens.remove(entity);
+    }
+}
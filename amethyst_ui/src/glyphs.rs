@@ -1,8 +1,8 @@
 //! Module containing the system managing glyphbrush state for visible UI Text components.
 
 use crate::{
-    pass::UiArgs, text::CachedGlyph, FontAsset, LineMode, Selected, TextEditing, UiText,
-    UiTransform,
+    pass::UiArgs, text::CachedGlyph, FontAsset, FontHandle, LineMode, Selected, TextEditing,
+    UiImage, UiRichText, UiText, UiTransform,
 };
 use amethyst_assets::{AssetStorage, Handle};
 use amethyst_core::{
@@ -27,7 +27,7 @@ use glyph_brush::{
     rusttype::Scale, BrushAction, BrushError, BuiltInLineBreaker, FontId, GlyphBrush,
     GlyphBrushBuilder, GlyphCruncher, Layout, LineBreak, LineBreaker, SectionText, VariedSection,
 };
-use std::{collections::HashMap, marker::PhantomData};
+use std::{collections::HashMap, marker::PhantomData, ops::Range};
 use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Debug)]
@@ -118,6 +118,7 @@ impl<'a, B: Backend> System<'a> for UiGlyphsSystem<B> {
         Entities<'a>,
         ReadStorage<'a, UiTransform>,
         WriteStorage<'a, UiText>,
+        WriteStorage<'a, UiRichText>,
         WriteStorage<'a, UiGlyphs>,
         ReadStorage<'a, TextEditing>,
         ReadStorage<'a, Hidden>,
@@ -137,6 +138,7 @@ impl<'a, B: Backend> System<'a> for UiGlyphsSystem<B> {
             entities,
             transforms,
             mut texts,
+            mut rich_texts,
             mut glyphs,
             text_editings,
             hiddens,
@@ -171,10 +173,11 @@ impl<'a, B: Backend> System<'a> for UiGlyphsSystem<B> {
         let fonts_map_ref = &mut self.fonts_map;
         let glyph_brush_ref = &mut self.glyph_brush;
 
-        for (entity, transform, ui_text, editing, tint, _, _) in (
+        for (entity, transform, ui_text, mut rich, editing, tint, _, _) in (
             &entities,
             &transforms,
             &mut texts,
+            (&mut rich_texts).maybe(),
             text_editings.maybe(),
             tints.maybe(),
             !&hiddens,
@@ -184,16 +187,20 @@ impl<'a, B: Backend> System<'a> for UiGlyphsSystem<B> {
         {
             ui_text.cached_glyphs.clear();
 
-            let font_lookup = fonts_map_ref
-                .entry(ui_text.font.id())
-                .or_insert(FontState::NotFound);
-            if font_lookup.id().is_none() {
-                if let Some(font) = font_storage.get(&ui_text.font) {
-                    *font_lookup = FontState::Ready(glyph_brush_ref.add_font(font.0.clone()));
+            // Editable and password texts are shown as plain text.
+            let use_markup = editing.is_none() && !ui_text.password;
+            if let Some(rich) = rich.as_mut() {
+                rich.link_boxes.clear();
+                rich.icon_quads.clear();
+                if use_markup {
+                    rich.update_spans(&ui_text.text);
                 }
             }
+            let rich = rich.filter(|_| use_markup);
 
-            if let Some(font_id) = font_lookup.id() {
+            if let Some(font_id) =
+                load_font(fonts_map_ref, glyph_brush_ref, &font_storage, &ui_text.font)
+            {
                 let tint_color = tint.map_or([1., 1., 1., 1.], |t| {
                     let (r, g, b, a) = t.0.into_components();
                     [r, g, b, a]
@@ -202,14 +209,32 @@ impl<'a, B: Backend> System<'a> for UiGlyphsSystem<B> {
 
                 let scale = Scale::uniform(ui_text.font_size);
 
-                let text = match (ui_text.password, editing) {
-                    (false, None) => vec![SectionText {
+                // Bold and italic fonts fall back to the regular font until they are loaded.
+                let span_fonts = rich.as_ref().map_or_else(Vec::new, |rich| {
+                    rich.spans()
+                        .iter()
+                        .map(|span| {
+                            let font = rich.font(span, &ui_text.font);
+                            load_font(fonts_map_ref, glyph_brush_ref, &font_storage, font)
+                                .and_then(|id| Some((id, font_storage.get(font)?)))
+                                .or_else(|| Some((font_id, font_storage.get(&ui_text.font)?)))
+                                .expect("Unreachable: The regular font is loaded")
+                        })
+                        .collect()
+                });
+                let mut placements = Vec::new();
+
+                let text = match (ui_text.password, editing, rich.as_ref()) {
+                    (_, _, Some(rich)) => {
+                        rich_sections(rich, ui_text, &span_fonts, &tint_color, &mut placements)
+                    }
+                    (false, None, None) => vec![SectionText {
                         text: &ui_text.text,
                         scale,
                         color: base_color,
                         font_id,
                     }],
-                    (false, Some(sel)) => {
+                    (false, Some(sel), None) => {
                         if let Some((start, end)) = selection_span(sel, &ui_text.text) {
                             vec![
                                 SectionText {
@@ -231,6 +256,33 @@ impl<'a, B: Backend> System<'a> for UiGlyphsSystem<B> {
                                     font_id,
                                 },
                             ]
+                        } else if let Some(preedit) = sel.preedit() {
+                            // The text being composed is shown at the cursor.
+                            let cursor = ui_text
+                                .text
+                                .grapheme_indices(true)
+                                .nth(sel.cursor_position as usize)
+                                .map_or(ui_text.text.len(), |(index, _)| index);
+                            vec![
+                                SectionText {
+                                    text: &ui_text.text[..cursor],
+                                    scale,
+                                    color: base_color,
+                                    font_id,
+                                },
+                                SectionText {
+                                    text: preedit,
+                                    scale,
+                                    color: mul_blend(&sel.selected_text_color, &tint_color),
+                                    font_id,
+                                },
+                                SectionText {
+                                    text: &ui_text.text[cursor..],
+                                    scale,
+                                    color: base_color,
+                                    font_id,
+                                },
+                            ]
                         } else {
                             vec![SectionText {
                                 text: &ui_text.text,
@@ -240,7 +292,7 @@ impl<'a, B: Backend> System<'a> for UiGlyphsSystem<B> {
                             }]
                         }
                     }
-                    (true, None) => {
+                    (true, None, None) => {
                         let string_len = ui_text.text.graphemes(true).count();
                         password_sections(string_len)
                             .map(|text| SectionText {
@@ -251,7 +303,7 @@ impl<'a, B: Backend> System<'a> for UiGlyphsSystem<B> {
                             })
                             .collect()
                     }
-                    (true, Some(sel)) => {
+                    (true, Some(sel), None) => {
                         let string_len = ui_text.text.graphemes(true).count();
                         let pos = sel.cursor_position;
                         let pos_highlight = sel.cursor_position + sel.highlight_vector;
@@ -325,6 +377,10 @@ impl<'a, B: Backend> System<'a> for UiGlyphsSystem<B> {
                 );
 
                 glyph_brush_ref.queue_custom_layout(section, &layout);
+
+                if let Some(rich) = rich {
+                    place_spans(placements, &ui_text.cached_glyphs, rich);
+                }
             }
         }
 
@@ -548,6 +604,152 @@ impl<'a, B: Backend> System<'a> for UiGlyphsSystem<B> {
     }
 }
 
+/// Registers a loaded font in the glyph brush, returning its id.
+fn load_font(
+    fonts_map: &mut HashMap<u32, FontState>,
+    glyph_brush: &mut GlyphBrush<'static, (u32, UiArgs)>,
+    font_storage: &AssetStorage<FontAsset>,
+    font: &FontHandle,
+) -> Option<FontId> {
+    let font_lookup = fonts_map.entry(font.id()).or_insert(FontState::NotFound);
+    if font_lookup.id().is_none() {
+        if let Some(font) = font_storage.get(font) {
+            *font_lookup = FontState::Ready(glyph_brush.add_font(font.0.clone()));
+        }
+    }
+    font_lookup.id()
+}
+
+/// Something to place over a range of the glyphs of a rich text once it is laid out.
+struct SpanPlacement {
+    kind: PlacementKind,
+    glyphs: Range<usize>,
+    size: f32,
+    ascent: f32,
+    descent: f32,
+}
+
+enum PlacementKind {
+    Link(String),
+    Icon(UiImage),
+}
+
+/// Creates a section for each span of a rich text.
+///
+/// Icons are laid out as a space scaled to be as wide as the text is high.
+fn rich_sections<'a>(
+    rich: &'a UiRichText,
+    ui_text: &UiText,
+    fonts: &[(FontId, &FontAsset)],
+    tint: &[f32; 4],
+    placements: &mut Vec<SpanPlacement>,
+) -> Vec<SectionText<'a>> {
+    let mut glyph_index = 0;
+    rich.spans()
+        .iter()
+        .zip(fonts)
+        .map(|(span, &(font_id, font))| {
+            let size = span.font_size.unwrap_or(ui_text.font_size);
+            let v_metrics = font.0.v_metrics(Scale::uniform(size));
+            let start = glyph_index;
+            let section = if let Some(ref icon) = span.icon {
+                glyph_index += 1;
+                if let Some(image) = rich.icons.get(icon) {
+                    placements.push(SpanPlacement {
+                        kind: PlacementKind::Icon(image.clone()),
+                        glyphs: start..glyph_index,
+                        size,
+                        ascent: v_metrics.ascent,
+                        descent: v_metrics.descent,
+                    });
+                }
+                let space_width = font
+                    .0
+                    .glyph(' ')
+                    .scaled(Scale::uniform(1.))
+                    .h_metrics()
+                    .advance_width
+                    .max(std::f32::EPSILON);
+                SectionText {
+                    text: " ",
+                    scale: Scale {
+                        x: size / space_width,
+                        y: size,
+                    },
+                    color: [0., 0., 0., 0.],
+                    font_id,
+                }
+            } else {
+                // The layout doesn't create glyphs for control characters.
+                glyph_index += span.text.chars().filter(|c| !c.is_control()).count();
+                SectionText {
+                    text: &span.text,
+                    scale: Scale::uniform(size),
+                    color: mul_blend(&span.color.unwrap_or(ui_text.color), tint),
+                    font_id,
+                }
+            };
+            if let Some(ref link) = span.link {
+                placements.push(SpanPlacement {
+                    kind: PlacementKind::Link(link.clone()),
+                    glyphs: start..glyph_index,
+                    size,
+                    ascent: v_metrics.ascent,
+                    descent: v_metrics.descent,
+                });
+            }
+            section
+        })
+        .collect()
+}
+
+/// Computes the rectangles of the links and icons of a rich text from its laid out glyphs.
+fn place_spans(placements: Vec<SpanPlacement>, glyphs: &[CachedGlyph], rich: &mut UiRichText) {
+    for placement in placements {
+        let end = placement.glyphs.end.min(glyphs.len());
+        let start = placement.glyphs.start.min(end);
+        let glyphs = &glyphs[start..end];
+        match placement.kind {
+            PlacementKind::Icon(image) => {
+                if let Some(glyph) = glyphs.first() {
+                    rich.icon_quads.push((
+                        image,
+                        [
+                            glyph.x + placement.size * 0.5,
+                            glyph.y + (placement.ascent + placement.descent) * 0.5,
+                            placement.size,
+                            placement.size,
+                        ],
+                    ));
+                }
+            }
+            PlacementKind::Link(link) => {
+                // One rectangle per line the link spans over.
+                let first_box = rich.link_boxes.len();
+                for glyph in glyphs {
+                    let rect = [
+                        glyph.x,
+                        glyph.y + placement.descent,
+                        glyph.x + glyph.advance_width,
+                        glyph.y + placement.ascent,
+                    ];
+                    let merged = match rich.link_boxes[first_box..].last_mut() {
+                        Some((_, last)) if (last[1] - rect[1]).abs() < std::f32::EPSILON => {
+                            last[0] = last[0].min(rect[0]);
+                            last[2] = last[2].max(rect[2]);
+                            true
+                        }
+                        _ => false,
+                    };
+                    if !merged {
+                        rich.link_boxes.push((link.clone(), rect));
+                    }
+                }
+            }
+        }
+    }
+}
+
 fn create_glyph_texture<B: Backend>(
     factory: &mut Factory<B>,
     queue: QueueId,
@@ -4,13 +4,16 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "profiler")]
 use thread_profiler::profile_scope;
 
+use amethyst_assets::PrefabData;
 use amethyst_core::{
     ecs::prelude::{
-        BitSet, ComponentEvent, Join, ReadExpect, ReadStorage, ReaderId, System, SystemData, World,
-        WriteStorage,
+        BitSet, Component, ComponentEvent, DenseVecStorage, Entities, Join, ReadExpect,
+        ReadStorage, ReaderId, System, SystemData, World, WriteStorage,
     },
     HierarchyEvent, Parent, ParentHierarchy, SystemDesc,
 };
+use amethyst_derive::PrefabData;
+use amethyst_error::Error;
 use amethyst_window::ScreenDimensions;
 
 use super::UiTransform;
@@ -124,6 +127,226 @@ pub enum Stretch {
     },
 }
 
+/// Space left empty between the border of a layout container and its children, in pixels.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Padding {
+    /// Space on the left side.
+    pub left: f32,
+    /// Space on the right side.
+    pub right: f32,
+    /// Space on the top side.
+    pub top: f32,
+    /// Space on the bottom side.
+    pub bottom: f32,
+}
+
+impl Padding {
+    /// Uses the same padding on every side.
+    pub fn uniform(padding: f32) -> Self {
+        Padding {
+            left: padding,
+            right: padding,
+            top: padding,
+            bottom: padding,
+        }
+    }
+}
+
+/// How the children of a `UiLayout` container are placed.
+/// Children are placed in the order they were added to the container.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum Arrangement {
+    /// Places the children from left to right, centered vertically.
+    Horizontal,
+    /// Places the children from top to bottom, centered horizontally.
+    Vertical,
+    /// Places the children from left to right, starting a new row below when the
+    /// container is full.
+    Wrap,
+    /// Places the children in cells of equal size, from left to right and top to bottom.
+    /// The cells share the width of the container and are as high as the highest child.
+    Grid {
+        /// The number of cells in a row.
+        columns: usize,
+    },
+}
+
+/// Component turning a ui element into a container placing its children automatically.
+///
+/// The `UiLayoutSystem` overwrites the position of the children of a container, placing them
+/// according to their size instead. Containers can be nested.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, PrefabData)]
+#[prefab(Component)]
+pub struct UiLayout {
+    /// How the children are placed.
+    pub arrangement: Arrangement,
+    /// Space between two children, in pixels. Used between rows too.
+    #[serde(default)]
+    pub spacing: f32,
+    /// Space between the border of the container and its children.
+    #[serde(default)]
+    pub padding: Padding,
+}
+
+impl UiLayout {
+    /// Creates a container without spacing nor padding.
+    pub fn new(arrangement: Arrangement) -> Self {
+        UiLayout {
+            arrangement,
+            spacing: 0.0,
+            padding: Padding::default(),
+        }
+    }
+
+    /// Sets the space between two children.
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Sets the space between the border of the container and its children.
+    pub fn with_padding(mut self, padding: Padding) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Computes the position of the center of each child, relative to the center of the
+    /// container.
+    pub fn arrange(&self, size: (f32, f32), children: &[(f32, f32)]) -> Vec<(f32, f32)> {
+        let left = -size.0 / 2.0 + self.padding.left;
+        let right = size.0 / 2.0 - self.padding.right;
+        let top = size.1 / 2.0 - self.padding.top;
+        let bottom = -size.1 / 2.0 + self.padding.bottom;
+
+        let tallest = children.iter().map(|child| child.1).fold(0.0f32, f32::max);
+        let mut cursor = (left, top);
+        let mut row_height = 0.0f32;
+        children
+            .iter()
+            .enumerate()
+            .map(|(index, &(width, height))| match self.arrangement {
+                Arrangement::Horizontal => {
+                    let x = cursor.0 + width / 2.0;
+                    cursor.0 += width + self.spacing;
+                    (x, (top + bottom) / 2.0)
+                }
+                Arrangement::Vertical => {
+                    let y = cursor.1 - height / 2.0;
+                    cursor.1 -= height + self.spacing;
+                    ((left + right) / 2.0, y)
+                }
+                Arrangement::Wrap => {
+                    if cursor.0 > left && cursor.0 + width > right {
+                        cursor = (left, cursor.1 - row_height - self.spacing);
+                        row_height = 0.0;
+                    }
+                    let position = (cursor.0 + width / 2.0, cursor.1 - height / 2.0);
+                    cursor.0 += width + self.spacing;
+                    row_height = row_height.max(height);
+                    position
+                }
+                Arrangement::Grid { columns } => {
+                    let columns = columns.max(1);
+                    let cell_width =
+                        (right - left - self.spacing * (columns - 1) as f32) / columns as f32;
+                    let (column, row) = (index % columns, index / columns);
+                    (
+                        left + (cell_width + self.spacing) * column as f32 + cell_width / 2.0,
+                        top - (tallest + self.spacing) * row as f32 - tallest / 2.0,
+                    )
+                }
+            })
+            .collect()
+    }
+}
+
+impl Component for UiLayout {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Places the children of `UiLayout` containers by writing their local position, so it runs
+/// before the `UiTransformSystem` computes the pixel positions.
+///
+/// The sizes of the container and its children are the pixel sizes computed by the
+/// `UiTransformSystem` on the previous frame.
+#[derive(Debug, Default)]
+pub struct UiLayoutSystem;
+
+impl<'a> System<'a> for UiLayoutSystem {
+    type SystemData = (
+        WriteStorage<'a, UiTransform>,
+        ReadStorage<'a, UiLayout>,
+        Entities<'a>,
+        ReadExpect<'a, ParentHierarchy>,
+    );
+
+    fn run(&mut self, (mut transforms, layouts, entities, hierarchy): Self::SystemData) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("ui_layout_system");
+
+        for (container, layout) in (&*entities, &layouts).join() {
+            let size = match transforms.get(container) {
+                Some(t) => (t.pixel_width, t.pixel_height),
+                None => continue,
+            };
+            let children = hierarchy.children(container);
+            let sizes = children
+                .iter()
+                .map(|child| {
+                    transforms
+                        .get(*child)
+                        .map_or((0.0, 0.0), |t| (t.pixel_width, t.pixel_height))
+                })
+                .collect::<Vec<_>>();
+            for (child, offset) in children.iter().zip(layout.arrange(size, &sizes)) {
+                let transform = match transforms.get(*child) {
+                    Some(t) => t,
+                    None => continue,
+                };
+                let local = layout_local_position(transform, size, offset);
+                if (transform.local_x - local.0).abs() < std::f32::EPSILON
+                    && (transform.local_y - local.1).abs() < std::f32::EPSILON
+                {
+                    continue;
+                }
+                // Only borrowing mutably on change, to not flag the transform as modified.
+                let transform = transforms.get_mut(*child).expect("Checked above");
+                transform.local_x = local.0;
+                transform.local_y = local.1;
+            }
+        }
+    }
+}
+
+/// Computes the local position placing the center of a child at `offset` pixels from the center
+/// of its container, given the anchor, pivot and scale mode of the child.
+fn layout_local_position(
+    child: &UiTransform,
+    container_size: (f32, f32),
+    offset: (f32, f32),
+) -> (f32, f32) {
+    let anchor = child.anchor.norm_offset();
+    let pivot = child.pivot.norm_offset();
+    let x = offset.0 - container_size.0 * anchor.0 + child.pixel_width * pivot.0;
+    let y = offset.1 - container_size.1 * anchor.1 + child.pixel_height * pivot.1;
+    match child.scale_mode {
+        ScaleMode::Pixel => (x, y),
+        ScaleMode::Percent => (
+            if container_size.0 > 0.0 {
+                x / container_size.0
+            } else {
+                child.local_x
+            },
+            if container_size.1 > 0.0 {
+                y / container_size.1
+            } else {
+                child.local_y
+            },
+        ),
+    }
+}
+
 /// Builds a `UiTransformSystem`.
 #[derive(Default, Debug)]
 pub struct UiTransformSystemDesc;
@@ -394,3 +617,76 @@ where
         transform.pixel_y += transform.pixel_height * -pivot_norm.1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHILDREN: [(f32, f32); 3] = [(10.0, 10.0), (20.0, 10.0), (10.0, 30.0)];
+
+    #[test]
+    fn stacks() {
+        let layout = UiLayout::new(Arrangement::Horizontal)
+            .with_spacing(5.0)
+            .with_padding(Padding::uniform(2.0));
+        assert_eq!(
+            layout.arrange((100.0, 50.0), &CHILDREN),
+            vec![(-43.0, 0.0), (-23.0, 0.0), (-3.0, 0.0)]
+        );
+
+        let layout = UiLayout::new(Arrangement::Vertical).with_spacing(5.0);
+        assert_eq!(
+            layout.arrange((100.0, 100.0), &CHILDREN),
+            vec![(0.0, 45.0), (0.0, 30.0), (0.0, 5.0)]
+        );
+    }
+
+    #[test]
+    fn wrap() {
+        let layout = UiLayout::new(Arrangement::Wrap).with_spacing(5.0);
+        assert_eq!(
+            layout.arrange((40.0, 100.0), &CHILDREN),
+            vec![(-15.0, 45.0), (5.0, 45.0), (-15.0, 20.0)]
+        );
+    }
+
+    #[test]
+    fn local_position_from_offset() {
+        let mut child = UiTransform::new(
+            String::from("child"),
+            Anchor::TopLeft,
+            Anchor::TopLeft,
+            0.0,
+            0.0,
+            0.0,
+            20.0,
+            10.0,
+        );
+        // Center 5 pixels right of the container's, the top left corner of a 100x50 container
+        // being 50 pixels left and 25 pixels up, and the child's center being 10 pixels right
+        // and 5 pixels down from its pivot.
+        assert_eq!(
+            layout_local_position(&child, (100.0, 50.0), (5.0, 0.0)),
+            (45.0, -20.0)
+        );
+
+        child.anchor = Anchor::Middle;
+        child.pivot = Anchor::Middle;
+        child.scale_mode = ScaleMode::Percent;
+        assert_eq!(
+            layout_local_position(&child, (100.0, 50.0), (5.0, -10.0)),
+            (0.05, -0.2)
+        );
+    }
+
+    #[test]
+    fn grid() {
+        let layout = UiLayout::new(Arrangement::Grid { columns: 2 })
+            .with_spacing(10.0)
+            .with_padding(Padding::uniform(5.0));
+        assert_eq!(
+            layout.arrange((100.0, 100.0), &CHILDREN),
+            vec![(-25.0, 30.0), (25.0, 30.0), (-25.0, -10.0)]
+        );
+    }
+}
@@ -10,6 +10,11 @@
 #![allow(clippy::new_without_default)]
 
 pub use self::{
+    binding::{
+        format_value, UiBinding, UiBindingMode, UiBindingSource, UiComponentBindingSystem,
+        UiComponentBindingSystemDesc, UiResourceBindingSystem, UiResourceBindingSystemDesc,
+        UiValue,
+    },
     blink::BlinkSystem,
     bundle::UiBundle,
     button::{
@@ -17,26 +22,44 @@ pub use self::{
         UiButtonActionRetriggerSystemDesc, UiButtonActionType, UiButtonBuilder,
         UiButtonBuilderResources, UiButtonSystem, UiButtonSystemDesc,
     },
+    drag::{DragAxis, Draggable, UiDragSystem},
     event::{targeted, Interactable, UiEvent, UiEventType, UiMouseSystem},
     event_retrigger::{EventReceiver, EventRetriggerSystem, EventRetriggerSystemDesc},
     font::{
         default::get_default_font,
         systemfont::{default_system_font, get_all_font_handles, list_system_font_families},
     },
+    form::{
+        UiCheckbox, UiCheckboxBuilder, UiCheckboxBuilderResources, UiCheckboxWidget, UiDropdown,
+        UiDropdownBuilder, UiDropdownBuilderResources, UiDropdownItem, UiDropdownWidget,
+        UiFormSystem, UiFormSystemDesc, UiProgressBar, UiProgressBarBuilder,
+        UiProgressBarBuilderResources, UiProgressBarWidget, UiSlider, UiSliderBuilder,
+        UiSliderBuilderResources, UiSliderWidget, UiToggleGroup, UiToggleGroupBuilder,
+        UiToggleGroupBuilderResources, UiToggleGroupWidget,
+    },
     format::{FontAsset, FontHandle, TtfFormat},
     glyphs::{UiGlyphsSystem, UiGlyphsSystemDesc},
     image::UiImage,
     label::{UiLabel, UiLabelBuilder, UiLabelBuilderResources},
-    layout::{Anchor, ScaleMode, Stretch, UiTransformSystem, UiTransformSystemDesc},
+    layout::{
+        Anchor, Arrangement, Padding, ScaleMode, Stretch, UiLayout, UiLayoutSystem,
+        UiTransformSystem, UiTransformSystemDesc,
+    },
+    markup::{parse_markup, strip_markup, TextSpan, UiRichText},
+    modal::{active_modal, focus_scope, is_inside, UiFocusScope, UiModal},
     pass::{DrawUi, DrawUiDesc, RenderUi},
     prefab::{
-        NoCustomUi, ToNativeWidget, UiButtonData, UiCreator, UiFormat, UiImagePrefab, UiLoader,
-        UiLoaderSystem, UiLoaderSystemDesc, UiPrefab, UiTextData, UiTransformData, UiWidget,
+        NoCustomUi, ToNativeWidget, UiButtonData, UiCheckboxData, UiCreator, UiDropdownData,
+        UiFormData, UiFormat, UiImagePrefab, UiLoader, UiLoaderSystem, UiLoaderSystemDesc,
+        UiNavigationData, UiPrefab, UiProgressBarData, UiRichTextData, UiScrollViewData,
+        UiSliderData, UiTextData, UiTransformData, UiWidget,
     },
     resize::{ResizeSystem, ResizeSystemDesc, UiResize},
+    scroll::{clip_rect, ClipRect, UiScrollSystem, UiScrollSystemDesc, UiScrollView},
     selection::{
         Selectable, Selected, SelectionKeyboardSystem, SelectionKeyboardSystemDesc,
-        SelectionMouseSystem, SelectionMouseSystemDesc,
+        SelectionMouseSystem, SelectionMouseSystemDesc, SelectionNavigationSystem, UiNavigation,
+        UiNavigationBindings, UiNavigationDirection,
     },
     selection_order_cache::{CacheSelectionOrderSystem, CachedSelectionOrder},
     sound::{
@@ -44,7 +67,13 @@ pub use self::{
         UiSoundSystem, UiSoundSystemDesc,
     },
     text::{LineMode, TextEditing, TextEditingMouseSystem, TextEditingMouseSystemDesc, UiText},
-    text_editing::{TextEditingInputSystem, TextEditingInputSystemDesc},
+    text_editing::{
+        TextComposition, TextEditingInputSystem, TextEditingInputSystemDesc, TextValidation,
+    },
+    theme::{
+        ActiveUiTheme, UiStyle, UiStyleImage, UiStyled, UiTheme, UiThemeSystem, UiThemeSystemDesc,
+    },
+    tooltip::{UiTooltip, UiTooltipSystem, UiTooltipSystemDesc},
     transform::{UiFinder, UiTransform},
     widgets::{Widget, WidgetId, Widgets},
 };
@@ -52,24 +81,32 @@ pub use self::{
 pub(crate) use amethyst_core::ecs::prelude::Entity;
 pub(crate) use paste;
 
+mod binding;
 mod blink;
 mod bundle;
 mod button;
+mod drag;
 mod event;
 mod event_retrigger;
 mod font;
+mod form;
 mod format;
 mod glyphs;
 mod image;
 mod label;
 mod layout;
+mod markup;
+mod modal;
 mod pass;
 mod prefab;
 mod resize;
+mod scroll;
 mod selection;
 mod selection_order_cache;
 mod sound;
 mod text;
 mod text_editing;
+mod theme;
+mod tooltip;
 mod transform;
 mod widgets;
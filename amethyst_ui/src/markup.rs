@@ -0,0 +1,375 @@
+//! Module holding the rich text markup of `UiText`s.
+
+use std::collections::HashMap;
+
+use amethyst_core::ecs::prelude::{Component, DenseVecStorage};
+
+use crate::{FontHandle, UiImage};
+
+/// A run of text sharing the same style, parsed from markup by `parse_markup`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextSpan {
+    /// The text of the span, without any markup. Empty for icons.
+    pub text: String,
+    /// Rendered with the bold font of the `UiRichText`.
+    pub bold: bool,
+    /// Rendered with the italic font of the `UiRichText`.
+    pub italic: bool,
+    /// Overrides the color of the `UiText`.
+    pub color: Option<[f32; 4]>,
+    /// Overrides the font size of the `UiText`.
+    pub font_size: Option<f32>,
+    /// The target of the link this span is part of.
+    pub link: Option<String>,
+    /// The name of the icon shown instead of text.
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tag {
+    Bold,
+    Italic,
+    Color([f32; 4]),
+    Size(f32),
+    Link(String),
+}
+
+impl Tag {
+    fn name(&self) -> &'static str {
+        match self {
+            Tag::Bold => "b",
+            Tag::Italic => "i",
+            Tag::Color(_) => "color",
+            Tag::Size(_) => "size",
+            Tag::Link(_) => "link",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Markup<'a> {
+    Open(Tag),
+    Close(&'a str),
+    Icon(&'a str),
+}
+
+impl<'a> Markup<'a> {
+    fn parse(tag: &'a str) -> Option<Self> {
+        if tag.starts_with('/') {
+            return match &tag[1..] {
+                name @ "b" | name @ "i" | name @ "color" | name @ "size" | name @ "link" => {
+                    Some(Markup::Close(name))
+                }
+                _ => None,
+            };
+        }
+        let mut parts = tag.splitn(2, '=');
+        match (parts.next(), parts.next()) {
+            (Some("b"), None) => Some(Markup::Open(Tag::Bold)),
+            (Some("i"), None) => Some(Markup::Open(Tag::Italic)),
+            (Some("color"), Some(color)) => parse_color(color).map(|c| Markup::Open(Tag::Color(c))),
+            (Some("size"), Some(size)) => size.parse().ok().map(|s| Markup::Open(Tag::Size(s))),
+            (Some("link"), Some(link)) => Some(Markup::Open(Tag::Link(link.to_string()))),
+            (Some("icon"), Some(icon)) => Some(Markup::Icon(icon)),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a `#rrggbb` or `#rrggbbaa` color.
+fn parse_color(color: &str) -> Option<[f32; 4]> {
+    if !color.starts_with('#') || (color.len() != 7 && color.len() != 9) {
+        return None;
+    }
+    let channel = |i: usize| {
+        color
+            .get(1 + i * 2..3 + i * 2)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .map(|value| f32::from(value) / 255.0)
+    };
+    Some([
+        channel(0)?,
+        channel(1)?,
+        channel(2)?,
+        if color.len() == 9 { channel(3)? } else { 1.0 },
+    ])
+}
+
+fn styled(text: String, stack: &[Tag]) -> TextSpan {
+    let mut span = TextSpan {
+        text,
+        ..TextSpan::default()
+    };
+    for tag in stack {
+        match tag {
+            Tag::Bold => span.bold = true,
+            Tag::Italic => span.italic = true,
+            Tag::Color(color) => span.color = Some(*color),
+            Tag::Size(size) => span.font_size = Some(*size),
+            Tag::Link(link) => span.link = Some(link.clone()),
+        }
+    }
+    span
+}
+
+/// Parses rich text markup into spans.
+///
+/// The markup supports `[b]bold[/b]`, `[i]italic[/i]`, `[color=#ff0000]colored[/color]`
+/// (with an optional alpha channel), `[size=24]sized[/size]`, `[link=target]links[/link]` and
+/// `[icon=name]` inline icons. Tags can be nested, and `[[` writes a literal `[`.
+///
+/// Malformed or unknown tags, and closing tags without a matching opening tag, are kept as
+/// text. Tags left open apply until the end of the text.
+pub fn parse_markup(markup: &str) -> Vec<TextSpan> {
+    let mut spans = Vec::new();
+    let mut stack = Vec::new();
+    let mut current = String::new();
+    let mut rest = markup;
+
+    while let Some(open) = rest.find('[') {
+        current.push_str(&rest[..open]);
+        rest = &rest[open..];
+        if rest.starts_with("[[") {
+            current.push('[');
+            rest = &rest[2..];
+            continue;
+        }
+
+        let parsed = rest
+            .find(']')
+            .and_then(|close| Markup::parse(&rest[1..close]).map(|markup| (markup, close)));
+        let closed_index = match parsed {
+            Some((Markup::Close(name), _)) => {
+                stack.iter().rposition(|tag: &Tag| tag.name() == name)
+            }
+            _ => None,
+        };
+        match parsed {
+            Some((Markup::Close(_), _)) if closed_index.is_none() => {
+                current.push('[');
+                rest = &rest[1..];
+            }
+            Some((markup, close)) => {
+                if !current.is_empty() {
+                    spans.push(styled(
+                        std::mem::replace(&mut current, String::new()),
+                        &stack,
+                    ));
+                }
+                match markup {
+                    Markup::Open(tag) => stack.push(tag),
+                    Markup::Close(_) => {
+                        stack.remove(closed_index.expect("Unreachable: Checked above"));
+                    }
+                    Markup::Icon(icon) => {
+                        let mut span = styled(String::new(), &stack);
+                        span.icon = Some(icon.to_string());
+                        spans.push(span);
+                    }
+                }
+                rest = &rest[close + 1..];
+            }
+            None => {
+                current.push('[');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    current.push_str(rest);
+    if !current.is_empty() {
+        spans.push(styled(current, &stack));
+    }
+    spans
+}
+
+/// Removes the markup of a text, keeping only what would be displayed as text.
+///
+/// Useful to fill an editable `UiText` from a rich one, as editable texts display their
+/// markup as is.
+pub fn strip_markup(markup: &str) -> String {
+    parse_markup(markup)
+        .into_iter()
+        .map(|span| span.text)
+        .collect()
+}
+
+/// Makes the `UiText` of the same entity parsed as markup, see `parse_markup`.
+///
+/// Clicking a link emits a `UiEventType::LinkClick` if the entity is `Interactable`.
+/// Editable and password texts ignore the markup and are displayed as plain text, so that the
+/// cursor matches the typed characters.
+#[derive(Debug, Clone, Default)]
+pub struct UiRichText {
+    /// Font of `[b]` spans, the font of the `UiText` is used if not set.
+    pub bold_font: Option<FontHandle>,
+    /// Font of `[i]` spans, the font of the `UiText` is used if not set.
+    pub italic_font: Option<FontHandle>,
+    /// Font of spans both bold and italic, falling back to the bold then the italic font.
+    pub bold_italic_font: Option<FontHandle>,
+    /// Images shown by `[icon=name]`, sized like the text around them.
+    pub icons: HashMap<String, UiImage>,
+    /// The text `spans` were parsed from.
+    source: Option<String>,
+    spans: Vec<TextSpan>,
+    /// Screen rectangles of the links, as `[min_x, min_y, max_x, max_y]`.
+    pub(crate) link_boxes: Vec<(String, [f32; 4])>,
+    /// Icons to draw, with the center and size of their quads.
+    pub(crate) icon_quads: Vec<(UiImage, [f32; 4])>,
+}
+
+impl UiRichText {
+    /// Creates rich text rendering every span with the font of the `UiText`.
+    pub fn new() -> Self {
+        UiRichText::default()
+    }
+
+    /// Sets the font of bold spans.
+    pub fn with_bold_font(mut self, font: FontHandle) -> Self {
+        self.bold_font = Some(font);
+        self
+    }
+
+    /// Sets the font of italic spans.
+    pub fn with_italic_font(mut self, font: FontHandle) -> Self {
+        self.italic_font = Some(font);
+        self
+    }
+
+    /// Sets the font of spans both bold and italic.
+    pub fn with_bold_italic_font(mut self, font: FontHandle) -> Self {
+        self.bold_italic_font = Some(font);
+        self
+    }
+
+    /// Adds an icon usable with `[icon=name]`.
+    pub fn with_icon<S: Into<String>>(mut self, name: S, image: UiImage) -> Self {
+        self.icons.insert(name.into(), image);
+        self
+    }
+
+    /// The spans of the text, as parsed the last time the text was laid out.
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+
+    /// Parses `text` again if it changed since the last call.
+    pub(crate) fn update_spans(&mut self, text: &str) {
+        if self.source.as_ref().map_or(true, |source| source != text) {
+            self.spans = parse_markup(text);
+            self.source = Some(text.to_string());
+        }
+    }
+
+    /// The font a span is rendered with.
+    pub(crate) fn font<'a>(&'a self, span: &TextSpan, regular: &'a FontHandle) -> &'a FontHandle {
+        let font = match (span.bold, span.italic) {
+            (true, true) => self
+                .bold_italic_font
+                .as_ref()
+                .or_else(|| self.bold_font.as_ref())
+                .or_else(|| self.italic_font.as_ref()),
+            (true, false) => self.bold_font.as_ref(),
+            (false, true) => self.italic_font.as_ref(),
+            (false, false) => None,
+        };
+        font.unwrap_or(regular)
+    }
+
+    /// The target of the link at the given screen position, if any.
+    pub fn link_at(&self, x: f32, y: f32) -> Option<&str> {
+        self.link_boxes
+            .iter()
+            .find(|(_, rect)| x >= rect[0] && y >= rect[1] && x <= rect[2] && y <= rect[3])
+            .map(|(link, _)| link.as_str())
+    }
+}
+
+impl Component for UiRichText {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> TextSpan {
+        TextSpan {
+            text: text.to_string(),
+            ..TextSpan::default()
+        }
+    }
+
+    #[test]
+    fn nested_tags() {
+        let spans = parse_markup("a [b]b [i]bi[/b] i[/i]");
+        assert_eq!(
+            spans,
+            vec![
+                text("a "),
+                TextSpan {
+                    bold: true,
+                    ..text("b ")
+                },
+                TextSpan {
+                    bold: true,
+                    italic: true,
+                    ..text("bi")
+                },
+                TextSpan {
+                    italic: true,
+                    ..text(" i")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn colors_sizes_links_and_icons() {
+        let spans =
+            parse_markup("[color=#ff000080][size=30]Press [icon=a][/size] to [link=help]jump");
+        let red = Some([1.0, 0.0, 0.0, 128.0 / 255.0]);
+        assert_eq!(
+            spans,
+            vec![
+                TextSpan {
+                    color: red,
+                    font_size: Some(30.0),
+                    ..text("Press ")
+                },
+                TextSpan {
+                    color: red,
+                    font_size: Some(30.0),
+                    icon: Some("a".to_string()),
+                    ..text("")
+                },
+                TextSpan {
+                    color: red,
+                    ..text(" to ")
+                },
+                TextSpan {
+                    color: red,
+                    link: Some("help".to_string()),
+                    ..text("jump")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_markup_is_text() {
+        assert_eq!(
+            strip_markup("[[b] [x] [color=red]a[/i] [b"),
+            "[b] [x] [color=red]a[/i] [b"
+        );
+        assert_eq!(strip_markup("[b]bold[/b] [icon=a]"), "bold ");
+    }
+
+    #[test]
+    fn link_hit_test() {
+        let mut rich = UiRichText::new();
+        rich.link_boxes = vec![("a".to_string(), [0.0, 0.0, 10.0, 10.0])];
+        assert_eq!(rich.link_at(5.0, 5.0), Some("a"));
+        assert_eq!(rich.link_at(15.0, 5.0), None);
+    }
+}
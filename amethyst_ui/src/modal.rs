@@ -0,0 +1,90 @@
+//! Modal dialogs and focus scopes.
+
+use amethyst_core::{
+    ecs::{
+        prelude::{Component, Entities, Entity, Join, ReadStorage},
+        storage::{GenericReadStorage, NullStorage},
+    },
+    Hidden, HiddenPropagate, Parent,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::UiTransform;
+
+/// Component making a ui element modal.
+///
+/// While a modal element is visible, only it and its descendants receive ui events and can
+/// be selected, and Tab navigation stays inside of it. When several modal elements are visible,
+/// the one with the highest global z is active.
+///
+/// Clicking outside of an active modal element emits a `UiEventType::ClickOutside` targeting it,
+/// which can be used to close it.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct UiModal;
+
+impl Component for UiModal {
+    type Storage = NullStorage<UiModal>;
+}
+
+/// Component keeping the Tab and directional navigation inside of a ui element, once one of its
+/// descendants is selected.
+///
+/// Modal elements are focus scopes too.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct UiFocusScope;
+
+impl Component for UiFocusScope {
+    type Storage = NullStorage<UiFocusScope>;
+}
+
+/// Checks if `entity` is `ancestor` or one of its descendants.
+pub fn is_inside(entity: Entity, ancestor: Entity, parents: &ReadStorage<'_, Parent>) -> bool {
+    let mut current = entity;
+    loop {
+        if current == ancestor {
+            return true;
+        }
+        match parents.get(current) {
+            Some(parent) => current = parent.entity,
+            None => return false,
+        }
+    }
+}
+
+/// Finds the modal element currently receiving the input, the visible `UiModal` with the
+/// highest global z.
+pub fn active_modal<T>(
+    entities: &Entities<'_>,
+    modals: &ReadStorage<'_, UiModal>,
+    transforms: &T,
+    hiddens: &ReadStorage<'_, Hidden>,
+    hidden_propagates: &ReadStorage<'_, HiddenPropagate>,
+) -> Option<Entity>
+where
+    T: GenericReadStorage<Component = UiTransform>,
+{
+    (&**entities, modals, !hiddens, !hidden_propagates)
+        .join()
+        .filter_map(|(entity, _, _, _)| transforms.get(entity).map(|t| (entity, t.global_z)))
+        .max_by(|(_, z1), (_, z2)| z1.partial_cmp(z2).expect("Unexpected NaN"))
+        .map(|(entity, _)| entity)
+}
+
+/// Finds the element the navigation from `selected` is restricted to: the active modal element
+/// if any, otherwise the nearest `UiFocusScope` containing `selected`.
+pub fn focus_scope(
+    selected: Option<Entity>,
+    modal: Option<Entity>,
+    scopes: &ReadStorage<'_, UiFocusScope>,
+    parents: &ReadStorage<'_, Parent>,
+) -> Option<Entity> {
+    modal.or_else(|| {
+        let mut current = selected?;
+        loop {
+            if scopes.contains(current) {
+                return Some(current);
+            }
+            current = parents.get(current)?.entity;
+        }
+    })
+}
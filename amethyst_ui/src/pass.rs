@@ -1,6 +1,7 @@
 use crate::{
     glyphs::{UiGlyphs, UiGlyphsResource},
-    Selected, TextEditing, UiGlyphsSystemDesc, UiImage, UiTransform,
+    scroll::{clip_rect, ClipRect},
+    Selected, TextEditing, UiGlyphsSystemDesc, UiImage, UiRichText, UiScrollView, UiTransform,
 };
 use amethyst_assets::{AssetStorage, Handle, Loader};
 use amethyst_core::{
@@ -8,7 +9,7 @@ use amethyst_core::{
         hibitset::BitSet, DispatcherBuilder, Entities, Entity, Join, Read, ReadExpect, ReadStorage,
         SystemData, World,
     },
-    Hidden, HiddenPropagate, SystemDesc,
+    Hidden, HiddenPropagate, Parent, SystemDesc,
 };
 use amethyst_error::Error;
 use amethyst_rendy::{
@@ -234,6 +235,9 @@ impl<B: Backend> RenderGroup<B, World> for DrawUi<B> {
             selected,
             tints,
             glyphs,
+            rich_texts,
+            parents,
+            scroll_views,
             glyphs_res,
             screen_dimesnions,
         ) = <(
@@ -246,6 +250,9 @@ impl<B: Backend> RenderGroup<B, World> for DrawUi<B> {
             ReadStorage<'_, Selected>,
             ReadStorage<'_, Tint>,
             ReadStorage<'_, UiGlyphs>,
+            ReadStorage<'_, UiRichText>,
+            ReadStorage<'_, Parent>,
+            ReadStorage<'_, UiScrollView>,
             ReadExpect<'_, UiGlyphsResource>,
             ReadExpect<'_, ScreenDimensions>,
         ) as SystemData>::fetch(resources);
@@ -341,6 +348,13 @@ impl<B: Backend> RenderGroup<B, World> for DrawUi<B> {
                 .get(entity)
                 .expect("Unreachable: Entity is guaranteed to be present based on earlier actions");
 
+            // Skip entities scrolled out of view
+            let clip = clip_rect(entity, &transforms, &parents, &scroll_views);
+            if clip.map_or(false, |clip| clip.is_empty()) {
+                continue;
+            }
+            let clip = clip.as_ref();
+
             let tint = tints.get(entity).map(|t| {
                 let (r, g, b, a) = t.0.into_components();
                 [r, g, b, a]
@@ -354,6 +368,7 @@ impl<B: Backend> RenderGroup<B, World> for DrawUi<B> {
                     transform,
                     image,
                     &tint,
+                    clip,
                     white_tex_id,
                     &mut self.textures,
                     &mut self.batches,
@@ -363,8 +378,13 @@ impl<B: Backend> RenderGroup<B, World> for DrawUi<B> {
 
             if let Some(glyph_data) = glyphs.get(entity) {
                 if !glyph_data.sel_vertices.is_empty() {
-                    self.batches
-                        .insert(white_tex_id, glyph_data.sel_vertices.iter().cloned());
+                    self.batches.insert(
+                        white_tex_id,
+                        glyph_data
+                            .sel_vertices
+                            .iter()
+                            .filter_map(|args| clip_args(*args, clip)),
+                    );
                 }
 
                 // blinking cursor
@@ -401,20 +421,51 @@ impl<B: Backend> RenderGroup<B, World> for DrawUi<B> {
 
                         self.batches.insert(
                             white_tex_id,
-                            Some(UiArgs {
-                                coords: [x, y].into(),
-                                dimensions: [w, h].into(),
-                                tex_coord_bounds: [0., 0., 1., 1.].into(),
-                                color: tint.unwrap_or([1., 1., 1., 1.]).into(),
-                                color_bias: [0., 0., 0., 0.].into(),
-                            }),
+                            clip_args(
+                                UiArgs {
+                                    coords: [x, y].into(),
+                                    dimensions: [w, h].into(),
+                                    tex_coord_bounds: [0., 0., 1., 1.].into(),
+                                    color: tint.unwrap_or([1., 1., 1., 1.]).into(),
+                                    color_bias: [0., 0., 0., 0.].into(),
+                                },
+                                clip,
+                            ),
                         )
                     }
                 }
 
                 if !glyph_data.vertices.is_empty() {
-                    self.batches
-                        .insert(glyph_tex_id, glyph_data.vertices.iter().cloned());
+                    self.batches.insert(
+                        glyph_tex_id,
+                        glyph_data
+                            .vertices
+                            .iter()
+                            .filter_map(|args| clip_args(*args, clip)),
+                    );
+                }
+            }
+
+            // inline icons of rich texts
+            if let Some(rich_text) = rich_texts.get(entity) {
+                for (icon, [x, y, width, height]) in &rich_text.icon_quads {
+                    let mut icon_transform = transform.clone();
+                    icon_transform.pixel_x = *x;
+                    icon_transform.pixel_y = *y;
+                    icon_transform.pixel_width = *width;
+                    icon_transform.pixel_height = *height;
+                    let this_changed = render_image(
+                        factory,
+                        resources,
+                        &icon_transform,
+                        icon,
+                        &tint,
+                        clip,
+                        white_tex_id,
+                        &mut self.textures,
+                        &mut self.batches,
+                    );
+                    changed = changed || this_changed;
                 }
             }
         }
@@ -532,12 +583,33 @@ fn mul_blend(a: &[f32; 4], b: &[f32; 4]) -> [f32; 4] {
     [a[0] * b[0], a[1] * b[1], a[2] * b[2], a[3] * b[3]]
 }
 
+/// Cuts the parts of a quad lying outside of the clip rectangle of its entity.
+fn clip_args(args: UiArgs, clip: Option<&ClipRect>) -> Option<UiArgs> {
+    let clip = match clip {
+        Some(clip) => clip,
+        None => return Some(args),
+    };
+    let (coords, dimensions, tex_coord_bounds) = clip.clip_quad(
+        args.coords.into(),
+        args.dimensions.into(),
+        args.tex_coord_bounds.into(),
+    )?;
+    Some(UiArgs {
+        coords: coords.into(),
+        dimensions: dimensions.into(),
+        tex_coord_bounds: tex_coord_bounds.into(),
+        ..args
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 fn render_image<B: Backend>(
     factory: &Factory<B>,
     resources: &World,
     transform: &UiTransform,
     raw_image: &UiImage,
     tint: &Option<[f32; 4]>,
+    clip: Option<&ClipRect>,
     white_tex_id: TextureId,
     textures: &mut TextureSub<B>,
     batches: &mut OrderedOneLevelBatch<TextureId, UiArgs>,
@@ -590,7 +662,7 @@ fn render_image<B: Backend>(
                 tex,
                 hal::image::Layout::ShaderReadOnlyOptimal,
             ) {
-                batches.insert(tex_id, Some(args));
+                batches.insert(tex_id, clip_args(args, clip));
                 this_changed
             } else {
                 false
@@ -603,7 +675,7 @@ fn render_image<B: Backend>(
                 tex,
                 hal::image::Layout::ShaderReadOnlyOptimal,
             ) {
-                batches.insert(tex_id, Some(args));
+                batches.insert(tex_id, clip_args(args, clip));
                 this_changed
             } else {
                 false
@@ -618,7 +690,7 @@ fn render_image<B: Backend>(
                     &sprite_sheet.texture,
                     hal::image::Layout::ShaderReadOnlyOptimal,
                 ) {
-                    batches.insert(tex_id, Some(args));
+                    batches.insert(tex_id, clip_args(args, clip));
                     this_changed
                 } else {
                     false
@@ -696,7 +768,7 @@ fn render_image<B: Backend>(
                         .into();
                         temp_args.dimensions = [x_dimensions[x], y_dimensions[y]].into();
                         temp_args.coords = [x_coords[x], y_coords[y]].into();
-                        batches.insert(tex_id, Some(temp_args));
+                        batches.insert(tex_id, clip_args(temp_args, clip));
                     }
                 }
 
@@ -706,7 +778,7 @@ fn render_image<B: Backend>(
             }
         }
         _ => {
-            batches.insert(white_tex_id, Some(args));
+            batches.insert(white_tex_id, clip_args(args, clip));
             false
         }
     }
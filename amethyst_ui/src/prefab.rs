@@ -1,6 +1,7 @@
 use derivative::Derivative;
 use serde::de::DeserializeOwned;
 use std::{
+    collections::HashMap,
     fmt::{Debug, Formatter},
     marker::PhantomData,
 };
@@ -19,13 +20,17 @@ use amethyst_core::{
 };
 use amethyst_error::{format_err, Error, ResultExt};
 use amethyst_rendy::TexturePrefab;
+use log::warn;
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    get_default_font, Anchor, FontAsset, Interactable, LineMode, Selectable, Stretch, TextEditing,
-    UiButton, UiButtonAction, UiButtonActionRetrigger, UiButtonActionType, UiImage,
-    UiPlaySoundAction, UiSoundRetrigger, UiText, UiTransform, WidgetId, Widgets,
+    get_default_font, Anchor, Arrangement, Draggable, FontAsset, Interactable, LineMode,
+    Selectable, Stretch, TextEditing, TextValidation, UiBinding, UiButton, UiButtonAction,
+    UiButtonActionRetrigger, UiButtonActionType, UiCheckbox, UiDropdown, UiDropdownItem,
+    UiFocusScope, UiImage, UiLayout, UiModal, UiNavigation, UiPlaySoundAction, UiProgressBar,
+    UiRichText, UiScrollView, UiSlider, UiSoundRetrigger, UiStyled, UiText, UiToggleGroup,
+    UiTransform, WidgetId, Widgets,
 };
 
 /// Loadable `UiTransform` data.
@@ -75,6 +80,18 @@ pub struct UiTransformData<G> {
     /// this ordering backwards.
     // TODO: Make full prefab for Selectable.
     pub selectable: Option<u32>,
+    /// Lets the user move the element by dragging it, requires `mouse_reactive`.
+    pub draggable: Option<Draggable>,
+    /// Name of the `UiStyle` of the active `UiTheme` applied to this element.
+    pub style: Option<String>,
+    /// Makes the element a `UiModal`, blocking the input to the elements behind it.
+    pub modal: bool,
+    /// Makes the element a `UiFocusScope`, keeping the navigation inside of it.
+    pub focus_scope: bool,
+    /// Overrides the elements selected when navigating away from this one, see `UiNavigation`.
+    pub navigation: Option<UiNavigationData>,
+    /// Binds the element to a field of a resource or component, see `UiBinding`.
+    pub binding: Option<UiBinding>,
     #[serde(skip)]
     _phantom: PhantomData<G>,
 }
@@ -144,6 +161,12 @@ where
         WriteStorage<'a, Interactable>,
         WriteStorage<'a, HiddenPropagate>,
         WriteStorage<'a, Selectable<G>>,
+        WriteStorage<'a, Draggable>,
+        WriteStorage<'a, UiStyled>,
+        WriteStorage<'a, UiModal>,
+        WriteStorage<'a, UiFocusScope>,
+        WriteStorage<'a, UiBinding>,
+        WriteStorage<'a, UiNavigation>,
     );
     type Result = ();
 
@@ -151,7 +174,7 @@ where
         &self,
         entity: Entity,
         system_data: &mut Self::SystemData,
-        _: &[Entity],
+        entities: &[Entity],
         _: &[Entity],
     ) -> Result<(), Error> {
         let mut transform = UiTransform::new(
@@ -186,10 +209,82 @@ where
             system_data.3.insert(entity, Selectable::<G>::new(u))?;
         }
 
+        if let Some(ref draggable) = self.draggable {
+            system_data.4.insert(entity, draggable.clone())?;
+        }
+
+        if let Some(ref style) = self.style {
+            system_data.5.insert(entity, UiStyled::new(style.clone()))?;
+        }
+
+        if self.modal {
+            system_data.6.insert(entity, UiModal)?;
+        }
+
+        if self.focus_scope {
+            system_data.7.insert(entity, UiFocusScope)?;
+        }
+
+        if let Some(ref binding) = self.binding {
+            system_data.8.insert(entity, binding.clone())?;
+        }
+
+        if let Some(ref navigation) = self.navigation {
+            system_data
+                .9
+                .insert(entity, navigation.to_navigation(entities))?;
+        }
+
         Ok(())
     }
 }
 
+/// Loadable `UiNavigation` data, naming the neighbours by the id of their `UiTransformData`.
+///
+/// The neighbours must be part of the same ui prefab.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UiNavigationData {
+    /// Id of the element selected when navigating up.
+    pub up: Option<String>,
+    /// Id of the element selected when navigating down.
+    pub down: Option<String>,
+    /// Id of the element selected when navigating left.
+    pub left: Option<String>,
+    /// Id of the element selected when navigating right.
+    pub right: Option<String>,
+    // The indices of the neighbours in the prefab, up, down, left and right. Filled in by
+    // `resolve_navigation` once the whole ui tree is walked.
+    #[serde(skip)]
+    indices: [Option<usize>; 4],
+}
+
+impl UiNavigationData {
+    /// Looks up the index of each neighbour in the ids of the prefab entities.
+    fn resolve(&mut self, ids: &[Option<String>]) {
+        let names = [&self.up, &self.down, &self.left, &self.right];
+        for (index, name) in self.indices.iter_mut().zip(names.iter()) {
+            *index = name.as_ref().and_then(|name| {
+                let found = ids.iter().position(|id| id.as_ref() == Some(name));
+                if found.is_none() {
+                    warn!("No ui element with the id {} to navigate to", name);
+                }
+                found
+            });
+        }
+    }
+
+    fn to_navigation(&self, entities: &[Entity]) -> UiNavigation {
+        let entity = |index: Option<usize>| index.and_then(|i| entities.get(i).cloned());
+        UiNavigation {
+            up: entity(self.indices[0]),
+            down: entity(self.indices[1]),
+            left: entity(self.indices[2]),
+            right: entity(self.indices[3]),
+        }
+    }
+}
+
 /// Loadable `UiText` data
 ///
 /// ### Type parameters:
@@ -216,6 +311,9 @@ pub struct UiTextData {
     /// Optionally make the text editable
     #[serde(default)]
     pub editable: Option<TextEditingPrefab>,
+    /// Optionally parse the text as markup
+    #[serde(default)]
+    pub rich: Option<UiRichTextData>,
 }
 impl Debug for UiTextData {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -236,6 +334,38 @@ impl Debug for UiTextData {
             .field("align", &self.align)
             .field("line_mode", &self.line_mode)
             .field("editable", &self.editable)
+            .field("rich", &self.rich)
+            .finish()
+    }
+}
+
+/// Loadable `UiRichText` data
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UiRichTextData {
+    /// Font of bold spans
+    pub bold_font: Option<AssetPrefab<FontAsset>>,
+    /// Font of italic spans
+    pub italic_font: Option<AssetPrefab<FontAsset>>,
+    /// Font of spans both bold and italic
+    pub bold_italic_font: Option<AssetPrefab<FontAsset>>,
+    /// Images of the inline icons, by name
+    pub icons: HashMap<String, UiImageLoadPrefab>,
+}
+
+impl Debug for UiRichTextData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let font = |font: &Option<AssetPrefab<FontAsset>>| match font.as_ref() {
+            Some(AssetPrefab::File(path, _)) => format!("<Font:{}>", path),
+            Some(_) => "<Font>".to_string(),
+            None => "None".to_string(),
+        };
+
+        f.debug_struct("UiRichTextData")
+            .field("bold_font", &font(&self.bold_font))
+            .field("italic_font", &font(&self.italic_font))
+            .field("bold_italic_font", &font(&self.bold_italic_font))
+            .field("icons", &self.icons)
             .finish()
     }
 }
@@ -252,6 +382,8 @@ pub struct TextEditingPrefab {
     pub selected_background_color: [f32; 4],
     /// Use block cursor instead of line cursor
     pub use_block_cursor: bool,
+    /// Restricts the text that can be typed or pasted
+    pub validation: TextValidation,
 }
 
 impl Default for TextEditingPrefab {
@@ -261,6 +393,7 @@ impl Default for TextEditingPrefab {
             selected_text_color: [0., 0., 0., 1.],
             selected_background_color: [1., 1., 1., 1.],
             use_block_cursor: false,
+            validation: TextValidation::Any,
         }
     }
 }
@@ -269,7 +402,9 @@ impl<'a> PrefabData<'a> for UiTextData {
     type SystemData = (
         WriteStorage<'a, UiText>,
         WriteStorage<'a, TextEditing>,
+        WriteStorage<'a, UiRichText>,
         <AssetPrefab<FontAsset> as PrefabData<'a>>::SystemData,
+        <UiImageLoadPrefab as PrefabData<'a>>::SystemData,
     );
     type Result = ();
 
@@ -280,7 +415,8 @@ impl<'a> PrefabData<'a> for UiTextData {
         _: &[Entity],
         _: &[Entity],
     ) -> Result<(), Error> {
-        let (ref mut texts, ref mut editables, ref mut fonts) = system_data;
+        let (ref mut texts, ref mut editables, ref mut rich_texts, ref mut fonts, ref mut images) =
+            system_data;
         let font_handle = self
             .font
             .as_ref()
@@ -306,9 +442,26 @@ impl<'a> PrefabData<'a> for UiTextData {
                     editing.selected_text_color,
                     editing.selected_background_color,
                     editing.use_block_cursor,
-                ),
+                )
+                .with_validation(editing.validation.clone()),
             )?;
         }
+        if let Some(ref rich) = self.rich {
+            let mut font = |font: &Option<AssetPrefab<FontAsset>>| {
+                font.as_ref()
+                    .map(|font| font.add_to_entity(entity, fonts, &[], &[]))
+                    .transpose()
+            };
+            let mut rich_text = UiRichText::new();
+            rich_text.bold_font = font(&rich.bold_font)?;
+            rich_text.italic_font = font(&rich.italic_font)?;
+            rich_text.bold_italic_font = font(&rich.bold_italic_font)?;
+            for (name, icon) in &rich.icons {
+                let icon = icon.add_to_entity(entity, images, &[], &[])?;
+                rich_text.icons.insert(name.clone(), icon);
+            }
+            rich_texts.insert(entity, rich_text)?;
+        }
         Ok(())
     }
 
@@ -317,14 +470,31 @@ impl<'a> PrefabData<'a> for UiTextData {
         progress: &mut ProgressCounter,
         system_data: &mut Self::SystemData,
     ) -> Result<bool, Error> {
-        let (_, _, ref mut fonts) = system_data;
+        let (_, _, _, ref mut fonts, ref mut images) = system_data;
 
-        self.font
+        let mut ret = self
+            .font
             .get_or_insert_with(|| {
                 let (ref loader, _, ref storage) = fonts;
                 AssetPrefab::Handle(get_default_font(loader, storage))
             })
-            .load_sub_assets(progress, fonts)
+            .load_sub_assets(progress, fonts)?;
+        if let Some(ref mut rich) = self.rich {
+            for font in vec![
+                &mut rich.bold_font,
+                &mut rich.italic_font,
+                &mut rich.bold_italic_font,
+            ]
+            .into_iter()
+            .filter_map(Option::as_mut)
+            {
+                ret |= font.load_sub_assets(progress, fonts)?;
+            }
+            for icon in rich.icons.values_mut() {
+                ret |= icon.load_sub_assets(progress, images)?;
+            }
+        }
+        Ok(ret)
     }
 }
 
@@ -682,6 +852,298 @@ where
     }
 }
 
+/// Loadable `UiScrollView` data
+#[derive(Debug, Clone, Deserialize, Serialize, Derivative)]
+#[serde(default)]
+#[derivative(Default)]
+pub struct UiScrollViewData {
+    /// Allows scrolling horizontally.
+    pub horizontal: bool,
+    /// Allows scrolling vertically.
+    #[derivative(Default(value = "true"))]
+    pub vertical: bool,
+    /// Distance scrolled by a mouse wheel step or an arrow key, in pixels.
+    #[derivative(Default(value = "20.0"))]
+    pub step: f32,
+    /// Fraction of the velocity kept after one second when the content is released.
+    #[derivative(Default(value = "0.05"))]
+    pub inertia: f32,
+    /// Distance the pointer has to move while pressed before the content is dragged, in pixels.
+    #[derivative(Default(value = "8.0"))]
+    pub drag_threshold: f32,
+    // this `scrollbar` is "transplanted" into the scrollbar thumb entities inside of the
+    // `walk_ui_tree` function. It means that it will always be `None` during `add_to_entity`.
+    /// Image of the scrollbar thumbs, no scrollbars are shown without it.
+    pub scrollbar: Option<UiImageLoadPrefab>,
+    /// Thickness of the scrollbars, in pixels.
+    #[derivative(Default(value = "8.0"))]
+    pub scrollbar_width: f32,
+    #[serde(skip)]
+    has_scrollbars: bool,
+}
+
+impl<'a> PrefabData<'a> for UiScrollViewData {
+    type SystemData = WriteStorage<'a, UiScrollView>;
+    type Result = ();
+
+    fn add_to_entity(
+        &self,
+        entity: Entity,
+        scroll_views: &mut Self::SystemData,
+        _: &[Entity],
+        children: &[Entity],
+    ) -> Result<(), Error> {
+        let content = *children.get(0).expect("Invalid: Should have content child");
+        // The scrollbar thumbs come after the content, horizontal first.
+        let mut bars = children.iter().skip(1).cloned();
+        let (horizontal_bar, vertical_bar) = if self.has_scrollbars {
+            (
+                if self.horizontal { bars.next() } else { None },
+                if self.vertical { bars.next() } else { None },
+            )
+        } else {
+            (None, None)
+        };
+
+        let mut scroll_view = UiScrollView::new(content)
+            .with_axes(self.horizontal, self.vertical)
+            .with_scrollbars(horizontal_bar, vertical_bar);
+        scroll_view.step = self.step;
+        scroll_view.inertia = self.inertia;
+        scroll_view.drag_threshold = self.drag_threshold;
+        scroll_views.insert(entity, scroll_view)?;
+        Ok(())
+    }
+}
+
+/// Loadable `UiSlider` data
+#[derive(Debug, Clone, Deserialize, Serialize, Derivative)]
+#[serde(default)]
+#[derivative(Default)]
+pub struct UiSliderData {
+    /// Value at the left, or bottom, end of the track.
+    pub min: f32,
+    /// Value at the right, or top, end of the track.
+    #[derivative(Default(value = "1.0"))]
+    pub max: f32,
+    /// Initial value
+    pub value: f32,
+    /// The value is rounded to multiples of `step`, zero means no rounding.
+    pub step: f32,
+    /// Moves the handle from bottom to top instead of left to right.
+    pub vertical: bool,
+    // this `handle` is "transplanted" into the handle entity inside of the `walk_ui_tree`
+    // function. It means that it will always be `None` during `add_to_entity`.
+    /// Image of the handle
+    #[derivative(Default(value = "Some(UiImageLoadPrefab::SolidColor(0.4, 0.4, 0.4, 1.0))"))]
+    pub handle: Option<UiImageLoadPrefab>,
+    /// Size of the handle, in pixels.
+    #[derivative(Default(value = "(16.0, 16.0)"))]
+    pub handle_size: (f32, f32),
+}
+
+/// Loadable `UiCheckbox` data
+#[derive(Debug, Clone, Deserialize, Serialize, Derivative)]
+#[serde(default)]
+#[derivative(Default)]
+pub struct UiCheckboxData {
+    /// Initial state of the box
+    pub checked: bool,
+    // this `check` is "transplanted" into the check mark entity inside of the `walk_ui_tree`
+    // function. It means that it will always be `None` during `add_to_entity`.
+    /// Image of the check mark, covering the middle of the box.
+    #[derivative(Default(value = "Some(UiImageLoadPrefab::SolidColor(0.2, 0.2, 0.2, 1.0))"))]
+    pub check: Option<UiImageLoadPrefab>,
+    /// Text shown at the right of the box, clicking it toggles the box too.
+    pub label: Option<UiTextData>,
+    /// Width of the label, in pixels.
+    #[derivative(Default(value = "128.0"))]
+    pub label_width: f32,
+}
+
+/// Loadable `UiDropdown` data
+#[derive(Deserialize, Serialize, Clone, Derivative)]
+#[serde(default)]
+#[derivative(Default)]
+pub struct UiDropdownData {
+    /// Text of each option
+    pub options: Vec<String>,
+    /// Index of the initially selected option
+    pub selected: usize,
+    /// Font size
+    #[derivative(Default(value = "20.0"))]
+    pub font_size: f32,
+    /// Font
+    pub font: Option<AssetPrefab<FontAsset>>,
+    /// Text color
+    #[derivative(Default(value = "[0.0, 0.0, 0.0, 1.0]"))]
+    pub text_color: [f32; 4],
+    // this `list_image` is "transplanted" into the option entities inside of the `walk_ui_tree`
+    // function. It means that it will always be `None` during `add_to_entity`.
+    /// Background image of each option
+    #[derivative(Default(value = "Some(UiImageLoadPrefab::SolidColor(0.92, 0.92, 0.92, 1.0))"))]
+    pub list_image: Option<UiImageLoadPrefab>,
+}
+
+impl Debug for UiDropdownData {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let font = match self.font.as_ref() {
+            Some(asset_prefab) => match asset_prefab {
+                AssetPrefab::File(path, _) => format!("<Font:{}>", path),
+                _ => "<Font>".to_string(),
+            },
+            _ => "<Font>".to_string(),
+        };
+
+        f.debug_struct("UiDropdownData")
+            .field("options", &self.options)
+            .field("selected", &self.selected)
+            .field("font_size", &self.font_size)
+            .field("font", &font)
+            .field("text_color", &self.text_color)
+            .field("list_image", &self.list_image)
+            .finish()
+    }
+}
+
+/// Loadable `UiProgressBar` data
+#[derive(Debug, Clone, Deserialize, Serialize, Derivative)]
+#[serde(default)]
+#[derivative(Default)]
+pub struct UiProgressBarData {
+    /// Initial value, between 0 and 1
+    pub value: f32,
+    // this `fill` is "transplanted" into the fill entity inside of the `walk_ui_tree`
+    // function. It means that it will always be `None` during `add_to_entity`.
+    /// Image of the filled part of the bar
+    #[derivative(Default(value = "Some(UiImageLoadPrefab::SolidColor(0.3, 0.6, 0.3, 1.0))"))]
+    pub fill: Option<UiImageLoadPrefab>,
+}
+
+/// Loadable form widget components, created by `walk_ui_tree` from the form widgets of
+/// `UiWidget`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub enum UiFormData {
+    /// A `UiSlider`, moving its first child.
+    Slider {
+        /// Value at the start of the track.
+        min: f32,
+        /// Value at the end of the track.
+        max: f32,
+        /// Initial value.
+        value: f32,
+        /// Rounding of the value.
+        step: f32,
+        /// Moves the handle from bottom to top.
+        vertical: bool,
+    },
+    /// A `UiCheckbox`, showing its first child while checked.
+    Checkbox {
+        /// Initial state.
+        checked: bool,
+    },
+    /// A `UiToggleGroup` of the children of the entity.
+    ToggleGroup {
+        /// Initially checked option.
+        selected: Option<usize>,
+    },
+    /// A `UiDropdown`, writing into its first child and opening its second one.
+    Dropdown {
+        /// Text of each option.
+        options: Vec<String>,
+        /// Initially selected option.
+        selected: usize,
+    },
+    /// The list of a `UiDropdown`, its children being the options.
+    DropdownList,
+    /// A `UiProgressBar`, resizing its first child.
+    ProgressBar {
+        /// Initial value.
+        value: f32,
+    },
+}
+
+impl<'a> PrefabData<'a> for UiFormData {
+    type SystemData = (
+        WriteStorage<'a, UiSlider>,
+        WriteStorage<'a, UiCheckbox>,
+        WriteStorage<'a, UiToggleGroup>,
+        WriteStorage<'a, UiDropdown>,
+        WriteStorage<'a, UiDropdownItem>,
+        WriteStorage<'a, UiProgressBar>,
+    );
+    type Result = ();
+
+    fn add_to_entity(
+        &self,
+        entity: Entity,
+        system_data: &mut Self::SystemData,
+        _: &[Entity],
+        children: &[Entity],
+    ) -> Result<(), Error> {
+        let (
+            ref mut sliders,
+            ref mut checkboxes,
+            ref mut groups,
+            ref mut dropdowns,
+            ref mut items,
+            ref mut progress_bars,
+        ) = system_data;
+
+        match self {
+            UiFormData::Slider {
+                min,
+                max,
+                value,
+                step,
+                vertical,
+            } => {
+                let handle = *children.get(0).expect("Invalid: Should have handle child");
+                let mut slider = UiSlider::new(handle, *min, *max)
+                    .with_step(*step)
+                    .with_value(*value);
+                slider.vertical = *vertical;
+                sliders.insert(entity, slider)?;
+            }
+            UiFormData::Checkbox { checked } => {
+                let check = *children.get(0).expect("Invalid: Should have check child");
+                checkboxes.insert(entity, UiCheckbox::new(check).with_checked(*checked))?;
+            }
+            UiFormData::ToggleGroup { selected } => {
+                let mut group = UiToggleGroup::new(children.to_vec());
+                if let Some(selected) = selected {
+                    group.select(*selected);
+                }
+                groups.insert(entity, group)?;
+            }
+            UiFormData::Dropdown { options, selected } => {
+                let text = *children.get(0).expect("Invalid: Should have text child");
+                let list = *children.get(1).expect("Invalid: Should have list child");
+                dropdowns.insert(
+                    entity,
+                    UiDropdown::new(options.clone(), text, list).with_selected(*selected),
+                )?;
+            }
+            UiFormData::DropdownList => {
+                for (index, item) in children.iter().enumerate() {
+                    items.insert(
+                        *item,
+                        UiDropdownItem {
+                            list: entity,
+                            index,
+                        },
+                    )?;
+                }
+            }
+            UiFormData::ProgressBar { value } => {
+                let fill = *children.get(0).expect("Invalid: Should have fill child");
+                progress_bars.insert(entity, UiProgressBar::new(fill).with_value(*value))?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Loadable ui components
 ///
 /// ### Type parameters:
@@ -704,6 +1166,31 @@ where
         /// Child widgets
         children: Vec<UiWidget<C, W>>,
     },
+    /// Container widget placing its children automatically
+    Layout {
+        /// Spatial information for the container
+        transform: UiTransformData<G>,
+        /// How the children are placed
+        layout: UiLayout,
+        /// Background image
+        #[serde(default = "default_container_image")]
+        background: Option<UiImagePrefab>,
+        /// Child widgets
+        children: Vec<UiWidget<C, W>>,
+    },
+    /// Scroll view widget, showing a part of its content
+    ScrollView {
+        /// Spatial information for the viewport
+        transform: UiTransformData<G>,
+        /// Background image
+        #[serde(default = "default_container_image")]
+        background: Option<UiImagePrefab>,
+        /// Scrolling behaviour
+        #[serde(default)]
+        scroll: UiScrollViewData,
+        /// The scrolled widget
+        content: Box<UiWidget<C, W>>,
+    },
     /// Image widget
     Image {
         /// Spatial information
@@ -725,6 +1212,65 @@ where
         /// Button
         button: UiButtonData<W>,
     },
+    /// Slider widget, the transform being the one of the track
+    Slider {
+        /// Spatial information
+        transform: UiTransformData<G>,
+        /// Image of the track
+        #[serde(default = "default_form_image")]
+        background: Option<UiImagePrefab>,
+        /// Slider
+        #[serde(default)]
+        slider: UiSliderData,
+    },
+    /// Checkbox widget, the transform being the one of the box
+    Checkbox {
+        /// Spatial information
+        transform: UiTransformData<G>,
+        /// Image of the box
+        #[serde(default = "default_form_image")]
+        background: Option<UiImagePrefab>,
+        /// Checkbox
+        #[serde(default)]
+        checkbox: UiCheckboxData,
+    },
+    /// Group of checkboxes, checking one of them unchecks the others
+    ToggleGroup {
+        /// Spatial information for the container
+        transform: UiTransformData<G>,
+        /// Background image
+        #[serde(default = "default_container_image")]
+        background: Option<UiImagePrefab>,
+        /// How the options are placed
+        #[serde(default)]
+        layout: Option<UiLayout>,
+        /// Index of the initially checked option
+        #[serde(default)]
+        selected: Option<usize>,
+        /// Option widgets, usually `Checkbox`es
+        options: Vec<UiWidget<C, W>>,
+    },
+    /// Dropdown widget
+    Dropdown {
+        /// Spatial information for the closed dropdown
+        transform: UiTransformData<G>,
+        /// Background image
+        #[serde(default = "default_form_image")]
+        background: Option<UiImagePrefab>,
+        /// Dropdown
+        dropdown: UiDropdownData,
+    },
+    /// Progress bar widget
+    ProgressBar {
+        /// Spatial information
+        transform: UiTransformData<G>,
+        /// Image of the empty bar
+        #[serde(default = "default_form_image")]
+        background: Option<UiImagePrefab>,
+        /// Progress bar
+        #[serde(default)]
+        progress: UiProgressBarData,
+    },
     /// Custom UI widget
     Custom(Box<C>),
 }
@@ -738,9 +1284,16 @@ where
     pub fn transform(&self) -> Option<&UiTransformData<G>> {
         match self {
             UiWidget::Container { ref transform, .. } => Some(transform),
+            UiWidget::Layout { ref transform, .. } => Some(transform),
+            UiWidget::ScrollView { ref transform, .. } => Some(transform),
             UiWidget::Image { ref transform, .. } => Some(transform),
             UiWidget::Label { ref transform, .. } => Some(transform),
             UiWidget::Button { ref transform, .. } => Some(transform),
+            UiWidget::Slider { ref transform, .. } => Some(transform),
+            UiWidget::Checkbox { ref transform, .. } => Some(transform),
+            UiWidget::ToggleGroup { ref transform, .. } => Some(transform),
+            UiWidget::Dropdown { ref transform, .. } => Some(transform),
+            UiWidget::ProgressBar { ref transform, .. } => Some(transform),
             UiWidget::Custom(_) => None,
         }
     }
@@ -751,6 +1304,12 @@ where
             UiWidget::Container {
                 ref mut transform, ..
             } => Some(transform),
+            UiWidget::Layout {
+                ref mut transform, ..
+            } => Some(transform),
+            UiWidget::ScrollView {
+                ref mut transform, ..
+            } => Some(transform),
             UiWidget::Image {
                 ref mut transform, ..
             } => Some(transform),
@@ -760,6 +1319,21 @@ where
             UiWidget::Button {
                 ref mut transform, ..
             } => Some(transform),
+            UiWidget::Slider {
+                ref mut transform, ..
+            } => Some(transform),
+            UiWidget::Checkbox {
+                ref mut transform, ..
+            } => Some(transform),
+            UiWidget::ToggleGroup {
+                ref mut transform, ..
+            } => Some(transform),
+            UiWidget::Dropdown {
+                ref mut transform, ..
+            } => Some(transform),
+            UiWidget::ProgressBar {
+                ref mut transform, ..
+            } => Some(transform),
             UiWidget::Custom(_) => None,
         }
     }
@@ -768,6 +1342,13 @@ where
     pub fn image(&self) -> Option<&UiImagePrefab> {
         match self {
             UiWidget::Container { ref background, .. } => background.as_ref(),
+            UiWidget::Layout { ref background, .. } => background.as_ref(),
+            UiWidget::ScrollView { ref background, .. } => background.as_ref(),
+            UiWidget::Slider { ref background, .. } => background.as_ref(),
+            UiWidget::Checkbox { ref background, .. } => background.as_ref(),
+            UiWidget::ToggleGroup { ref background, .. } => background.as_ref(),
+            UiWidget::Dropdown { ref background, .. } => background.as_ref(),
+            UiWidget::ProgressBar { ref background, .. } => background.as_ref(),
             UiWidget::Image { ref image, .. } => Some(image),
             _ => None,
         }
@@ -779,6 +1360,27 @@ where
             UiWidget::Container {
                 ref mut background, ..
             } => background.as_mut(),
+            UiWidget::Layout {
+                ref mut background, ..
+            } => background.as_mut(),
+            UiWidget::ScrollView {
+                ref mut background, ..
+            } => background.as_mut(),
+            UiWidget::Slider {
+                ref mut background, ..
+            } => background.as_mut(),
+            UiWidget::Checkbox {
+                ref mut background, ..
+            } => background.as_mut(),
+            UiWidget::ToggleGroup {
+                ref mut background, ..
+            } => background.as_mut(),
+            UiWidget::Dropdown {
+                ref mut background, ..
+            } => background.as_mut(),
+            UiWidget::ProgressBar {
+                ref mut background, ..
+            } => background.as_mut(),
             UiWidget::Image { ref mut image, .. } => Some(image),
             _ => None,
         }
@@ -822,11 +1424,20 @@ fn default_container_image() -> Option<UiImagePrefab> {
     None
 }
 
+fn default_form_image() -> Option<UiImagePrefab> {
+    Some(UiImagePrefab(UiImageLoadPrefab::SolidColor(
+        0.82, 0.83, 0.83, 1.0,
+    )))
+}
+
 type UiPrefabData<D = <NoCustomUi as ToNativeWidget>::PrefabData, W = u32, G = ()> = (
     Option<UiTransformData<G>>,
     Option<UiImagePrefab>,
     Option<UiTextData>,
     Option<UiButtonData<W>>,
+    Option<UiLayout>,
+    Option<UiScrollViewData>,
+    Option<UiFormData>,
     D,
 );
 
@@ -869,11 +1480,35 @@ where
 
         let mut prefab = Prefab::new();
         walk_ui_tree(root, 0, &mut prefab, Default::default());
+        resolve_navigation(&mut prefab);
 
         Ok(prefab)
     }
 }
 
+/// Turns the ids of the `UiNavigationData` neighbours into indices of prefab entities.
+fn resolve_navigation<D, W>(prefab: &mut Prefab<UiPrefabData<D, W>>) {
+    let ids = prefab
+        .entities()
+        .map(|entity| {
+            entity
+                .data()
+                .and_then(|data| data.0.as_ref())
+                .map(|transform| transform.id.clone())
+        })
+        .collect::<Vec<_>>();
+    for index in 0..ids.len() {
+        let navigation = prefab
+            .entity(index)
+            .and_then(|entity| entity.data_mut())
+            .and_then(|data| data.0.as_mut())
+            .and_then(|transform| transform.navigation.as_mut());
+        if let Some(navigation) = navigation {
+            navigation.resolve(&ids);
+        }
+    }
+}
+
 fn walk_ui_tree<C, W>(
     widget: UiWidget<C, W>,
     current_index: usize,
@@ -893,14 +1528,32 @@ fn walk_ui_tree<C, W>(
             prefab
                 .entity(current_index)
                 .expect("Unreachable: `Prefab` entity should always be set when walking ui tree")
-                .set_data((Some(transform), Some(image), None, None, custom_data));
+                .set_data((
+                    Some(transform),
+                    Some(image),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    custom_data,
+                ));
         }
 
         UiWidget::Label { transform, text } => {
             prefab
                 .entity(current_index)
                 .expect("Unreachable: `Prefab` entity should always be set when walking ui tree")
-                .set_data((Some(transform), None, Some(text), None, custom_data));
+                .set_data((
+                    Some(transform),
+                    None,
+                    Some(text),
+                    None,
+                    None,
+                    None,
+                    None,
+                    custom_data,
+                ));
         }
 
         UiWidget::Container {
@@ -911,7 +1564,16 @@ fn walk_ui_tree<C, W>(
             prefab
                 .entity(current_index)
                 .expect("Unreachable: `Prefab` entity should always be set when walking ui tree")
-                .set_data((Some(transform), background, None, None, custom_data));
+                .set_data((
+                    Some(transform),
+                    background,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    custom_data,
+                ));
 
             for child_widget in children {
                 let child_index = prefab.add(Some(current_index), None);
@@ -919,6 +1581,89 @@ fn walk_ui_tree<C, W>(
             }
         }
 
+        UiWidget::Layout {
+            transform,
+            layout,
+            background,
+            children,
+        } => {
+            prefab
+                .entity(current_index)
+                .expect("Unreachable: `Prefab` entity should always be set when walking ui tree")
+                .set_data((
+                    Some(transform),
+                    background,
+                    None,
+                    None,
+                    Some(layout),
+                    None,
+                    None,
+                    custom_data,
+                ));
+
+            for child_widget in children {
+                let child_index = prefab.add(Some(current_index), None);
+                walk_ui_tree(child_widget, child_index, prefab, Default::default());
+            }
+        }
+
+        UiWidget::ScrollView {
+            transform,
+            background,
+            mut scroll,
+            content,
+        } => {
+            let id = transform.id.clone();
+            let scrollbar = scroll.scrollbar.take();
+            scroll.has_scrollbars = scrollbar.is_some();
+            let bars = [
+                (scroll.horizontal, "_h_bar", (0., scroll.scrollbar_width)),
+                (scroll.vertical, "_v_bar", (scroll.scrollbar_width, 0.)),
+            ];
+
+            prefab
+                .entity(current_index)
+                .expect("Unreachable: `Prefab` entity should always be set when walking ui tree")
+                .set_data((
+                    Some(transform),
+                    background,
+                    None,
+                    None,
+                    None,
+                    Some(scroll),
+                    None,
+                    custom_data,
+                ));
+
+            let content_index = prefab.add(Some(current_index), None);
+            walk_ui_tree(*content, content_index, prefab, Default::default());
+
+            if let Some(scrollbar) = scrollbar {
+                for (enabled, suffix, (width, height)) in bars.iter() {
+                    if !enabled {
+                        continue;
+                    }
+                    let thumb = UiTransformData::default()
+                        .with_id(format!("{}{}", id, suffix))
+                        .with_position(0., 0., 2.)
+                        .with_size(*width, *height);
+                    prefab.add(
+                        Some(current_index),
+                        Some((
+                            Some(thumb),
+                            Some(UiImagePrefab(scrollbar.clone())),
+                            None,
+                            None,
+                            None,
+                            None,
+                            None,
+                            Default::default(),
+                        )),
+                    );
+                }
+            }
+        }
+
         UiWidget::Button {
             transform,
             mut button,
@@ -927,6 +1672,7 @@ fn walk_ui_tree<C, W>(
             let text = UiTextData {
                 color: button.normal_text_color,
                 editable: None,
+                rich: None,
                 font: button.font.clone(),
                 password: false,
                 align: None,
@@ -943,6 +1689,9 @@ fn walk_ui_tree<C, W>(
                     button.normal_image.take().map(UiImagePrefab),
                     None,
                     Some(button),
+                    None,
+                    None,
+                    None,
                     custom_data,
                 ));
 
@@ -953,6 +1702,314 @@ fn walk_ui_tree<C, W>(
                     None,
                     Some(text),
                     None,
+                    None,
+                    None,
+                    None,
+                    Default::default(),
+                )),
+            );
+        }
+
+        UiWidget::Slider {
+            mut transform,
+            background,
+            mut slider,
+        } => {
+            let id = transform.id.clone();
+            transform.mouse_reactive = true;
+            let handle = slider.handle.take();
+            let handle_transform = UiTransformData::default()
+                .with_id(format!("{}_handle", id))
+                .with_position(0., 0., 1.)
+                .with_size(slider.handle_size.0, slider.handle_size.1)
+                .transparent();
+            let form = UiFormData::Slider {
+                min: slider.min,
+                max: slider.max,
+                value: slider.value,
+                step: slider.step,
+                vertical: slider.vertical,
+            };
+
+            prefab
+                .entity(current_index)
+                .expect("Unreachable: `Prefab` entity should always be set when walking ui tree")
+                .set_data((
+                    Some(transform),
+                    background,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(form),
+                    custom_data,
+                ));
+
+            prefab.add(
+                Some(current_index),
+                Some((
+                    Some(handle_transform),
+                    handle.map(UiImagePrefab),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Default::default(),
+                )),
+            );
+        }
+
+        UiWidget::Checkbox {
+            mut transform,
+            background,
+            mut checkbox,
+        } => {
+            let id = transform.id.clone();
+            transform.mouse_reactive = true;
+            let check = checkbox.check.take();
+            let check_transform = UiTransformData::default()
+                .with_id(format!("{}_check", id))
+                .with_position(0., 0., 1.)
+                .with_stretch(Stretch::XY {
+                    x_margin: transform.width / 4.,
+                    y_margin: transform.height / 4.,
+                    keep_aspect_ratio: false,
+                })
+                .transparent();
+            let label = checkbox.label.take().map(|mut label| {
+                label.align.get_or_insert(Anchor::MiddleLeft);
+                let mut label_transform = UiTransformData::default()
+                    .with_id(format!("{}_label", id))
+                    .with_position(8., 0., 1.)
+                    .with_size(checkbox.label_width, transform.height)
+                    .with_anchor(Anchor::MiddleRight)
+                    .reactive();
+                label_transform.pivot = Anchor::MiddleLeft;
+                (label_transform, label)
+            });
+            let form = UiFormData::Checkbox {
+                checked: checkbox.checked,
+            };
+
+            prefab
+                .entity(current_index)
+                .expect("Unreachable: `Prefab` entity should always be set when walking ui tree")
+                .set_data((
+                    Some(transform),
+                    background,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(form),
+                    custom_data,
+                ));
+
+            prefab.add(
+                Some(current_index),
+                Some((
+                    Some(check_transform),
+                    check.map(UiImagePrefab),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Default::default(),
+                )),
+            );
+            if let Some((label_transform, label)) = label {
+                prefab.add(
+                    Some(current_index),
+                    Some((
+                        Some(label_transform),
+                        None,
+                        Some(label),
+                        None,
+                        None,
+                        None,
+                        None,
+                        Default::default(),
+                    )),
+                );
+            }
+        }
+
+        UiWidget::ToggleGroup {
+            transform,
+            background,
+            layout,
+            selected,
+            options,
+        } => {
+            prefab
+                .entity(current_index)
+                .expect("Unreachable: `Prefab` entity should always be set when walking ui tree")
+                .set_data((
+                    Some(transform),
+                    background,
+                    None,
+                    None,
+                    layout,
+                    None,
+                    Some(UiFormData::ToggleGroup { selected }),
+                    custom_data,
+                ));
+
+            for option_widget in options {
+                let option_index = prefab.add(Some(current_index), None);
+                walk_ui_tree(option_widget, option_index, prefab, Default::default());
+            }
+        }
+
+        UiWidget::Dropdown {
+            mut transform,
+            background,
+            mut dropdown,
+        } => {
+            let id = transform.id.clone();
+            transform.mouse_reactive = true;
+            let (width, height) = (transform.width, transform.height);
+            let list_image = dropdown.list_image.take();
+            let text = |text: String| UiTextData {
+                color: dropdown.text_color,
+                editable: None,
+                rich: None,
+                font: dropdown.font.clone(),
+                password: false,
+                align: None,
+                line_mode: None,
+                text,
+                font_size: dropdown.font_size,
+            };
+            let mut text_transform = button_text_transform(id.clone());
+            text_transform.id = format!("{}_text", id);
+            let mut list_transform = UiTransformData::default()
+                .with_id(format!("{}_list", id))
+                .with_position(0., 0., 10.)
+                .with_size(width, height * dropdown.options.len() as f32)
+                .with_anchor(Anchor::BottomMiddle)
+                .transparent()
+                .hide();
+            list_transform.pivot = Anchor::TopMiddle;
+            let selected_text = dropdown
+                .options
+                .get(dropdown.selected)
+                .cloned()
+                .unwrap_or_default();
+            let form = UiFormData::Dropdown {
+                options: dropdown.options.clone(),
+                selected: dropdown.selected,
+            };
+
+            prefab
+                .entity(current_index)
+                .expect("Unreachable: `Prefab` entity should always be set when walking ui tree")
+                .set_data((
+                    Some(transform),
+                    background,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(form),
+                    custom_data,
+                ));
+
+            prefab.add(
+                Some(current_index),
+                Some((
+                    Some(text_transform),
+                    None,
+                    Some(text(selected_text)),
+                    None,
+                    None,
+                    None,
+                    None,
+                    Default::default(),
+                )),
+            );
+            let list_index = prefab.add(
+                Some(current_index),
+                Some((
+                    Some(list_transform),
+                    None,
+                    None,
+                    None,
+                    Some(UiLayout::new(Arrangement::Vertical)),
+                    None,
+                    Some(UiFormData::DropdownList),
+                    Default::default(),
+                )),
+            );
+            for (index, option) in dropdown.options.iter().enumerate() {
+                let item_transform = UiTransformData::default()
+                    .with_id(format!("{}_item_{}", id, index))
+                    .with_position(0., 0., 1.)
+                    .with_size(width, height)
+                    .reactive()
+                    .hide();
+                prefab.add(
+                    Some(list_index),
+                    Some((
+                        Some(item_transform),
+                        list_image.clone().map(UiImagePrefab),
+                        Some(text(option.clone())),
+                        None,
+                        None,
+                        None,
+                        None,
+                        Default::default(),
+                    )),
+                );
+            }
+        }
+
+        UiWidget::ProgressBar {
+            transform,
+            background,
+            mut progress,
+        } => {
+            let id = transform.id.clone();
+            let fill = progress.fill.take();
+            let mut fill_transform = UiTransformData::default()
+                .with_id(format!("{}_fill", id))
+                .with_position(0., 0., 1.)
+                .with_size(
+                    progress.value.max(0.).min(1.) * transform.width,
+                    transform.height,
+                )
+                .with_anchor(Anchor::MiddleLeft)
+                .transparent();
+            fill_transform.pivot = Anchor::MiddleLeft;
+
+            prefab
+                .entity(current_index)
+                .expect("Unreachable: `Prefab` entity should always be set when walking ui tree")
+                .set_data((
+                    Some(transform),
+                    background,
+                    None,
+                    None,
+                    None,
+                    None,
+                    Some(UiFormData::ProgressBar {
+                        value: progress.value,
+                    }),
+                    custom_data,
+                ));
+
+            prefab.add(
+                Some(current_index),
+                Some((
+                    Some(fill_transform),
+                    fill.map(UiImagePrefab),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
                     Default::default(),
                 )),
             );
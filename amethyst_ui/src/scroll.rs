@@ -0,0 +1,807 @@
+//! Scroll views and clipping of their content.
+
+use std::marker::PhantomData;
+
+use amethyst_core::{
+    ecs::prelude::{
+        Component, DenseVecStorage, Entities, Entity, Join, Read, ReadExpect, ReadStorage,
+        ReaderId, System, SystemData, World, WriteStorage,
+    },
+    shrev::EventChannel,
+    Hidden, HiddenPropagate, Parent, SystemDesc, Time,
+};
+use amethyst_derive::SystemDesc;
+use amethyst_input::{BindingTypes, InputHandler};
+use amethyst_window::ScreenDimensions;
+use winit::{ElementState, Event, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use crate::{
+    modal::{active_modal, is_inside},
+    targeted, Interactable, ScaleMode, Selected, UiModal, UiTransform,
+};
+
+/// Velocities under this value, in pixels per second, stop the inertia.
+const MIN_VELOCITY: f32 = 1.0;
+
+/// A rectangle outside of which ui elements are not drawn nor clicked, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipRect {
+    /// The left border.
+    pub left: f32,
+    /// The right border.
+    pub right: f32,
+    /// The bottom border.
+    pub bottom: f32,
+    /// The top border.
+    pub top: f32,
+}
+
+impl ClipRect {
+    /// Creates the rectangle covered by a `UiTransform`, as computed by the `UiTransformSystem`.
+    pub fn from_transform(transform: &UiTransform) -> Self {
+        ClipRect {
+            left: transform.pixel_x - transform.pixel_width / 2.0,
+            right: transform.pixel_x + transform.pixel_width / 2.0,
+            bottom: transform.pixel_y - transform.pixel_height / 2.0,
+            top: transform.pixel_y + transform.pixel_height / 2.0,
+        }
+    }
+
+    /// Returns the area covered by both rectangles, which might be empty.
+    pub fn intersection(&self, other: &ClipRect) -> ClipRect {
+        ClipRect {
+            left: self.left.max(other.left),
+            right: self.right.min(other.right),
+            bottom: self.bottom.max(other.bottom),
+            top: self.top.min(other.top),
+        }
+    }
+
+    /// Checks if the rectangle covers no area.
+    pub fn is_empty(&self) -> bool {
+        self.left >= self.right || self.bottom >= self.top
+    }
+
+    /// Checks if the position is inside of the rectangle.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.left && x <= self.right && y >= self.bottom && y <= self.top
+    }
+
+    /// Cuts the parts of a textured quad lying outside of the rectangle.
+    ///
+    /// The quad is given by its center, its size and its texture coordinates
+    /// (left, top, right, bottom), and is returned in the same form with the texture
+    /// coordinates cut accordingly. Returns `None` if nothing of the quad is visible.
+    pub fn clip_quad(
+        &self,
+        center: [f32; 2],
+        size: [f32; 2],
+        tex_coords: [f32; 4],
+    ) -> Option<([f32; 2], [f32; 2], [f32; 4])> {
+        let quad = ClipRect {
+            left: center[0] - size[0] / 2.0,
+            right: center[0] + size[0] / 2.0,
+            bottom: center[1] - size[1] / 2.0,
+            top: center[1] + size[1] / 2.0,
+        };
+        let clipped = quad.intersection(self);
+        if clipped == quad {
+            return Some((center, size, tex_coords));
+        }
+        if clipped.is_empty() {
+            return None;
+        }
+
+        let u = |x: f32| {
+            tex_coords[0]
+                + (x - quad.left) / (quad.right - quad.left) * (tex_coords[2] - tex_coords[0])
+        };
+        let v = |y: f32| {
+            tex_coords[1]
+                + (quad.top - y) / (quad.top - quad.bottom) * (tex_coords[3] - tex_coords[1])
+        };
+        Some((
+            [
+                (clipped.left + clipped.right) / 2.0,
+                (clipped.bottom + clipped.top) / 2.0,
+            ],
+            [clipped.right - clipped.left, clipped.top - clipped.bottom],
+            [
+                u(clipped.left),
+                v(clipped.top),
+                u(clipped.right),
+                v(clipped.bottom),
+            ],
+        ))
+    }
+}
+
+/// Computes the area in which an entity is visible, that is the intersection of the
+/// viewports of the scroll views it is in.
+///
+/// Returns `None` if the entity isn't in a scroll view. The returned rectangle can be empty
+/// if the entity is completely scrolled out of view.
+pub fn clip_rect(
+    entity: Entity,
+    transforms: &ReadStorage<'_, UiTransform>,
+    parents: &ReadStorage<'_, Parent>,
+    scroll_views: &ReadStorage<'_, UiScrollView>,
+) -> Option<ClipRect> {
+    let mut clip: Option<ClipRect> = None;
+    let mut current = entity;
+    while let Some(parent) = parents.get(current) {
+        current = parent.entity;
+        if !scroll_views.contains(current) {
+            continue;
+        }
+        if let Some(transform) = transforms.get(current) {
+            let viewport = ClipRect::from_transform(transform);
+            clip = Some(match clip {
+                Some(clip) => clip.intersection(&viewport),
+                None => viewport,
+            });
+        }
+    }
+    clip
+}
+
+/// Component turning a ui element into a viewport showing a part of a bigger content entity.
+///
+/// The content must be a child of the scroll view. The `UiScrollSystem` moves it according to
+/// the `offset`, and the children of the scroll view are only drawn and clicked inside of it.
+/// The content can be scrolled with the mouse wheel while hovered, by dragging it, and with
+/// the arrow, page and home/end keys while the scroll view is `Selected`. Dragging only starts
+/// once the pointer moved by `drag_threshold`, and not when pressing an `Interactable` element
+/// of the content like a button or a `Draggable`, which handles the press itself.
+#[derive(Debug, Clone)]
+pub struct UiScrollView {
+    /// The entity scrolled inside of the viewport.
+    pub content: Entity,
+    /// How far the content is scrolled to the right and to the bottom, in pixels.
+    /// Zero shows the top left corner of the content.
+    pub offset: (f32, f32),
+    /// The current scrolling speed, in pixels per second.
+    pub velocity: (f32, f32),
+    /// Allows scrolling horizontally.
+    pub horizontal: bool,
+    /// Allows scrolling vertically.
+    pub vertical: bool,
+    /// Distance scrolled by a mouse wheel step or an arrow key, in pixels.
+    pub step: f32,
+    /// Fraction of the velocity kept after one second when the content is released.
+    /// Zero disables the inertia.
+    pub inertia: f32,
+    /// Distance the pointer has to move while pressed before the content is dragged, in pixels.
+    pub drag_threshold: f32,
+    /// The thumb of the horizontal scrollbar, resized and moved along the bottom of the viewport.
+    pub horizontal_bar: Option<Entity>,
+    /// The thumb of the vertical scrollbar, resized and moved along the right of the viewport.
+    pub vertical_bar: Option<Entity>,
+}
+
+impl UiScrollView {
+    /// Creates a vertical scroll view of the given content.
+    pub fn new(content: Entity) -> Self {
+        UiScrollView {
+            content,
+            offset: (0.0, 0.0),
+            velocity: (0.0, 0.0),
+            horizontal: false,
+            vertical: true,
+            step: 20.0,
+            inertia: 0.05,
+            drag_threshold: 8.0,
+            horizontal_bar: None,
+            vertical_bar: None,
+        }
+    }
+
+    /// Sets the axes along which the content can be scrolled.
+    pub fn with_axes(mut self, horizontal: bool, vertical: bool) -> Self {
+        self.horizontal = horizontal;
+        self.vertical = vertical;
+        self
+    }
+
+    /// Sets the thumbs of the scrollbars.
+    pub fn with_scrollbars(mut self, horizontal: Option<Entity>, vertical: Option<Entity>) -> Self {
+        self.horizontal_bar = horizontal;
+        self.vertical_bar = vertical;
+        self
+    }
+
+    /// Returns how far the content can be scrolled on each axis.
+    pub fn max_offset(&self, viewport: (f32, f32), content: (f32, f32)) -> (f32, f32) {
+        (
+            if self.horizontal {
+                (content.0 - viewport.0).max(0.0)
+            } else {
+                0.0
+            },
+            if self.vertical {
+                (content.1 - viewport.1).max(0.0)
+            } else {
+                0.0
+            },
+        )
+    }
+
+    /// Scrolls the content by the given distance, without going past its borders.
+    pub fn scroll_by(&mut self, delta: (f32, f32), max_offset: (f32, f32)) {
+        self.offset.0 = (self.offset.0 + delta.0).max(0.0).min(max_offset.0);
+        self.offset.1 = (self.offset.1 + delta.1).max(0.0).min(max_offset.1);
+    }
+
+    /// Moves the content by its velocity, slowing it down.
+    fn apply_inertia(&mut self, delta_seconds: f32, max_offset: (f32, f32)) {
+        let previous = self.offset;
+        let wanted = (
+            self.velocity.0 * delta_seconds,
+            self.velocity.1 * delta_seconds,
+        );
+        self.scroll_by(wanted, max_offset);
+        let decay = self.inertia.powf(delta_seconds);
+        self.velocity = (
+            slow_down(self.velocity.0, decay, self.offset.0 - previous.0, wanted.0),
+            slow_down(self.velocity.1, decay, self.offset.1 - previous.1, wanted.1),
+        );
+    }
+}
+
+/// Returns the distance between two positions.
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt()
+}
+
+/// Returns the velocity after applying the inertia, stopping at the borders of the content
+/// and once slow enough.
+fn slow_down(velocity: f32, decay: f32, moved: f32, wanted: f32) -> f32 {
+    let velocity = velocity * decay;
+    if (moved - wanted).abs() > std::f32::EPSILON || velocity.abs() < MIN_VELOCITY {
+        0.0
+    } else {
+        velocity
+    }
+}
+
+impl Component for UiScrollView {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Returns the length of a scrollbar thumb and the distance from the start of the track to
+/// the thumb.
+fn thumb(track: f32, viewport: f32, content: f32, offset: f32, max_offset: f32) -> (f32, f32) {
+    if content <= viewport || max_offset <= 0.0 {
+        return (track, 0.0);
+    }
+    let length = track * viewport / content;
+    (length, (track - length) * offset / max_offset)
+}
+
+/// Moves a ui element so that its center ends up at `center`.
+pub(crate) fn move_to(transform: &mut UiTransform, center: (f32, f32), parent_size: (f32, f32)) {
+    let delta = (center.0 - transform.pixel_x, center.1 - transform.pixel_y);
+    if delta.0.abs() < std::f32::EPSILON && delta.1.abs() < std::f32::EPSILON {
+        return;
+    }
+    match transform.scale_mode {
+        ScaleMode::Pixel => {
+            transform.local_x += delta.0;
+            transform.local_y += delta.1;
+        }
+        ScaleMode::Percent => {
+            transform.local_x += delta.0 / parent_size.0;
+            transform.local_y += delta.1 / parent_size.1;
+        }
+    }
+}
+
+/// Resizes a ui element, keeping its position unchanged.
+pub(crate) fn resize(transform: &mut UiTransform, size: (f32, f32), parent_size: (f32, f32)) {
+    let size = match transform.scale_mode {
+        ScaleMode::Pixel => size,
+        ScaleMode::Percent => (size.0 / parent_size.0, size.1 / parent_size.1),
+    };
+    if (transform.width - size.0).abs() > std::f32::EPSILON
+        || (transform.height - size.1).abs() > std::f32::EPSILON
+    {
+        transform.width = size.0;
+        transform.height = size.1;
+    }
+}
+
+/// A press on a scroll view, which may become a drag.
+#[derive(Debug)]
+struct ScrollDrag {
+    scroll_view: Entity,
+    start: (f32, f32),
+    last_position: (f32, f32),
+    started: bool,
+}
+
+/// System scrolling the content of the `UiScrollView`s and placing their scrollbars.
+/// The generic type T represent the T generic parameter of the InputHandler<T>.
+#[derive(Debug, SystemDesc)]
+#[system_desc(name(UiScrollSystemDesc))]
+pub struct UiScrollSystem<T: BindingTypes> {
+    #[system_desc(event_channel_reader)]
+    window_reader_id: ReaderId<Event>,
+    /// The scroll view being pressed or dragged.
+    #[system_desc(skip)]
+    dragging: Option<ScrollDrag>,
+    #[system_desc(skip)]
+    was_down: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<T: BindingTypes> UiScrollSystem<T> {
+    /// Creates a new `UiScrollSystem`.
+    pub fn new(window_reader_id: ReaderId<Event>) -> Self {
+        Self {
+            window_reader_id,
+            dragging: None,
+            was_down: false,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: BindingTypes> System<'a> for UiScrollSystem<T> {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, UiTransform>,
+        WriteStorage<'a, UiScrollView>,
+        ReadStorage<'a, Selected>,
+        ReadStorage<'a, Interactable>,
+        ReadStorage<'a, Parent>,
+        ReadStorage<'a, UiModal>,
+        ReadStorage<'a, Hidden>,
+        ReadStorage<'a, HiddenPropagate>,
+        Read<'a, InputHandler<T>>,
+        Read<'a, EventChannel<Event>>,
+        Read<'a, Time>,
+        ReadExpect<'a, ScreenDimensions>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut transforms,
+            mut scroll_views,
+            selecteds,
+            interactables,
+            parents,
+            modals,
+            hiddens,
+            hidden_propagates,
+            input,
+            window_events,
+            time,
+            screen_dimensions,
+        ): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("ui_scroll_system");
+
+        let touch_position = input.primary_touch().map(|touch| touch.position);
+        let down = input.mouse_button_is_down(MouseButton::Left) || touch_position.is_some();
+        let pointer_position = touch_position
+            .or_else(|| input.mouse_position())
+            .map(|(x, y)| (x as f32, screen_dimensions.height() - y as f32));
+
+        // The topmost scroll view under the pointer. Scroll views behind the active modal
+        // element can't be scrolled with the pointer.
+        let modal = active_modal(
+            &entities,
+            &modals,
+            &transforms,
+            &hiddens,
+            &hidden_propagates,
+        );
+        let hovered = pointer_position.and_then(|(x, y)| {
+            (&*entities, &scroll_views)
+                .join()
+                .filter(|(entity, _)| {
+                    modal.map_or(true, |modal| is_inside(*entity, modal, &parents))
+                })
+                .filter_map(|(entity, _)| transforms.get(entity).map(|t| (entity, t)))
+                .filter(|(_, t)| t.position_inside(x, y))
+                .max_by(|(_, t1), (_, t2)| {
+                    t1.global_z
+                        .partial_cmp(&t2.global_z)
+                        .expect("Unexpected NaN")
+                })
+                .map(|(entity, _)| entity)
+        });
+
+        if down && !self.was_down {
+            // Elements of the content handling the press themselves aren't dragged.
+            let pressed = pointer_position.and_then(|position| {
+                targeted(
+                    position,
+                    (&*entities, &transforms, interactables.maybe()).join(),
+                )
+            });
+            self.dragging = match (hovered, pointer_position) {
+                (Some(entity), Some(position))
+                    if pressed.is_none()
+                        || pressed == Some(entity)
+                        || pressed == scroll_views.get(entity).map(|view| view.content) =>
+                {
+                    Some(ScrollDrag {
+                        scroll_view: entity,
+                        start: position,
+                        last_position: position,
+                        started: false,
+                    })
+                }
+                _ => None,
+            };
+        } else if !down {
+            self.dragging = None;
+        }
+        self.was_down = down;
+
+        let mut pressed_keys = Vec::new();
+        for event in window_events.read(&mut self.window_reader_id) {
+            if let Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(key),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } = *event
+            {
+                pressed_keys.push(key);
+            }
+        }
+
+        let delta_seconds = time.delta_real_seconds();
+        for (entity, scroll_view) in (&*entities, &mut scroll_views).join() {
+            let (viewport, content) =
+                match (transforms.get(entity), transforms.get(scroll_view.content)) {
+                    (Some(viewport), Some(content)) => (
+                        ClipRect::from_transform(viewport),
+                        (content.pixel_width, content.pixel_height),
+                    ),
+                    _ => continue,
+                };
+            let viewport_size = (
+                viewport.right - viewport.left,
+                viewport.top - viewport.bottom,
+            );
+            let max_offset = scroll_view.max_offset(viewport_size, content);
+
+            if Some(entity) == hovered {
+                let wheel = (
+                    -input.mouse_wheel_value(true),
+                    -input.mouse_wheel_value(false),
+                );
+                if wheel != (0.0, 0.0) {
+                    scroll_view.velocity = (0.0, 0.0);
+                    scroll_view.scroll_by(
+                        (wheel.0 * scroll_view.step, wheel.1 * scroll_view.step),
+                        max_offset,
+                    );
+                }
+            }
+
+            if selecteds.contains(entity) {
+                for key in &pressed_keys {
+                    let delta = match key {
+                        VirtualKeyCode::Left => (-scroll_view.step, 0.0),
+                        VirtualKeyCode::Right => (scroll_view.step, 0.0),
+                        VirtualKeyCode::Up => (0.0, -scroll_view.step),
+                        VirtualKeyCode::Down => (0.0, scroll_view.step),
+                        VirtualKeyCode::PageUp => (0.0, -viewport_size.1),
+                        VirtualKeyCode::PageDown => (0.0, viewport_size.1),
+                        VirtualKeyCode::Home => (-max_offset.0, -max_offset.1),
+                        VirtualKeyCode::End => (max_offset.0, max_offset.1),
+                        _ => continue,
+                    };
+                    scroll_view.velocity = (0.0, 0.0);
+                    scroll_view.scroll_by(delta, max_offset);
+                }
+            }
+
+            match (&mut self.dragging, pointer_position) {
+                (Some(drag), Some(position))
+                    if drag.scroll_view == entity
+                        && (drag.started
+                            || distance(drag.start, position) >= scroll_view.drag_threshold) =>
+                {
+                    drag.started = true;
+                    // Moving the pointer up scrolls the content down.
+                    let delta = (
+                        drag.last_position.0 - position.0,
+                        position.1 - drag.last_position.1,
+                    );
+                    drag.last_position = position;
+                    scroll_view.scroll_by(delta, max_offset);
+                    if delta_seconds > 0.0 {
+                        scroll_view.velocity = (delta.0 / delta_seconds, delta.1 / delta_seconds);
+                    }
+                }
+                _ => scroll_view.apply_inertia(delta_seconds, max_offset),
+            }
+
+            if let Some(content_transform) = transforms.get_mut(scroll_view.content) {
+                move_to(
+                    content_transform,
+                    (
+                        viewport.left - scroll_view.offset.0 + content.0 / 2.0,
+                        viewport.top + scroll_view.offset.1 - content.1 / 2.0,
+                    ),
+                    viewport_size,
+                );
+            }
+            if let Some(bar) = scroll_view.vertical_bar {
+                if let Some(bar_transform) = transforms.get_mut(bar) {
+                    let (length, start) = thumb(
+                        viewport_size.1,
+                        viewport_size.1,
+                        content.1,
+                        scroll_view.offset.1,
+                        max_offset.1,
+                    );
+                    let width = bar_transform.pixel_width;
+                    resize(bar_transform, (width, length), viewport_size);
+                    move_to(
+                        bar_transform,
+                        (
+                            viewport.right - width / 2.0,
+                            viewport.top - start - length / 2.0,
+                        ),
+                        viewport_size,
+                    );
+                }
+            }
+            if let Some(bar) = scroll_view.horizontal_bar {
+                if let Some(bar_transform) = transforms.get_mut(bar) {
+                    let (length, start) = thumb(
+                        viewport_size.0,
+                        viewport_size.0,
+                        content.0,
+                        scroll_view.offset.0,
+                        max_offset.0,
+                    );
+                    let height = bar_transform.pixel_height;
+                    resize(bar_transform, (length, height), viewport_size);
+                    move_to(
+                        bar_transform,
+                        (
+                            viewport.left + start + length / 2.0,
+                            viewport.bottom + height / 2.0,
+                        ),
+                        viewport_size,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst_core::ecs::{Builder, RunNow, WorldExt};
+    use amethyst_input::{Button, ScrollDirection, StringBindings, VirtualEvent};
+
+    #[test]
+    fn clip_quad() {
+        let clip = ClipRect {
+            left: 0.0,
+            right: 10.0,
+            bottom: 0.0,
+            top: 10.0,
+        };
+        // Fully inside.
+        assert_eq!(
+            clip.clip_quad([5.0, 5.0], [2.0, 2.0], [0.0, 0.0, 1.0, 1.0]),
+            Some(([5.0, 5.0], [2.0, 2.0], [0.0, 0.0, 1.0, 1.0]))
+        );
+        // Fully outside.
+        assert_eq!(
+            clip.clip_quad([15.0, 5.0], [2.0, 2.0], [0.0, 0.0, 1.0, 1.0]),
+            None
+        );
+        // The right half and the top half are cut.
+        assert_eq!(
+            clip.clip_quad([10.0, 10.0], [4.0, 4.0], [0.0, 0.0, 1.0, 1.0]),
+            Some(([9.0, 9.0], [2.0, 2.0], [0.0, 0.5, 0.5, 1.0]))
+        );
+    }
+
+    #[test]
+    fn nested_clip_rects() {
+        let mut world = World::new();
+        world.register::<UiTransform>();
+        world.register::<Parent>();
+        world.register::<UiScrollView>();
+
+        let transform = |x: f32, y: f32, size: f32| {
+            let mut transform = UiTransform::new(
+                String::new(),
+                crate::Anchor::Middle,
+                crate::Anchor::Middle,
+                x,
+                y,
+                0.0,
+                size,
+                size,
+            );
+            transform.pixel_x = x;
+            transform.pixel_y = y;
+            transform
+        };
+        let content = world
+            .create_entity()
+            .with(transform(0.0, 0.0, 100.0))
+            .build();
+        let outer = world
+            .create_entity()
+            .with(transform(0.0, 0.0, 20.0))
+            .with(UiScrollView::new(content))
+            .build();
+        let inner = world
+            .create_entity()
+            .with(transform(10.0, 10.0, 20.0))
+            .with(UiScrollView::new(content))
+            .with(Parent::new(outer))
+            .build();
+        let element = world
+            .create_entity()
+            .with(transform(10.0, 10.0, 4.0))
+            .with(Parent::new(inner))
+            .build();
+        let outside = world.create_entity().with(transform(0.0, 0.0, 4.0)).build();
+
+        let (transforms, parents, scroll_views) = <(
+            ReadStorage<'_, UiTransform>,
+            ReadStorage<'_, Parent>,
+            ReadStorage<'_, UiScrollView>,
+        )>::fetch(&world);
+        assert_eq!(
+            clip_rect(element, &transforms, &parents, &scroll_views),
+            Some(ClipRect {
+                left: 0.0,
+                right: 10.0,
+                bottom: 0.0,
+                top: 10.0,
+            })
+        );
+        assert_eq!(
+            clip_rect(inner, &transforms, &parents, &scroll_views),
+            Some(ClipRect {
+                left: -10.0,
+                right: 10.0,
+                bottom: -10.0,
+                top: 10.0,
+            })
+        );
+        assert_eq!(
+            clip_rect(outside, &transforms, &parents, &scroll_views),
+            None
+        );
+    }
+
+    #[test]
+    fn scrolling_stays_in_content() {
+        let mut world = World::new();
+        let content = world.create_entity().build();
+        let mut scroll_view = UiScrollView::new(content);
+        let max = scroll_view.max_offset((100.0, 100.0), (300.0, 250.0));
+        assert_eq!(max, (0.0, 150.0));
+
+        scroll_view.scroll_by((50.0, 100.0), max);
+        assert_eq!(scroll_view.offset, (0.0, 100.0));
+        scroll_view.scroll_by((0.0, 100.0), max);
+        assert_eq!(scroll_view.offset, (0.0, 150.0));
+        scroll_view.scroll_by((0.0, -200.0), max);
+        assert_eq!(scroll_view.offset, (0.0, 0.0));
+    }
+
+    #[test]
+    fn inertia() {
+        let mut world = World::new();
+        let content = world.create_entity().build();
+        let mut scroll_view = UiScrollView::new(content);
+        scroll_view.inertia = 0.5;
+        scroll_view.velocity = (0.0, 100.0);
+
+        scroll_view.apply_inertia(1.0, (0.0, 1000.0));
+        assert_eq!(scroll_view.offset, (0.0, 100.0));
+        assert_eq!(scroll_view.velocity, (0.0, 50.0));
+
+        // Stops at the border.
+        scroll_view.apply_inertia(1.0, (0.0, 120.0));
+        assert_eq!(scroll_view.offset, (0.0, 120.0));
+        assert_eq!(scroll_view.velocity, (0.0, 0.0));
+    }
+
+    #[test]
+    fn modal_blocks_scrolling_behind_it() {
+        let mut world = World::new();
+        <UiScrollSystem<StringBindings> as System<'_>>::SystemData::setup(&mut world);
+        world.insert(ScreenDimensions::new(200, 200, 1.0));
+        let mut system = UiScrollSystem::<StringBindings>::new(
+            world.fetch_mut::<EventChannel<Event>>().register_reader(),
+        );
+
+        let transform = |x: f32, z: f32, width: f32, height: f32| {
+            UiTransform::new(
+                String::new(),
+                crate::Anchor::Middle,
+                crate::Anchor::Middle,
+                x,
+                100.0,
+                z,
+                width,
+                height,
+            )
+        };
+        let content = world
+            .create_entity()
+            .with(transform(100.0, 1.0, 100.0, 300.0))
+            .build();
+        let scroll_view = world
+            .create_entity()
+            .with(transform(100.0, 0.0, 100.0, 100.0))
+            .with(UiScrollView::new(content))
+            .build();
+        let modal = world
+            .create_entity()
+            .with(transform(20.0, 2.0, 20.0, 20.0))
+            .with(UiModal)
+            .build();
+
+        let scroll_wheel = |world: &mut World| {
+            let mut events = EventChannel::new();
+            let mut input = world.write_resource::<InputHandler<StringBindings>>();
+            input.send_virtual_event(
+                &VirtualEvent::CursorMoved { x: 100.0, y: 100.0 },
+                &mut events,
+            );
+            input.send_virtual_event(
+                &VirtualEvent::ButtonPressed(Button::MouseWheel(ScrollDirection::ScrollDown)),
+                &mut events,
+            );
+        };
+        let offset = |world: &World| {
+            world
+                .read_storage::<UiScrollView>()
+                .get(scroll_view)
+                .unwrap()
+                .offset
+        };
+
+        scroll_wheel(&mut world);
+        system.run_now(&world);
+        assert_eq!(offset(&world), (0.0, 0.0));
+
+        world
+            .write_storage::<Hidden>()
+            .insert(modal, Hidden)
+            .unwrap();
+        scroll_wheel(&mut world);
+        system.run_now(&world);
+        assert_eq!(offset(&world), (0.0, 20.0));
+    }
+
+    #[test]
+    fn scrollbar_thumb() {
+        assert_eq!(thumb(100.0, 100.0, 400.0, 0.0, 300.0), (25.0, 0.0));
+        assert_eq!(thumb(100.0, 100.0, 400.0, 150.0, 300.0), (25.0, 37.5));
+        assert_eq!(thumb(100.0, 100.0, 50.0, 0.0, 0.0), (100.0, 0.0));
+    }
+}
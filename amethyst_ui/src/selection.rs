@@ -7,16 +7,22 @@ use winit::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent};
 
 use amethyst_core::{
     ecs::{
-        Component, DenseVecStorage, Entities, FlaggedStorage, Join, Read, ReadStorage, ReaderId,
-        System, SystemData, World, Write, WriteStorage,
+        Component, DenseVecStorage, Entities, Entity, FlaggedStorage, Join, Read, ReadStorage,
+        ReaderId, System, SystemData, World, Write, WriteStorage,
     },
     shrev::EventChannel,
-    SystemDesc,
+    Hidden, HiddenPropagate, Parent, SystemDesc,
 };
 use amethyst_derive::SystemDesc;
 use amethyst_input::{BindingTypes, InputHandler};
 
-use crate::{CachedSelectionOrder, UiEvent, UiEventType};
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use crate::{
+    modal::{active_modal, focus_scope, is_inside},
+    CachedSelectionOrder, UiEvent, UiEventType, UiFocusScope, UiModal, UiTransform,
+};
 
 // TODO: If none selected and there is a Selectable in the World, select the lower ordered one automatically?
 
@@ -32,13 +38,12 @@ pub struct Selectable<G> {
     /// the same time by holding shift or control and clicking them.
     /// You can also select the first element, then hold shift and press the keyboard arrow keys.
     // TODO: Holding shift + arrow keys to select more.
-    // TODO: Pressing the arrow keys could optionally be binded to change the selected ui element.
     pub multi_select_group: Option<G>,
     #[new(default)]
     /// Indicates if you can select multiple entities at once without having to press the shift or control key.
     pub auto_multi_select: bool,
     /// Indicates if this consumes the inputs. If enabled, all inputs (except Tab) will be ignored when the component is focused.
-    /// For example, the arrow keys will not change the selected ui element.
+    /// For example, the navigation actions will not change the selected ui element.
     /// Example usage: Ui Editable Text.
     #[new(default)]
     pub consumes_inputs: bool,
@@ -56,9 +61,347 @@ impl Component for Selected {
     type Storage = DenseVecStorage<Self>;
 }
 
+/// A direction in which the selection can be moved, see `SelectionNavigationSystem`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UiNavigationDirection {
+    /// Towards the top of the screen.
+    Up,
+    /// Towards the bottom of the screen.
+    Down,
+    /// Towards the left of the screen.
+    Left,
+    /// Towards the right of the screen.
+    Right,
+}
+
+impl UiNavigationDirection {
+    fn vector(self) -> (f32, f32) {
+        match self {
+            UiNavigationDirection::Up => (0.0, 1.0),
+            UiNavigationDirection::Down => (0.0, -1.0),
+            UiNavigationDirection::Left => (-1.0, 0.0),
+            UiNavigationDirection::Right => (1.0, 0.0),
+        }
+    }
+}
+
+/// Component overriding the entities selected when navigating away from a `Selectable` entity,
+/// instead of the nearest `Selectable` in that direction.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UiNavigation {
+    /// Entity selected when navigating up.
+    pub up: Option<Entity>,
+    /// Entity selected when navigating down.
+    pub down: Option<Entity>,
+    /// Entity selected when navigating left.
+    pub left: Option<Entity>,
+    /// Entity selected when navigating right.
+    pub right: Option<Entity>,
+}
+
+impl UiNavigation {
+    /// Creates a `UiNavigation` overriding no direction.
+    pub fn new() -> Self {
+        UiNavigation::default()
+    }
+
+    /// Selects `entity` when navigating in `direction`.
+    pub fn with(mut self, direction: UiNavigationDirection, entity: Entity) -> Self {
+        *self.neighbour_mut(direction) = Some(entity);
+        self
+    }
+
+    /// The entity selected when navigating in `direction`, if overridden.
+    pub fn neighbour(&self, direction: UiNavigationDirection) -> Option<Entity> {
+        match direction {
+            UiNavigationDirection::Up => self.up,
+            UiNavigationDirection::Down => self.down,
+            UiNavigationDirection::Left => self.left,
+            UiNavigationDirection::Right => self.right,
+        }
+    }
+
+    fn neighbour_mut(&mut self, direction: UiNavigationDirection) -> &mut Option<Entity> {
+        match direction {
+            UiNavigationDirection::Up => &mut self.up,
+            UiNavigationDirection::Down => &mut self.down,
+            UiNavigationDirection::Left => &mut self.left,
+            UiNavigationDirection::Right => &mut self.right,
+        }
+    }
+}
+
+impl Component for UiNavigation {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Resource holding the input actions and axes moving the selection, read by the
+/// `SelectionNavigationSystem`. Nothing is bound by default.
+///
+/// Bind the actions to the D-pad or arrow keys and the axes to a stick:
+///
+/// ```rust,ignore
+/// world.insert(
+///     UiNavigationBindings::<StringBindings>::default()
+///         .with_actions("ui_up", "ui_down", "ui_left", "ui_right")
+///         .with_axes("ui_horizontal", "ui_vertical"),
+/// );
+/// ```
+///
+/// Focused widgets also using the arrow keys, like sliders, should then be made to consume
+/// the inputs with `Selectable::consumes_inputs`.
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""), Default(bound = ""))]
+pub struct UiNavigationBindings<T: BindingTypes> {
+    /// Action moving the selection up.
+    pub up: Option<T::Action>,
+    /// Action moving the selection down.
+    pub down: Option<T::Action>,
+    /// Action moving the selection left.
+    pub left: Option<T::Action>,
+    /// Action moving the selection right.
+    pub right: Option<T::Action>,
+    /// Axis moving the selection right for positive values and left for negative ones.
+    pub horizontal_axis: Option<T::Axis>,
+    /// Axis moving the selection up for positive values and down for negative ones.
+    pub vertical_axis: Option<T::Axis>,
+    /// How far the axes have to be pushed to move the selection.
+    #[derivative(Default(value = "0.5"))]
+    pub axis_threshold: f32,
+}
+
+impl<T: BindingTypes> UiNavigationBindings<T> {
+    /// Sets the actions moving the selection.
+    pub fn with_actions<A: Into<T::Action>>(mut self, up: A, down: A, left: A, right: A) -> Self {
+        self.up = Some(up.into());
+        self.down = Some(down.into());
+        self.left = Some(left.into());
+        self.right = Some(right.into());
+        self
+    }
+
+    /// Sets the axes moving the selection.
+    pub fn with_axes<A: Into<T::Axis>>(mut self, horizontal: A, vertical: A) -> Self {
+        self.horizontal_axis = Some(horizontal.into());
+        self.vertical_axis = Some(vertical.into());
+        self
+    }
+
+    /// The direction currently requested by the inputs, actions taking precedence over axes.
+    fn direction(&self, input: &InputHandler<T>) -> Option<UiNavigationDirection> {
+        let actions = [
+            (&self.up, UiNavigationDirection::Up),
+            (&self.down, UiNavigationDirection::Down),
+            (&self.left, UiNavigationDirection::Left),
+            (&self.right, UiNavigationDirection::Right),
+        ];
+        let action = actions.iter().find_map(|(action, direction)| {
+            action
+                .as_ref()
+                .and_then(|action| input.action_is_down(action))
+                .filter(|down| *down)
+                .map(|_| *direction)
+        });
+        let axis = |axis: &Option<T::Axis>| {
+            axis.as_ref()
+                .and_then(|axis| input.axis_value(axis))
+                .unwrap_or(0.0)
+        };
+        action.or_else(|| {
+            let (x, y) = (axis(&self.horizontal_axis), axis(&self.vertical_axis));
+            if x.abs() < self.axis_threshold && y.abs() < self.axis_threshold {
+                None
+            } else if x.abs() > y.abs() {
+                Some(if x > 0.0 {
+                    UiNavigationDirection::Right
+                } else {
+                    UiNavigationDirection::Left
+                })
+            } else if y > 0.0 {
+                Some(UiNavigationDirection::Up)
+            } else {
+                Some(UiNavigationDirection::Down)
+            }
+        })
+    }
+}
+
+/// Finds the candidate nearest to `from` in `direction`.
+///
+/// Candidates must be ahead of `from`, and not more than twice as far on the side as they are
+/// ahead. Distances on the side count double, so that aligned candidates are preferred.
+fn nearest_in_direction<K>(
+    from: (f32, f32),
+    direction: UiNavigationDirection,
+    candidates: impl IntoIterator<Item = (K, (f32, f32))>,
+) -> Option<K> {
+    let (dx, dy) = direction.vector();
+    candidates
+        .into_iter()
+        .filter_map(|(key, (x, y))| {
+            let (x, y) = (x - from.0, y - from.1);
+            let ahead = x * dx + y * dy;
+            let side = (x * dy - y * dx).abs();
+            if ahead > 0.0 && side <= ahead * 2.0 {
+                Some((key, ahead + side * 2.0))
+            } else {
+                None
+            }
+        })
+        .min_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).expect("Unexpected NaN"))
+        .map(|(key, _)| key)
+}
+
+/// System moving the selection to the nearest `Selectable` in the direction requested by the
+/// `UiNavigationBindings`, or to the neighbour set by the `UiNavigation` of the selected entity.
+/// The generic type T represent the T generic parameter of the InputHandler<T>.
+///
+/// The selection moves once each time a direction is pressed, and stays inside of the active
+/// `UiModal` or `UiFocusScope`. When nothing is selected, the lowest ordered `Selectable` is
+/// selected.
+#[derive(Debug)]
+pub struct SelectionNavigationSystem<G, T: BindingTypes> {
+    pressed: Option<UiNavigationDirection>,
+    phantom: PhantomData<(G, T)>,
+}
+
+impl<G, T> SelectionNavigationSystem<G, T>
+where
+    G: Send + Sync + 'static + PartialEq,
+    T: BindingTypes,
+{
+    /// Creates a new `SelectionNavigationSystem`.
+    pub fn new() -> Self {
+        Self {
+            pressed: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, G, T> System<'a> for SelectionNavigationSystem<G, T>
+where
+    G: Send + Sync + 'static + PartialEq,
+    T: BindingTypes,
+{
+    type SystemData = (
+        Read<'a, UiNavigationBindings<T>>,
+        Read<'a, InputHandler<T>>,
+        Read<'a, CachedSelectionOrder>,
+        ReadStorage<'a, Selectable<G>>,
+        ReadStorage<'a, UiNavigation>,
+        ReadStorage<'a, UiTransform>,
+        ReadStorage<'a, Hidden>,
+        ReadStorage<'a, HiddenPropagate>,
+        ReadStorage<'a, UiModal>,
+        ReadStorage<'a, UiFocusScope>,
+        ReadStorage<'a, Parent>,
+        WriteStorage<'a, Selected>,
+        Write<'a, EventChannel<UiEvent>>,
+        Entities<'a>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            bindings,
+            input,
+            cached,
+            selectables,
+            navigations,
+            transforms,
+            hiddens,
+            hidden_propagates,
+            modals,
+            scopes,
+            parents,
+            mut selecteds,
+            mut ui_events,
+            entities,
+        ): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("ui_navigation_selection");
+
+        let direction = bindings.direction(&input);
+        let pressed = direction.filter(|direction| self.pressed != Some(*direction));
+        self.pressed = direction;
+        let direction = match pressed {
+            Some(direction) => direction,
+            None => return,
+        };
+
+        let modal = active_modal(
+            &entities,
+            &modals,
+            &transforms,
+            &hiddens,
+            &hidden_propagates,
+        );
+        let highest = cached
+            .highest_order_selected_index(&selecteds)
+            .map(|index| cached.cache[index].1);
+        let scope = focus_scope(highest, modal, &scopes, &parents);
+        let reachable = |entity: Entity| {
+            !hiddens.contains(entity)
+                && !hidden_propagates.contains(entity)
+                && scope.map_or(true, |scope| is_inside(entity, scope, &parents))
+        };
+
+        // An entity selected before a modal element opened is left for the modal element.
+        let target = match highest.filter(|highest| reachable(*highest)) {
+            Some(highest) => {
+                if selectables
+                    .get(highest)
+                    .map_or(false, |selectable| selectable.consumes_inputs)
+                {
+                    return;
+                }
+                let neighbour = navigations
+                    .get(highest)
+                    .and_then(|navigation| navigation.neighbour(direction))
+                    .filter(|neighbour| entities.is_alive(*neighbour) && reachable(*neighbour));
+                neighbour.or_else(|| {
+                    let from = transforms.get(highest)?;
+                    nearest_in_direction(
+                        (from.pixel_x, from.pixel_y),
+                        direction,
+                        cached
+                            .cache
+                            .iter()
+                            .map(|(_, entity)| *entity)
+                            .filter(|entity| *entity != highest && reachable(*entity))
+                            .filter_map(|entity| {
+                                transforms
+                                    .get(entity)
+                                    .map(|t| (entity, (t.pixel_x, t.pixel_y)))
+                            }),
+                    )
+                })
+            }
+            None => cached
+                .cache
+                .iter()
+                .map(|(_, entity)| *entity)
+                .find(|entity| reachable(*entity)),
+        };
+
+        if let Some(target) = target {
+            for (entity, _) in (&*entities, &selecteds).join() {
+                ui_events.single_write(UiEvent::new(UiEventType::Blur, entity));
+            }
+            selecteds.clear();
+            selecteds
+                .insert(target, Selected)
+                .expect("unreachable: We are inserting");
+            ui_events.single_write(UiEvent::new(UiEventType::Focus, target));
+        }
+    }
+}
+
 /// System managing the selection of entities.
 /// Reacts to `UiEvent`.
-/// Reacts to Tab and Shift+Tab.
+/// Reacts to Tab and Shift+Tab, staying inside of the active `UiModal` or `UiFocusScope`.
 #[derive(Debug, SystemDesc)]
 #[system_desc(name(SelectionKeyboardSystemDesc))]
 pub struct SelectionKeyboardSystem<G>
@@ -93,10 +436,28 @@ where
         WriteStorage<'a, Selected>,
         Write<'a, EventChannel<UiEvent>>,
         Entities<'a>,
+        ReadStorage<'a, UiTransform>,
+        ReadStorage<'a, UiModal>,
+        ReadStorage<'a, UiFocusScope>,
+        ReadStorage<'a, Parent>,
+        ReadStorage<'a, Hidden>,
+        ReadStorage<'a, HiddenPropagate>,
     );
     fn run(
         &mut self,
-        (window_events, cached, mut selecteds, mut ui_events, entities): Self::SystemData,
+        (
+            window_events,
+            cached,
+            mut selecteds,
+            mut ui_events,
+            entities,
+            transforms,
+            modals,
+            scopes,
+            parents,
+            hiddens,
+            hidden_propagates,
+        ): Self::SystemData,
     ) {
         /*
         Algorithm in use:
@@ -119,7 +480,7 @@ where
         */
 
         // Checks if tab was pressed.
-        // TODO: Controller support/Use InputEvent in addition to keys.
+        // Directional navigation is handled by the `SelectionNavigationSystem`.
         for event in window_events.read(&mut self.window_reader_id) {
             if let Event::WindowEvent {
                 event:
@@ -136,6 +497,34 @@ where
                 ..
             } = *event
             {
+                // Only cycle through the elements of the active modal element or focus scope.
+                let modal = active_modal(
+                    &entities,
+                    &modals,
+                    &transforms,
+                    &hiddens,
+                    &hidden_propagates,
+                );
+                let highest = cached
+                    .highest_order_selected_index(&selecteds)
+                    .map(|index| cached.cache[index].1);
+                let scoped;
+                let cached = match focus_scope(highest, modal, &scopes, &parents) {
+                    Some(scope) => {
+                        scoped = CachedSelectionOrder {
+                            cached: cached.cached.clone(),
+                            cache: cached
+                                .cache
+                                .iter()
+                                .filter(|(_, entity)| is_inside(*entity, scope, &parents))
+                                .cloned()
+                                .collect(),
+                        };
+                        &scoped
+                    }
+                    None => &*cached,
+                };
+
                 // Get index of highest selected ui element
                 let highest = cached.highest_order_selected_index(&selecteds);
 
@@ -357,3 +746,90 @@ where
         ui_events.iter_write(emitted.into_iter());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CacheSelectionOrderSystem, UiFocusScope};
+    use amethyst_core::ecs::{Builder, RunNow, WorldExt};
+    use winit::{DeviceId, ModifiersState, WindowId};
+
+    fn tab() -> Event {
+        Event::WindowEvent {
+            window_id: unsafe { WindowId::dummy() },
+            event: WindowEvent::KeyboardInput {
+                device_id: unsafe { DeviceId::dummy() },
+                input: KeyboardInput {
+                    scancode: 15,
+                    state: ElementState::Pressed,
+                    virtual_keycode: Some(VirtualKeyCode::Tab),
+                    modifiers: ModifiersState {
+                        shift: false,
+                        ctrl: false,
+                        alt: false,
+                        logo: false,
+                    },
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn tab_stays_inside_focus_scope() {
+        let mut world = World::new();
+        <SelectionKeyboardSystem<()> as System<'_>>::SystemData::setup(&mut world);
+        <CacheSelectionOrderSystem<()> as System<'_>>::SystemData::setup(&mut world);
+        let mut system = SelectionKeyboardSystem::<()>::new(
+            world.fetch_mut::<EventChannel<Event>>().register_reader(),
+        );
+
+        let scope = world.create_entity().with(UiFocusScope).build();
+        let mut selectable = |order: u32, parent: Option<Entity>| {
+            let builder = world.create_entity().with(Selectable::<()>::new(order));
+            match parent {
+                Some(parent) => builder.with(Parent::new(parent)),
+                None => builder,
+            }
+            .build()
+        };
+        let _before = selectable(0, None);
+        let first = selectable(1, Some(scope));
+        let second = selectable(2, Some(scope));
+        let _after = selectable(3, None);
+        CacheSelectionOrderSystem::<()>::new().run_now(&world);
+        world
+            .write_storage::<Selected>()
+            .insert(second, Selected)
+            .unwrap();
+
+        let mut press_tab = |world: &mut World| {
+            world
+                .write_resource::<EventChannel<Event>>()
+                .single_write(tab());
+            system.run_now(world);
+            let (entities, selecteds) = <(Entities<'_>, ReadStorage<'_, Selected>)>::fetch(world);
+            (&*entities, &selecteds)
+                .join()
+                .map(|(entity, _)| entity)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(press_tab(&mut world), vec![first]);
+        assert_eq!(press_tab(&mut world), vec![second]);
+    }
+
+    #[test]
+    fn nearest_in_direction_prefers_aligned() {
+        let candidates = vec![
+            ("above", (0.0, 50.0)),
+            ("above_far", (0.0, 100.0)),
+            ("above_side", (30.0, 40.0)),
+            ("beside", (100.0, 1.0)),
+            ("below", (0.0, -50.0)),
+        ];
+        let nearest = |direction| nearest_in_direction((0.0, 0.0), direction, candidates.clone());
+        assert_eq!(nearest(UiNavigationDirection::Up), Some("above"));
+        assert_eq!(nearest(UiNavigationDirection::Down), Some("below"));
+        assert_eq!(nearest(UiNavigationDirection::Right), Some("beside"));
+        assert_eq!(nearest(UiNavigationDirection::Left), None);
+    }
+}
@@ -1,5 +1,5 @@
 use amethyst_assets::AssetStorage;
-use amethyst_audio::{output::Output, Source, SourceHandle};
+use amethyst_audio::{output::Output, AudioBus, AudioMixer, Source, SourceHandle};
 use amethyst_core::{
     ecs::{
         prelude::{Component, DenseVecStorage},
@@ -72,7 +72,7 @@ impl EventRetrigger for UiSoundRetrigger {
 }
 
 /// Handles any dispatches `UiPlaySoundAction`s and plays the received
-/// sounds through the set `Output`.
+/// sounds through the set `Output`, on the `Ui` bus of the `AudioMixer`.
 #[derive(Debug, SystemDesc)]
 #[system_desc(name(UiSoundSystemDesc))]
 pub struct UiSoundSystem {
@@ -94,9 +94,10 @@ impl<'s> System<'s> for UiSoundSystem {
         Write<'s, EventChannel<UiPlaySoundAction>>,
         Read<'s, AssetStorage<Source>>,
         Option<Read<'s, Output>>,
+        Write<'s, AudioMixer>,
     );
 
-    fn run(&mut self, (sound_events, audio_storage, audio_output): Self::SystemData) {
+    fn run(&mut self, (sound_events, audio_storage, audio_output, mut mixer): Self::SystemData) {
         #[cfg(feature = "profiler")]
         profile_scope!("ui_sound_system");
 
@@ -105,7 +106,7 @@ impl<'s> System<'s> for UiSoundSystem {
         for event in sound_events.read(event_reader) {
             if let Some(output) = audio_output.as_ref() {
                 if let Some(sound) = audio_storage.get(&event.0) {
-                    output.play_once(sound, 1.0);
+                    mixer.play_once(output, sound, 1.0, AudioBus::Ui);
                 }
             }
         }
@@ -18,6 +18,7 @@ use amethyst_derive::SystemDesc;
 use amethyst_window::ScreenDimensions;
 
 use super::*;
+use crate::text_editing::EditHistory;
 
 /// How lines should behave when they are longer than the maximum line length.
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
@@ -88,7 +89,7 @@ impl Component for UiText {
 
 /// If this component is attached to an entity with a UiText then that UiText is editable.
 /// This component also controls how that editing works.
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TextEditing {
     /// The current editing cursor position, specified in terms of glyphs, not characters.
     pub cursor_position: isize,
@@ -103,6 +104,8 @@ pub struct TextEditing {
     /// If this is true the text will use a block cursor for editing.  Otherwise this uses a
     /// standard line cursor.  This is not recommended if your font is not monospace.
     pub use_block_cursor: bool,
+    /// Restricts the text that can be typed or pasted.
+    pub validation: TextValidation,
 
     /// This value is used to control cursor blinking.
     ///
@@ -110,6 +113,10 @@ pub struct TextEditing {
     /// is greater than or equal to 1.0 / CURSOR_BLINK_RATE it should be reset to 0.  When the
     /// player types it should be reset to 0.
     pub(crate) cursor_blink_timer: f32,
+    /// The edits that can be undone and redone.
+    pub(crate) history: EditHistory,
+    /// The text being composed by the input method, shown at the cursor.
+    pub(crate) preedit: Option<String>,
 }
 
 impl TextEditing {
@@ -127,9 +134,28 @@ impl TextEditing {
             selected_text_color,
             selected_background_color,
             use_block_cursor,
+            validation: TextValidation::Any,
             cursor_blink_timer: 0.0,
+            history: EditHistory::default(),
+            preedit: None,
         }
     }
+
+    /// Restricts the text that can be typed or pasted.
+    pub fn with_validation(mut self, validation: TextValidation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// The text being composed by the input method, if any, see `TextComposition`.
+    pub fn preedit(&self) -> Option<&str> {
+        self.preedit.as_ref().map(String::as_str)
+    }
+
+    /// Forgets the edits that could be undone and redone, for example after replacing the text.
+    pub fn clear_history(&mut self) {
+        self.history = EditHistory::default();
+    }
 }
 
 impl Component for TextEditing {
@@ -2,6 +2,8 @@ use std::ops::Range;
 
 use clipboard::{ClipboardContext, ClipboardProvider};
 use log::error;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
 use unicode_segmentation::UnicodeSegmentation;
 use winit::{ElementState, Event, KeyboardInput, ModifiersState, VirtualKeyCode, WindowEvent};
@@ -17,23 +19,186 @@ use amethyst_derive::SystemDesc;
 
 use crate::{LineMode, Selected, TextEditing, UiEvent, UiEventType, UiText};
 
+/// Maximum number of edits that can be undone.
+const MAX_UNDO: usize = 100;
+
+/// Restricts the text that can be typed or pasted in a `TextEditing` text.
+///
+/// Only the insertions are checked, so the text can still be made invalid by deleting parts of it.
+/// As the text is checked after every typed character, incomplete inputs must be accepted too.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TextValidation {
+    /// Any text is accepted.
+    Any,
+    /// Integers, optionally negative.
+    Integer,
+    /// Decimal numbers, optionally negative.
+    Decimal,
+    /// Texts matching the regular expression, which should be anchored with `^` and `$`.
+    Pattern(#[serde(with = "serde_regex")] Regex),
+}
+
+/// (De)serializes a `Regex` as its pattern.
+mod serde_regex {
+    use regex::Regex;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(regex: &Regex, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(regex.as_str())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Regex, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Regex::new(&pattern).map_err(D::Error::custom)
+    }
+}
+
+impl TextValidation {
+    /// Checks if the text is accepted.
+    pub fn accepts(&self, text: &str) -> bool {
+        let unsigned = if text.starts_with('-') {
+            &text[1..]
+        } else {
+            text
+        };
+        match self {
+            TextValidation::Any => true,
+            TextValidation::Integer => unsigned.chars().all(|c| c.is_ascii_digit()),
+            TextValidation::Decimal => {
+                unsigned.chars().all(|c| c.is_ascii_digit() || c == '.')
+                    && unsigned.matches('.').count() <= 1
+            }
+            TextValidation::Pattern(regex) => regex.is_match(text),
+        }
+    }
+}
+
+impl Default for TextValidation {
+    fn default() -> Self {
+        TextValidation::Any
+    }
+}
+
+impl PartialEq for TextValidation {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (TextValidation::Any, TextValidation::Any)
+            | (TextValidation::Integer, TextValidation::Integer)
+            | (TextValidation::Decimal, TextValidation::Decimal) => true,
+            (TextValidation::Pattern(a), TextValidation::Pattern(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+/// An input method (IME) composition event for the selected editable text.
+///
+/// The window events don't report compositions yet, so platform integrations write these to the
+/// `EventChannel<TextComposition>` read by the `TextEditingInputSystem`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextComposition {
+    /// The text being composed changed. It is shown at the cursor, but isn't part of the text
+    /// until committed.
+    Preedit(String),
+    /// The composition ended with this text, inserted like pasted text.
+    Commit(String),
+    /// The composition was cancelled.
+    Cancel,
+}
+
+/// The text and cursor of a `TextEditing` before an edit.
+#[derive(Debug, Clone, PartialEq)]
+struct EditSnapshot {
+    text: String,
+    cursor_position: isize,
+    highlight_vector: isize,
+}
+
+impl EditSnapshot {
+    fn of(edit: &TextEditing, text: &UiText) -> Self {
+        EditSnapshot {
+            text: text.text.clone(),
+            cursor_position: edit.cursor_position,
+            highlight_vector: edit.highlight_vector,
+        }
+    }
+
+    fn restore(self, edit: &mut TextEditing, text: &mut UiText) {
+        text.text = self.text;
+        edit.cursor_position = self.cursor_position;
+        edit.highlight_vector = self.highlight_vector;
+        edit.cursor_blink_timer = 0.0;
+    }
+}
+
+/// The undo and redo history of a `TextEditing`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct EditHistory {
+    undo: Vec<EditSnapshot>,
+    redo: Vec<EditSnapshot>,
+    /// If the last edit was typing a word, to undo the characters of a word at once.
+    typing: bool,
+}
+
+impl EditHistory {
+    /// Records the state before an edit. Typed characters are merged into the previous edit if
+    /// it was typing too.
+    fn record(&mut self, before: EditSnapshot, typing: bool) {
+        if !(typing && self.typing) {
+            self.undo.push(before);
+            if self.undo.len() > MAX_UNDO {
+                self.undo.remove(0);
+            }
+        }
+        self.typing = typing;
+        self.redo.clear();
+    }
+
+    /// Stops merging the next typed characters into the last edit.
+    fn interrupt(&mut self) {
+        self.typing = false;
+    }
+
+    fn undo(&mut self, current: EditSnapshot) -> Option<EditSnapshot> {
+        self.typing = false;
+        let snapshot = self.undo.pop()?;
+        self.redo.push(current);
+        Some(snapshot)
+    }
+
+    fn redo(&mut self, current: EditSnapshot) -> Option<EditSnapshot> {
+        self.typing = false;
+        let snapshot = self.redo.pop()?;
+        self.undo.push(current);
+        Some(snapshot)
+    }
+}
+
 /// System managing the keyboard inputs for the editable text fields.
 /// ## Features
-/// * Adds and removes text.
-/// * Moves selection cursor.
+/// * Adds and removes text, checked by the `TextValidation` of the `TextEditing`.
+/// * Moves selection cursor, by grapheme or by word while holding control.
 /// * Grows and shrinks selected text zone.
+/// * Undoes and redoes edits with control+z and control+shift+z or control+y.
+/// * Shows the `TextComposition` of input methods.
 #[derive(Debug, SystemDesc)]
 #[system_desc(name(TextEditingInputSystemDesc))]
 pub struct TextEditingInputSystem {
     /// A reader for winit events.
     #[system_desc(event_channel_reader)]
     reader: ReaderId<Event>,
+    /// A reader for input method compositions.
+    #[system_desc(event_channel_reader)]
+    composition_reader: ReaderId<TextComposition>,
 }
 
 impl TextEditingInputSystem {
     /// Creates a new instance of this system
-    pub fn new(reader: ReaderId<Event>) -> Self {
-        Self { reader }
+    pub fn new(reader: ReaderId<Event>, composition_reader: ReaderId<TextComposition>) -> Self {
+        Self {
+            reader,
+            composition_reader,
+        }
     }
 }
 
@@ -44,12 +209,13 @@ impl<'a> System<'a> for TextEditingInputSystem {
         WriteStorage<'a, TextEditing>,
         ReadStorage<'a, Selected>,
         Read<'a, EventChannel<Event>>,
+        Read<'a, EventChannel<TextComposition>>,
         Write<'a, EventChannel<UiEvent>>,
     );
 
     fn run(
         &mut self,
-        (entities, mut texts, mut editables, selecteds, events, mut edit_events): Self::SystemData,
+        (entities, mut texts, mut editables, selecteds, events, compositions, mut edit_events): Self::SystemData,
     ) {
         for text in (&mut texts).join() {
             if (*text.text).chars().any(is_combining_mark) {
@@ -58,6 +224,28 @@ impl<'a> System<'a> for TextEditingInputSystem {
             }
         }
 
+        for composition in compositions.read(&mut self.composition_reader) {
+            if let Some((entity, focused_text, focused_edit, _)) =
+                (&*entities, &mut texts, &mut editables, &selecteds)
+                    .join()
+                    .next()
+            {
+                match composition {
+                    TextComposition::Preedit(preedit) => {
+                        focused_edit.preedit = Some(preedit.clone()).filter(|p| !p.is_empty());
+                    }
+                    TextComposition::Commit(committed) => {
+                        focused_edit.preedit = None;
+                        if insert_text(focused_edit, focused_text, committed, false) {
+                            edit_events
+                                .single_write(UiEvent::new(UiEventType::ValueChange, entity));
+                        }
+                    }
+                    TextComposition::Cancel => focused_edit.preedit = None,
+                }
+            }
+        }
+
         for event in events.read(&mut self.reader) {
             // Process events for the focused text element
             if let Some((entity, ref mut focused_text, ref mut focused_edit, _)) =
@@ -73,22 +261,14 @@ impl<'a> System<'a> for TextEditingInputSystem {
                         if should_skip_char(input) {
                             continue;
                         }
-                        focused_edit.cursor_blink_timer = 0.0;
-                        delete_highlighted(focused_edit, focused_text);
-                        let start_byte = focused_text
-                            .text
-                            .grapheme_indices(true)
-                            .nth(focused_edit.cursor_position as usize)
-                            .map(|i| i.0)
-                            .unwrap_or_else(|| {
-                                // We are either in a 0 length string, or at the end of a string
-                                // This line returns the correct byte index for both.
-                                focused_text.text.len()
-                            });
-                        if focused_text.text.graphemes(true).count() < focused_edit.max_length {
-                            focused_text.text.insert(start_byte, input);
-                            focused_edit.cursor_position += 1;
-
+                        let mut buffer = [0; 4];
+                        let typing = !input.is_whitespace();
+                        if insert_text(
+                            focused_edit,
+                            focused_text,
+                            input.encode_utf8(&mut buffer),
+                            typing,
+                        ) {
                             edit_events
                                 .single_write(UiEvent::new(UiEventType::ValueChange, entity));
                         }
@@ -106,145 +286,152 @@ impl<'a> System<'a> for TextEditingInputSystem {
                                 ..
                             },
                         ..
-                    } => match v_keycode {
-                        VirtualKeyCode::Home | VirtualKeyCode::Up => {
-                            focused_edit.highlight_vector = if modifiers.shift {
-                                focused_edit.cursor_position
-                            } else {
-                                0
-                            };
-                            focused_edit.cursor_position = 0;
-                            focused_edit.cursor_blink_timer = 0.0;
-                        }
-                        VirtualKeyCode::End | VirtualKeyCode::Down => {
-                            let glyph_len = focused_text.text.graphemes(true).count() as isize;
-                            focused_edit.highlight_vector = if modifiers.shift {
-                                focused_edit.cursor_position - glyph_len
-                            } else {
-                                0
-                            };
-                            focused_edit.cursor_position = glyph_len;
-                            focused_edit.cursor_blink_timer = 0.0;
-                        }
-                        VirtualKeyCode::Back => {
-                            if !delete_highlighted(focused_edit, focused_text)
-                                && focused_edit.cursor_position > 0
-                            {
-                                if let Some((byte, len)) = focused_text
-                                    .text
-                                    .grapheme_indices(true)
-                                    .nth(focused_edit.cursor_position as usize - 1)
-                                    .map(|i| (i.0, i.1.len()))
-                                {
-                                    focused_text.text.drain(byte..(byte + len));
-                                    focused_edit.cursor_position -= 1;
-                                }
+                    } => {
+                        let changed = match v_keycode {
+                            VirtualKeyCode::Home | VirtualKeyCode::Up => {
+                                focused_edit.highlight_vector = if modifiers.shift {
+                                    focused_edit.cursor_position
+                                } else {
+                                    0
+                                };
+                                focused_edit.cursor_position = 0;
+                                focused_edit.cursor_blink_timer = 0.0;
+                                false
                             }
-                        }
-                        VirtualKeyCode::Delete => {
-                            if !delete_highlighted(focused_edit, focused_text) {
-                                if let Some((start_byte, start_glyph_len)) = focused_text
-                                    .text
-                                    .grapheme_indices(true)
-                                    .nth(focused_edit.cursor_position as usize)
-                                    .map(|i| (i.0, i.1.len()))
-                                {
-                                    focused_edit.cursor_blink_timer = 0.0;
-                                    focused_text
-                                        .text
-                                        .drain(start_byte..(start_byte + start_glyph_len));
+                            VirtualKeyCode::End | VirtualKeyCode::Down => {
+                                let glyph_len = focused_text.text.graphemes(true).count() as isize;
+                                focused_edit.highlight_vector = if modifiers.shift {
+                                    focused_edit.cursor_position - glyph_len
+                                } else {
+                                    0
+                                };
+                                focused_edit.cursor_position = glyph_len;
+                                focused_edit.cursor_blink_timer = 0.0;
+                                false
+                            }
+                            VirtualKeyCode::Back => {
+                                if focused_edit.highlight_vector != 0 {
+                                    delete_highlighted(focused_edit, focused_text)
+                                } else {
+                                    let start = if ctrl_or_cmd(modifiers) {
+                                        previous_word_start(
+                                            &focused_text.text,
+                                            focused_edit.cursor_position,
+                                        )
+                                    } else {
+                                        focused_edit.cursor_position - 1
+                                    };
+                                    let end = focused_edit.cursor_position;
+                                    delete_graphemes(focused_edit, focused_text, start.max(0), end)
                                 }
                             }
-                        }
-                        VirtualKeyCode::Left => {
-                            if focused_edit.highlight_vector == 0 || modifiers.shift {
-                                if focused_edit.cursor_position > 0 {
-                                    let delta = if ctrl_or_cmd(modifiers) {
-                                        let mut graphemes = 0;
-                                        for word in focused_text.text.split_word_bounds() {
-                                            let word_graphemes =
-                                                word.graphemes(true).count() as isize;
-                                            if graphemes + word_graphemes
-                                                >= focused_edit.cursor_position
-                                            {
-                                                break;
-                                            }
-                                            graphemes += word_graphemes;
-                                        }
-                                        focused_edit.cursor_position - graphemes
+                            VirtualKeyCode::Delete => {
+                                if focused_edit.highlight_vector != 0 {
+                                    delete_highlighted(focused_edit, focused_text)
+                                } else {
+                                    let start = focused_edit.cursor_position;
+                                    let end = if ctrl_or_cmd(modifiers) {
+                                        next_word_end(&focused_text.text, start)
                                     } else {
-                                        1
+                                        start + 1
                                     };
-                                    focused_edit.cursor_position -= delta;
-                                    if modifiers.shift {
-                                        focused_edit.highlight_vector += delta;
+                                    delete_graphemes(focused_edit, focused_text, start, end)
+                                }
+                            }
+                            VirtualKeyCode::Left => {
+                                if focused_edit.highlight_vector == 0 || modifiers.shift {
+                                    if focused_edit.cursor_position > 0 {
+                                        let delta = if ctrl_or_cmd(modifiers) {
+                                            focused_edit.cursor_position
+                                                - previous_word_start(
+                                                    &focused_text.text,
+                                                    focused_edit.cursor_position,
+                                                )
+                                        } else {
+                                            1
+                                        };
+                                        focused_edit.cursor_position -= delta;
+                                        if modifiers.shift {
+                                            focused_edit.highlight_vector += delta;
+                                        }
+                                        focused_edit.cursor_blink_timer = 0.0;
                                     }
-                                    focused_edit.cursor_blink_timer = 0.0;
+                                } else {
+                                    focused_edit.cursor_position =
+                                        focused_edit.cursor_position.min(
+                                            focused_edit.cursor_position
+                                                + focused_edit.highlight_vector,
+                                        );
+                                    focused_edit.highlight_vector = 0;
                                 }
-                            } else {
-                                focused_edit.cursor_position = focused_edit.cursor_position.min(
-                                    focused_edit.cursor_position + focused_edit.highlight_vector,
-                                );
-                                focused_edit.highlight_vector = 0;
+                                false
                             }
-                        }
-                        VirtualKeyCode::Right => {
-                            if focused_edit.highlight_vector == 0 || modifiers.shift {
-                                let glyph_len = focused_text.text.graphemes(true).count();
-                                if (focused_edit.cursor_position as usize) < glyph_len {
-                                    let delta = if ctrl_or_cmd(modifiers) {
-                                        let mut graphemes = 0;
-                                        for word in focused_text.text.split_word_bounds() {
-                                            graphemes += word.graphemes(true).count() as isize;
-                                            if graphemes > focused_edit.cursor_position {
-                                                break;
-                                            }
+                            VirtualKeyCode::Right => {
+                                if focused_edit.highlight_vector == 0 || modifiers.shift {
+                                    let glyph_len = focused_text.text.graphemes(true).count();
+                                    if (focused_edit.cursor_position as usize) < glyph_len {
+                                        let delta = if ctrl_or_cmd(modifiers) {
+                                            next_word_end(
+                                                &focused_text.text,
+                                                focused_edit.cursor_position,
+                                            ) - focused_edit.cursor_position
+                                        } else {
+                                            1
+                                        };
+                                        focused_edit.cursor_position += delta;
+                                        if modifiers.shift {
+                                            focused_edit.highlight_vector -= delta;
                                         }
-                                        graphemes - focused_edit.cursor_position
-                                    } else {
-                                        1
-                                    };
-                                    focused_edit.cursor_position += delta;
-                                    if modifiers.shift {
-                                        focused_edit.highlight_vector -= delta;
+                                        focused_edit.cursor_blink_timer = 0.0;
                                     }
-                                    focused_edit.cursor_blink_timer = 0.0;
+                                } else {
+                                    focused_edit.cursor_position =
+                                        focused_edit.cursor_position.max(
+                                            focused_edit.cursor_position
+                                                + focused_edit.highlight_vector,
+                                        );
+                                    focused_edit.highlight_vector = 0;
                                 }
-                            } else {
-                                focused_edit.cursor_position = focused_edit.cursor_position.max(
-                                    focused_edit.cursor_position + focused_edit.highlight_vector,
-                                );
-                                focused_edit.highlight_vector = 0;
+                                false
                             }
-                        }
-                        VirtualKeyCode::A => {
-                            if ctrl_or_cmd(modifiers) {
+                            VirtualKeyCode::A if ctrl_or_cmd(modifiers) => {
                                 let glyph_len = focused_text.text.graphemes(true).count() as isize;
                                 focused_edit.cursor_position = glyph_len;
                                 focused_edit.highlight_vector = -glyph_len;
+                                false
                             }
-                        }
-                        VirtualKeyCode::X => {
-                            if ctrl_or_cmd(modifiers) {
-                                let new_clip = extract_highlighted(focused_edit, focused_text);
-                                if !new_clip.is_empty() {
+                            VirtualKeyCode::Z if ctrl_or_cmd(modifiers) => {
+                                if modifiers.shift {
+                                    redo(focused_edit, focused_text)
+                                } else {
+                                    undo(focused_edit, focused_text)
+                                }
+                            }
+                            VirtualKeyCode::Y if ctrl_or_cmd(modifiers) => {
+                                redo(focused_edit, focused_text)
+                            }
+                            VirtualKeyCode::X if ctrl_or_cmd(modifiers) => {
+                                let new_clip = read_highlighted(focused_edit, focused_text);
+                                if new_clip.is_empty() {
+                                    false
+                                } else {
                                     match ClipboardProvider::new().and_then(
-                                        |mut ctx: ClipboardContext| ctx.set_contents(new_clip),
+                                        |mut ctx: ClipboardContext| {
+                                            ctx.set_contents(new_clip.to_owned())
+                                        },
                                     ) {
-                                        Ok(_) => edit_events.single_write(UiEvent::new(
-                                            UiEventType::ValueChange,
-                                            entity,
-                                        )),
-                                        Err(e) => error!(
-                                            "Error occured when cutting to clipboard: {:?}",
-                                            e
-                                        ),
+                                        Ok(_) => delete_highlighted(focused_edit, focused_text),
+                                        Err(e) => {
+                                            error!(
+                                                "Error occured when cutting to clipboard: {:?}",
+                                                e
+                                            );
+                                            false
+                                        }
                                     }
                                 }
                             }
-                        }
-                        VirtualKeyCode::C => {
-                            if ctrl_or_cmd(modifiers) {
+                            VirtualKeyCode::C if ctrl_or_cmd(modifiers) => {
                                 let new_clip = read_highlighted(focused_edit, focused_text);
                                 if !new_clip.is_empty() {
                                     if let Err(e) = ClipboardProvider::new().and_then(
@@ -255,81 +442,52 @@ impl<'a> System<'a> for TextEditingInputSystem {
                                         error!("Error occured when copying to clipboard: {:?}", e);
                                     }
                                 }
+                                false
                             }
-                        }
-                        VirtualKeyCode::V => {
-                            if ctrl_or_cmd(modifiers) {
-                                delete_highlighted(focused_edit, focused_text);
-
+                            VirtualKeyCode::V if ctrl_or_cmd(modifiers) => {
                                 match ClipboardProvider::new()
                                     .and_then(|mut ctx: ClipboardContext| ctx.get_contents())
                                 {
                                     Ok(contents) => {
-                                        let index = cursor_byte_index(focused_edit, focused_text);
-                                        let empty_space = focused_edit.max_length
-                                            - focused_text.text.graphemes(true).count();
-                                        let contents = contents
-                                            .graphemes(true)
-                                            .take(empty_space)
-                                            .fold(String::new(), |mut init, new| {
-                                                init.push_str(new);
-                                                init
-                                            });
-                                        focused_text.text.insert_str(index, &contents);
-                                        focused_edit.cursor_position +=
-                                            contents.graphemes(true).count() as isize;
-
-                                        edit_events.single_write(UiEvent::new(
-                                            UiEventType::ValueChange,
-                                            entity,
-                                        ));
+                                        insert_text(focused_edit, focused_text, &contents, false)
+                                    }
+                                    Err(e) => {
+                                        error!(
+                                            "Error occured when pasting contents of clipboard: {:?}",
+                                            e
+                                        );
+                                        false
                                     }
-                                    Err(e) => error!(
-                                        "Error occured when pasting contents of clipboard: {:?}",
-                                        e
-                                    ),
                                 }
                             }
-                        }
-                        VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
-                            match focused_text.line_mode {
-                                LineMode::Single => {
+                            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                                if focused_text.line_mode == LineMode::Wrap && modifiers.shift {
+                                    insert_text(focused_edit, focused_text, "\n", false)
+                                } else {
                                     edit_events.single_write(UiEvent::new(
                                         UiEventType::ValueCommit,
                                         entity,
                                     ));
-                                }
-                                LineMode::Wrap => {
-                                    if modifiers.shift {
-                                        if focused_text.text.graphemes(true).count()
-                                            < focused_edit.max_length
-                                        {
-                                            let start_byte = focused_text
-                                                .text
-                                                .grapheme_indices(true)
-                                                .nth(focused_edit.cursor_position as usize)
-                                                .map(|i| i.0)
-                                                .unwrap_or_else(|| focused_text.text.len());
-
-                                            focused_text.text.insert(start_byte, '\n');
-                                            focused_edit.cursor_position += 1;
-
-                                            edit_events.single_write(UiEvent::new(
-                                                UiEventType::ValueChange,
-                                                entity,
-                                            ));
-                                        }
-                                    } else {
-                                        edit_events.single_write(UiEvent::new(
-                                            UiEventType::ValueCommit,
-                                            entity,
-                                        ));
-                                    }
+                                    false
                                 }
                             }
+                            _ => false,
+                        };
+                        if changed {
+                            edit_events
+                                .single_write(UiEvent::new(UiEventType::ValueChange, entity));
+                        }
+                        // Typing after moving the cursor is undone separately.
+                        match v_keycode {
+                            VirtualKeyCode::Home
+                            | VirtualKeyCode::End
+                            | VirtualKeyCode::Up
+                            | VirtualKeyCode::Down
+                            | VirtualKeyCode::Left
+                            | VirtualKeyCode::Right => focused_edit.history.interrupt(),
+                            _ => {}
                         }
-                        _ => {}
-                    },
+                    }
                     _ => {}
                 }
             }
@@ -348,31 +506,115 @@ fn read_highlighted<'a>(edit: &TextEditing, text: &'a UiText) -> &'a str {
     &text.text[range]
 }
 
-/// Removes the highlighted text and returns it in a String.
-fn extract_highlighted(edit: &mut TextEditing, text: &mut UiText) -> String {
+/// Replaces the highlighted text, or inserts at the cursor, as much of `inserted` as fits in the
+/// maximum length. Returns false if the resulting text isn't valid or nothing changed.
+fn insert_text(edit: &mut TextEditing, text: &mut UiText, inserted: &str, typing: bool) -> bool {
     let range = highlighted_bytes(edit, text);
-    edit.cursor_position = range.start as isize;
+    let kept = text.text.graphemes(true).count() - text.text[range.clone()].graphemes(true).count();
+    let inserted = inserted
+        .graphemes(true)
+        .take(edit.max_length.saturating_sub(kept))
+        .collect::<String>();
+    if inserted.is_empty() && range.start == range.end {
+        return false;
+    }
+
+    let mut new_text = text.text.clone();
+    new_text.replace_range(range.clone(), &inserted);
+    if !edit.validation.accepts(&new_text) {
+        return false;
+    }
+
+    edit.history.record(EditSnapshot::of(edit, text), typing);
+    // Counted in the new text, as inserted combining marks merge with the previous grapheme.
+    edit.cursor_position = new_text[..range.start + inserted.len()]
+        .graphemes(true)
+        .count() as isize;
     edit.highlight_vector = 0;
-    text.text.drain(range).collect::<String>()
+    edit.cursor_blink_timer = 0.0;
+    text.text = new_text;
+    true
 }
 
-/// Removes the highlighted text and returns true if anything was deleted..
+/// Removes the graphemes from `start` to `end`, returns true if anything was deleted.
+fn delete_graphemes(edit: &mut TextEditing, text: &mut UiText, start: isize, end: isize) -> bool {
+    let start_byte = grapheme_byte_index(text, start as usize);
+    let end_byte = grapheme_byte_index(text, end as usize);
+    if start_byte >= end_byte {
+        return false;
+    }
+    edit.history.record(EditSnapshot::of(edit, text), false);
+    text.text.drain(start_byte..end_byte);
+    edit.cursor_position = start;
+    edit.highlight_vector = 0;
+    edit.cursor_blink_timer = 0.0;
+    true
+}
+
+/// Removes the highlighted text and returns true if anything was deleted.
 fn delete_highlighted(edit: &mut TextEditing, text: &mut UiText) -> bool {
-    if edit.highlight_vector != 0 {
-        let range = highlighted_bytes(edit, text);
-        edit.cursor_position = range.start as isize;
-        edit.highlight_vector = 0;
-        text.text.drain(range);
-        return true;
+    let start = edit
+        .cursor_position
+        .min(edit.cursor_position + edit.highlight_vector);
+    let end = edit
+        .cursor_position
+        .max(edit.cursor_position + edit.highlight_vector);
+    delete_graphemes(edit, text, start, end)
+}
+
+fn undo(edit: &mut TextEditing, text: &mut UiText) -> bool {
+    match edit.history.undo(EditSnapshot::of(edit, text)) {
+        Some(snapshot) => {
+            snapshot.restore(edit, text);
+            true
+        }
+        None => false,
+    }
+}
+
+fn redo(edit: &mut TextEditing, text: &mut UiText) -> bool {
+    match edit.history.redo(EditSnapshot::of(edit, text)) {
+        Some(snapshot) => {
+            snapshot.restore(edit, text);
+            true
+        }
+        None => false,
+    }
+}
+
+/// The grapheme index of the start of the word before `position`, skipping whitespace.
+fn previous_word_start(text: &str, position: isize) -> isize {
+    let mut start = 0;
+    let mut graphemes = 0;
+    for word in text.split_word_bounds() {
+        if graphemes >= position {
+            break;
+        }
+        if !word.trim().is_empty() {
+            start = graphemes;
+        }
+        graphemes += word.graphemes(true).count() as isize;
     }
-    false
+    start
 }
 
-// Gets the byte index of the cursor.
-fn cursor_byte_index(edit: &TextEditing, text: &UiText) -> usize {
+/// The grapheme index of the end of the word after `position`, skipping whitespace.
+fn next_word_end(text: &str, position: isize) -> isize {
+    let mut graphemes = 0;
+    for word in text.split_word_bounds() {
+        graphemes += word.graphemes(true).count() as isize;
+        if graphemes > position && !word.trim().is_empty() {
+            break;
+        }
+    }
+    graphemes
+}
+
+/// Gets the byte index of a grapheme, or the length of the text past its end.
+fn grapheme_byte_index(text: &UiText, grapheme: usize) -> usize {
     text.text
         .grapheme_indices(true)
-        .nth(edit.cursor_position as usize)
+        .nth(grapheme)
         .map(|i| i.0)
         .unwrap_or_else(|| text.text.len())
 }
@@ -385,19 +627,7 @@ fn highlighted_bytes(edit: &TextEditing, text: &UiText) -> Range<usize> {
     let end = edit
         .cursor_position
         .max(edit.cursor_position + edit.highlight_vector) as usize;
-    let start_byte = text
-        .text
-        .grapheme_indices(true)
-        .nth(start)
-        .map(|i| i.0)
-        .unwrap_or_else(|| text.text.len());
-    let end_byte = text
-        .text
-        .grapheme_indices(true)
-        .nth(end)
-        .map(|i| i.0)
-        .unwrap_or_else(|| text.text.len());
-    start_byte..end_byte
+    grapheme_byte_index(text, start)..grapheme_byte_index(text, end)
 }
 
 fn should_skip_char(input: char) -> bool {
@@ -413,3 +643,209 @@ fn should_skip_char(input: char) -> bool {
     || (input >= '\u{F0000}' && input <= '\u{FFFFF}')
     || (input >= '\u{100000}' && input <= '\u{10FFFF}')
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use amethyst_assets::AssetStorage;
+    use amethyst_core::ecs::{Builder, Entity, RunNow, WorldExt};
+    use glyph_brush::rusttype::Font;
+    use winit::{DeviceId, WindowId};
+
+    use crate::FontAsset;
+
+    struct Editor {
+        world: World,
+        system: TextEditingInputSystem,
+        entity: Entity,
+    }
+
+    impl Editor {
+        fn new(edit: TextEditing) -> Self {
+            let mut world = World::new();
+            let system = TextEditingInputSystemDesc::default().build(&mut world);
+            let font = Font::from_bytes(&include_bytes!("font/square.ttf")[..])
+                .expect("Failed to load the default font");
+            let font = AssetStorage::<FontAsset>::new().insert(FontAsset(font));
+            let entity = world
+                .create_entity()
+                .with(UiText::new(font, String::new(), [1.0; 4], 20.0))
+                .with(edit)
+                .with(Selected)
+                .build();
+            Editor {
+                world,
+                system,
+                entity,
+            }
+        }
+
+        fn send(&mut self, events: Vec<Event>) {
+            self.world
+                .write_resource::<EventChannel<Event>>()
+                .iter_write(events);
+            self.system.run_now(&self.world);
+        }
+
+        fn compose(&mut self, composition: TextComposition) {
+            self.world
+                .write_resource::<EventChannel<TextComposition>>()
+                .single_write(composition);
+            self.system.run_now(&self.world);
+        }
+
+        fn text(&self) -> String {
+            self.world
+                .read_storage::<UiText>()
+                .get(self.entity)
+                .unwrap()
+                .text
+                .clone()
+        }
+
+        fn edit(&self) -> TextEditing {
+            self.world
+                .read_storage::<TextEditing>()
+                .get(self.entity)
+                .unwrap()
+                .clone()
+        }
+    }
+
+    fn edit() -> TextEditing {
+        TextEditing::new(20, [0.0; 4], [1.0; 4], false)
+    }
+
+    fn window_event(event: WindowEvent) -> Event {
+        Event::WindowEvent {
+            window_id: unsafe { WindowId::dummy() },
+            event,
+        }
+    }
+
+    fn typed(text: &str) -> Vec<Event> {
+        text.chars()
+            .map(|c| window_event(WindowEvent::ReceivedCharacter(c)))
+            .collect()
+    }
+
+    fn key(key: VirtualKeyCode, ctrl: bool, shift: bool) -> Event {
+        window_event(WindowEvent::KeyboardInput {
+            device_id: unsafe { DeviceId::dummy() },
+            input: KeyboardInput {
+                scancode: 0,
+                state: ElementState::Pressed,
+                virtual_keycode: Some(key),
+                // Control on every platform, command on macOS.
+                modifiers: ModifiersState {
+                    shift,
+                    ctrl,
+                    alt: false,
+                    logo: ctrl,
+                },
+            },
+        })
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut editor = Editor::new(edit());
+        editor.send(typed("hello world"));
+        // The characters of a word are undone at once.
+        editor.send(vec![key(VirtualKeyCode::Z, true, false)]);
+        assert_eq!(editor.text(), "hello ");
+        editor.send(vec![key(VirtualKeyCode::Z, true, false)]);
+        assert_eq!(editor.text(), "hello");
+        assert_eq!(editor.edit().cursor_position, 5);
+        editor.send(vec![key(VirtualKeyCode::Y, true, false)]);
+        assert_eq!(editor.text(), "hello ");
+        editor.send(vec![key(VirtualKeyCode::Z, true, true)]);
+        assert_eq!(editor.text(), "hello world");
+        assert_eq!(editor.edit().cursor_position, 11);
+
+        // Typing after moving the cursor is a new edit.
+        editor.send(vec![
+            key(VirtualKeyCode::Home, false, false),
+            key(VirtualKeyCode::Back, false, false),
+        ]);
+        editor.send(typed("oh"));
+        assert_eq!(editor.text(), "ohhello world");
+        editor.send(vec![key(VirtualKeyCode::Z, true, false)]);
+        assert_eq!(editor.text(), "hello world");
+        editor.send(vec![key(VirtualKeyCode::Z, true, true)]);
+        editor.send(vec![key(VirtualKeyCode::Z, true, true)]);
+        assert_eq!(editor.text(), "ohhello world");
+    }
+
+    #[test]
+    fn word_navigation() {
+        let mut editor = Editor::new(edit());
+        editor.send(typed("hello big world"));
+        editor.send(vec![key(VirtualKeyCode::Left, true, false)]);
+        assert_eq!(editor.edit().cursor_position, 10);
+        editor.send(vec![key(VirtualKeyCode::Left, true, false)]);
+        assert_eq!(editor.edit().cursor_position, 6);
+        editor.send(vec![key(VirtualKeyCode::Right, true, false)]);
+        assert_eq!(editor.edit().cursor_position, 9);
+        editor.send(vec![key(VirtualKeyCode::Back, true, false)]);
+        assert_eq!(editor.text(), "hello  world");
+        assert_eq!(editor.edit().cursor_position, 6);
+        editor.send(vec![key(VirtualKeyCode::Delete, true, false)]);
+        assert_eq!(editor.text(), "hello ");
+        editor.send(vec![key(VirtualKeyCode::Left, true, true)]);
+        let edit = editor.edit();
+        assert_eq!((edit.cursor_position, edit.highlight_vector), (0, 6));
+    }
+
+    #[test]
+    fn validation() {
+        let mut editor = Editor::new(edit().with_validation(TextValidation::Integer));
+        editor.send(typed("-12a.3"));
+        assert_eq!(editor.text(), "-123");
+
+        let mut editor = Editor::new(edit().with_validation(TextValidation::Decimal));
+        editor.send(typed("1.5.2"));
+        assert_eq!(editor.text(), "1.52");
+
+        let pattern = Regex::new("^[a-z]*$").unwrap();
+        let mut editor = Editor::new(edit().with_validation(TextValidation::Pattern(pattern)));
+        editor.send(typed("aB1c"));
+        assert_eq!(editor.text(), "ac");
+
+        let mut editor = Editor::new(TextEditing::new(3, [0.0; 4], [1.0; 4], false));
+        editor.send(typed("e\u{301}abc"));
+        assert_eq!(editor.text(), "e\u{301}ab");
+        assert_eq!(editor.edit().cursor_position, 3);
+    }
+
+    #[test]
+    fn validation_from_ron() {
+        let validation: TextValidation = ron::de::from_str("Pattern(\"^[0-9]*$\")").unwrap();
+        assert!(validation.accepts("42"));
+        assert!(!validation.accepts("4a"));
+        assert_eq!(
+            ron::de::from_str::<TextValidation>("Integer").unwrap(),
+            TextValidation::Integer
+        );
+        assert!(ron::de::from_str::<TextValidation>("Pattern(\"[\")").is_err());
+    }
+
+    #[test]
+    fn composition() {
+        let mut editor = Editor::new(edit());
+        editor.send(typed("ab"));
+        editor.send(vec![key(VirtualKeyCode::Left, false, false)]);
+        editor.compose(TextComposition::Preedit("ni".to_string()));
+        assert_eq!(editor.edit().preedit(), Some("ni"));
+        assert_eq!(editor.text(), "ab");
+        editor.compose(TextComposition::Commit("你".to_string()));
+        assert_eq!(editor.edit().preedit(), None);
+        assert_eq!(editor.text(), "a你b");
+        assert_eq!(editor.edit().cursor_position, 2);
+        editor.compose(TextComposition::Preedit("x".to_string()));
+        editor.compose(TextComposition::Cancel);
+        assert_eq!(editor.edit().preedit(), None);
+        assert_eq!(editor.text(), "a你b");
+    }
+}
@@ -0,0 +1,612 @@
+//! Module holding the themes styling ui widgets by name.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use amethyst_assets::{Asset, AssetStorage, Handle, Loader};
+use amethyst_audio::{FlacFormat, Mp3Format, OggFormat, Source, SourceHandle, WavFormat};
+use amethyst_core::{
+    ecs::prelude::{
+        Component, DenseVecStorage, Entities, Entity, Join, Read, ReadExpect, ReadStorage, System,
+        SystemData, VecStorage, World, WriteStorage,
+    },
+    ParentHierarchy, SystemDesc,
+};
+use amethyst_derive::SystemDesc;
+use amethyst_rendy::{ImageFormat, Texture};
+
+use crate::{
+    FontAsset, FontHandle, TtfFormat, UiButtonAction, UiButtonActionRetrigger, UiButtonActionType,
+    UiImage, UiPlaySoundAction, UiSoundRetrigger, UiText,
+};
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+/// An image of a `UiStyle`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum UiStyleImage {
+    /// Solid color image
+    SolidColor(f32, f32, f32, f32),
+    /// Texture loaded from the given path
+    Texture(String),
+}
+
+/// A named set of properties applied to the widgets referencing it, see `UiTheme`.
+///
+/// Every property is optional. Unset properties are inherited from the `parent` style, and
+/// properties set by no style in the chain are left untouched on the widgets.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UiStyle {
+    /// The style this one inherits from
+    pub parent: Option<String>,
+    /// Path of the font
+    pub font: Option<String>,
+    /// Font size
+    pub font_size: Option<f32>,
+    /// Text color
+    pub text_color: Option<[f32; 4]>,
+    /// Background image
+    pub image: Option<UiStyleImage>,
+    /// Image used when the mouse hovers over the widget
+    pub hover_image: Option<UiStyleImage>,
+    /// Text color used when the mouse hovers over the widget
+    pub hover_text_color: Option<[f32; 4]>,
+    /// Image used when the widget is pressed
+    pub press_image: Option<UiStyleImage>,
+    /// Text color used when the widget is pressed
+    pub press_text_color: Option<[f32; 4]>,
+    /// Path of the sound made when the widget is hovered over
+    pub hover_sound: Option<String>,
+    /// Path of the sound made when the widget is pressed
+    pub press_sound: Option<String>,
+    /// Path of the sound made when the widget is released
+    pub release_sound: Option<String>,
+}
+
+impl UiStyle {
+    /// Sets the properties unset in this style to the ones of `parent`.
+    pub fn inherit(&mut self, parent: &UiStyle) {
+        fn or<T: Clone>(value: &mut Option<T>, parent: &Option<T>) {
+            if value.is_none() {
+                *value = parent.clone();
+            }
+        }
+        or(&mut self.font, &parent.font);
+        or(&mut self.font_size, &parent.font_size);
+        or(&mut self.text_color, &parent.text_color);
+        or(&mut self.image, &parent.image);
+        or(&mut self.hover_image, &parent.hover_image);
+        or(&mut self.hover_text_color, &parent.hover_text_color);
+        or(&mut self.press_image, &parent.press_image);
+        or(&mut self.press_text_color, &parent.press_text_color);
+        or(&mut self.hover_sound, &parent.hover_sound);
+        or(&mut self.press_sound, &parent.press_sound);
+        or(&mut self.release_sound, &parent.release_sound);
+    }
+
+    fn has_text(&self) -> bool {
+        self.font.is_some() || self.font_size.is_some() || self.text_color.is_some()
+    }
+
+    fn has_actions(&self) -> bool {
+        self.hover_image.is_some()
+            || self.hover_text_color.is_some()
+            || self.press_image.is_some()
+            || self.press_text_color.is_some()
+    }
+
+    fn has_sounds(&self) -> bool {
+        self.hover_sound.is_some() || self.press_sound.is_some() || self.release_sound.is_some()
+    }
+}
+
+/// A set of named `UiStyle`s, usually loaded from a `ron` file with `RonFormat`:
+///
+/// ```ron
+/// (
+///     styles: {
+///         "button": (
+///             font_size: Some(20.0),
+///             image: Some(SolidColor(0.8, 0.8, 0.8, 1.0)),
+///             hover_text_color: Some((0.2, 0.2, 0.8, 1.0)),
+///         ),
+///         "danger_button": (
+///             parent: Some("button"),
+///             text_color: Some((0.8, 0.1, 0.1, 1.0)),
+///         ),
+///     },
+/// )
+/// ```
+///
+/// The `Processor<UiTheme>` added by the `UiBundle` makes loaded themes available, and hot
+/// reloading a theme restyles the widgets using it.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UiTheme {
+    /// The styles, by name
+    pub styles: HashMap<String, UiStyle>,
+}
+
+impl UiTheme {
+    /// The style with the given name, with the properties it inherits set.
+    ///
+    /// Returns `None` if the style doesn't exist. Inheritance stops at unknown parents and
+    /// cycles.
+    pub fn style(&self, name: &str) -> Option<UiStyle> {
+        let mut style = self.styles.get(name)?.clone();
+        let mut visited = vec![name.to_string()];
+        let mut parent_name = style.parent.clone();
+        while let Some(name) = parent_name {
+            if visited.contains(&name) {
+                break;
+            }
+            let parent = match self.styles.get(&name) {
+                Some(parent) => parent,
+                None => break,
+            };
+            style.inherit(parent);
+            parent_name = parent.parent.clone();
+            visited.push(name);
+        }
+        Some(style)
+    }
+}
+
+impl Asset for UiTheme {
+    const NAME: &'static str = "ui::Theme";
+    type Data = Self;
+    type HandleStorage = VecStorage<Handle<Self>>;
+}
+
+/// Resource holding the theme used to style the `UiStyled` widgets.
+///
+/// Changing it restyles all the widgets.
+#[derive(Debug, Clone, Default)]
+pub struct ActiveUiTheme {
+    /// The active theme, nothing is styled while it is `None` or not loaded yet
+    pub theme: Option<Handle<UiTheme>>,
+}
+
+impl ActiveUiTheme {
+    /// Creates the resource with the given theme.
+    pub fn new(theme: Handle<UiTheme>) -> Self {
+        ActiveUiTheme { theme: Some(theme) }
+    }
+}
+
+/// Component styling a widget with the style of the `ActiveUiTheme` it names.
+///
+/// Text properties are applied to the `UiText` of the entity, or to the ones of its children
+/// for buttons. Hover and press properties replace the `UiButtonActionRetrigger` and sounds
+/// the `UiSoundRetrigger` of the entity.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct UiStyled {
+    /// The name of the style
+    pub style: String,
+}
+
+impl UiStyled {
+    /// Styles an entity with the style named `style`.
+    pub fn new<S: Into<String>>(style: S) -> Self {
+        UiStyled {
+            style: style.into(),
+        }
+    }
+}
+
+impl Component for UiStyled {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Assets referenced by themes, loaded once per path.
+#[derive(Debug, Default)]
+struct ThemeAssets {
+    fonts: HashMap<String, FontHandle>,
+    textures: HashMap<String, Handle<Texture>>,
+    sounds: HashMap<String, Option<SourceHandle>>,
+}
+
+impl ThemeAssets {
+    fn font(
+        &mut self,
+        path: &str,
+        loader: &Loader,
+        storage: &AssetStorage<FontAsset>,
+    ) -> FontHandle {
+        self.fonts
+            .entry(path.to_string())
+            .or_insert_with(|| loader.load(path, TtfFormat, (), storage))
+            .clone()
+    }
+
+    fn image(
+        &mut self,
+        image: &UiStyleImage,
+        loader: &Loader,
+        storage: &AssetStorage<Texture>,
+    ) -> UiImage {
+        match image {
+            UiStyleImage::SolidColor(r, g, b, a) => UiImage::SolidColor([*r, *g, *b, *a]),
+            UiStyleImage::Texture(path) => UiImage::Texture(
+                self.textures
+                    .entry(path.clone())
+                    .or_insert_with(|| {
+                        loader.load(path.as_str(), ImageFormat::default(), (), storage)
+                    })
+                    .clone(),
+            ),
+        }
+    }
+
+    fn sound(
+        &mut self,
+        path: &str,
+        loader: &Loader,
+        storage: &AssetStorage<Source>,
+    ) -> Option<UiPlaySoundAction> {
+        self.sounds
+            .entry(path.to_string())
+            .or_insert_with(|| {
+                let extension = Path::new(path)
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .map(str::to_lowercase);
+                match extension.as_ref().map(String::as_str) {
+                    Some("wav") => Some(loader.load(path, WavFormat, (), storage)),
+                    Some("ogg") => Some(loader.load(path, OggFormat, (), storage)),
+                    Some("flac") => Some(loader.load(path, FlacFormat, (), storage)),
+                    Some("mp3") => Some(loader.load(path, Mp3Format, (), storage)),
+                    _ => {
+                        log::warn!("Unsupported sound format for ui style: {}", path);
+                        None
+                    }
+                }
+            })
+            .clone()
+            .map(UiPlaySoundAction)
+    }
+}
+
+/// Applies the styles of the `ActiveUiTheme` to the `UiStyled` widgets.
+///
+/// Widgets are styled when they are created or their style changes, and all of them are styled
+/// again when the active theme changes or is reloaded.
+#[derive(Debug, Default, SystemDesc)]
+#[system_desc(name(UiThemeSystemDesc))]
+pub struct UiThemeSystem {
+    /// The id and version of the theme the widgets are styled with.
+    #[system_desc(skip)]
+    theme: Option<(u32, u32)>,
+    /// The style each widget was styled with.
+    #[system_desc(skip)]
+    applied: HashMap<Entity, String>,
+    /// The styled widgets whose `UiText` wasn't found yet.
+    #[system_desc(skip)]
+    missing_text: HashSet<Entity>,
+    #[system_desc(skip)]
+    assets: ThemeAssets,
+}
+
+impl UiThemeSystem {
+    /// Creates a new `UiThemeSystem`.
+    pub fn new() -> Self {
+        UiThemeSystem::default()
+    }
+}
+
+impl<'a> System<'a> for UiThemeSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, ActiveUiTheme>,
+        Read<'a, AssetStorage<UiTheme>>,
+        ReadStorage<'a, UiStyled>,
+        ReadExpect<'a, ParentHierarchy>,
+        WriteStorage<'a, UiText>,
+        WriteStorage<'a, UiImage>,
+        WriteStorage<'a, UiButtonActionRetrigger>,
+        WriteStorage<'a, UiSoundRetrigger>,
+        ReadExpect<'a, Loader>,
+        Read<'a, AssetStorage<FontAsset>>,
+        Read<'a, AssetStorage<Texture>>,
+        Read<'a, AssetStorage<Source>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            active_theme,
+            themes,
+            styled,
+            hierarchy,
+            mut texts,
+            mut images,
+            mut action_retriggers,
+            mut sound_retriggers,
+            loader,
+            fonts,
+            textures,
+            sounds,
+        ): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("ui_theme_system");
+
+        let (handle, (theme, version)) = match active_theme
+            .theme
+            .as_ref()
+            .and_then(|handle| Some((handle, themes.get_with_version(handle)?)))
+        {
+            Some(theme) => theme,
+            None => return,
+        };
+        if self.theme != Some((handle.id(), *version)) {
+            self.theme = Some((handle.id(), *version));
+            self.applied.clear();
+            self.missing_text.clear();
+        }
+        self.applied.retain(|entity, _| entities.is_alive(*entity));
+        self.missing_text
+            .retain(|entity| entities.is_alive(*entity));
+
+        for (entity, styled) in (&entities, &styled).join() {
+            let restyle = self.applied.get(&entity) != Some(&styled.style);
+            if !restyle && !self.missing_text.contains(&entity) {
+                continue;
+            }
+            if restyle {
+                self.applied.insert(entity, styled.style.clone());
+            }
+            let style = match theme.style(&styled.style) {
+                Some(style) => style,
+                None => {
+                    log::warn!("Unknown ui style: {}", styled.style);
+                    self.missing_text.remove(&entity);
+                    continue;
+                }
+            };
+
+            let font = style
+                .font
+                .as_ref()
+                .map(|font| self.assets.font(font, &loader, &fonts));
+            let text_entities = if texts.contains(entity) {
+                vec![entity]
+            } else {
+                hierarchy.children(entity).to_vec()
+            };
+            let mut found_text = false;
+            for text in text_entities {
+                let text = match texts.get_mut(text) {
+                    Some(text) => text,
+                    None => continue,
+                };
+                found_text = true;
+                if let Some(ref font) = font {
+                    text.font = font.clone();
+                }
+                if let Some(font_size) = style.font_size {
+                    text.font_size = font_size;
+                }
+                if let Some(text_color) = style.text_color {
+                    text.color = text_color;
+                }
+            }
+            // The label of a button created this frame may not be in the `ParentHierarchy`
+            // yet, it is then styled on the next frames.
+            if found_text || !style.has_text() {
+                self.missing_text.remove(&entity);
+            } else {
+                self.missing_text.insert(entity);
+            }
+            if !restyle {
+                continue;
+            }
+
+            if let Some(ref image) = style.image {
+                let image = self.assets.image(image, &loader, &textures);
+                images
+                    .insert(entity, image)
+                    .expect("Unreachable: Entity is alive");
+            }
+
+            if style.has_actions() {
+                let mut image = |image: &Option<UiStyleImage>| {
+                    image
+                        .as_ref()
+                        .map(|image| self.assets.image(image, &loader, &textures))
+                };
+                let retrigger = button_actions(
+                    entity,
+                    image(&style.hover_image),
+                    style.hover_text_color,
+                    image(&style.press_image),
+                    style.press_text_color,
+                );
+                action_retriggers
+                    .insert(entity, retrigger)
+                    .expect("Unreachable: Entity is alive");
+            }
+
+            if style.has_sounds() {
+                let mut sound = |sound: &Option<String>| {
+                    sound
+                        .as_ref()
+                        .and_then(|sound| self.assets.sound(sound, &loader, &sounds))
+                };
+                let retrigger = UiSoundRetrigger {
+                    on_click_start: sound(&style.press_sound),
+                    on_click_stop: sound(&style.release_sound),
+                    on_hover_start: sound(&style.hover_sound),
+                    on_hover_stop: None,
+                };
+                sound_retriggers
+                    .insert(entity, retrigger)
+                    .expect("Unreachable: Entity is alive");
+            }
+        }
+    }
+}
+
+/// Creates the actions changing the image and text color of a button on hover and press.
+fn button_actions(
+    entity: Entity,
+    hover_image: Option<UiImage>,
+    hover_text_color: Option<[f32; 4]>,
+    press_image: Option<UiImage>,
+    press_text_color: Option<[f32; 4]>,
+) -> UiButtonActionRetrigger {
+    let action = |event_type| UiButtonAction {
+        target: entity,
+        event_type,
+    };
+    let mut retrigger = UiButtonActionRetrigger {
+        on_click_start: Vec::new(),
+        on_click_stop: Vec::new(),
+        on_hover_start: Vec::new(),
+        on_hover_stop: Vec::new(),
+    };
+
+    if let Some(press_image) = press_image {
+        retrigger
+            .on_click_start
+            .push(action(UiButtonActionType::SetImage(press_image.clone())));
+        retrigger
+            .on_click_stop
+            .push(action(UiButtonActionType::UnsetTexture(press_image)));
+    }
+    if let Some(hover_image) = hover_image {
+        retrigger
+            .on_hover_start
+            .push(action(UiButtonActionType::SetImage(hover_image.clone())));
+        retrigger
+            .on_hover_stop
+            .push(action(UiButtonActionType::UnsetTexture(hover_image)));
+    }
+    if let Some(press_text_color) = press_text_color {
+        retrigger
+            .on_click_start
+            .push(action(UiButtonActionType::SetTextColor(press_text_color)));
+        retrigger
+            .on_click_stop
+            .push(action(UiButtonActionType::UnsetTextColor(press_text_color)));
+    }
+    if let Some(hover_text_color) = hover_text_color {
+        retrigger
+            .on_hover_start
+            .push(action(UiButtonActionType::SetTextColor(hover_text_color)));
+        retrigger
+            .on_hover_stop
+            .push(action(UiButtonActionType::UnsetTextColor(hover_text_color)));
+    }
+    retrigger
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UiButton, UiButtonBuilder, UiButtonBuilderResources, Widgets};
+    use amethyst_core::{
+        bundle::SystemBundle,
+        ecs::{DispatcherBuilder, RunNow, WorldExt},
+        TransformBundle,
+    };
+    use glyph_brush::rusttype::Font;
+    use rayon::ThreadPoolBuilder;
+    use std::sync::Arc;
+
+    const THEME: &str = r#"(
+        styles: {
+            "base": (
+                font_size: Some(20.0),
+                text_color: Some((0.0, 0.0, 0.0, 1.0)),
+            ),
+            "button": (
+                parent: Some("base"),
+                image: Some(SolidColor(0.8, 0.8, 0.8, 1.0)),
+            ),
+            "danger": (
+                parent: Some("button"),
+                text_color: Some((1.0, 0.0, 0.0, 1.0)),
+            ),
+            "loop_a": (parent: Some("loop_b"), font_size: Some(10.0)),
+            "loop_b": (parent: Some("loop_a"), text_color: Some((0.0, 1.0, 0.0, 1.0))),
+        },
+    )"#;
+
+    #[test]
+    fn inheritance() {
+        let theme: UiTheme = ron::de::from_str(THEME).unwrap();
+        let danger = theme.style("danger").unwrap();
+        assert_eq!(danger.font_size, Some(20.0));
+        assert_eq!(danger.text_color, Some([1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(
+            danger.image,
+            Some(UiStyleImage::SolidColor(0.8, 0.8, 0.8, 1.0))
+        );
+        assert_eq!(danger.hover_image, None);
+        assert_eq!(theme.style("missing"), None);
+    }
+
+    #[test]
+    fn inheritance_cycles() {
+        let theme: UiTheme = ron::de::from_str(THEME).unwrap();
+        let style = theme.style("loop_a").unwrap();
+        assert_eq!(style.font_size, Some(10.0));
+        assert_eq!(style.text_color, Some([0.0, 1.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn button_label() {
+        let mut world = World::new();
+        let pool = Arc::new(ThreadPoolBuilder::new().build().unwrap());
+        world.insert(Loader::new(".", pool));
+        world.insert(Widgets::<UiButton, u32>::default());
+        let mut transform = DispatcherBuilder::new();
+        TransformBundle::new()
+            .build(&mut world, &mut transform)
+            .unwrap();
+        let mut transform = transform.build();
+        transform.setup(&mut world);
+        <UiButtonBuilderResources<'_, ()> as SystemData<'_>>::setup(&mut world);
+        let mut system = UiThemeSystemDesc::default().build(&mut world);
+
+        let theme: UiTheme = ron::de::from_str(THEME).unwrap();
+        let theme = world
+            .write_resource::<AssetStorage<UiTheme>>()
+            .insert(theme);
+        world.insert(ActiveUiTheme::new(theme));
+        let font = Font::from_bytes(&include_bytes!("font/square.ttf")[..])
+            .expect("Failed to load the default font");
+        let font = AssetStorage::<FontAsset>::new().insert(FontAsset(font));
+        let (_, button) = UiButtonBuilder::<(), u32>::new("Delete")
+            .with_font(font)
+            .with_style("danger")
+            .build_from_world(&world);
+        world.maintain();
+
+        // The label isn't in the `ParentHierarchy` yet.
+        system.run_now(&world);
+        let label = |world: &World| {
+            let texts = world.read_storage::<UiText>();
+            let text = texts.get(button.text_entity).unwrap();
+            (text.font_size, text.color)
+        };
+        assert_eq!(label(&world), (32.0, [0.0, 0.0, 0.0, 1.0]));
+        assert_eq!(
+            world.read_storage::<UiImage>().get(button.image_entity),
+            Some(&UiImage::SolidColor([0.8, 0.8, 0.8, 1.0]))
+        );
+
+        transform.dispatch(&world);
+        system.run_now(&world);
+        assert_eq!(label(&world), (20.0, [1.0, 0.0, 0.0, 1.0]));
+    }
+}
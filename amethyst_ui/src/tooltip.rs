@@ -0,0 +1,222 @@
+//! Tooltips shown when hovering ui elements.
+
+use std::marker::PhantomData;
+
+use amethyst_core::{
+    ecs::prelude::{
+        Component, DenseVecStorage, Entity, Join, Read, ReadExpect, ReadStorage, ReaderId, System,
+        SystemData, World, WriteStorage,
+    },
+    shrev::EventChannel,
+    HiddenPropagate, SystemDesc, Time,
+};
+use amethyst_derive::SystemDesc;
+use amethyst_input::{BindingTypes, InputHandler};
+use amethyst_window::ScreenDimensions;
+
+#[cfg(feature = "profiler")]
+use thread_profiler::profile_scope;
+
+use crate::{Anchor, UiEvent, UiEventType, UiTransform};
+
+/// Component showing a tooltip once the pointer hovered an `Interactable` element for a while.
+///
+/// The tooltip is another ui element, hidden by the `UiTooltipSystem` until it is shown next
+/// to the pointer, on the side where it fits on screen. It shouldn't have a parent, as it is
+/// placed relative to the screen, and should have a global z high enough to be drawn on top of
+/// the other elements.
+#[derive(Debug, Clone)]
+pub struct UiTooltip {
+    /// The ui element shown as tooltip.
+    pub tooltip: Entity,
+    /// Seconds the pointer has to stay over the element before the tooltip shows.
+    pub delay: f32,
+    /// Distance in pixels between the pointer and the tooltip.
+    pub offset: f32,
+}
+
+impl UiTooltip {
+    /// Creates a tooltip showing `tooltip` after half a second.
+    pub fn new(tooltip: Entity) -> Self {
+        UiTooltip {
+            tooltip,
+            delay: 0.5,
+            offset: 16.0,
+        }
+    }
+
+    /// Sets the delay before the tooltip shows, in seconds.
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets the distance in pixels between the pointer and the tooltip.
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl Component for UiTooltip {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Finds the bottom left corner of a tooltip of `size`, below and to the right of the pointer
+/// if there is room for it, otherwise on the other side of the pointer, and always on screen.
+fn place_tooltip(
+    pointer: (f32, f32),
+    size: (f32, f32),
+    screen: (f32, f32),
+    offset: f32,
+) -> (f32, f32) {
+    let mut x = pointer.0 + offset;
+    if x + size.0 > screen.0 {
+        x = pointer.0 - offset - size.0;
+    }
+    let mut y = pointer.1 - offset - size.1;
+    if y < 0.0 {
+        y = pointer.1 + offset;
+    }
+    (
+        x.min(screen.0 - size.0).max(0.0),
+        y.min(screen.1 - size.1).max(0.0),
+    )
+}
+
+/// System showing and placing the tooltips of the hovered `UiTooltip` elements.
+/// The generic type T represent the T generic parameter of the InputHandler<T>.
+///
+/// Tooltips hide when the pointer leaves their element or clicks it.
+#[derive(Debug, SystemDesc)]
+#[system_desc(name(UiTooltipSystemDesc))]
+pub struct UiTooltipSystem<T: BindingTypes> {
+    #[system_desc(event_channel_reader)]
+    ui_reader_id: ReaderId<UiEvent>,
+    /// The hovered element and for how long it has been hovered.
+    #[system_desc(skip)]
+    hovered: Option<(Entity, f32)>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: BindingTypes> UiTooltipSystem<T> {
+    /// Creates a new `UiTooltipSystem`.
+    pub fn new(ui_reader_id: ReaderId<UiEvent>) -> Self {
+        Self {
+            ui_reader_id,
+            hovered: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: BindingTypes> System<'a> for UiTooltipSystem<T> {
+    type SystemData = (
+        ReadStorage<'a, UiTooltip>,
+        WriteStorage<'a, UiTransform>,
+        WriteStorage<'a, HiddenPropagate>,
+        Read<'a, EventChannel<UiEvent>>,
+        Read<'a, InputHandler<T>>,
+        Read<'a, Time>,
+        ReadExpect<'a, ScreenDimensions>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            tooltips,
+            mut transforms,
+            mut hidden_propagates,
+            ui_events,
+            input,
+            time,
+            screen_dimensions,
+        ): Self::SystemData,
+    ) {
+        #[cfg(feature = "profiler")]
+        profile_scope!("ui_tooltip_system");
+
+        for event in ui_events.read(&mut self.ui_reader_id) {
+            match event.event_type {
+                UiEventType::HoverStart if tooltips.contains(event.target) => {
+                    self.hovered = Some((event.target, 0.0));
+                }
+                UiEventType::HoverStop | UiEventType::ClickStart
+                    if self.hovered.map(|(entity, _)| entity) == Some(event.target) =>
+                {
+                    self.hovered = None;
+                }
+                _ => {}
+            }
+        }
+
+        let delta_seconds = time.delta_real_seconds();
+        let shown = match self.hovered.as_mut() {
+            Some((entity, elapsed)) => {
+                *elapsed += delta_seconds;
+                tooltips
+                    .get(*entity)
+                    .filter(|tooltip| *elapsed >= tooltip.delay)
+            }
+            None => None,
+        };
+
+        for tooltip in (&tooltips).join() {
+            let is_shown = shown.map_or(false, |shown| shown.tooltip == tooltip.tooltip);
+            if !is_shown && !hidden_propagates.contains(tooltip.tooltip) {
+                hidden_propagates
+                    .insert(tooltip.tooltip, HiddenPropagate)
+                    .expect("Unreachable: Inserting on a tooltip entity");
+            }
+        }
+
+        let tooltip = match shown {
+            Some(tooltip) => tooltip,
+            None => return,
+        };
+        hidden_propagates.remove(tooltip.tooltip);
+        let pointer = input
+            .primary_touch()
+            .map(|touch| touch.position)
+            .or_else(|| input.mouse_position())
+            .map(|(x, y)| (x as f32, screen_dimensions.height() - y as f32));
+        if let (Some(pointer), Some(transform)) = (pointer, transforms.get_mut(tooltip.tooltip)) {
+            let (x, y) = place_tooltip(
+                pointer,
+                (transform.pixel_width, transform.pixel_height),
+                (screen_dimensions.width(), screen_dimensions.height()),
+                tooltip.offset,
+            );
+            transform.anchor = Anchor::BottomLeft;
+            transform.pivot = Anchor::BottomLeft;
+            transform.local_x = x;
+            transform.local_y = y;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tooltip_placement() {
+        let screen = (800.0, 600.0);
+        let size = (100.0, 50.0);
+        // Below and to the right of the pointer.
+        assert_eq!(
+            place_tooltip((100.0, 300.0), size, screen, 10.0),
+            (110.0, 240.0)
+        );
+        // Flipped in the bottom right corner.
+        assert_eq!(
+            place_tooltip((750.0, 20.0), size, screen, 10.0),
+            (640.0, 30.0)
+        );
+        // Kept on screen when it fits on neither side.
+        assert_eq!(
+            place_tooltip((50.0, 580.0), (790.0, 590.0), screen, 10.0),
+            (0.0, 10.0)
+        );
+    }
+}
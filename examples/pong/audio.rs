@@ -1,6 +1,6 @@
 use amethyst::{
     assets::{AssetStorage, Loader},
-    audio::{output::Output, AudioSink, OggFormat, Source, SourceHandle},
+    audio::{output::Output, AudioBus, AudioMixer, AudioSink, OggFormat, Source, SourceHandle},
     ecs::{World, WorldExt},
 };
 use std::{iter::Cycle, vec::IntoIter};
@@ -53,10 +53,15 @@ pub fn initialise_audio(world: &mut World) {
 }
 
 /// Plays the bounce sound when a ball hits a side or a paddle.
-pub fn play_bounce(sounds: &Sounds, storage: &AssetStorage<Source>, output: Option<&Output>) {
+pub fn play_bounce(
+    sounds: &Sounds,
+    storage: &AssetStorage<Source>,
+    output: Option<&Output>,
+    mixer: &mut AudioMixer,
+) {
     if let Some(ref output) = output.as_ref() {
         if let Some(sound) = storage.get(&sounds.bounce_sfx) {
-            output.play_once(sound, 1.0);
+            mixer.play_once(output, sound, 1.0, AudioBus::Sfx);
         }
     }
 }
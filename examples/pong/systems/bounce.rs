@@ -4,10 +4,12 @@ use crate::{
 };
 use amethyst::{
     assets::AssetStorage,
-    audio::{output::Output, Source},
+    audio::{output::Output, AudioMixer, Source},
     core::{transform::Transform, SystemDesc},
     derive::SystemDesc,
-    ecs::prelude::{Join, Read, ReadExpect, ReadStorage, System, SystemData, World, WriteStorage},
+    ecs::prelude::{
+        Join, Read, ReadExpect, ReadStorage, System, SystemData, World, Write, WriteStorage,
+    },
 };
 use std::ops::Deref;
 
@@ -24,11 +26,12 @@ impl<'s> System<'s> for BounceSystem {
         Read<'s, AssetStorage<Source>>,
         ReadExpect<'s, Sounds>,
         Option<Read<'s, Output>>,
+        Write<'s, AudioMixer>,
     );
 
     fn run(
         &mut self,
-        (mut balls, paddles, transforms, storage, sounds, audio_output): Self::SystemData,
+        (mut balls, paddles, transforms, storage, sounds, audio_output, mut mixer): Self::SystemData,
     ) {
         // Check whether a ball collided, and bounce off accordingly.
         //
@@ -45,7 +48,12 @@ impl<'s> System<'s> for BounceSystem {
                 || (ball_y >= ARENA_HEIGHT - ball.radius && ball.velocity[1] > 0.0)
             {
                 ball.velocity[1] = -ball.velocity[1];
-                play_bounce(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
+                play_bounce(
+                    &*sounds,
+                    &storage,
+                    audio_output.as_ref().map(|o| o.deref()),
+                    &mut mixer,
+                );
             }
 
             // Bounce at the paddles.
@@ -69,7 +77,12 @@ impl<'s> System<'s> for BounceSystem {
                     || (paddle.side == Side::Right && ball.velocity[0] > 0.0))
                 {
                     ball.velocity[0] = -ball.velocity[0];
-                    play_bounce(&*sounds, &storage, audio_output.as_ref().map(|o| o.deref()));
+                    play_bounce(
+                        &*sounds,
+                        &storage,
+                        audio_output.as_ref().map(|o| o.deref()),
+                        &mut mixer,
+                    );
                 }
             }
         }
@@ -1,7 +1,7 @@
 use crate::{audio::Sounds, Ball, ScoreBoard};
 use amethyst::{
     assets::AssetStorage,
-    audio::{output::Output, Source},
+    audio::{output::Output, AudioBus, AudioMixer, Source},
     core::{SystemDesc, Transform},
     derive::SystemDesc,
     ecs::prelude::{
@@ -26,6 +26,7 @@ impl<'s> System<'s> for WinnerSystem {
         ReadExpect<'s, Sounds>,
         ReadExpect<'s, ScoreText>,
         Option<Read<'s, Output>>,
+        Write<'s, AudioMixer>,
     );
 
     fn run(
@@ -39,6 +40,7 @@ impl<'s> System<'s> for WinnerSystem {
             sounds,
             score_text,
             audio_output,
+            mut mixer,
         ): Self::SystemData,
     ) {
         for (ball, transform) in (&mut balls, &mut transforms).join() {
@@ -80,7 +82,7 @@ impl<'s> System<'s> for WinnerSystem {
                 // Play audio.
                 if let Some(ref output) = audio_output {
                     if let Some(sound) = storage.get(&sounds.score_sfx) {
-                        output.play_once(sound, 1.0);
+                        mixer.play_once(output, sound, 1.0, AudioBus::Sfx);
                     }
                 }
             }